@@ -0,0 +1,52 @@
+//! Embeds build metadata shown by `controller version`.
+
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn main() {
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs/heads");
+    println!("cargo:rerun-if-changed=Cargo.toml");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+
+    let commit = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|commit| commit.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=CONTROLLER_CLI_GIT_COMMIT={commit}");
+
+    // Honour SOURCE_DATE_EPOCH so reproducible builds get a stable date
+    let timestamp = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse::<u64>().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0)
+        });
+    println!("cargo:rustc-env=CONTROLLER_CLI_BUILD_TIMESTAMP={timestamp}");
+
+    println!(
+        "cargo:rustc-env=CONTROLLER_CLI_ACCOUNT_SDK={}",
+        account_sdk_version().unwrap_or_else(|| "unknown".to_string())
+    );
+}
+
+/// The controller-rs tag (or path) account_sdk is built from, read from Cargo.toml
+fn account_sdk_version() -> Option<String> {
+    let manifest = std::fs::read_to_string("Cargo.toml").ok()?;
+    let line = manifest
+        .lines()
+        .find(|line| line.trim_start().starts_with("account_sdk"))?;
+    for key in ["tag", "rev", "branch", "path"] {
+        if let Some(rest) = line.split(&format!("{key} = \"")).nth(1) {
+            return rest.split('"').next().map(str::to_string);
+        }
+    }
+    None
+}
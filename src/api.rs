@@ -1,6 +1,152 @@
 use crate::error::{CliError, Result};
 use serde::{Deserialize, Serialize};
 use starknet::core::types::Felt;
+use starknet::providers::jsonrpc::{
+    HttpTransport, HttpTransportError, JsonRpcMethod, JsonRpcResponse, JsonRpcTransport,
+};
+use starknet::providers::ProviderRequestData;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Application identifier (`client-app`) sent as `x-client-app` so Cartridge can attribute
+/// traffic from different deployments sharing a machine
+static CLIENT_APP: OnceLock<String> = OnceLock::new();
+
+/// Set the application identifier attached to every subsequent Cartridge request.
+pub fn set_client_app(app: &str) {
+    let _ = CLIENT_APP.set(app.to_string());
+}
+
+/// Headers identifying the CLI (and the application driving it, if configured)
+fn attribution_headers() -> Vec<(&'static str, String)> {
+    let version = env!("CARGO_PKG_VERSION");
+    match CLIENT_APP.get() {
+        Some(app) => vec![
+            ("user-agent", format!("controller-cli/{version} {app}")),
+            ("x-client-app", app.clone()),
+        ],
+        None => vec![("user-agent", format!("controller-cli/{version}"))],
+    }
+}
+
+/// HTTP client for Cartridge API requests, carrying the attribution headers.
+pub fn http_client(timeout: Duration) -> Result<reqwest::Client> {
+    let headers = attribution_headers()
+        .into_iter()
+        .filter_map(|(name, value)| {
+            Some((
+                reqwest::header::HeaderName::from_static(name),
+                reqwest::header::HeaderValue::from_str(&value).ok()?,
+            ))
+        })
+        .collect();
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .default_headers(headers)
+        .build()
+        .map_err(|e| CliError::ApiError(format!("Failed to build HTTP client: {e}")))
+}
+
+/// JSON-RPC transport carrying the attribution headers.
+pub fn rpc_transport(url: url::Url) -> RpcTransport {
+    RpcTransport(
+        attribution_headers()
+            .into_iter()
+            .fold(HttpTransport::new(url), |transport, (name, value)| {
+                transport.with_header(name.to_string(), value)
+            }),
+    )
+}
+
+/// HTTP JSON-RPC transport that counts requests for the `--meta` block
+#[derive(Debug)]
+pub struct RpcTransport(HttpTransport);
+
+#[async_trait::async_trait]
+impl JsonRpcTransport for RpcTransport {
+    type Error = HttpTransportError;
+
+    async fn send_request<P, R>(
+        &self,
+        method: JsonRpcMethod,
+        params: P,
+    ) -> std::result::Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: serde::de::DeserializeOwned + Send,
+    {
+        crate::meta::rpc_calls(1);
+        self.0.send_request(method, params).await
+    }
+
+    async fn send_requests<R>(
+        &self,
+        requests: R,
+    ) -> std::result::Result<Vec<JsonRpcResponse<serde_json::Value>>, Self::Error>
+    where
+        R: AsRef<[ProviderRequestData]> + Send + Sync,
+    {
+        crate::meta::rpc_calls(requests.as_ref().len() as u64);
+        self.0.send_requests(requests).await
+    }
+}
+
+/// Longest `Retry-After` waited out automatically before surfacing `RateLimited`
+const MAX_AUTO_RETRY_AFTER: u64 = 30;
+
+/// Send a Cartridge API request, mapping HTTP 429 to [`CliError::RateLimited`].
+///
+/// When the server asks to retry within [`MAX_AUTO_RETRY_AFTER`] seconds, the request is retried
+/// once after that delay. Transport errors become `ApiError("{context}: ...")`.
+pub async fn send(request: reqwest::RequestBuilder, context: &str) -> Result<reqwest::Response> {
+    let retry = request.try_clone();
+    crate::meta::api_request();
+    let response = request
+        .send()
+        .await
+        .map_err(|e| CliError::ApiError(format!("{context}: {e}")))?;
+
+    let response = match (rate_limit(&response), retry) {
+        (Some(Some(retry_after)), Some(retry)) if retry_after <= MAX_AUTO_RETRY_AFTER => {
+            tokio::time::sleep(std::time::Duration::from_secs(retry_after)).await;
+            crate::meta::retries(1);
+            retry
+                .send()
+                .await
+                .map_err(|e| CliError::ApiError(format!("{context}: {e}")))?
+        }
+        _ => response,
+    };
+
+    match rate_limit(&response) {
+        Some(retry_after) => Err(CliError::RateLimited { retry_after }),
+        None => Ok(response),
+    }
+}
+
+/// `Some(retry_after)` if the response is a 429, with the `Retry-After` delay in seconds if given
+fn rate_limit(response: &reqwest::Response) -> Option<Option<u64>> {
+    (response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS).then(|| {
+        response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse().ok())
+    })
+}
+
+/// Map GraphQL error messages to an error, recognizing Cartridge quota/rate-limit errors.
+pub fn graphql_error(messages: &[String]) -> CliError {
+    let quota = messages.iter().any(|m| {
+        let m = m.to_lowercase();
+        m.contains("rate limit") || m.contains("quota") || m.contains("too many requests")
+    });
+    if quota {
+        CliError::RateLimited { retry_after: None }
+    } else {
+        CliError::ApiError(format!("GraphQL errors: {}", messages.join(", ")))
+    }
+}
 
 /// Shorten a URL via the Cartridge URL shortener service.
 ///
@@ -10,10 +156,7 @@ pub async fn shorten_url(api_url: &str, long_url: &str) -> Result<String> {
     // Derive base URL by stripping `/query` from the API URL
     let api_base = api_url.trim_end_matches("/query").trim_end_matches('/');
 
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(5))
-        .build()
-        .map_err(|e| CliError::ApiError(format!("Failed to build HTTP client: {e}")))?;
+    let client = http_client(Duration::from_secs(5))?;
 
     #[derive(Serialize)]
     struct ShortenRequest<'a> {
@@ -76,10 +219,8 @@ pub async fn query_session_info(
     api_url: &str,
     session_key_guid: &str,
 ) -> Result<Option<SessionInfo>> {
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(130)) // Slightly longer than backend's 2min timeout
-        .build()
-        .map_err(|e| CliError::ApiError(format!("Failed to build HTTP client: {e}")))?;
+    // Slightly longer than backend's 2min timeout
+    let client = http_client(Duration::from_secs(130))?;
 
     // This is a QUERY (not subscription) despite the name
     let query = r#"
@@ -137,12 +278,11 @@ pub async fn query_session_info(
         },
     };
 
-    let response = client
-        .post(api_url)
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| CliError::ApiError(format!("Failed to query session info: {e}")))?;
+    let response = send(
+        client.post(api_url).json(&request),
+        "Failed to query session info",
+    )
+    .await?;
 
     if !response.status().is_success() {
         return Err(CliError::ApiError(format!(
@@ -158,10 +298,7 @@ pub async fn query_session_info(
 
     if let Some(errors) = graphql_response.errors {
         let error_messages: Vec<String> = errors.iter().map(|e| e.message.clone()).collect();
-        return Err(CliError::ApiError(format!(
-            "GraphQL errors: {}",
-            error_messages.join(", ")
-        )));
+        return Err(graphql_error(&error_messages));
     }
 
     Ok(graphql_response
@@ -207,3 +344,112 @@ impl SessionInfo {
         )))
     }
 }
+
+/// Attempts made for transient failures (connection errors and 5xx responses); 429s are
+/// handled by [`send`]
+const GRAPHQL_ATTEMPTS: u32 = 3;
+
+/// Run an arbitrary GraphQL document against the Cartridge API.
+///
+/// Transient failures are retried with a short backoff, and short `Retry-After`s are honored.
+/// Returns the `data` object, or an `ApiError` listing the GraphQL errors when the API returned
+/// no data. Errors accompanying partial data are returned alongside it so the caller can surface
+/// them.
+pub async fn graphql_query(
+    api_url: &str,
+    query: &str,
+    variables: serde_json::Value,
+) -> Result<(serde_json::Value, Vec<String>)> {
+    let client = http_client(Duration::from_secs(30))?;
+
+    #[derive(Serialize)]
+    struct GraphQLRequest<'a> {
+        query: &'a str,
+        variables: serde_json::Value,
+    }
+
+    #[derive(Deserialize)]
+    struct GraphQLResponse {
+        data: Option<serde_json::Value>,
+        errors: Option<Vec<GraphQLError>>,
+    }
+
+    #[derive(Deserialize)]
+    struct GraphQLError {
+        message: String,
+    }
+
+    let request = GraphQLRequest { query, variables };
+
+    let mut attempt = 1;
+    let response = loop {
+        let last_error =
+            match send(client.post(api_url).json(&request), "Failed to send query").await {
+                Ok(response) if response.status().is_server_error() => {
+                    format!("API returned error status: {}", response.status())
+                }
+                Ok(response) => break response,
+                Err(CliError::ApiError(message)) => message,
+                Err(e) => return Err(e),
+            };
+        if attempt >= GRAPHQL_ATTEMPTS {
+            return Err(CliError::ApiError(last_error));
+        }
+        crate::meta::retries(1);
+        tokio::time::sleep(std::time::Duration::from_millis(
+            500 * 2u64.pow(attempt - 1),
+        ))
+        .await;
+        attempt += 1;
+    };
+
+    let status = response.status();
+    // GraphQL servers commonly report query errors with a 4xx status and an `errors` body
+    let graphql_response: GraphQLResponse = response.json().await.map_err(|e| {
+        if status.is_success() {
+            CliError::ApiError(format!("Failed to parse API response: {e}"))
+        } else {
+            CliError::ApiError(format!("API returned error status: {status}"))
+        }
+    })?;
+
+    let errors: Vec<String> = graphql_response
+        .errors
+        .unwrap_or_default()
+        .into_iter()
+        .map(|e| e.message)
+        .collect();
+
+    match graphql_response.data {
+        Some(data) if !data.is_null() => Ok((data, errors)),
+        _ if !errors.is_empty() => Err(graphql_error(&errors)),
+        _ => Err(CliError::ApiError(format!(
+            "API returned no data (status {status})"
+        ))),
+    }
+}
+
+/// Whether the session registered under `session_key_guid` has been revoked, or `None` if the
+/// API has no record of it.
+pub async fn query_session_revoked(api_url: &str, session_key_guid: &str) -> Result<Option<bool>> {
+    let query = r#"
+        query SessionRevoked($sessionKeyGUID: String!) {
+            sessions(where: { sessionKeyGUID: $sessionKeyGUID }, first: 1) {
+                edges {
+                    node {
+                        isRevoked
+                    }
+                }
+            }
+        }
+    "#;
+
+    let (data, _) = graphql_query(
+        api_url,
+        query,
+        serde_json::json!({ "sessionKeyGUID": session_key_guid }),
+    )
+    .await?;
+
+    Ok(data["sessions"]["edges"][0]["node"]["isRevoked"].as_bool())
+}
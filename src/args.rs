@@ -0,0 +1,69 @@
+use std::ffi::OsString;
+
+/// Expand `@path` argument values into the (trimmed) contents of the file at `path`, so secrets
+/// and long values (calldata, inline JSON, RPC URLs with keys) stay out of process lists and
+/// shell history. Works for positional values and `--flag=@path`; `@@` escapes a literal `@`.
+pub fn expand_file_args(args: impl IntoIterator<Item = OsString>) -> Result<Vec<OsString>, String> {
+    let mut args = args.into_iter();
+    // Never touch the binary name
+    let mut expanded: Vec<OsString> = args.next().into_iter().collect();
+
+    for arg in args {
+        let Some(value) = arg.to_str() else {
+            expanded.push(arg);
+            continue;
+        };
+        let (prefix, value) = match value.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (format!("{flag}="), value),
+            _ => (String::new(), value),
+        };
+        let value = match value.strip_prefix('@') {
+            Some(escaped) if escaped.starts_with('@') => escaped.to_string(),
+            Some(path) if !path.is_empty() => std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read argument file '{path}': {e}"))?
+                .trim()
+                .to_string(),
+            _ => value.to_string(),
+        };
+        expanded.push(format!("{prefix}{value}").into());
+    }
+
+    Ok(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(args: &[&str]) -> Result<Vec<String>, String> {
+        expand_file_args(args.iter().map(OsString::from))
+            .map(|args| args.into_iter().map(|a| a.into_string().unwrap()).collect())
+    }
+
+    #[test]
+    fn reads_values_from_files() {
+        let path = std::env::temp_dir().join(format!("controller-args-{}", std::process::id()));
+        std::fs::write(&path, "  0x1,u256:100\n").unwrap();
+        let file_arg = format!("@{}", path.display());
+
+        let args = expand(&["controller", "execute", "0xabc", "transfer", &file_arg]).unwrap();
+        assert_eq!(args[4], "0x1,u256:100");
+
+        let flag_arg = format!("--rpc-url={file_arg}");
+        let args = expand(&["controller", "call", &flag_arg]).unwrap();
+        assert_eq!(args[2], "--rpc-url=0x1,u256:100");
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn leaves_other_values_alone() {
+        let args = expand(&["@controller", "lookup", "--usernames", "@@alice", "@"]).unwrap();
+        assert_eq!(
+            args,
+            vec!["@controller", "lookup", "--usernames", "@alice", "@"]
+        );
+
+        assert!(expand(&["controller", "@/definitely/missing/file"]).is_err());
+    }
+}
@@ -0,0 +1,420 @@
+use crate::config::Config;
+use crate::error::{CliError, Result};
+use crate::output::OutputFormatter;
+use account_sdk::storage::{filestorage::FileSystemBackend, StorageBackend, StorageValue};
+use starknet::core::types::Felt;
+use starknet::providers::Provider;
+
+/// Contract name for the starterpack registry, used as `contracts.<CHAIN>.starterpack`
+pub const STARTERPACK: &str = "starterpack";
+/// Contract name for the marketplace, used as `contracts.<CHAIN>.marketplace`
+pub const MARKETPLACE: &str = "marketplace";
+
+/// A Cartridge deployment. Keychain, API, preset branch and RPC base are switched together by
+/// `--env` so they never point at different deployments.
+pub struct Environment {
+    pub name: &'static str,
+    pub keychain_url: &'static str,
+    pub api_url: &'static str,
+    /// Branch of the presets repository to fetch presets from
+    pub presets_ref: &'static str,
+    /// Base URL chain RPC paths are appended to
+    pub rpc_base: &'static str,
+}
+
+pub const PRODUCTION: &str = "production";
+
+pub const ENVIRONMENTS: &[Environment] = &[
+    Environment {
+        name: PRODUCTION,
+        keychain_url: "https://x.cartridge.gg",
+        api_url: "https://api.cartridge.gg/query",
+        presets_ref: "main",
+        rpc_base: "https://api.cartridge.gg",
+    },
+    Environment {
+        name: "staging",
+        keychain_url: "https://x.staging.cartridge.gg",
+        api_url: "https://api.staging.cartridge.gg/query",
+        presets_ref: "staging",
+        rpc_base: "https://api.staging.cartridge.gg",
+    },
+    Environment {
+        name: "dev",
+        keychain_url: "http://localhost:3001",
+        api_url: "http://localhost:8000/query",
+        presets_ref: "staging",
+        rpc_base: "http://localhost:8000",
+    },
+];
+
+/// Look up an environment by name (e.g., 'staging').
+pub fn environment(name: &str) -> Option<&'static Environment> {
+    ENVIRONMENTS.iter().find(|e| e.name == name)
+}
+
+/// Built-in knowledge about a supported chain.
+pub struct ChainInfo {
+    pub id: &'static str,
+    /// RPC path under the environment's `rpc_base`
+    pub rpc_path: &'static str,
+    pub explorer_url: &'static str,
+    /// Well-known contract deployments, keyed by name
+    pub contracts: &'static [(&'static str, &'static str)],
+}
+
+pub const CHAINS: &[ChainInfo] = &[
+    ChainInfo {
+        id: "SN_MAIN",
+        rpc_path: "/x/starknet/mainnet",
+        explorer_url: "https://voyager.online",
+        contracts: &[
+            (
+                STARTERPACK,
+                "0x3eb03b8f2be0ec2aafd186d72f6d8f3dd320dbc89f2b6802bca7465f6ccaa43",
+            ),
+            (
+                MARKETPLACE,
+                "0x057b4ca2f7b58e1b940eb89c4376d6e166abc640abf326512b0c77091f3f9652",
+            ),
+        ],
+    },
+    ChainInfo {
+        id: "SN_SEPOLIA",
+        rpc_path: "/x/starknet/sepolia",
+        explorer_url: "https://sepolia.voyager.online",
+        contracts: &[
+            (
+                STARTERPACK,
+                "0x3eb03b8f2be0ec2aafd186d72f6d8f3dd320dbc89f2b6802bca7465f6ccaa43",
+            ),
+            (
+                MARKETPLACE,
+                "0x057b4ca2f7b58e1b940eb89c4376d6e166abc640abf326512b0c77091f3f9652",
+            ),
+        ],
+    },
+];
+
+/// Look up a built-in chain by its ID (e.g., 'SN_MAIN').
+pub fn get(chain_id: &str) -> Option<&'static ChainInfo> {
+    CHAINS.iter().find(|c| c.id == chain_id)
+}
+
+impl ChainInfo {
+    /// RPC URL of this chain in the given environment.
+    pub fn rpc_url(&self, env: &Environment) -> String {
+        format!("{}{}", env.rpc_base, self.rpc_path)
+    }
+}
+
+/// IDs of the built-in chains followed by the chain profiles from the config.
+pub fn supported_ids(config: &Config) -> Vec<&str> {
+    CHAINS
+        .iter()
+        .map(|c| c.id)
+        .chain(config.chains.keys().map(String::as_str))
+        .collect()
+}
+
+/// RPC URL for a chain ID: a built-in chain in the configured environment, or a chain profile
+/// from the config (e.g. a linked Slot deployment).
+pub fn rpc_url_for(config: &Config, chain_id: &str) -> Result<String> {
+    let env = config.environment();
+    if let Some(chain) = get(chain_id) {
+        return Ok(chain.rpc_url(env));
+    }
+    if let Some(profile) = config.chains.get(chain_id) {
+        return Ok(profile.rpc_url.clone());
+    }
+    Err(CliError::InvalidInput(format!(
+        "Unsupported chain ID '{chain_id}'. Supported chains: {}",
+        supported_ids(config).join(", ")
+    )))
+}
+
+/// What to do when a command is given neither `--chain-id` nor `--rpc-url` and no RPC URL is
+/// configured (`default-chain` config key).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefaultChain {
+    /// Fall back to the configured default RPC (Sepolia unless changed), with a warning
+    Sepolia,
+    /// Refuse to guess and require an explicit chain
+    Strict,
+    /// Use the chain of the stored session, erroring if there is none
+    Auto,
+}
+
+impl DefaultChain {
+    pub const VALUES: &'static [&'static str] = &["sepolia", "strict", "auto"];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "sepolia" => Some(DefaultChain::Sepolia),
+            "strict" => Some(DefaultChain::Strict),
+            "auto" => Some(DefaultChain::Auto),
+            _ => None,
+        }
+    }
+}
+
+/// Resolve the RPC URL for a command.
+///
+/// Priority: `--rpc-url` > `--chain-id` > explicitly configured `rpc-url` > `default-chain` mode.
+pub fn resolve_rpc_url(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    chain_id: Option<String>,
+    rpc_url: Option<String>,
+    account: Option<&str>,
+) -> Result<String> {
+    if let Some(url) = rpc_url {
+        return Ok(url);
+    }
+    if let Some(chain) = chain_id {
+        return rpc_url_for(config, &chain);
+    }
+    if config.session.rpc_url_explicitly_set {
+        return Ok(config.session.rpc_url.clone());
+    }
+
+    match DefaultChain::parse(&config.session.default_chain).unwrap_or(DefaultChain::Sepolia) {
+        DefaultChain::Sepolia => {
+            formatter.warning(&format!(
+                "No --chain-id or --rpc-url specified, using {} by default",
+                config.session.rpc_url
+            ));
+            Ok(config.session.rpc_url.clone())
+        }
+        DefaultChain::Strict => Err(CliError::InvalidInput(
+            "No chain specified and default-chain is 'strict'. Pass --chain-id or --rpc-url"
+                .to_string(),
+        )),
+        DefaultChain::Auto => {
+            let backend = FileSystemBackend::new(config.resolve_storage_path(account));
+            match backend.get("session_rpc_url") {
+                Ok(Some(StorageValue::String(url))) => Ok(url),
+                _ => Err(CliError::InvalidInput(
+                    "No chain specified and no stored session to infer it from (default-chain is 'auto'). \
+                     Pass --chain-id or --rpc-url"
+                        .to_string(),
+                )),
+            }
+        }
+    }
+}
+
+/// Reject RPC URLs outside the configured environment's Cartridge RPC.
+pub fn ensure_cartridge_rpc(config: &Config, url: &str) -> Result<()> {
+    let env = config.environment();
+    if url.starts_with(env.rpc_base) || config.chains.values().any(|p| p.rpc_url == url) {
+        return Ok(());
+    }
+    let supported: Vec<String> = CHAINS.iter().map(|c| c.rpc_url(env)).collect();
+    Err(CliError::InvalidInput(format!(
+        "Only Cartridge RPC endpoints are supported. Use: {}",
+        supported.join(" or ")
+    )))
+}
+
+/// Explorer link for a transaction, if the chain has a known explorer.
+pub fn explorer_tx_url(chain_id: &str, transaction_hash: &str) -> Option<String> {
+    get(chain_id).map(|chain| format!("{}/tx/{transaction_hash}", chain.explorer_url))
+}
+
+/// Resolve a named contract on a chain. A `contracts.<CHAIN>.<name>` config override takes
+/// precedence over the built-in registry, so SLOT chains and migrated contracts work without a
+/// new release.
+pub fn resolve_contract(config: &Config, chain_id: &str, name: &str) -> Result<Felt> {
+    if let Some(address) = config
+        .contracts
+        .get(chain_id)
+        .and_then(|contracts| contracts.get(name))
+    {
+        return Felt::from_hex(address).map_err(|e| {
+            CliError::Config(format!(
+                "Invalid address for contracts.{chain_id}.{name} '{address}': {e}"
+            ))
+        });
+    }
+
+    get(chain_id)
+        .and_then(|chain| chain.contracts.iter().find(|(n, _)| *n == name))
+        .map(|(_, address)| Felt::from_hex_unchecked(address))
+        .ok_or_else(|| {
+            CliError::Config(format!(
+                "No {name} contract known for chain {chain_id}. \
+                 Set one with 'controller config set contracts.{chain_id}.{name} <address>'"
+            ))
+        })
+}
+
+/// Query the provider for its chain ID and decode it as a short string (e.g., 'SN_SEPOLIA').
+pub async fn chain_name<P: Provider + Sync>(provider: &P) -> Result<String> {
+    let felt = provider
+        .chain_id()
+        .await
+        .map_err(|e| CliError::Network(format!("Failed to get chain ID: {e}")))?;
+    starknet::core::utils::parse_cairo_short_string(&felt)
+        .map_err(|e| CliError::InvalidInput(format!("Failed to parse chain ID: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::NullFormatter;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn resolves_builtin_contracts() {
+        let config = Config::default();
+        let marketplace = resolve_contract(&config, "SN_MAIN", MARKETPLACE).unwrap();
+        assert_eq!(
+            marketplace,
+            Felt::from_hex_unchecked(
+                "0x057b4ca2f7b58e1b940eb89c4376d6e166abc640abf326512b0c77091f3f9652"
+            )
+        );
+        assert!(resolve_contract(&config, "SN_SEPOLIA", STARTERPACK).is_ok());
+    }
+
+    fn test_config(default_chain: &str) -> Config {
+        let mut config = Config::default();
+        config.session.default_chain = default_chain.to_string();
+        config.session.storage_path = std::env::temp_dir()
+            .join("controller-chains-test-missing")
+            .to_string_lossy()
+            .to_string();
+        config
+    }
+
+    #[test]
+    fn explicit_flags_win_in_every_mode() {
+        for mode in DefaultChain::VALUES {
+            let config = test_config(mode);
+            let url = resolve_rpc_url(
+                &config,
+                &NullFormatter,
+                Some("SN_MAIN".to_string()),
+                None,
+                None,
+            )
+            .unwrap();
+            assert_eq!(url, "https://api.cartridge.gg/x/starknet/mainnet");
+        }
+    }
+
+    #[test]
+    fn default_chain_modes() {
+        let url = resolve_rpc_url(&test_config("sepolia"), &NullFormatter, None, None, None);
+        assert_eq!(url.unwrap(), "https://api.cartridge.gg/x/starknet/sepolia");
+
+        assert!(resolve_rpc_url(&test_config("strict"), &NullFormatter, None, None, None).is_err());
+
+        // No stored session to infer from
+        assert!(resolve_rpc_url(&test_config("auto"), &NullFormatter, None, None, None).is_err());
+    }
+
+    #[test]
+    fn unsupported_chain_lists_registry() {
+        let err = rpc_url_for(&Config::default(), "SN_GOERLI")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("SN_MAIN, SN_SEPOLIA"));
+    }
+
+    #[test]
+    fn chain_profiles_resolve_and_pass_rpc_check() {
+        let mut config = Config::default();
+        config.chains.insert(
+            "WP_MY_SLOT".to_string(),
+            crate::config::ChainProfile {
+                rpc_url: "https://slot.example/katana".to_string(),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            rpc_url_for(&config, "WP_MY_SLOT").unwrap(),
+            "https://slot.example/katana"
+        );
+        assert_eq!(
+            supported_ids(&config),
+            ["SN_MAIN", "SN_SEPOLIA", "WP_MY_SLOT"]
+        );
+        assert!(rpc_url_for(&config, "SN_GOERLI")
+            .unwrap_err()
+            .to_string()
+            .contains("WP_MY_SLOT"));
+        assert!(ensure_cartridge_rpc(&config, "https://slot.example/katana").is_ok());
+        assert!(ensure_cartridge_rpc(&config, "https://other.example/rpc").is_err());
+    }
+
+    #[test]
+    fn environment_switches_rpc_base() {
+        let mut config = Config::default();
+        assert_eq!(
+            rpc_url_for(&config, "SN_SEPOLIA").unwrap(),
+            "https://api.cartridge.gg/x/starknet/sepolia"
+        );
+        assert!(
+            ensure_cartridge_rpc(&config, "https://api.cartridge.gg/x/starknet/mainnet").is_ok()
+        );
+
+        config.apply_environment("staging").unwrap();
+        assert_eq!(
+            rpc_url_for(&config, "SN_SEPOLIA").unwrap(),
+            "https://api.staging.cartridge.gg/x/starknet/sepolia"
+        );
+        assert_eq!(
+            config.session.api_url,
+            "https://api.staging.cartridge.gg/query"
+        );
+        assert!(
+            ensure_cartridge_rpc(&config, "https://api.cartridge.gg/x/starknet/mainnet").is_err()
+        );
+    }
+
+    #[test]
+    fn explorer_urls_per_chain() {
+        assert_eq!(
+            explorer_tx_url("SN_MAIN", "0xabc").as_deref(),
+            Some("https://voyager.online/tx/0xabc")
+        );
+        assert_eq!(
+            explorer_tx_url("SN_SEPOLIA", "0xabc").as_deref(),
+            Some("https://sepolia.voyager.online/tx/0xabc")
+        );
+        assert!(explorer_tx_url("WP_MY_SLOT", "0xabc").is_none());
+    }
+
+    #[test]
+    fn config_override_takes_precedence() {
+        let mut config = Config::default();
+        config.contracts.insert(
+            "SN_SEPOLIA".to_string(),
+            BTreeMap::from([(MARKETPLACE.to_string(), "0x123".to_string())]),
+        );
+        assert_eq!(
+            resolve_contract(&config, "SN_SEPOLIA", MARKETPLACE).unwrap(),
+            Felt::from_hex_unchecked("0x123")
+        );
+        // Other chains still use the registry
+        assert_ne!(
+            resolve_contract(&config, "SN_MAIN", MARKETPLACE).unwrap(),
+            Felt::from_hex_unchecked("0x123")
+        );
+    }
+
+    #[test]
+    fn unknown_chain_requires_override() {
+        let mut config = Config::default();
+        let err = resolve_contract(&config, "WP_MY_SLOT", STARTERPACK).unwrap_err();
+        assert!(err.to_string().contains("contracts.WP_MY_SLOT.starterpack"));
+
+        config.contracts.insert(
+            "WP_MY_SLOT".to_string(),
+            BTreeMap::from([(STARTERPACK.to_string(), "0xabc".to_string())]),
+        );
+        assert!(resolve_contract(&config, "WP_MY_SLOT", STARTERPACK).is_ok());
+    }
+}
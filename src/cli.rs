@@ -0,0 +1,3223 @@
+//! The `controller` command line: clap definitions and dispatch to `commands`. The binary only
+//! parses arguments and asks the questions `run` hands back.
+
+use crate::config::Config;
+use crate::error::InputRequest;
+use crate::output::create_formatter;
+use crate::{api, chains, commands, error, meta, output, version};
+use clap::{Parser, Subcommand};
+
+#[derive(Parser)]
+#[command(name = "controller")]
+#[command(about = "CLI for Cartridge Controller session management", long_about = None)]
+#[command(version)]
+pub struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+
+    /// Output in JSON format (for LLMs)
+    #[arg(long, global = true, env = "CARTRIDGE_JSON_OUTPUT")]
+    json: bool,
+
+    /// Disable colored output
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Account label for multi-account support (e.g., 'player1')
+    #[arg(long, global = true)]
+    account: Option<String>,
+
+    /// Restrict session storage permissions (0700/0600) if they are too open
+    #[arg(long, global = true)]
+    fix_permissions: bool,
+
+    /// Write the structured JSON result to this file instead of the terminal
+    #[arg(long, global = true, value_name = "PATH")]
+    output_file: Option<std::path::PathBuf>,
+
+    /// With --output-file, also print the result to the terminal
+    #[arg(long, global = true, requires = "output_file")]
+    tee: bool,
+
+    /// Refuse any command that would sign or submit a transaction
+    #[arg(long, global = true)]
+    read_only: bool,
+
+    /// Application identifier sent as x-client-app on Cartridge requests (e.g. 'my-agent/1.2')
+    #[arg(long, global = true, value_parser = parse_client_app)]
+    client_app: Option<String>,
+
+    /// Print what a transacting command would do (chain, calls, policies, fees, files) without
+    /// submitting anything
+    #[arg(long, global = true)]
+    explain: bool,
+
+    /// Directory for config, default session storage and caches, isolating this invocation from
+    /// the user's default (e.g. one per service on a shared host)
+    #[arg(long, global = true, env = "CARTRIDGE_CONFIG_DIR", value_name = "DIR")]
+    config_dir: Option<String>,
+
+    /// Cartridge environment: switches keychain, API, presets and RPC endpoints together
+    #[arg(long, global = true, value_parser = ["production", "staging", "dev"])]
+    env: Option<String>,
+
+    /// Use cached presets instead of fetching them from GitHub (e.g. when it is unreachable or
+    /// rate-limited)
+    #[arg(long, global = true, env = "CARTRIDGE_OFFLINE")]
+    offline: bool,
+
+    /// Add a 'meta' block to JSON output: duration, RPC calls, API requests, retries, cache hits
+    /// and fee paid
+    #[arg(long, global = true, env = "CARTRIDGE_META")]
+    meta: bool,
+}
+
+fn parse_client_app(value: &str) -> Result<String, String> {
+    Config::validate_client_app(value).map(|()| value.to_string())
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Inspect and secure local session storage
+    Storage {
+        #[command(subcommand)]
+        command: StorageCommands,
+    },
+
+    /// Interactive first-time setup: pick a chain and preset, authorize, and verify
+    Init {
+        /// Chain to set up (e.g., 'SN_SEPOLIA'); asked when not set
+        #[arg(long)]
+        chain: Option<String>,
+
+        /// Preset to authorize; asked when not set
+        #[arg(long)]
+        preset: Option<String>,
+
+        /// Account label for the session, empty for the default storage; asked when neither
+        /// this nor --account is set
+        #[arg(long)]
+        label: Option<String>,
+
+        /// Session expiration duration (e.g., '7days'); asked when not set
+        #[arg(long)]
+        expires: Option<String>,
+
+        /// Replace an active session without asking
+        #[arg(long)]
+        overwrite: bool,
+    },
+
+    /// Show build metadata (commit, build date, account_sdk, supported RPC specs)
+    Version {
+        /// Probe the configured RPC, API and keychain for compatibility with this build
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Diagnose local config and storage; with --fix, apply the safe remediations
+    Doctor {
+        /// Create missing directories, restrict permissions, clear stale caches and refresh
+        /// stale username metadata
+        #[arg(long)]
+        fix: bool,
+    },
+
+    /// Work with Slot deployments
+    Slot {
+        #[command(subcommand)]
+        command: SlotCommands,
+    },
+
+    /// Serve session status, execute, call, balance and lookup to LLM agents over stdio
+    Serve {
+        /// Speak the Model Context Protocol (JSON-RPC over stdin/stdout)
+        #[arg(long, required = true)]
+        mcp: bool,
+    },
+
+    /// Manage account labels and the account contract
+    #[command(visible_alias = "account")]
+    Accounts {
+        #[command(subcommand)]
+        command: AccountsCommands,
+    },
+
+    /// Manage session lifecycle
+    Session {
+        #[command(subcommand)]
+        command: SessionCommands,
+    },
+
+    /// Execute a transaction using the active session
+    Execute {
+        /// Contract address (positional)
+        contract: Option<String>,
+
+        /// Entrypoint/function name, or raw selector as 'selector:0x...' (positional)
+        entrypoint: Option<String>,
+
+        /// Calldata as comma-separated hex values (positional)
+        calldata: Option<String>,
+
+        /// Read calls from JSON, YAML or TOML file
+        #[arg(long)]
+        file: Option<String>,
+
+        /// Encode human arguments (e.g. '0xabc,1.5strk', '{x: 1, y: 2}', 'Some(3)', or named JSON)
+        /// against the contract's ABI: a path to an ABI or class file, or 'fetch' to read the class
+        /// from the RPC. The entrypoint may carry the arguments, as in 'transfer(0xabc, 1.5strk)'
+        #[arg(long, value_name = "PATH|fetch", conflicts_with_all = ["file", "stdin", "intent"])]
+        abi: Option<String>,
+
+        /// Read newline-delimited JSON call batches from stdin and submit each, printing one JSON
+        /// event per line as batches are submitted, confirmed or fail
+        #[arg(long, conflicts_with_all = ["contract", "entrypoint", "calldata", "file", "intent", "once", "only_if"])]
+        stdin: bool,
+
+        /// Submit at this time instead of now (RFC 3339, e.g. '2025-01-01T00:00Z'); run
+        /// 'controller execute-due' to submit what is due
+        #[arg(long, conflicts_with_all = ["delay", "stdin", "intent", "once"])]
+        at: Option<String>,
+
+        /// Submit after this long instead of now (e.g. '2h', '30min')
+        #[arg(long = "in", value_name = "DURATION", conflicts_with_all = ["stdin", "intent", "once"])]
+        delay: Option<String>,
+
+        /// Batches from --stdin in flight at once
+        #[arg(long, default_value = "1", requires = "stdin")]
+        concurrency: usize,
+
+        /// Wait for transaction confirmation
+        #[arg(long)]
+        wait: bool,
+
+        /// Timeout in seconds when waiting
+        #[arg(long, default_value = "300")]
+        timeout: u64,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config and stored session RPC)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+
+        /// Run the calls through starknet_simulateTransactions after policy validation and report
+        /// the trace, state diff, fee, gas of each call and any revert reason instead of submitting
+        #[arg(long, conflicts_with_all = ["stdin", "at", "delay", "wait", "once"])]
+        simulate: bool,
+
+        /// Simulate against this confirmed block (number, hash or 'latest') to reproduce a result
+        #[arg(long, requires = "simulate", conflicts_with = "override_timestamp")]
+        at_block: Option<String>,
+
+        /// Simulate against the last block at or before this time (unix seconds or RFC 3339)
+        #[arg(long, value_name = "TIME", requires = "simulate")]
+        override_timestamp: Option<String>,
+
+        /// Force self-pay (don't use paymaster)
+        #[arg(long)]
+        no_paymaster: bool,
+
+        /// Don't wait for other submissions from this account (safe with the paymaster)
+        #[arg(long)]
+        no_queue: bool,
+
+        /// Tip in FRI per unit of L2 gas to prioritize inclusion during congestion (self-paid
+        /// only; default: config key 'tip', else none)
+        #[arg(long, requires = "no_paymaster")]
+        tip: Option<u64>,
+
+        /// Audit the transaction under this batch job ID, to follow a run of several executes
+        /// with 'controller batch status <id>'
+        #[arg(long, value_name = "JOB_ID")]
+        batch_id: Option<String>,
+
+        /// Submit a pending intent approved via 'approve-intent' (ID or path to intent file)
+        #[arg(long, conflicts_with_all = ["contract", "entrypoint", "calldata", "file"])]
+        intent: Option<String>,
+
+        /// If the RPC is on a different chain than the session, use the session's chain instead of failing
+        #[arg(long)]
+        switch_chain: bool,
+
+        /// If the session policies don't cover the calls, add the missing entrypoints, re-authorize
+        /// and retry (asks interactively when not set)
+        #[arg(long)]
+        auto_fix_policies: bool,
+
+        /// Refuse to submit a call file identical to one already submitted from this session
+        /// within the replay window (config key 'replay-window', default 1 hour)
+        #[arg(long, requires = "file")]
+        once: bool,
+
+        /// With --once, submit even if the same file was already submitted
+        #[arg(long, requires = "once")]
+        force: bool,
+
+        /// Fail (exit code 5) unless the confirmed receipt has an event matching
+        /// '[<contract>:]<EventName|0xselector>'. Comma-separated or repeated; needs --wait
+        #[arg(long, value_delimiter = ',', requires = "wait")]
+        require_events: Vec<String>,
+
+        /// After confirmation, keep polling until an event matching
+        /// '[<contract>:]<EventName|0xselector>' appears, in the receipt or a later block
+        /// (within --timeout). The JSON result includes the event; needs --wait
+        #[arg(
+            long,
+            value_name = "CONTRACT:EVENT",
+            requires = "wait",
+            conflicts_with = "stdin"
+        )]
+        wait_for_event: Option<String>,
+
+        /// Submit only if a view call returns the expected value, checked right before submitting
+        /// (e.g. '0xgame:get_state:()==0x2'). Fails with exit code 6 otherwise
+        #[arg(long)]
+        only_if: Option<String>,
+    },
+
+    /// Approve a pending intent created by an execute above an approval threshold
+    ApproveIntent {
+        /// Intent ID or path to intent file
+        intent: String,
+    },
+
+    /// Execute a read-only call to a contract
+    Call {
+        /// Contract address (positional)
+        contract: Option<String>,
+
+        /// Entrypoint/function name, or raw selector as 'selector:0x...' (positional)
+        entrypoint: Option<String>,
+
+        /// Calldata as comma-separated hex values (positional)
+        calldata: Option<String>,
+
+        /// Read calls from JSON, YAML or TOML file
+        #[arg(long)]
+        file: Option<String>,
+
+        /// Encode human arguments (e.g. '0xabc,1.5strk', '{x: 1, y: 2}', 'Some(3)', or named JSON)
+        /// against the contract's ABI: a path to an ABI or class file, or 'fetch' to read the class
+        /// from the RPC. The entrypoint may carry the arguments, as in 'transfer(0xabc, 1.5strk)'
+        #[arg(long, value_name = "PATH|fetch", conflicts_with = "file")]
+        abi: Option<String>,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+
+        /// Block ID to query (latest, pending, block number, or block hash)
+        #[arg(long, conflicts_with = "block_range")]
+        block_id: Option<String>,
+
+        /// Evaluate the call across blocks as 'start:end[:step]' and emit a time series
+        #[arg(long, conflicts_with = "file")]
+        block_range: Option<String>,
+
+        /// Calls from --file to keep in flight at once
+        #[arg(long, default_value = "1", requires = "file")]
+        concurrency: usize,
+
+        /// Cap on RPC requests for --file, e.g. '10/s' or '300/m'
+        #[arg(long, requires = "file")]
+        rate: Option<String>,
+    },
+
+    /// Show a contract's recent events as a decoded feed, and with --follow stream new ones
+    Tail {
+        /// Contract to show events of
+        #[arg(long)]
+        contract: String,
+
+        /// ABI or contract class file to decode events with (default: the contract's on-chain
+        /// ABI, falling back to names from the selector database)
+        #[arg(long)]
+        abi: Option<String>,
+
+        /// How many recent blocks to show events from
+        #[arg(long, default_value = "20")]
+        blocks: u64,
+
+        /// Keep printing events from new blocks until Ctrl-C
+        #[arg(long)]
+        follow: bool,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+    },
+
+    /// Query contract events with filters, following pagination, decoded when an ABI is given
+    Events {
+        /// Only events emitted by this contract
+        #[arg(long)]
+        contract: Option<String>,
+
+        /// Key filter: comma-separated positions, each '*' or '|'-separated alternatives; names
+        /// are hashed to selectors (e.g. 'Moved,*,0x123')
+        #[arg(long)]
+        keys: Option<String>,
+
+        /// First block to search (number, hash or 'latest')
+        #[arg(long, default_value = "latest")]
+        from_block: String,
+
+        /// Last block to search (number, hash or 'latest')
+        #[arg(long, default_value = "latest")]
+        to_block: String,
+
+        /// Events requested per page
+        #[arg(long, default_value = "100")]
+        chunk_size: u64,
+
+        /// Stop after this many events and print the token to continue from
+        #[arg(long)]
+        limit: Option<usize>,
+
+        /// Continue a previous query from its continuation_token
+        #[arg(long)]
+        continuation_token: Option<String>,
+
+        /// ABI to decode events with: a path to an ABI or class file, or 'fetch' to read the
+        /// class of --contract from the RPC
+        #[arg(long, value_name = "PATH|fetch")]
+        abi: Option<String>,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+    },
+
+    /// Record view call results and diff them, e.g. around a game action
+    Snapshot {
+        #[command(subcommand)]
+        command: SnapshotCommands,
+    },
+
+    /// Get transaction status and details
+    Transaction {
+        /// Transaction hash
+        hash: String,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+
+        /// Wait for transaction to be confirmed
+        #[arg(long)]
+        wait: bool,
+
+        /// Timeout in seconds when waiting
+        #[arg(long, default_value = "300")]
+        timeout: u64,
+    },
+
+    /// Get transaction receipt
+    Receipt {
+        /// Transaction hash
+        hash: String,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+
+        /// Wait for transaction to be confirmed
+        #[arg(long)]
+        wait: bool,
+
+        /// Timeout in seconds when waiting
+        #[arg(long, default_value = "300")]
+        timeout: u64,
+    },
+
+    /// Manage CLI configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+
+    /// Query ERC20 token balances for the active session account
+    Balance {
+        /// Token symbol (e.g., 'eth', 'strk'). If omitted, queries all known tokens
+        symbol: Option<String>,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+
+        /// Bypass the 30s balance cache and query the chain
+        #[arg(long)]
+        fresh: bool,
+
+        /// Keep refreshing every interval (e.g. '10s', '1min') until Ctrl-C; with --json,
+        /// emit one JSON line per changed balance
+        #[arg(long, value_name = "INTERVAL")]
+        watch: Option<String>,
+    },
+
+    /// Fund an account on a test chain (Sepolia faucet, or mint on Katana/SLOT)
+    Faucet {
+        /// Token symbol (e.g., STRK, ETH)
+        #[arg(long, default_value = "STRK")]
+        token: String,
+
+        /// Amount in whole tokens (e.g., '100' or '0.5')
+        #[arg(long, default_value = "100")]
+        amount: String,
+
+        /// Address to fund (defaults to current controller)
+        #[arg(long)]
+        recipient: Option<String>,
+
+        /// Chain ID (e.g., 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+    },
+
+    /// Approve a spender for a limited time, scheduling a revoke once the window passes
+    ApproveFor {
+        /// Token symbol (e.g., STRK, ETH)
+        token: String,
+
+        /// Address allowed to spend the tokens
+        spender: String,
+
+        /// Amount in whole tokens (e.g., '100' or '0.5')
+        amount: String,
+
+        /// How long the allowance should last (e.g., '30min', '1hr', '1day')
+        #[arg(long)]
+        revoke_after: String,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+    },
+
+    /// Submit the revokes scheduled by approve-for whose window has passed
+    RevokeDue,
+
+    /// Submit the executions scheduled with 'execute --at/--in' whose time has come
+    ExecuteDue,
+
+    /// Send tokens to an address or username
+    Transfer {
+        /// Token symbol (e.g., STRK, ETH) or contract address
+        token: String,
+
+        /// Recipient address or controller username
+        recipient: String,
+
+        /// Amount in whole tokens (e.g., '100' or '0.5'), converted with the token's decimals
+        amount: String,
+
+        /// Wait for transaction confirmation
+        #[arg(long)]
+        wait: bool,
+
+        /// Timeout in seconds when waiting
+        #[arg(long, default_value = "300")]
+        timeout: u64,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+
+        /// Force self-pay (don't use paymaster)
+        #[arg(long)]
+        no_paymaster: bool,
+    },
+
+    /// Send a token to every recipient in a CSV, chunked into multicalls
+    TransferBatch {
+        /// CSV of 'recipient,amount' rows (recipient is an address or username)
+        #[arg(long)]
+        file: String,
+
+        /// Token symbol (e.g., STRK, ETH)
+        #[arg(long)]
+        token: String,
+
+        /// Maximum transfers per transaction (capped by the calldata size limit)
+        #[arg(long)]
+        chunk_size: Option<usize>,
+
+        /// Where to write the results CSV (defaults to <file>.results.csv)
+        #[arg(long)]
+        results: Option<String>,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+    },
+
+    /// Display the username associated with the active session account
+    Username,
+
+    /// Print the active session's address, chain and username as shell exports
+    Env,
+
+    /// Look up controller addresses by usernames or usernames by addresses
+    Lookup {
+        /// Comma-separated usernames to resolve (e.g., 'shinobi,sensei')
+        #[arg(long)]
+        usernames: Option<String>,
+
+        /// Comma-separated addresses to resolve (e.g., '0x123...,0x456...')
+        #[arg(long)]
+        addresses: Option<String>,
+
+        /// Ignore cached mappings and refetch from the API
+        #[arg(long, conflicts_with = "cached_only")]
+        refresh: bool,
+
+        /// Resolve from the local cache only, without network access
+        #[arg(long)]
+        cached_only: bool,
+    },
+
+    /// Compute the selector of one or more entrypoint names
+    SelectorOf {
+        /// Entrypoint names (e.g., 'transfer')
+        #[arg(required = true)]
+        names: Vec<String>,
+    },
+
+    /// Sign SNIP-12 typed data with the session key, within the session's message policies
+    SignMessage {
+        /// Typed data JSON file (types, primaryType, domain and message)
+        #[arg(long)]
+        file: String,
+    },
+
+    /// Check a SNIP-12 signature with the account's is_valid_signature
+    VerifyMessage {
+        /// Typed data JSON file that was signed
+        #[arg(long)]
+        file: String,
+
+        /// Signature as printed by sign-message: a JSON array or comma-separated felts
+        #[arg(long)]
+        signature: String,
+
+        /// Signer address (defaults to the session's controller)
+        #[arg(long)]
+        address: Option<String>,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+    },
+
+    /// Convert values between hex, decimal, felt, Cairo short string and u256 low/high halves
+    Convert {
+        /// Values to convert (a u256 as 'low,high')
+        #[arg(required = true)]
+        values: Vec<String>,
+
+        /// Input format; guessed when omitted ('low,high' pairs are u256, '0x' hex, digits
+        /// decimal, anything else a short string)
+        #[arg(long, value_parser = ["felt", "hex", "dec", "shortstr", "u256"])]
+        from: Option<String>,
+
+        /// Output only this format instead of every reading
+        #[arg(long, value_parser = ["felt", "hex", "dec", "shortstr", "u256"])]
+        to: Option<String>,
+    },
+
+    /// Manage the local database naming selectors in receipts and transactions
+    Selectors {
+        #[command(subcommand)]
+        command: SelectorsCommands,
+    },
+
+    /// Decode raw felts (calldata, event keys/data) for debugging
+    Decode {
+        #[command(subcommand)]
+        command: DecodeCommands,
+    },
+
+    /// Run raw GraphQL against the Cartridge API
+    Api {
+        #[command(subcommand)]
+        command: ApiCommands,
+    },
+
+    /// Discover games with Cartridge presets
+    Games {
+        #[command(subcommand)]
+        command: GamesCommands,
+    },
+
+    /// List, inspect and author presets of the cartridge-gg/presets repository
+    #[command(visible_alias = "preset")]
+    Presets {
+        #[command(subcommand)]
+        command: PresetsCommands,
+    },
+
+    /// Quote and purchase starterpacks
+    Starterpack {
+        #[command(subcommand)]
+        command: StarterpackCommands,
+    },
+
+    /// Buy and interact with the Arcade marketplace
+    Marketplace {
+        #[command(subcommand)]
+        command: MarketplaceCommands,
+    },
+
+    /// Track the transactions of a batch job
+    Batch {
+        #[command(subcommand)]
+        command: BatchCommands,
+    },
+
+    /// Track gas usage of game actions across runs to catch cost regressions
+    Bench {
+        #[command(subcommand)]
+        command: BenchCommands,
+    },
+
+    /// Queue executions with priorities and dependencies, submitted with retries by 'queue run'
+    Queue {
+        #[command(subcommand)]
+        command: QueueCommands,
+    },
+
+    /// Lint policy files and compare them with the authorized session
+    Policy {
+        #[command(subcommand)]
+        command: PolicyCommands,
+    },
+
+    /// Inspect the local log of transactions submitted by execute, purchase and buy
+    Audit {
+        #[command(subcommand)]
+        command: AuditCommands,
+    },
+
+    /// Deposit into and withdraw from ERC-4626 vaults, with share/asset previews
+    Vault {
+        #[command(subcommand)]
+        command: VaultCommands,
+    },
+
+    /// List the NFTs (ERC721/ERC1155) the account owns, grouped by collection, from Torii
+    Inventory {
+        /// Only show this collection (contract address)
+        #[arg(long)]
+        collection: Option<String>,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+
+        /// Torii indexer URL (defaults to the chain profile's torii-url)
+        #[arg(long)]
+        torii_url: Option<String>,
+    },
+
+    /// Live terminal dashboard of session status, balances, pending work and recent history
+    Dashboard {
+        /// Show every account (the default storage and all labels) instead of the selected one
+        #[arg(long)]
+        all: bool,
+
+        /// How often to refresh (e.g. '5s', '1min')
+        #[arg(long, default_value = "5s", value_name = "INTERVAL")]
+        interval: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum VaultCommands {
+    /// Show the account's shares in a vault and what they are worth
+    Balance {
+        /// Vault address
+        vault: String,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+    },
+
+    /// Approve and deposit an amount of the vault's asset in one multicall
+    Deposit {
+        /// Vault address
+        vault: String,
+
+        /// Amount of the underlying asset in token units (e.g., '100' or '0.5')
+        amount: String,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+
+        /// Self-pay the transaction instead of using the paymaster
+        #[arg(long)]
+        no_paymaster: bool,
+    },
+
+    /// Withdraw an amount of the vault's asset, or redeem shares with --shares
+    Withdraw {
+        /// Vault address
+        vault: String,
+
+        /// Amount of the underlying asset in token units, or 'max' to redeem every share
+        amount: String,
+
+        /// Read the amount as shares and redeem them
+        #[arg(long)]
+        shares: bool,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+
+        /// Self-pay the transaction instead of using the paymaster
+        #[arg(long)]
+        no_paymaster: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum QueueCommands {
+    /// Add calls to the account's queue
+    Add {
+        /// Contract address (positional)
+        contract: Option<String>,
+
+        /// Entrypoint/function name, or raw selector as 'selector:0x...' (positional)
+        entrypoint: Option<String>,
+
+        /// Calldata as comma-separated hex values (positional)
+        calldata: Option<String>,
+
+        /// Read calls from JSON, YAML or TOML file
+        #[arg(long)]
+        file: Option<String>,
+
+        /// ID to refer to this execution by in --after (default: generated)
+        #[arg(long)]
+        id: Option<String>,
+
+        /// Higher priorities run first; ties run in the order they were added
+        #[arg(long, default_value = "0", allow_negative_numbers = true)]
+        priority: i64,
+
+        /// Run only once these queued executions are confirmed (comma-separated or repeated)
+        #[arg(long, value_delimiter = ',')]
+        after: Vec<String>,
+
+        /// Attempts before the execution is marked failed
+        #[arg(long, default_value = "3")]
+        max_attempts: u32,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+
+        /// Self-pay the transaction instead of using the paymaster
+        #[arg(long)]
+        no_paymaster: bool,
+    },
+
+    /// Show pending executions in run order, then finished ones
+    List,
+
+    /// Submit runnable executions, highest priority first, waiting for each to confirm
+    Run {
+        /// Stop after this many executions
+        #[arg(long)]
+        max: Option<usize>,
+    },
+
+    /// Re-check recorded transactions (queue and audit log) against the chain, flagging
+    /// reverted or reorged ones
+    Verify,
+}
+
+#[derive(Subcommand)]
+enum PolicyCommands {
+    /// Check a policy file for bad addresses, duplicate entrypoints and invalid names
+    Validate {
+        /// Policy file (JSON, YAML or TOML)
+        #[arg(long)]
+        file: String,
+
+        /// Also check that each contract is deployed on the session's chain and has the
+        /// entrypoints
+        #[arg(long)]
+        against_session: bool,
+    },
+
+    /// Show the contracts and methods a policy file or preset adds to or removes from the
+    /// authorized session
+    Diff {
+        /// Policy file (JSON, YAML or TOML)
+        #[arg(long, required_unless_present = "preset")]
+        file: Option<String>,
+
+        /// Preset name, resolved for the session's chain. Repeat to merge several presets
+        #[arg(long)]
+        preset: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuditCommands {
+    /// List audited submissions, newest first (all accounts unless --account is given)
+    Show {
+        /// Only entries on this chain (e.g., 'SN_MAIN')
+        #[arg(long)]
+        chain_id: Option<String>,
+
+        /// Only entries with this result
+        #[arg(long, value_parser = ["submitted", "failed", "confirmed", "reverted"])]
+        result: Option<String>,
+
+        /// Only entries for this transaction hash
+        #[arg(long)]
+        tx_hash: Option<String>,
+
+        /// Maximum number of entries
+        #[arg(long, default_value = "50")]
+        limit: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum BenchCommands {
+    /// Measure a call file's gas, record it and report the change since the last run
+    Action {
+        /// Calls to measure (JSON, YAML or TOML call file)
+        #[arg(long)]
+        file: String,
+
+        /// Name the results are recorded under (defaults to the file name)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Runs to take the median of
+        #[arg(long, default_value = "5")]
+        runs: usize,
+
+        /// Estimate through the session instead of submitting each run
+        #[arg(long)]
+        simulate: bool,
+
+        /// RPC URL to use (overrides the session's)
+        #[arg(long)]
+        rpc_url: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Set a configuration value, or merge a JSON object into the config with --json
+    Set {
+        /// Config key (e.g., chain-id, rpc-url)
+        #[arg(required_unless_present = "json")]
+        key: Option<String>,
+        /// Value to set
+        #[arg(required_unless_present = "json")]
+        value: Option<String>,
+        /// JSON object merged into the config file: nested objects merge, null removes a
+        /// field (e.g. '{"chains":{"WP_MY_SLOT":{"rpc_url":"https://..."}}}')
+        #[arg(long, conflicts_with_all = ["key", "value"])]
+        json: Option<String>,
+    },
+    /// Get a configuration value, by key or by dotted path in the config file
+    Get {
+        /// Config key (e.g., chain-id, rpc-url) or path (e.g., session.rpc_url)
+        key: String,
+    },
+    /// List all configuration values
+    List,
+    /// Show recorded config changes, numbered oldest first, with secrets redacted
+    History,
+    /// Restore the config to how it was before change <N> from 'config history'
+    Rollback {
+        /// Change number from 'config history'
+        n: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum SelectorsCommands {
+    /// Add entrypoint or event names (e.g., 'move' 'Moved')
+    Add {
+        #[arg(required = true)]
+        names: Vec<String>,
+    },
+    /// Import every function, event and entrypoint name from an ABI, contract class, policy
+    /// file or preset config
+    Import {
+        /// JSON file to import from
+        file: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum DecodeCommands {
+    /// Show possible interpretations of calldata felts, or decode them with an ABI
+    Calldata {
+        /// Felts in hex or decimal, as separate arguments or comma-separated
+        #[arg(required = true)]
+        values: Vec<String>,
+
+        /// ABI JSON file (bare ABI array or contract class) to decode named parameters
+        #[arg(long, requires = "function")]
+        abi: Option<String>,
+
+        /// Function whose inputs the calldata encodes (used with --abi)
+        #[arg(long, requires = "abi")]
+        function: Option<String>,
+    },
+    /// Show possible interpretations of an event's keys and data, or decode them with an ABI
+    Event {
+        /// Comma-separated event keys (keys[0] is the event selector)
+        keys: String,
+
+        /// Comma-separated event data
+        data: String,
+
+        /// ABI JSON file (bare ABI array or contract class) to match and decode the event
+        #[arg(long)]
+        abi: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ApiCommands {
+    /// Execute a GraphQL query against the configured api-url
+    Query {
+        /// Path to a file containing the GraphQL document
+        #[arg(long, conflicts_with = "query", required_unless_present = "query")]
+        file: Option<String>,
+
+        /// Inline GraphQL document
+        #[arg(long)]
+        query: Option<String>,
+
+        /// Query variables as a JSON object, or @path to read them from a file
+        #[arg(long)]
+        vars: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum GamesCommands {
+    /// List games with a published preset
+    List,
+
+    /// Show a game's supported chains, contracts and entrypoints
+    Info {
+        /// Preset name (e.g., 'loot-survivor')
+        preset: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum SlotCommands {
+    /// Discover a Slot project's Katana RPC and Torii URL and save them as a chain profile
+    Link {
+        /// Slot project name
+        project: String,
+
+        /// Chain profile name to use with --chain-id (defaults to the chain ID Katana reports)
+        #[arg(long)]
+        name: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SnapshotCommands {
+    /// Run the view calls in a file and write their results
+    Take {
+        /// Calls file (JSON, YAML or TOML), in the same format as 'call --file'
+        #[arg(long)]
+        calls: String,
+
+        /// Where to write the snapshot
+        #[arg(long)]
+        out: String,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+
+        /// Block ID to query (latest, pending, block number, or block hash)
+        #[arg(long)]
+        block_id: Option<String>,
+    },
+
+    /// Show which call results changed between two snapshots
+    Diff {
+        /// Snapshot taken before the action
+        before: String,
+
+        /// Snapshot taken after the action
+        after: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum PresetsCommands {
+    /// List the published presets
+    List,
+
+    /// Show the contracts and methods a preset authorizes, with selectors and risk
+    Show {
+        /// Preset name (e.g., 'loot-survivor')
+        name: String,
+
+        /// Only this chain (e.g., 'SN_MAIN'); default: every chain the preset supports
+        #[arg(long, visible_alias = "chain")]
+        chain_id: Option<String>,
+    },
+
+    /// Download the preset index and presets again, replacing the local cache
+    Refresh {
+        /// Presets to refresh (default: every cached preset)
+        names: Vec<String>,
+    },
+
+    /// Generate configs/<name>/config.json in the presets repo format from a local policy file
+    Scaffold {
+        /// Policy file (JSON, YAML or TOML) to convert
+        #[arg(long)]
+        from_policy: String,
+
+        /// Preset name, used as the directory name (e.g., 'my-game')
+        #[arg(long)]
+        name: String,
+
+        /// Chains the policies apply to. Repeat for several chains
+        #[arg(long = "chain-id", default_value = "SN_MAIN")]
+        chain_ids: Vec<String>,
+
+        /// Website origin allowed to use the preset (e.g., 'my-game.xyz'). Repeat for several
+        #[arg(long)]
+        origin: Vec<String>,
+
+        /// Directory to create configs/<name>/ in (a presets repo checkout)
+        #[arg(long, default_value = ".")]
+        out: std::path::PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum StarterpackCommands {
+    /// Get a quote for a starterpack (payment token and amount)
+    Quote {
+        /// Starterpack ID
+        id: String,
+
+        /// Quantity to purchase
+        #[arg(long, default_value = "1")]
+        quantity: u32,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+    },
+
+    /// Get info for a starterpack
+    Info {
+        /// Starterpack ID
+        id: String,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+    },
+
+    /// Purchase a starterpack
+    Purchase {
+        /// Starterpack ID
+        id: String,
+
+        /// Recipient address (defaults to current controller)
+        #[arg(long)]
+        recipient: Option<String>,
+
+        /// Quantity to purchase
+        #[arg(long, default_value = "1")]
+        quantity: u32,
+
+        /// Open a UI for purchase (default)
+        #[arg(long, group = "mode")]
+        ui: bool,
+
+        /// Execute purchase directly from Controller wallet
+        #[arg(long, group = "mode")]
+        direct: bool,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+
+        /// Wait for transaction confirmation (direct mode only)
+        #[arg(long)]
+        wait: bool,
+
+        /// Timeout in seconds when waiting (direct mode only)
+        #[arg(long, default_value = "300")]
+        timeout: u64,
+
+        /// Force self-pay, don't use paymaster (direct mode only)
+        #[arg(long)]
+        no_paymaster: bool,
+    },
+
+    /// Buy a starterpack for another player, by username or address
+    Gift {
+        /// Starterpack ID
+        id: String,
+
+        /// Recipient username or address
+        recipient: String,
+
+        /// Quantity to purchase
+        #[arg(long, default_value = "1")]
+        quantity: u32,
+
+        /// Skip the confirmation prompt
+        #[arg(long)]
+        yes: bool,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+
+        /// Wait for transaction confirmation
+        #[arg(long)]
+        wait: bool,
+
+        /// Timeout in seconds when waiting
+        #[arg(long, default_value = "300")]
+        timeout: u64,
+
+        /// Force self-pay, don't use paymaster
+        #[arg(long)]
+        no_paymaster: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum MarketplaceCommands {
+    /// Query order validity and details
+    Info {
+        /// Order ID
+        #[arg(long)]
+        order_id: u32,
+
+        /// NFT collection address
+        #[arg(long)]
+        collection: String,
+
+        /// Token ID in the collection
+        #[arg(long)]
+        token_id: String,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+    },
+
+    /// Purchase an NFT from a marketplace listing
+    Buy {
+        /// Order ID to purchase
+        #[arg(long)]
+        order_id: u32,
+
+        /// NFT collection address
+        #[arg(long)]
+        collection: String,
+
+        /// Token ID in the collection
+        #[arg(long)]
+        token_id: String,
+
+        /// Asset ID (for ERC1155, defaults to 0)
+        #[arg(long)]
+        asset_id: Option<String>,
+
+        /// Quantity to purchase
+        #[arg(long, default_value = "1")]
+        quantity: u128,
+
+        /// Skip paying royalties
+        #[arg(long)]
+        no_royalties: bool,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+
+        /// Wait for transaction confirmation
+        #[arg(long)]
+        wait: bool,
+
+        /// Timeout in seconds when waiting
+        #[arg(long, default_value = "300")]
+        timeout: u64,
+
+        /// Force self-pay, don't use paymaster
+        #[arg(long)]
+        no_paymaster: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum BatchCommands {
+    /// Show the state of each transaction of a batch job and success/failure counts
+    Status {
+        /// Job ID from execute --batch-id or printed by transfer-batch
+        job_id: String,
+
+        /// Chain ID to fetch receipts from (defaults to the chain the job ran on)
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides the job's chain)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+
+        /// Times to retry fetching receipts that are still missing
+        #[arg(long, default_value = "5")]
+        retries: u32,
+    },
+}
+
+#[derive(Subcommand)]
+enum StorageCommands {
+    /// Set 0700 on directories and 0600 on files under the storage path
+    Harden,
+}
+
+#[derive(Subcommand)]
+enum AccountsCommands {
+    /// Copy an account's policies and RPC binding to a new label and authorize it (session keys
+    /// are never copied)
+    Clone {
+        /// Account label to copy from
+        src: String,
+
+        /// Account label to create
+        dst: String,
+
+        /// Copy only the policies; the new session uses the configured RPC
+        #[arg(long)]
+        policies_only: bool,
+
+        /// Session expiration duration for the new label (e.g., '1hr', '7days')
+        #[arg(long, default_value = "7days")]
+        expires: String,
+
+        /// Replace an active session of the new label without asking
+        #[arg(long)]
+        overwrite: bool,
+    },
+
+    /// Upgrade the account contract to a new controller class
+    Upgrade {
+        /// Class hash to upgrade to
+        #[arg(long)]
+        to: String,
+
+        /// Wait for confirmation and check the new class on-chain
+        #[arg(long)]
+        wait: bool,
+
+        /// Timeout in seconds when waiting
+        #[arg(long, default_value = "300")]
+        timeout: u64,
+
+        /// Skip the confirmation prompt
+        #[arg(long)]
+        yes: bool,
+
+        /// If the session isn't allowed to call 'upgrade', add it to the policies and
+        /// re-authorize (asks interactively when not set)
+        #[arg(long)]
+        auto_fix_policies: bool,
+    },
+}
+
+/// Commands that can print their plan instead of running under `--explain`, by command path.
+/// Transacting commands not listed here are refused so `--explain` never submits anything.
+const EXPLAIN_COMMANDS: &[&str] = &[
+    "execute",
+    "approve-for",
+    "revoke-due",
+    "transfer",
+    "transfer-batch",
+    "vault deposit",
+    "vault withdraw",
+];
+
+/// The subcommand names only (e.g. 'session auth'), never arguments
+pub fn command_path(matches: &clap::ArgMatches) -> String {
+    let mut names = Vec::new();
+    let mut current = matches;
+    while let Some((name, sub)) = current.subcommand() {
+        names.push(name);
+        current = sub;
+    }
+    names.join(" ")
+}
+
+impl Commands {
+    /// Whether the command signs or submits a transaction (refused under `--read-only`).
+    fn submits_transactions(&self) -> bool {
+        match self {
+            Commands::Execute { .. }
+            | Commands::ApproveIntent { .. }
+            | Commands::Faucet { .. }
+            | Commands::ApproveFor { .. }
+            | Commands::RevokeDue
+            | Commands::ExecuteDue
+            | Commands::Transfer { .. }
+            | Commands::TransferBatch { .. }
+            | Commands::Queue {
+                command: QueueCommands::Run { .. },
+            }
+            | Commands::Vault {
+                command: VaultCommands::Deposit { .. } | VaultCommands::Withdraw { .. },
+            }
+            | Commands::Session {
+                command: SessionCommands::Revoke { .. },
+            }
+            | Commands::Accounts {
+                command: AccountsCommands::Upgrade { .. },
+            }
+            | Commands::Starterpack {
+                command:
+                    StarterpackCommands::Purchase { direct: true, .. }
+                    | StarterpackCommands::Gift { .. },
+            }
+            | Commands::SignMessage { .. }
+            | Commands::Bench {
+                command:
+                    BenchCommands::Action {
+                        simulate: false, ..
+                    },
+            }
+            | Commands::Marketplace {
+                command: MarketplaceCommands::Buy { .. },
+            } => true,
+            Commands::Init { .. }
+            | Commands::Storage { .. }
+            | Commands::Version { .. }
+            | Commands::Doctor { .. }
+            | Commands::Slot { .. }
+            | Commands::Serve { .. }
+            | Commands::Accounts { .. }
+            | Commands::Session { .. }
+            | Commands::Call { .. }
+            | Commands::Snapshot { .. }
+            | Commands::Transaction { .. }
+            | Commands::Receipt { .. }
+            | Commands::Balance { .. }
+            | Commands::Username
+            | Commands::Env
+            | Commands::Config { .. }
+            | Commands::Lookup { .. }
+            | Commands::SelectorOf { .. }
+            | Commands::Convert { .. }
+            | Commands::Events { .. }
+            | Commands::Tail { .. }
+            | Commands::VerifyMessage { .. }
+            | Commands::Selectors { .. }
+            | Commands::Decode { .. }
+            | Commands::Api { .. }
+            | Commands::Games { .. }
+            | Commands::Presets { .. }
+            | Commands::Starterpack { .. }
+            | Commands::Bench { .. }
+            | Commands::Queue { .. }
+            | Commands::Vault { .. }
+            | Commands::Dashboard { .. }
+            | Commands::Inventory { .. }
+            | Commands::Audit { .. }
+            | Commands::Policy { .. }
+            | Commands::Marketplace { .. }
+            | Commands::Batch { .. } => false,
+        }
+    }
+
+    /// Whether the command loads the stored session signer.
+    fn reads_credentials(&self) -> bool {
+        match self {
+            Commands::Execute { .. }
+            | Commands::Balance { .. }
+            | Commands::Faucet { .. }
+            | Commands::ApproveFor { .. }
+            | Commands::RevokeDue
+            | Commands::ExecuteDue
+            | Commands::Transfer { .. }
+            | Commands::TransferBatch { .. }
+            | Commands::Queue {
+                command: QueueCommands::Run { .. },
+            }
+            | Commands::Vault { .. }
+            | Commands::Dashboard { all: false, .. }
+            | Commands::Inventory { .. }
+            | Commands::Policy {
+                command:
+                    PolicyCommands::Validate {
+                        against_session: true,
+                        ..
+                    }
+                    | PolicyCommands::Diff { .. },
+            }
+            | Commands::Username
+            | Commands::Env
+            | Commands::Accounts {
+                command: AccountsCommands::Upgrade { .. },
+            }
+            | Commands::Session {
+                command:
+                    SessionCommands::Status
+                    | SessionCommands::List { .. }
+                    | SessionCommands::Revoke { .. }
+                    | SessionCommands::Export { .. },
+            }
+            | Commands::Starterpack {
+                command: StarterpackCommands::Purchase { .. } | StarterpackCommands::Gift { .. },
+            }
+            | Commands::SignMessage { .. }
+            | Commands::Bench { .. }
+            | Commands::Marketplace {
+                command: MarketplaceCommands::Buy { .. },
+            } => true,
+            Commands::Init { .. }
+            | Commands::Storage { .. }
+            | Commands::Version { .. }
+            | Commands::Doctor { .. }
+            | Commands::Slot { .. }
+            | Commands::Serve { .. }
+            | Commands::Accounts { .. }
+            | Commands::Session { .. }
+            | Commands::Call { .. }
+            | Commands::Snapshot { .. }
+            | Commands::Transaction { .. }
+            | Commands::Receipt { .. }
+            | Commands::Config { .. }
+            | Commands::Lookup { .. }
+            | Commands::SelectorOf { .. }
+            | Commands::Convert { .. }
+            | Commands::Events { .. }
+            | Commands::Tail { .. }
+            | Commands::VerifyMessage { .. }
+            | Commands::Selectors { .. }
+            | Commands::ApproveIntent { .. }
+            | Commands::Decode { .. }
+            | Commands::Api { .. }
+            | Commands::Games { .. }
+            | Commands::Presets { .. }
+            | Commands::Starterpack { .. }
+            | Commands::Queue { .. }
+            | Commands::Dashboard { .. }
+            | Commands::Audit { .. }
+            | Commands::Policy { .. }
+            | Commands::Marketplace { .. }
+            | Commands::Batch { .. } => false,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum SessionCommands {
+    /// Generate keypair and authorize a new session
+    Auth {
+        /// Preset name (e.g., 'loot-survivor'). Repeat to merge several presets
+        #[arg(long)]
+        preset: Vec<String>,
+
+        /// Path to local policy file (JSON, YAML or TOML), merged with any presets
+        #[arg(long)]
+        file: Option<String>,
+
+        /// Built-in policy template ('erc20-transfers', 'nft-trading', 'dojo-world-default'),
+        /// applied to --contracts and merged with any presets/file
+        #[arg(long, requires = "contracts")]
+        template: Option<String>,
+
+        /// Comma-separated contract addresses the template is applied to
+        #[arg(long, value_delimiter = ',', requires = "template")]
+        contracts: Vec<String>,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+
+        /// Overwrite existing session without confirmation
+        #[arg(long)]
+        overwrite: bool,
+
+        /// Print the resolved policies (sorted as they will be hashed) and ask for confirmation
+        /// before opening the browser
+        #[arg(long)]
+        print_policies: bool,
+
+        /// With --print-policies, authorize without asking
+        #[arg(long, requires = "print_policies")]
+        yes: bool,
+
+        /// Also draw the authorization URL as a QR code, to scan with a phone. On by default
+        /// when printing to a terminal without --json
+        #[arg(long)]
+        qr: bool,
+
+        /// Don't try to open a browser: print the URL with a short code to check on another
+        /// device, and report progress while waiting (for SSH sessions and containers). Implied
+        /// over SSH, inside Docker, and on Linux without a display
+        #[arg(long, env = "CARTRIDGE_HEADLESS")]
+        headless: bool,
+
+        /// POST progress events (url_generated, authorized, timeout) as JSON to this URL, e.g. to
+        /// track which accounts still wait for a human to open their link
+        #[arg(long, env = "CARTRIDGE_NOTIFY_URL", value_name = "URL")]
+        notify_url: Option<String>,
+
+        /// Session expiration duration (e.g., '1min', '1hr', '1day', '7days', '1week', '1year')
+        /// Defaults to 7days
+        #[arg(long, default_value = "7days")]
+        expires: String,
+    },
+
+    /// Keep the current session if it matches the policies and chain and lives long enough,
+    /// otherwise authorize a new one (idempotent, for bootstrap scripts)
+    Ensure {
+        /// Preset name (e.g., 'loot-survivor'). Repeat to merge several presets
+        #[arg(long)]
+        preset: Vec<String>,
+
+        /// Path to local policy file (JSON, YAML or TOML), merged with any presets
+        #[arg(long)]
+        file: Option<String>,
+
+        /// Built-in policy template, applied to --contracts and merged with any presets/file
+        #[arg(long, requires = "contracts")]
+        template: Option<String>,
+
+        /// Comma-separated contract addresses the template is applied to
+        #[arg(long, value_delimiter = ',', requires = "template")]
+        contracts: Vec<String>,
+
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - auto-selects RPC URL
+        #[arg(long, conflicts_with = "rpc_url")]
+        chain_id: Option<String>,
+
+        /// RPC URL to use (overrides config)
+        #[arg(long, conflicts_with = "chain_id")]
+        rpc_url: Option<String>,
+
+        /// Minimum remaining lifetime for the current session to be kept (e.g., '30min', '1h',
+        /// '1day')
+        #[arg(long, default_value = "1h")]
+        min_ttl: String,
+
+        /// Also draw the authorization URL as a QR code, to scan with a phone
+        #[arg(long)]
+        qr: bool,
+
+        /// Don't try to open a browser: print the URL with a short code to check on another
+        /// device, and report progress while waiting (for SSH sessions and containers). Implied
+        /// over SSH, inside Docker, and on Linux without a display
+        #[arg(long, env = "CARTRIDGE_HEADLESS")]
+        headless: bool,
+
+        /// POST progress events of a new authorization as JSON to this URL
+        #[arg(long, env = "CARTRIDGE_NOTIFY_URL", value_name = "URL")]
+        notify_url: Option<String>,
+
+        /// Expiration of a newly authorized session (e.g., '1hr', '7days')
+        #[arg(long, default_value = "7days")]
+        expires: String,
+    },
+
+    /// Display current session status and information
+    Status,
+
+    /// List sessions
+    List {
+        /// Chain ID (e.g., 'SN_MAIN' or 'SN_SEPOLIA') - defaults to session chain
+        #[arg(long)]
+        chain_id: Option<String>,
+
+        /// Number of sessions per page
+        #[arg(long, default_value = "10")]
+        limit: u32,
+
+        /// Cursor to continue from (the `end_cursor` of the previous page)
+        #[arg(long)]
+        after: Option<String>,
+    },
+
+    /// Block until the session is close to expiry, then exit with code 3 (expiring) or 4 (expired)
+    WaitExpiry {
+        /// Seconds before expiry at which to return
+        #[arg(long, default_value = "3600")]
+        within: u64,
+
+        /// Give up after this many seconds and exit 0 if the session is still valid
+        #[arg(long)]
+        timeout: Option<u64>,
+    },
+
+    /// Watch the audit log and alert when submissions deviate from expectations, for unattended
+    /// agents. Runs until Ctrl-C
+    Guard {
+        /// Alert when the account submits more transactions than this within an hour
+        #[arg(long = "max-tx-per-hour", value_name = "N")]
+        max_tx_per_hour: Option<usize>,
+
+        /// Alert when the account makes more token transfers than this within an hour
+        #[arg(long, value_name = "N")]
+        max_transfers_per_hour: Option<usize>,
+
+        /// Alert on calls to a contract the account has never called before
+        #[arg(long)]
+        new_contracts: bool,
+
+        /// On the first alert, also turn on read-only mode so further transactions are refused
+        #[arg(long)]
+        block: bool,
+
+        /// Seconds between checks of the audit log
+        #[arg(long, default_value_t = 5, value_name = "SECONDS")]
+        interval: u64,
+    },
+
+    /// Revoke a session onchain (the active one by default). Revoking the active session also
+    /// removes it locally
+    Revoke {
+        /// Hash of the session to revoke (the session struct's hash the controller registered),
+        /// when it isn't the active one
+        #[arg(long)]
+        session_hash: Option<String>,
+    },
+
+    /// Clear all stored session data
+    Clear {
+        /// Skip confirmation prompt
+        #[arg(long)]
+        yes: bool,
+    },
+
+    /// Print the stored session (credentials, metadata, policies, chain and RPC) as one blob
+    /// for 'session import' on another machine
+    Export {
+        /// Encrypt the blob with this passphrase (AES-256-GCM, scrypt-derived key)
+        #[arg(long, env = "CARTRIDGE_SESSION_PASSPHRASE", hide_env_values = true)]
+        encrypt: Option<String>,
+
+        /// Write the blob to this file (mode 0600) instead of stdout
+        #[arg(long)]
+        out: Option<String>,
+    },
+
+    /// Store a session exported with 'session export'
+    Import {
+        /// The exported blob (default: read from --file or stdin)
+        blob: Option<String>,
+
+        /// Read the blob from a file
+        #[arg(long, conflicts_with = "blob")]
+        file: Option<String>,
+
+        /// Passphrase the blob was encrypted with
+        #[arg(long, env = "CARTRIDGE_SESSION_PASSPHRASE", hide_env_values = true)]
+        passphrase: Option<String>,
+
+        /// Replace a session that is already stored
+        #[arg(long)]
+        overwrite: bool,
+    },
+}
+
+/// Run one parsed invocation and return its exit code. With `can_prompt` (stdin is a terminal),
+/// a question the command needs answered comes back as `Err` instead, for the caller to ask and
+/// run the command again with the answer.
+pub async fn run(
+    cli: Cli,
+    command_path: &str,
+    can_prompt: bool,
+) -> std::result::Result<i32, InputRequest> {
+    if cli.meta {
+        meta::enable();
+    }
+
+    if let Some(dir) = &cli.config_dir {
+        crate::config::set_config_dir(dir);
+    }
+
+    // Load config and merge with environment
+    let mut config = Config::load().unwrap_or_default();
+    config.merge_from_env();
+
+    // --env (or the `env` config key) switches keychain, API and RPC endpoints as a set
+    let env = cli
+        .env
+        .clone()
+        .unwrap_or_else(|| config.session.env.clone());
+    if cli.env.is_some() || env != chains::PRODUCTION {
+        let _ = config.apply_environment(&env);
+    }
+
+    // Override config with CLI flags
+    if cli.json {
+        config.cli.json_output = true;
+    }
+    if let Commands::Execute {
+        simulate: true,
+        at_block,
+        override_timestamp,
+        ..
+    } = &cli.command
+    {
+        config.cli.simulate = true;
+        config.cli.simulate_at_block = at_block.clone();
+        config.cli.simulate_timestamp = override_timestamp.clone();
+    }
+    if let Commands::Execute { tip: Some(tip), .. } = &cli.command {
+        config.cli.tip = *tip;
+    }
+    if let Commands::Execute { batch_id, .. } = &cli.command {
+        config.cli.batch_id = batch_id.clone();
+    }
+    if cli.no_color {
+        config.cli.use_colors = false;
+    }
+    if cli.read_only {
+        config.cli.read_only = true;
+    }
+    if cli.explain {
+        config.cli.explain = true;
+    }
+    if cli.offline {
+        config.cli.offline = true;
+    }
+    if let Some(app) = cli.client_app {
+        config.cli.client_app = Some(app);
+    }
+    if let Some(app) = &config.cli.client_app {
+        api::set_client_app(app);
+    }
+
+    let account = cli.account;
+
+    // Terminal format is decided before --output-file forces structured results, so the
+    // terminal can stay human-readable while the file gets the JSON document.
+    let terminal_json = config.cli.json_output;
+    let sink = match cli.output_file {
+        Some(path) => {
+            config.cli.json_output = true;
+            if cli.tee {
+                output::OutputSink::Tee(path)
+            } else {
+                output::OutputSink::File(path)
+            }
+        }
+        None => output::OutputSink::Stdout,
+    };
+
+    config.cli.interactive = can_prompt && !terminal_json;
+
+    let formatter = create_formatter(
+        terminal_json,
+        config.cli.use_colors,
+        error::HintContext::new(account.as_deref()),
+        sink,
+    );
+
+    // Start version check in background (non-blocking)
+    let update_check = tokio::spawn(version::check_for_update());
+
+    // Validate account name early, before any command uses it
+    if let Some(ref name) = account {
+        if let Err(e) = Config::validate_account_name(name) {
+            formatter.error(&error::CliError::InvalidInput(e));
+            return Ok(1);
+        }
+    }
+
+    if config.cli.explain
+        && cli.command.submits_transactions()
+        && !EXPLAIN_COMMANDS.contains(&command_path)
+    {
+        formatter.error(&error::CliError::InvalidInput(format!(
+            "--explain is only supported by {}",
+            EXPLAIN_COMMANDS.join(", ")
+        )));
+        return Ok(1);
+    }
+
+    // Explaining or simulating submits nothing, so it is allowed on read-only hosts
+    if config.cli.read_only
+        && !config.cli.explain
+        && !config.cli.simulate
+        && cli.command.submits_transactions()
+    {
+        formatter.error(&error::CliError::ReadOnly(
+            "this command signs or submits transactions".to_string(),
+        ));
+        return Ok(1);
+    }
+
+    // Don't silently fall back to the empty default storage when several labels exist
+    let account = match account {
+        None if cli.command.reads_credentials() || cli.command.submits_transactions() => {
+            match commands::accounts::select(&config) {
+                Ok(selected) => selected,
+                Err(error::CliError::InputRequired(request)) => return Err(request),
+                Err(e) => {
+                    formatter.error(&e);
+                    return Ok(1);
+                }
+            }
+        }
+        account => account,
+    };
+
+    if cli.command.reads_credentials() {
+        let storage_path = config.resolve_storage_path(account.as_deref());
+        commands::storage::check_permissions(&storage_path, cli.fix_permissions, &*formatter);
+        commands::storage::check_integrity(&storage_path, &*formatter);
+    }
+
+    // Set by commands that report through the exit code, like 'session wait-expiry'
+    let mut exit_code = 0;
+    let result = match cli.command {
+        Commands::Storage { command } => match command {
+            StorageCommands::Harden => {
+                commands::storage::harden(&config, &*formatter, account.as_deref()).await
+            }
+        },
+        Commands::Init {
+            chain,
+            preset,
+            label,
+            expires,
+            overwrite,
+        } => {
+            commands::init::execute(
+                &config,
+                &*formatter,
+                commands::init::InitOptions {
+                    chain,
+                    preset,
+                    label,
+                    expires,
+                    overwrite,
+                },
+                account.as_deref(),
+            )
+            .await
+        }
+        Commands::Version { check } => {
+            commands::version_cmd::execute(&config, &*formatter, check).await
+        }
+        Commands::Doctor { fix } => {
+            commands::doctor::execute(&config, &*formatter, fix, account.as_deref()).await
+        }
+        // Tools check read-only mode and storage themselves, reporting into each result
+        Commands::Serve { .. } => commands::serve::mcp(&config, account.as_deref()).await,
+        Commands::Slot { command } => match command {
+            SlotCommands::Link { project, name } => {
+                commands::slot::link(&config, &*formatter, project, name).await
+            }
+        },
+        Commands::Accounts { command } => match command {
+            AccountsCommands::Clone {
+                src,
+                dst,
+                policies_only,
+                expires,
+                overwrite,
+            } => {
+                commands::accounts::clone(
+                    &config,
+                    &*formatter,
+                    &src,
+                    &dst,
+                    policies_only,
+                    &expires,
+                    overwrite,
+                )
+                .await
+            }
+            AccountsCommands::Upgrade {
+                to,
+                wait,
+                timeout,
+                yes,
+                auto_fix_policies,
+            } => {
+                commands::accounts::upgrade(
+                    &config,
+                    &*formatter,
+                    to,
+                    wait,
+                    timeout,
+                    yes,
+                    auto_fix_policies,
+                    account.as_deref(),
+                )
+                .await
+            }
+        },
+        Commands::Session { command } => match command {
+            SessionCommands::Auth {
+                preset,
+                file,
+                template,
+                contracts,
+                chain_id,
+                rpc_url,
+                overwrite,
+                print_policies,
+                yes,
+                qr,
+                headless,
+                notify_url,
+                expires,
+            } => {
+                commands::session::authorize::execute(
+                    &config,
+                    &*formatter,
+                    commands::session::authorize::AuthorizeOptions {
+                        preset_names: preset,
+                        file,
+                        template: template.map(|name| (name, contracts)),
+                        chain_id,
+                        rpc_url,
+                        overwrite,
+                        print_policies,
+                        yes,
+                        qr,
+                        headless,
+                        notify_url,
+                        expires,
+                    },
+                    account.as_deref(),
+                )
+                .await
+            }
+            SessionCommands::Ensure {
+                preset,
+                file,
+                template,
+                contracts,
+                chain_id,
+                rpc_url,
+                min_ttl,
+                qr,
+                headless,
+                notify_url,
+                expires,
+            } => {
+                commands::session::ensure::execute(
+                    &config,
+                    &*formatter,
+                    commands::session::authorize::AuthorizeOptions {
+                        preset_names: preset,
+                        file,
+                        template: template.map(|name| (name, contracts)),
+                        chain_id,
+                        rpc_url,
+                        qr,
+                        headless,
+                        notify_url,
+                        expires,
+                        ..Default::default()
+                    },
+                    &min_ttl,
+                    account.as_deref(),
+                )
+                .await
+            }
+            SessionCommands::Guard {
+                max_tx_per_hour,
+                max_transfers_per_hour,
+                new_contracts,
+                block,
+                interval,
+            } => {
+                commands::session::guard::execute(
+                    &config,
+                    &*formatter,
+                    commands::session::guard::GuardLimits {
+                        max_transactions_per_hour: max_tx_per_hour,
+                        max_transfers_per_hour,
+                        new_contracts,
+                    },
+                    block,
+                    interval,
+                    account.as_deref(),
+                )
+                .await
+            }
+            SessionCommands::WaitExpiry { within, timeout } => {
+                commands::session::wait_expiry::execute(
+                    &config,
+                    &*formatter,
+                    within,
+                    timeout,
+                    account.as_deref(),
+                )
+                .await
+                .map(|code| exit_code = code)
+            }
+            SessionCommands::Status => {
+                commands::status::execute(&config, &*formatter, account.as_deref()).await
+            }
+            SessionCommands::List {
+                chain_id,
+                limit,
+                after,
+            } => {
+                commands::session::list::execute(
+                    &config,
+                    &*formatter,
+                    chain_id,
+                    limit,
+                    after,
+                    account.as_deref(),
+                )
+                .await
+            }
+            SessionCommands::Revoke { session_hash } => {
+                commands::session::revoke::execute(
+                    &config,
+                    &*formatter,
+                    session_hash,
+                    account.as_deref(),
+                )
+                .await
+            }
+            SessionCommands::Clear { yes } => {
+                commands::clear::execute(&config, &*formatter, yes, account.as_deref()).await
+            }
+            SessionCommands::Export { encrypt, out } => {
+                commands::session::bundle::export(
+                    &config,
+                    &*formatter,
+                    encrypt,
+                    out,
+                    account.as_deref(),
+                )
+                .await
+            }
+            SessionCommands::Import {
+                blob,
+                file,
+                passphrase,
+                overwrite,
+            } => {
+                commands::session::bundle::import(
+                    &config,
+                    &*formatter,
+                    blob,
+                    file,
+                    passphrase,
+                    overwrite,
+                    account.as_deref(),
+                )
+                .await
+            }
+        },
+        Commands::Config { command } => match command {
+            ConfigCommands::Set {
+                json: Some(patch), ..
+            } => commands::config_cmd::execute_merge(&*formatter, patch).await,
+            ConfigCommands::Set {
+                key: Some(key),
+                value: Some(value),
+                ..
+            } => commands::config_cmd::execute_set(&*formatter, key, value).await,
+            ConfigCommands::Set { .. } => Err(error::CliError::InvalidInput(
+                "config set needs <KEY> <VALUE> or --json <OBJECT>".to_string(),
+            )),
+            ConfigCommands::Get { key } => {
+                commands::config_cmd::execute_get(&*formatter, config.cli.json_output, key).await
+            }
+            ConfigCommands::List => {
+                commands::config_cmd::execute_list(&*formatter, config.cli.json_output).await
+            }
+            ConfigCommands::History => commands::config_cmd::execute_history(&*formatter).await,
+            ConfigCommands::Rollback { n } => {
+                commands::config_cmd::execute_rollback(&*formatter, n).await
+            }
+        },
+        Commands::Execute {
+            contract,
+            entrypoint,
+            calldata,
+            file,
+            abi,
+            at,
+            delay,
+            wait,
+            timeout,
+            chain_id,
+            rpc_url,
+            no_paymaster,
+            no_queue,
+            switch_chain,
+            auto_fix_policies,
+            require_events,
+            wait_for_event,
+            only_if,
+            ..
+        } if at.is_some() || delay.is_some() => {
+            async {
+                let run_at = commands::schedule::parse_run_at(at.as_deref(), delay.as_deref())?;
+                let (entrypoint, calldata) = commands::encode::encode_args(
+                    &config,
+                    &*formatter,
+                    abi.as_deref(),
+                    contract.as_deref(),
+                    entrypoint,
+                    calldata,
+                    chain_id.clone(),
+                    rpc_url.clone(),
+                    account.as_deref(),
+                )
+                .await?;
+                let (calls, _) =
+                    commands::execute::calls_from_args(contract, entrypoint, calldata, file)?;
+                commands::schedule::create(
+                    &config,
+                    &*formatter,
+                    calls,
+                    run_at,
+                    commands::execute::ExecuteOptions {
+                        wait,
+                        timeout,
+                        chain_id,
+                        rpc_url,
+                        no_paymaster,
+                        no_queue,
+                        switch_chain,
+                        auto_fix_policies,
+                        require_events,
+                        wait_for_event,
+                        only_if,
+                        ..Default::default()
+                    },
+                    account.as_deref(),
+                )
+                .await
+            }
+            .await
+        }
+        Commands::Execute {
+            stdin,
+            concurrency,
+            wait,
+            timeout,
+            chain_id,
+            rpc_url,
+            simulate: _,
+            at_block: _,
+            override_timestamp: _,
+            no_paymaster,
+            no_queue,
+            require_events,
+            ..
+        } if stdin => {
+            let options = commands::execute_stdin::StreamOptions {
+                wait,
+                timeout,
+                chain_id,
+                rpc_url,
+                no_paymaster,
+                no_queue,
+                require_events,
+            };
+            commands::execute_stdin::execute(&config, options, concurrency, account.as_deref())
+                .await
+        }
+        Commands::Execute {
+            contract,
+            entrypoint,
+            calldata,
+            file,
+            wait,
+            timeout,
+            chain_id,
+            rpc_url,
+            no_paymaster,
+            no_queue,
+            intent,
+            switch_chain,
+            auto_fix_policies,
+            once,
+            force,
+            require_events,
+            wait_for_event,
+            only_if,
+            abi,
+            ..
+        } => {
+            async {
+                let (entrypoint, calldata) = commands::encode::encode_args(
+                    &config,
+                    &*formatter,
+                    abi.as_deref(),
+                    contract.as_deref(),
+                    entrypoint,
+                    calldata,
+                    chain_id.clone(),
+                    rpc_url.clone(),
+                    account.as_deref(),
+                )
+                .await?;
+                commands::execute::execute(
+                    &config,
+                    &*formatter,
+                    contract,
+                    entrypoint,
+                    calldata,
+                    file,
+                    commands::execute::ExecuteOptions {
+                        wait,
+                        timeout,
+                        chain_id,
+                        rpc_url,
+                        no_paymaster,
+                        no_queue,
+                        intent,
+                        switch_chain,
+                        auto_fix_policies,
+                        offer_policy_fix: true,
+                        once,
+                        force,
+                        require_events,
+                        wait_for_event,
+                        only_if,
+                    },
+                    account.as_deref(),
+                )
+                .await
+            }
+            .await
+        }
+        Commands::ApproveIntent { intent } => {
+            commands::intent::approve(&config, &*formatter, &intent, account.as_deref()).await
+        }
+        Commands::Balance {
+            symbol,
+            chain_id,
+            rpc_url,
+            fresh,
+            watch,
+        } => {
+            commands::balance::execute(
+                &config,
+                &*formatter,
+                symbol,
+                chain_id,
+                rpc_url,
+                fresh,
+                watch,
+                account.as_deref(),
+            )
+            .await
+        }
+        Commands::Faucet {
+            token,
+            amount,
+            recipient,
+            chain_id,
+            rpc_url,
+        } => {
+            commands::faucet::execute(
+                &config,
+                &*formatter,
+                token,
+                amount,
+                recipient,
+                chain_id,
+                rpc_url,
+                account.as_deref(),
+            )
+            .await
+        }
+        Commands::ApproveFor {
+            token,
+            spender,
+            amount,
+            revoke_after,
+            chain_id,
+            rpc_url,
+        } => {
+            commands::approve_for::execute(
+                &config,
+                &*formatter,
+                token,
+                spender,
+                amount,
+                revoke_after,
+                chain_id,
+                rpc_url,
+                account.as_deref(),
+            )
+            .await
+        }
+        Commands::RevokeDue => {
+            commands::approve_for::revoke_due(&config, &*formatter, account.as_deref()).await
+        }
+        Commands::ExecuteDue => {
+            commands::schedule::execute_due(&config, &*formatter, account.as_deref()).await
+        }
+        Commands::Queue { command } => match command {
+            QueueCommands::Add {
+                contract,
+                entrypoint,
+                calldata,
+                file,
+                id,
+                priority,
+                after,
+                max_attempts,
+                chain_id,
+                rpc_url,
+                no_paymaster,
+            } => {
+                async {
+                    let (calls, _) =
+                        commands::execute::calls_from_args(contract, entrypoint, calldata, file)?;
+                    commands::queue::add(
+                        &config,
+                        &*formatter,
+                        calls,
+                        id,
+                        priority,
+                        after,
+                        max_attempts,
+                        chain_id,
+                        rpc_url,
+                        no_paymaster,
+                        account.as_deref(),
+                    )
+                    .await
+                }
+                .await
+            }
+            QueueCommands::List => {
+                commands::queue::list(&config, &*formatter, account.as_deref()).await
+            }
+            QueueCommands::Run { max } => {
+                commands::queue::run(&config, &*formatter, max, account.as_deref()).await
+            }
+            QueueCommands::Verify => {
+                commands::queue::verify(&config, &*formatter, account.as_deref()).await
+            }
+        },
+        Commands::Policy { command } => match command {
+            PolicyCommands::Validate {
+                file,
+                against_session,
+            } => {
+                commands::policy::validate(
+                    &config,
+                    &*formatter,
+                    file,
+                    against_session,
+                    account.as_deref(),
+                )
+                .await
+            }
+            PolicyCommands::Diff { file, preset } => {
+                commands::policy::diff_session(
+                    &config,
+                    &*formatter,
+                    preset,
+                    file,
+                    account.as_deref(),
+                )
+                .await
+            }
+        },
+        Commands::Audit { command } => match command {
+            AuditCommands::Show {
+                chain_id,
+                result,
+                tx_hash,
+                limit,
+            } => {
+                commands::audit::show(
+                    &config,
+                    &*formatter,
+                    chain_id,
+                    result,
+                    tx_hash,
+                    limit,
+                    account.as_deref(),
+                )
+                .await
+            }
+        },
+        Commands::Inventory {
+            collection,
+            chain_id,
+            rpc_url,
+            torii_url,
+        } => {
+            commands::inventory::execute(
+                &config,
+                &*formatter,
+                collection,
+                chain_id,
+                rpc_url,
+                torii_url,
+                account.as_deref(),
+            )
+            .await
+        }
+        Commands::Dashboard { all, interval } => {
+            commands::dashboard::execute(&config, &*formatter, all, interval, account.as_deref())
+                .await
+        }
+        Commands::Vault { command } => match command {
+            VaultCommands::Balance {
+                vault,
+                chain_id,
+                rpc_url,
+            } => {
+                commands::vault::balance(
+                    &config,
+                    &*formatter,
+                    vault,
+                    chain_id,
+                    rpc_url,
+                    account.as_deref(),
+                )
+                .await
+            }
+            VaultCommands::Deposit {
+                vault,
+                amount,
+                chain_id,
+                rpc_url,
+                no_paymaster,
+            } => {
+                commands::vault::deposit(
+                    &config,
+                    &*formatter,
+                    vault,
+                    amount,
+                    chain_id,
+                    rpc_url,
+                    no_paymaster,
+                    account.as_deref(),
+                )
+                .await
+            }
+            VaultCommands::Withdraw {
+                vault,
+                amount,
+                shares,
+                chain_id,
+                rpc_url,
+                no_paymaster,
+            } => {
+                commands::vault::withdraw(
+                    &config,
+                    &*formatter,
+                    vault,
+                    amount,
+                    shares,
+                    chain_id,
+                    rpc_url,
+                    no_paymaster,
+                    account.as_deref(),
+                )
+                .await
+            }
+        },
+        Commands::Transfer {
+            token,
+            recipient,
+            amount,
+            wait,
+            timeout,
+            chain_id,
+            rpc_url,
+            no_paymaster,
+        } => {
+            commands::transfer::execute(
+                &config,
+                &*formatter,
+                token,
+                recipient,
+                amount,
+                wait,
+                timeout,
+                chain_id,
+                rpc_url,
+                no_paymaster,
+                account.as_deref(),
+            )
+            .await
+        }
+        Commands::TransferBatch {
+            file,
+            token,
+            chunk_size,
+            results,
+            chain_id,
+            rpc_url,
+        } => {
+            commands::transfer_batch::execute(
+                &config,
+                &*formatter,
+                file,
+                token,
+                chunk_size,
+                results,
+                chain_id,
+                rpc_url,
+                account.as_deref(),
+            )
+            .await
+        }
+        Commands::Username => {
+            commands::username::execute(&config, &*formatter, account.as_deref()).await
+        }
+        Commands::Env => commands::env::execute(&config, &*formatter, account.as_deref()).await,
+        Commands::Lookup {
+            usernames,
+            addresses,
+            refresh,
+            cached_only,
+        } => {
+            commands::lookup::execute(
+                &config,
+                &*formatter,
+                usernames,
+                addresses,
+                refresh,
+                cached_only,
+            )
+            .await
+        }
+        Commands::SelectorOf { names } => commands::selector_of::execute(&*formatter, names).await,
+        Commands::Convert { values, from, to } => {
+            commands::convert::execute(&*formatter, values, from, to).await
+        }
+        Commands::Tail {
+            contract,
+            abi,
+            blocks,
+            follow,
+            chain_id,
+            rpc_url,
+        } => {
+            commands::tail::execute(
+                &config,
+                &*formatter,
+                contract,
+                abi,
+                blocks,
+                follow,
+                chain_id,
+                rpc_url,
+                account.as_deref(),
+            )
+            .await
+        }
+        Commands::Events {
+            contract,
+            keys,
+            from_block,
+            to_block,
+            chunk_size,
+            limit,
+            continuation_token,
+            abi,
+            chain_id,
+            rpc_url,
+        } => {
+            commands::events::execute(
+                &config,
+                &*formatter,
+                contract,
+                keys,
+                Some(from_block),
+                Some(to_block),
+                chunk_size,
+                limit,
+                continuation_token,
+                abi,
+                chain_id,
+                rpc_url,
+                account.as_deref(),
+            )
+            .await
+        }
+        Commands::SignMessage { file } => {
+            commands::sign_message::sign(&config, &*formatter, &file, account.as_deref()).await
+        }
+        Commands::VerifyMessage {
+            file,
+            signature,
+            address,
+            chain_id,
+            rpc_url,
+        } => {
+            commands::sign_message::verify(
+                &config,
+                &*formatter,
+                &file,
+                &signature,
+                address,
+                chain_id,
+                rpc_url,
+                account.as_deref(),
+            )
+            .await
+        }
+        Commands::Bench { command } => match command {
+            BenchCommands::Action {
+                file,
+                name,
+                runs,
+                simulate,
+                rpc_url,
+            } => {
+                commands::bench::action(
+                    &config,
+                    &*formatter,
+                    file,
+                    name,
+                    runs,
+                    simulate,
+                    rpc_url,
+                    account.as_deref(),
+                )
+                .await
+            }
+        },
+        Commands::Selectors { command } => match command {
+            SelectorsCommands::Add { names } => commands::selectors::add(&*formatter, names).await,
+            SelectorsCommands::Import { file } => {
+                commands::selectors::import(&*formatter, file).await
+            }
+        },
+        Commands::Call {
+            contract,
+            entrypoint,
+            calldata,
+            file,
+            chain_id,
+            rpc_url,
+            block_id,
+            block_range,
+            concurrency,
+            rate,
+            abi,
+        } => {
+            async {
+                let (entrypoint, calldata) = commands::encode::encode_args(
+                    &config,
+                    &*formatter,
+                    abi.as_deref(),
+                    contract.as_deref(),
+                    entrypoint,
+                    calldata,
+                    chain_id.clone(),
+                    rpc_url.clone(),
+                    account.as_deref(),
+                )
+                .await?;
+                commands::call::execute(
+                    &config,
+                    &*formatter,
+                    contract,
+                    entrypoint,
+                    calldata,
+                    file,
+                    chain_id,
+                    rpc_url,
+                    block_id,
+                    block_range,
+                    concurrency,
+                    rate,
+                    account.as_deref(),
+                )
+                .await
+            }
+            .await
+        }
+        Commands::Snapshot { command } => match command {
+            SnapshotCommands::Take {
+                calls,
+                out,
+                chain_id,
+                rpc_url,
+                block_id,
+            } => {
+                commands::snapshot::take(
+                    &config,
+                    &*formatter,
+                    calls,
+                    out,
+                    chain_id,
+                    rpc_url,
+                    block_id,
+                    account.as_deref(),
+                )
+                .await
+            }
+            SnapshotCommands::Diff { before, after } => {
+                commands::snapshot::diff(&*formatter, before, after).await
+            }
+        },
+        Commands::Transaction {
+            hash,
+            chain_id,
+            rpc_url,
+            wait,
+            timeout,
+        } => {
+            commands::transaction::execute(
+                &config,
+                &*formatter,
+                hash,
+                chain_id,
+                rpc_url,
+                wait,
+                timeout,
+                account.as_deref(),
+            )
+            .await
+        }
+        Commands::Receipt {
+            hash,
+            chain_id,
+            rpc_url,
+            wait,
+            timeout,
+        } => {
+            commands::receipt::execute(
+                &config,
+                &*formatter,
+                hash,
+                chain_id,
+                rpc_url,
+                wait,
+                timeout,
+                account.as_deref(),
+            )
+            .await
+        }
+        Commands::Decode { command } => match command {
+            DecodeCommands::Calldata {
+                values,
+                abi,
+                function,
+            } => commands::decode::calldata(&*formatter, values, abi, function).await,
+            DecodeCommands::Event { keys, data, abi } => {
+                commands::decode::event(&*formatter, keys, data, abi).await
+            }
+        },
+        Commands::Api { command } => match command {
+            ApiCommands::Query { file, query, vars } => {
+                commands::api_cmd::query(&config, &*formatter, file, query, vars).await
+            }
+        },
+        Commands::Games { command } => match command {
+            GamesCommands::List => commands::games::list(&config, &*formatter).await,
+            GamesCommands::Info { preset } => {
+                commands::games::info(&config, &*formatter, preset).await
+            }
+        },
+        Commands::Presets { command } => match command {
+            PresetsCommands::List => commands::presets_cmd::list(&config, &*formatter).await,
+            PresetsCommands::Show { name, chain_id } => {
+                commands::presets_cmd::show(&config, &*formatter, name, chain_id).await
+            }
+            PresetsCommands::Refresh { names } => {
+                commands::presets_cmd::refresh(&config, &*formatter, names).await
+            }
+            PresetsCommands::Scaffold {
+                from_policy,
+                name,
+                chain_ids,
+                origin,
+                out,
+            } => {
+                commands::presets_cmd::scaffold(
+                    &*formatter,
+                    from_policy,
+                    name,
+                    chain_ids,
+                    origin,
+                    out,
+                )
+                .await
+            }
+        },
+        Commands::Starterpack { command } => match command {
+            StarterpackCommands::Quote {
+                id,
+                quantity,
+                chain_id,
+                rpc_url,
+            } => {
+                commands::starterpack::quote::execute(
+                    &config,
+                    &*formatter,
+                    id,
+                    quantity,
+                    chain_id,
+                    rpc_url,
+                    account.as_deref(),
+                )
+                .await
+            }
+            StarterpackCommands::Info {
+                id,
+                chain_id,
+                rpc_url,
+            } => {
+                commands::starterpack::info::execute(
+                    &config,
+                    &*formatter,
+                    id,
+                    chain_id,
+                    rpc_url,
+                    account.as_deref(),
+                )
+                .await
+            }
+            StarterpackCommands::Purchase {
+                id,
+                recipient,
+                quantity,
+                ui,
+                direct,
+                chain_id,
+                rpc_url,
+                wait,
+                timeout,
+                no_paymaster,
+            } => {
+                commands::starterpack::purchase::execute(
+                    &config,
+                    &*formatter,
+                    id,
+                    recipient,
+                    quantity,
+                    ui,
+                    direct,
+                    chain_id,
+                    rpc_url,
+                    wait,
+                    timeout,
+                    no_paymaster,
+                    account.as_deref(),
+                )
+                .await
+            }
+            StarterpackCommands::Gift {
+                id,
+                recipient,
+                quantity,
+                yes,
+                chain_id,
+                rpc_url,
+                wait,
+                timeout,
+                no_paymaster,
+            } => {
+                commands::starterpack::gift::execute(
+                    &config,
+                    &*formatter,
+                    id,
+                    recipient,
+                    quantity,
+                    yes,
+                    chain_id,
+                    rpc_url,
+                    wait,
+                    timeout,
+                    no_paymaster,
+                    account.as_deref(),
+                )
+                .await
+            }
+        },
+        Commands::Marketplace { command } => match command {
+            MarketplaceCommands::Info {
+                order_id,
+                collection,
+                token_id,
+                chain_id,
+                rpc_url,
+            } => {
+                commands::marketplace::info::execute(
+                    &config,
+                    &*formatter,
+                    order_id,
+                    collection,
+                    token_id,
+                    chain_id,
+                    rpc_url,
+                    account.as_deref(),
+                )
+                .await
+            }
+            MarketplaceCommands::Buy {
+                order_id,
+                collection,
+                token_id,
+                asset_id,
+                quantity,
+                no_royalties,
+                chain_id,
+                rpc_url,
+                wait,
+                timeout,
+                no_paymaster,
+            } => {
+                commands::marketplace::buy::execute(
+                    &config,
+                    &*formatter,
+                    order_id,
+                    collection,
+                    token_id,
+                    asset_id,
+                    quantity,
+                    no_royalties,
+                    chain_id,
+                    rpc_url,
+                    wait,
+                    timeout,
+                    no_paymaster,
+                    account.as_deref(),
+                )
+                .await
+            }
+        },
+        Commands::Batch { command } => match command {
+            BatchCommands::Status {
+                job_id,
+                chain_id,
+                rpc_url,
+                retries,
+            } => {
+                commands::batch::status(
+                    &config,
+                    &*formatter,
+                    job_id,
+                    chain_id,
+                    rpc_url,
+                    retries,
+                    account.as_deref(),
+                )
+                .await
+            }
+        },
+    };
+
+    let exit_code = match result {
+        Err(error::CliError::InputRequired(request)) if config.cli.interactive => {
+            return Err(request)
+        }
+        Err(e) => {
+            formatter.error(&e);
+            e.exit_code()
+        }
+        Ok(()) => exit_code,
+    };
+
+    // Show update warning after the command output, also on error
+    if let Ok(Some(msg)) = update_check.await {
+        formatter.warning(&msg);
+    }
+    Ok(exit_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    /// Walk the subcommand path of an invocation and check each step exists in the CLI.
+    fn assert_real_command(invocation: &str) {
+        let mut command = Cli::command();
+        for token in invocation.split_whitespace().skip(1) {
+            // Past the last subcommand, the rest are its values (e.g. 'config set <key>')
+            if token.starts_with('-') || token.starts_with('<') || !command.has_subcommands() {
+                break;
+            }
+            command = command
+                .find_subcommand(token)
+                .unwrap_or_else(|| panic!("'{invocation}' references unknown subcommand '{token}'"))
+                .clone();
+        }
+    }
+
+    /// Check every `--flag` in an invocation is accepted by the target subcommand or globally.
+    fn assert_real_flags(invocation: &str) {
+        let root = Cli::command();
+        let mut command = root.clone();
+        for token in invocation.split_whitespace().skip(1) {
+            if let Some(flag) = token.strip_prefix("--") {
+                let known = command
+                    .get_arguments()
+                    .chain(root.get_arguments())
+                    .any(|arg| arg.get_long() == Some(flag));
+                assert!(known, "'{invocation}' uses unknown flag '--{flag}'");
+            } else if !token.starts_with('<') {
+                if let Some(sub) = command.find_subcommand(token) {
+                    command = sub.clone();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_hints_reference_current_commands() {
+        for ctx in [
+            error::HintContext::default(),
+            error::HintContext::new(Some("player1")),
+        ] {
+            for error in error::all_errors() {
+                let mut texts = vec![error.to_string()];
+                texts.extend(error.recovery_hint(&ctx));
+                for text in texts {
+                    for invocation in error::quoted_commands(&text) {
+                        assert_real_command(&invocation);
+                        assert_real_flags(&invocation);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn command_path_is_subcommand_names_only() {
+        let matches = Cli::command()
+            .try_get_matches_from(["controller", "session", "auth", "--preset", "secret-game"])
+            .unwrap();
+        assert_eq!(command_path(&matches), "session auth");
+    }
+
+    #[test]
+    fn explain_commands_are_real_commands() {
+        for path in EXPLAIN_COMMANDS {
+            assert_real_command(&format!("controller {path}"));
+        }
+    }
+}
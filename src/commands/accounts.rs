@@ -0,0 +1,306 @@
+use crate::{
+    commands::{
+        execute::CallSpec,
+        session::authorize::{PolicyFile, PolicyStorage},
+    },
+    config::Config,
+    error::{CliError, InputRequest, Result},
+    output::OutputFormatter,
+};
+use account_sdk::storage::{filestorage::FileSystemBackend, StorageBackend, StorageValue};
+use serde::Serialize;
+use starknet::core::types::{BlockId, BlockTag, Felt};
+use starknet::providers::{JsonRpcClient, Provider};
+
+/// Policy file written into the destination account's storage and used to authorize it
+const CLONED_POLICY_FILE: &str = "cloned_policies.json";
+
+#[derive(Serialize)]
+pub struct UpgradeOutput {
+    pub address: String,
+    pub from_class_hash: String,
+    pub to_class_hash: String,
+    /// Set once the upgrade was submitted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<String>,
+    /// Whether the new class was seen on-chain (`--wait`), or the account was already on it
+    pub verified: bool,
+    pub message: String,
+}
+
+/// Labels with a directory under `<storage>/accounts/`, sorted by name.
+pub fn labels(config: &Config) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(config.resolve_storage_path(None).join("accounts")) else {
+        return Vec::new();
+    };
+    let mut labels: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| Config::validate_account_name(name).is_ok())
+        .collect();
+    labels.sort();
+    labels
+}
+
+/// Pick the account for a command run without `--account`.
+///
+/// The default storage is used when it holds a controller or when at most one label exists.
+/// Otherwise running on the empty default storage would only fail with `NoSession`, so an
+/// interactive caller is asked to pick a label, and others get an error listing the labels.
+pub fn select(config: &Config) -> Result<Option<String>> {
+    let has_default = FileSystemBackend::new(config.resolve_storage_path(None))
+        .controller()
+        .ok()
+        .flatten()
+        .is_some();
+    let labels = labels(config);
+    if has_default || labels.len() < 2 {
+        return Ok(None);
+    }
+    if !config.cli.interactive {
+        return Err(CliError::InvalidInput(format!(
+            "No --account given and several accounts exist: {}. Pass --account <label>",
+            labels.join(", ")
+        )));
+    }
+    Err(InputRequest::choice(
+        "Several accounts exist. Select one",
+        "--account",
+        labels,
+        None,
+    ))
+}
+
+/// Copy an account's policies (and, unless `policies_only`, its RPC binding) to another label,
+/// then authorize a session for the new label. Session keys are never copied: the destination
+/// generates its own signer during authorization.
+pub async fn clone(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    source: &str,
+    destination: &str,
+    policies_only: bool,
+    expires: &str,
+    overwrite: bool,
+) -> Result<()> {
+    validate_labels(source, destination)?;
+
+    let source_backend = FileSystemBackend::new(config.resolve_storage_path(Some(source)));
+    let policies: PolicyStorage = read_string(&source_backend, "session_policies")
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .ok_or_else(|| {
+            CliError::NotFoundError(format!(
+                "No stored policies for account '{source}'. \
+                 Authorize it first with 'controller session auth --account {source}'"
+            ))
+        })?;
+    let rpc_url = if policies_only {
+        None
+    } else {
+        read_string(&source_backend, "session_rpc_url")
+    };
+
+    let storage_path = config.resolve_storage_path(Some(destination));
+    std::fs::create_dir_all(&storage_path).map_err(|e| CliError::FileError {
+        path: storage_path.display().to_string(),
+        message: e.to_string(),
+    })?;
+    let path = storage_path.join(CLONED_POLICY_FILE);
+    let json = serde_json::to_string_pretty(&PolicyFile {
+        contracts: policies.contracts,
+        messages: policies.messages,
+    })
+    .map_err(|e| CliError::Storage(format!("Failed to serialize policies: {e}")))?;
+    super::storage::write_atomic(&path, json).map_err(|e| CliError::FileError {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    })?;
+
+    formatter.info(&format!(
+        "Copied policies from '{source}' to {}{}. Authorizing '{destination}'...",
+        path.display(),
+        rpc_url
+            .as_ref()
+            .map(|url| format!(" (RPC: {url})"))
+            .unwrap_or_default()
+    ));
+
+    crate::commands::session::authorize::execute(
+        config,
+        formatter,
+        crate::commands::session::authorize::AuthorizeOptions {
+            file: Some(path.display().to_string()),
+            rpc_url,
+            overwrite,
+            expires: expires.to_string(),
+            ..Default::default()
+        },
+        Some(destination),
+    )
+    .await
+}
+
+fn validate_labels(source: &str, destination: &str) -> Result<()> {
+    Config::validate_account_name(source).map_err(CliError::InvalidInput)?;
+    Config::validate_account_name(destination).map_err(CliError::InvalidInput)?;
+    if source == destination {
+        return Err(CliError::InvalidInput(
+            "Source and destination accounts must differ".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Upgrade the controller contract to `to` (or the class the Cartridge API recommends), by
+/// calling its own `upgrade` entrypoint with the session. Sessions are rarely authorized for
+/// that, in which case `execute` offers to re-authorize so the owner approves it in the browser.
+#[allow(clippy::too_many_arguments)]
+pub async fn upgrade(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    to: String,
+    wait: bool,
+    timeout: u64,
+    yes: bool,
+    auto_fix_policies: bool,
+    account: Option<&str>,
+) -> Result<()> {
+    let backend = FileSystemBackend::new(config.resolve_storage_path(account));
+    let controller = backend
+        .controller()
+        .ok()
+        .flatten()
+        .ok_or(CliError::NoSession)?;
+    let rpc_url =
+        read_string(&backend, "session_rpc_url").unwrap_or_else(|| config.session.rpc_url.clone());
+    let provider = JsonRpcClient::new(crate::api::rpc_transport(
+        url::Url::parse(&rpc_url)
+            .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?,
+    ));
+    let current = class_hash_at(&provider, controller.address).await?;
+
+    let target = parse_class_hash(&to)?;
+
+    let address = format!("0x{:x}", controller.address);
+    let mut output = UpgradeOutput {
+        address: address.clone(),
+        from_class_hash: format!("0x{current:x}"),
+        to_class_hash: format!("0x{target:x}"),
+        transaction_hash: None,
+        verified: false,
+        message: String::new(),
+    };
+    if current == target {
+        output.verified = true;
+        output.message = "The account already runs this class".to_string();
+        formatter.success(&output);
+        return Ok(());
+    }
+
+    formatter.warning(&format!(
+        "Upgrading replaces the code of account {address} (0x{current:x} -> 0x{target:x}). \
+         Only upgrade to classes published by Cartridge"
+    ));
+    if !yes && !config.cli.json_output {
+        return Err(InputRequest::confirm("Upgrade the account?", "--yes"));
+    }
+
+    let calls = vec![CallSpec {
+        contract_address: address,
+        entrypoint: "upgrade".to_string(),
+        calldata: vec![format!("0x{target:x}")],
+    }];
+    let execution = crate::commands::execute::execute_calls(
+        config,
+        formatter,
+        calls,
+        crate::commands::execute::ExecuteOptions {
+            wait,
+            timeout,
+            rpc_url: Some(rpc_url),
+            auto_fix_policies,
+            offer_policy_fix: true,
+            ..Default::default()
+        },
+        account,
+    )
+    .await?;
+    output.transaction_hash = Some(execution.into_submitted("Upgrade")?.transaction_hash);
+
+    if wait {
+        let now = class_hash_at(&provider, controller.address).await?;
+        if now != target {
+            return Err(CliError::TransactionFailed(format!(
+                "Upgrade transaction confirmed but the account still runs class 0x{now:x}"
+            )));
+        }
+        output.verified = true;
+        output.message = "Account upgraded".to_string();
+    } else {
+        output.message =
+            "Upgrade submitted. Run again with --wait, or check 'controller status', to verify"
+                .to_string();
+    }
+    formatter.success(&output);
+    Ok(())
+}
+
+async fn class_hash_at<P: Provider + Sync>(provider: &P, address: Felt) -> Result<Felt> {
+    provider
+        .get_class_hash_at(BlockId::Tag(BlockTag::Latest), address)
+        .await
+        .map_err(|e| CliError::Network(format!("Failed to read the account's class: {e}")))
+}
+
+fn parse_class_hash(hash: &str) -> Result<Felt> {
+    Felt::from_hex(hash.trim())
+        .map_err(|e| CliError::InvalidInput(format!("Invalid class hash '{hash}': {e}")))
+}
+
+fn read_string(backend: &FileSystemBackend, key: &str) -> Option<String> {
+    match backend.get(key).ok().flatten() {
+        Some(StorageValue::String(value)) => Some(value),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_account_labels() {
+        let dir = std::env::temp_dir().join(format!("controller-labels-{}", std::process::id()));
+        let config = Config {
+            session: crate::config::SessionConfig {
+                storage_path: dir.display().to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(labels(&config).is_empty());
+
+        for name in ["player2", "player1", "bad name"] {
+            std::fs::create_dir_all(dir.join("accounts").join(name)).unwrap();
+        }
+        std::fs::write(dir.join("accounts").join("notes.txt"), "").unwrap();
+        assert_eq!(labels(&config), vec!["player1", "player2"]);
+        assert!(select(&config, false).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn validates_clone_labels() {
+        assert!(validate_labels("player1", "player2").is_ok());
+        assert!(validate_labels("player1", "player1").is_err());
+        assert!(validate_labels("../etc", "player2").is_err());
+        assert!(validate_labels("player1", "").is_err());
+    }
+
+    #[test]
+    fn parses_upgrade_targets() {
+        assert_eq!(parse_class_hash(" 0x2a ").unwrap(), Felt::from(42u8));
+        assert!(parse_class_hash("latest").is_err());
+    }
+}
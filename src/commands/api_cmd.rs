@@ -0,0 +1,85 @@
+use crate::{
+    api,
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+
+/// Execute a GraphQL document against the configured `api-url` and print its `data`.
+pub async fn query(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    file: Option<String>,
+    query: Option<String>,
+    vars: Option<String>,
+) -> Result<()> {
+    let document = match (file, query) {
+        (Some(path), _) => std::fs::read_to_string(&path).map_err(|e| CliError::FileError {
+            path,
+            message: e.to_string(),
+        })?,
+        (None, Some(query)) => query,
+        (None, None) => {
+            return Err(CliError::InvalidInput(
+                "Provide the GraphQL document with --file or --query".to_string(),
+            ))
+        }
+    };
+
+    let variables = match vars {
+        Some(vars) => parse_variables(&vars)?,
+        None => serde_json::Value::Object(Default::default()),
+    };
+
+    let (data, errors) = api::graphql_query(&config.session.api_url, &document, variables).await?;
+    for error in errors {
+        formatter.warning(&format!("GraphQL error: {error}"));
+    }
+
+    formatter.success(&data);
+    Ok(())
+}
+
+/// Parse `--vars`: an inline JSON object, or `@path` to read one from a file.
+fn parse_variables(vars: &str) -> Result<serde_json::Value> {
+    let (content, source) = match vars.strip_prefix('@') {
+        Some(path) => (
+            std::fs::read_to_string(path).map_err(|e| CliError::FileError {
+                path: path.to_string(),
+                message: e.to_string(),
+            })?,
+            path,
+        ),
+        None => (vars.to_string(), "--vars"),
+    };
+
+    let value: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid JSON in {source}: {e}")))?;
+    if !value.is_object() {
+        return Err(CliError::InvalidInput(format!(
+            "{source} must be a JSON object of query variables"
+        )));
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_inline_and_file_variables() {
+        let inline = parse_variables(r#"{"address": "0x1", "first": 5}"#).unwrap();
+        assert_eq!(inline["first"], 5);
+
+        assert!(parse_variables("[1, 2]").is_err());
+        assert!(parse_variables("{not json").is_err());
+
+        let path =
+            std::env::temp_dir().join(format!("controller-vars-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"chainID": "SN_MAIN"}"#).unwrap();
+        let from_file = parse_variables(&format!("@{}", path.display())).unwrap();
+        assert_eq!(from_file["chainID"], "SN_MAIN");
+        let _ = std::fs::remove_file(&path);
+    }
+}
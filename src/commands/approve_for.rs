@@ -0,0 +1,277 @@
+use crate::{
+    chains,
+    commands::{
+        balance,
+        execute::{CallSpec, Execution},
+        session::authorize::parse_expiration,
+    },
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use starknet::core::types::Felt;
+use starknet::providers::JsonRpcClient;
+use std::path::Path;
+
+/// Pending revokes, relative to the account's storage path
+const SCHEDULE_FILE: &str = "scheduled_revokes.json";
+
+/// An allowance to be reset to zero once `revoke_at` has passed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledRevoke {
+    pub token: String,
+    pub symbol: String,
+    pub spender: String,
+    pub amount: String,
+    pub chain_id: String,
+    pub rpc_url: String,
+    pub revoke_at: u64,
+}
+
+#[derive(Serialize)]
+pub struct ScheduledRevokeInfo {
+    pub token: String,
+    pub spender: String,
+    pub amount: String,
+    pub chain_id: String,
+    pub revoke_at: u64,
+    pub revoke_at_formatted: String,
+    pub is_due: bool,
+}
+
+impl ScheduledRevoke {
+    fn is_due(&self, now: u64) -> bool {
+        now >= self.revoke_at
+    }
+
+    pub fn info(&self, now: u64) -> ScheduledRevokeInfo {
+        ScheduledRevokeInfo {
+            token: self.symbol.clone(),
+            spender: self.spender.clone(),
+            amount: self.amount.clone(),
+            chain_id: self.chain_id.clone(),
+            revoke_at: self.revoke_at,
+            revoke_at_formatted: DateTime::from_timestamp(self.revoke_at as i64, 0)
+                .unwrap_or_else(Utc::now)
+                .format("%Y-%m-%d %H:%M:%S UTC")
+                .to_string(),
+            is_due: self.is_due(now),
+        }
+    }
+}
+
+pub fn load_schedule(storage_path: &Path) -> Vec<ScheduledRevoke> {
+    std::fs::read_to_string(storage_path.join(SCHEDULE_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_schedule(storage_path: &Path, schedule: &[ScheduledRevoke]) -> Result<()> {
+    let path = storage_path.join(SCHEDULE_FILE);
+    let content = serde_json::to_string_pretty(schedule)
+        .map_err(|e| CliError::Storage(format!("Failed to serialize scheduled revokes: {e}")))?;
+    super::storage::write_atomic(&path, content).map_err(|e| CliError::FileError {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    })
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Approve `spender` for `amount` of `token`, then record a revoke (approve to zero) due once
+/// `revoke_after` has elapsed. Due revokes are submitted by `controller revoke-due`.
+#[allow(clippy::too_many_arguments)]
+pub async fn execute(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    token: String,
+    spender: String,
+    amount: String,
+    revoke_after: String,
+    chain_id: Option<String>,
+    rpc_url: Option<String>,
+    account: Option<&str>,
+) -> Result<()> {
+    let revoke_at = parse_expiration(&revoke_after)?;
+    let (token_address, decimals) = balance::resolve_token(config, &token).ok_or_else(|| {
+        CliError::InvalidInput(format!(
+            "Unknown token '{token}'. Add it with 'controller config set token.{token} <address>'"
+        ))
+    })?;
+    let raw_amount = balance::parse_token_amount(&amount, decimals).ok_or_else(|| {
+        CliError::InvalidInput(format!(
+            "Invalid amount '{amount}': expected a token amount like '100' or '0.5'"
+        ))
+    })?;
+    let spender = Felt::from_hex(&spender)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid spender address: {e}")))?;
+
+    let rpc_url = chains::resolve_rpc_url(config, formatter, chain_id, rpc_url, account)?;
+    let url = url::Url::parse(&rpc_url)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
+    let chain_name =
+        chains::chain_name(&JsonRpcClient::new(crate::api::rpc_transport(url))).await?;
+
+    crate::commands::execute::execute(
+        config,
+        formatter,
+        Some(format!("0x{token_address:x}")),
+        Some("approve".to_string()),
+        Some(format!("0x{spender:x},u256:{raw_amount}")),
+        None,
+        crate::commands::execute::ExecuteOptions {
+            wait: true,
+            rpc_url: Some(rpc_url.clone()),
+            ..Default::default()
+        },
+        account,
+    )
+    .await?;
+
+    let storage_path = config.resolve_storage_path(account);
+    let revoke = ScheduledRevoke {
+        token: format!("0x{token_address:x}"),
+        symbol: token.to_uppercase(),
+        spender: format!("0x{spender:x}"),
+        amount,
+        chain_id: chain_name,
+        rpc_url,
+        revoke_at,
+    };
+    let info = revoke.info(now());
+    if config.cli.explain {
+        formatter.info(&format!(
+            "Would schedule a revoke of the {} allowance for {} at {}",
+            info.token, info.spender, info.revoke_at_formatted
+        ));
+        return Ok(());
+    }
+
+    let mut schedule = load_schedule(&storage_path);
+    // A new approval for the same pair replaces the previous window
+    schedule.retain(|r| !(r.token == revoke.token && r.spender == revoke.spender));
+    schedule.push(revoke);
+    save_schedule(&storage_path, &schedule)?;
+
+    formatter.info(&format!(
+        "Scheduled revoke of the {} allowance for {} at {}. Run 'controller revoke-due' after then.",
+        info.token, info.spender, info.revoke_at_formatted
+    ));
+    Ok(())
+}
+
+/// Submit every scheduled revoke whose window has passed, one multicall per chain.
+pub async fn revoke_due(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    account: Option<&str>,
+) -> Result<()> {
+    let storage_path = config.resolve_storage_path(account);
+    let (mut due, pending) = split_due(load_schedule(&storage_path), now());
+
+    if due.is_empty() {
+        formatter.info(&format!(
+            "No scheduled revokes are due ({} pending)",
+            pending.len()
+        ));
+        return Ok(());
+    }
+
+    while let Some(rpc_url) = due.first().map(|r| r.rpc_url.clone()) {
+        let (batch, rest): (Vec<_>, Vec<_>) = due.into_iter().partition(|r| r.rpc_url == rpc_url);
+        let calls: Vec<CallSpec> = batch
+            .iter()
+            .map(|r| CallSpec {
+                contract_address: r.token.clone(),
+                entrypoint: "approve".to_string(),
+                calldata: vec![r.spender.clone(), "u256:0".to_string()],
+            })
+            .collect();
+
+        let result = crate::commands::execute::execute_calls(
+            config,
+            formatter,
+            calls,
+            crate::commands::execute::ExecuteOptions {
+                wait: true,
+                rpc_url: Some(rpc_url),
+                ..Default::default()
+            },
+            account,
+        )
+        .await;
+        if config.cli.explain {
+            crate::commands::execute::report(config, formatter, result?)?;
+            due = rest;
+            continue;
+        }
+        // A pending intent leaves the allowances in place, so those revokes stay scheduled
+        let result = match result {
+            Ok(execution @ Execution::Submitted { .. }) => {
+                crate::commands::execute::report(config, formatter, execution)
+            }
+            Ok(execution) => execution.into_submitted("Revoke").map(drop),
+            Err(e) => Err(e),
+        };
+
+        // Keep whatever has not been revoked yet so the next run retries it
+        let mut remaining = pending.clone();
+        if result.is_err() {
+            remaining.extend(batch);
+        }
+        remaining.extend(rest.iter().cloned());
+        save_schedule(&storage_path, &remaining)?;
+        result?;
+        due = rest;
+    }
+
+    Ok(())
+}
+
+/// Split a schedule into (due, pending) revokes.
+fn split_due(
+    schedule: Vec<ScheduledRevoke>,
+    now: u64,
+) -> (Vec<ScheduledRevoke>, Vec<ScheduledRevoke>) {
+    schedule.into_iter().partition(|r| r.is_due(now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn revoke(spender: &str, revoke_at: u64) -> ScheduledRevoke {
+        ScheduledRevoke {
+            token: "0x4718f".to_string(),
+            symbol: "STRK".to_string(),
+            spender: spender.to_string(),
+            amount: "10".to_string(),
+            chain_id: "SN_SEPOLIA".to_string(),
+            rpc_url: "https://api.cartridge.gg/x/starknet/sepolia".to_string(),
+            revoke_at,
+        }
+    }
+
+    #[test]
+    fn splits_due_revokes_and_roundtrips_schedule() {
+        let (due, pending) = split_due(vec![revoke("0x1", 100), revoke("0x2", 200)], 150);
+        assert_eq!(due, vec![revoke("0x1", 100)]);
+        assert_eq!(pending, vec![revoke("0x2", 200)]);
+        assert!(revoke("0x1", 100).info(100).is_due);
+
+        let dir = std::env::temp_dir().join(format!("controller-revokes-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(load_schedule(&dir).is_empty());
+        save_schedule(&dir, &pending).unwrap();
+        assert_eq!(load_schedule(&dir), pending);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,285 @@
+use crate::commands::execute::CallSpec;
+use crate::config::Config;
+use crate::error::{CliError, Result};
+use crate::output::OutputFormatter;
+use serde::{Deserialize, Serialize};
+use starknet::core::types::{Call, ExecutionResult, Felt, TransactionReceipt};
+use std::io::Write;
+use std::path::Path;
+
+/// Append-only log of submitted transactions, in the base storage path so it spans all accounts
+pub const AUDIT_FILE: &str = "audit.jsonl";
+
+/// One line of the audit log. A submission is logged once it succeeds or fails; with `--wait`
+/// a second line with the same transaction hash records whether it was confirmed or reverted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// RFC 3339 timestamp
+    pub timestamp: String,
+    /// 'execute', 'session revoke', 'starterpack purchase' or 'marketplace buy'
+    pub command: String,
+    /// Batch job the submission belongs to (`--batch-id`, `transfer-batch`, `execute --stdin`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch_id: Option<String>,
+    /// Account label; absent for the default storage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    pub address: String,
+    pub chain_id: String,
+    pub calls: Vec<CallSpec>,
+    /// Session policies that authorized the calls, as 'contract:entrypoint'
+    pub policies_matched: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<String>,
+    pub paymaster_used: bool,
+    /// 'submitted', 'failed', 'confirmed' or 'reverted'
+    pub result: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct AuditShowOutput {
+    entries: Vec<AuditEntry>,
+}
+
+impl AuditEntry {
+    pub fn new(
+        command: &str,
+        account: Option<&str>,
+        address: Felt,
+        chain_id: &str,
+        calls: Vec<CallSpec>,
+        policies_matched: Vec<String>,
+        paymaster_used: bool,
+    ) -> Self {
+        Self {
+            timestamp: String::new(),
+            command: command.to_string(),
+            batch_id: None,
+            account: account.map(str::to_string),
+            address: format!("0x{address:x}"),
+            chain_id: chain_id.to_string(),
+            calls,
+            policies_matched,
+            transaction_hash: None,
+            paymaster_used,
+            result: String::new(),
+            error: None,
+        }
+    }
+
+    /// Log the outcome of submitting: the transaction hash, or why nothing was submitted.
+    pub fn submitted(
+        &mut self,
+        config: &Config,
+        formatter: &dyn OutputFormatter,
+        outcome: std::result::Result<Felt, &CliError>,
+    ) {
+        match outcome {
+            Ok(hash) => {
+                self.transaction_hash = Some(format!("0x{hash:x}"));
+                self.result = "submitted".to_string();
+            }
+            Err(e) => {
+                self.result = "failed".to_string();
+                self.error = Some(e.to_string());
+            }
+        }
+        self.append(config, formatter);
+    }
+
+    /// Log whether a submitted transaction was confirmed or reverted.
+    pub fn confirmed(
+        &mut self,
+        config: &Config,
+        formatter: &dyn OutputFormatter,
+        receipt: &TransactionReceipt,
+    ) {
+        match receipt.execution_result() {
+            ExecutionResult::Succeeded => {
+                self.result = "confirmed".to_string();
+                self.error = None;
+            }
+            ExecutionResult::Reverted { reason } => {
+                self.result = "reverted".to_string();
+                self.error = Some(reason.clone());
+            }
+        }
+        self.append(config, formatter);
+    }
+
+    /// Best effort: a failure to write warns but never fails the transaction that was sent
+    fn append(&mut self, config: &Config, formatter: &dyn OutputFormatter) {
+        self.timestamp = chrono::Utc::now().to_rfc3339();
+        let path = config.resolve_storage_path(None).join(AUDIT_FILE);
+        if let Err(e) = append_line(&path, self) {
+            formatter.warning(&format!(
+                "Failed to write the audit log {}: {e}",
+                path.display()
+            ));
+        }
+    }
+}
+
+fn append_line(path: &Path, entry: &AuditEntry) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+
+    let mut options = std::fs::OpenOptions::new();
+    options.append(true).create(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    // A single write per line keeps concurrent appenders from interleaving
+    options.open(path)?.write_all(line.as_bytes())
+}
+
+/// Calls built in code, logged with raw selectors like `execute` accepts them
+pub fn call_specs(calls: &[Call]) -> Vec<CallSpec> {
+    calls
+        .iter()
+        .map(|call| CallSpec {
+            contract_address: format!("0x{:x}", call.to),
+            entrypoint: format!("selector:0x{:x}", call.selector),
+            calldata: call.calldata.iter().map(|f| format!("0x{f:x}")).collect(),
+        })
+        .collect()
+}
+
+pub fn load(path: &Path) -> Result<Vec<AuditEntry>> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(CliError::FileError {
+                path: path.display().to_string(),
+                message: e.to_string(),
+            })
+        }
+    };
+    // A line cut short by a crash is skipped rather than hiding the rest of the log
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+fn matches(
+    entry: &AuditEntry,
+    account: Option<&str>,
+    chain_id: Option<&str>,
+    result: Option<&str>,
+    transaction_hash: Option<Felt>,
+) -> bool {
+    account.is_none_or(|label| entry.account.as_deref() == Some(label))
+        && chain_id.is_none_or(|chain| entry.chain_id.eq_ignore_ascii_case(chain))
+        && result.is_none_or(|result| entry.result == result)
+        && transaction_hash.is_none_or(|hash| {
+            entry
+                .transaction_hash
+                .as_deref()
+                .and_then(|h| Felt::from_hex(h).ok())
+                == Some(hash)
+        })
+}
+
+/// Show the audit log, newest first. `--account` narrows it to one label; without it every
+/// account's submissions are listed.
+pub async fn show(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    chain_id: Option<String>,
+    result: Option<String>,
+    transaction_hash: Option<String>,
+    limit: usize,
+    account: Option<&str>,
+) -> Result<()> {
+    let transaction_hash = transaction_hash
+        .map(|hash| {
+            Felt::from_hex(&hash).map_err(|e| {
+                CliError::InvalidInput(format!("Invalid transaction hash '{hash}': {e}"))
+            })
+        })
+        .transpose()?;
+
+    let entries: Vec<AuditEntry> = load(&config.resolve_storage_path(None).join(AUDIT_FILE))?
+        .into_iter()
+        .rev()
+        .filter(|entry| {
+            matches(
+                entry,
+                account,
+                chain_id.as_deref(),
+                result.as_deref(),
+                transaction_hash,
+            )
+        })
+        .take(limit)
+        .collect();
+
+    if entries.is_empty() {
+        formatter.info("No audited transactions match");
+    }
+    formatter.success(&AuditShowOutput { entries });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appends_and_filters_entries() {
+        let dir = std::env::temp_dir().join(format!("controller-audit-{}", std::process::id()));
+        let path = dir.join(AUDIT_FILE);
+        let _ = std::fs::remove_file(&path);
+
+        let calls = call_specs(&[Call {
+            to: Felt::from(0x123u64),
+            selector: Felt::from(0x45u64),
+            calldata: vec![Felt::ONE],
+        }]);
+        assert_eq!(calls[0].entrypoint, "selector:0x45");
+
+        let mut entry = AuditEntry::new(
+            "execute",
+            Some("player1"),
+            Felt::from(0xabcu64),
+            "SN_SEPOLIA",
+            calls,
+            vec!["0x123:transfer".to_string()],
+            true,
+        );
+        entry.result = "submitted".to_string();
+        entry.transaction_hash = Some("0x0def".to_string());
+        append_line(&path, &entry).unwrap();
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"{\"truncated\n")
+            .unwrap();
+        entry.result = "confirmed".to_string();
+        append_line(&path, &entry).unwrap();
+
+        let entries = load(&path).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(matches(
+            &entries[0],
+            Some("player1"),
+            Some("sn_sepolia"),
+            None,
+            Some(Felt::from(0xdefu64))
+        ));
+        assert!(!matches(&entries[0], None, None, Some("confirmed"), None));
+        assert!(!matches(&entries[1], Some("player2"), None, None, None));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,15 +1,18 @@
+use crate::api::RpcTransport;
 use crate::config::Config;
 use crate::error::{CliError, Result};
 use crate::output::OutputFormatter;
 use account_sdk::storage::{filestorage::FileSystemBackend, StorageBackend};
 use serde::{Deserialize, Serialize};
 use starknet::core::types::{BlockId, BlockTag, Felt, FunctionCall};
-use starknet::providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider};
+use starknet::providers::{JsonRpcClient, Provider};
 use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 
 const CACHE_TTL_SECS: u64 = 30;
+/// Token self-paid (v3) transactions pay their fees in
+const FEE_TOKEN: &str = "STRK";
 
 struct TokenInfo {
     address: &'static str,
@@ -70,9 +73,61 @@ fn builtin_tokens() -> Vec<(&'static str, TokenInfo)> {
     ]
 }
 
+/// Resolve a token symbol (case-insensitive) to its address and decimals. Configured tokens take
+/// precedence; custom tokens without built-in metadata are assumed to have 18 decimals.
+pub fn resolve_token(config: &Config, symbol: &str) -> Option<(Felt, u8)> {
+    let builtin = builtin_tokens()
+        .into_iter()
+        .find(|(s, _)| s.eq_ignore_ascii_case(symbol));
+    let decimals = builtin
+        .as_ref()
+        .map(|(_, info)| info.decimals)
+        .unwrap_or(18);
+
+    let address = config
+        .tokens
+        .iter()
+        .find(|(s, _)| s.eq_ignore_ascii_case(symbol))
+        .map(|(_, addr)| addr.as_str())
+        .or(builtin.as_ref().map(|(_, info)| info.address))?;
+
+    Felt::from_hex(address).ok().map(|a| (a, decimals))
+}
+
+/// Parse a human token amount (e.g., '100' or '0.5') into raw units with the given decimals.
+pub fn parse_token_amount(value: &str, decimals: u8) -> Option<u128> {
+    let (whole, fraction) = value.trim().split_once('.').unwrap_or((value.trim(), ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return None;
+    }
+    if !whole
+        .chars()
+        .chain(fraction.chars())
+        .all(|c| c.is_ascii_digit())
+        || fraction.len() > decimals as usize
+    {
+        return None;
+    }
+
+    let scale = 10u128.checked_pow(decimals as u32)?;
+    let whole: u128 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().ok()?
+    };
+    let fraction: u128 = if fraction.is_empty() {
+        0
+    } else {
+        let padded = format!("{fraction:0<width$}", width = decimals as usize);
+        padded.parse().ok()?
+    };
+
+    whole.checked_mul(scale)?.checked_add(fraction)
+}
+
 /// Query a single token's balance and decimals
 async fn query_token_balance(
-    provider: Arc<JsonRpcClient<HttpTransport>>,
+    provider: Arc<JsonRpcClient<RpcTransport>>,
     sym: String,
     contract_address: Felt,
     account_address: Felt,
@@ -137,12 +192,15 @@ async fn query_token_balance(
 }
 
 /// Query ERC20 token balances for the active session account
+#[allow(clippy::too_many_arguments)]
 pub async fn execute(
     config: &Config,
     formatter: &dyn OutputFormatter,
     symbol: Option<String>,
     chain_id: Option<String>,
     rpc_url: Option<String>,
+    fresh: bool,
+    watch: Option<String>,
     account: Option<&str>,
 ) -> Result<()> {
     // Load session to get account address
@@ -158,19 +216,53 @@ pub async fn execute(
     let account_address = controller.address;
 
     // Resolve RPC URL
-    let rpc_url = resolve_rpc_url(chain_id, rpc_url, config, formatter)?;
+    let rpc_url = crate::chains::resolve_rpc_url(config, formatter, chain_id, rpc_url, account)?;
 
-    // Check cache
+    // Check cache (unless --fresh); watching always queries the chain
     let cache_key = format!("0x{account_address:x}");
-    if let Some(cached) = load_cache(&storage_path, &cache_key) {
-        let results = filter_results(cached, &symbol);
-        return output_results(config, formatter, &results);
+    if !fresh && watch.is_none() {
+        if let Some(cached) = load_cache(&storage_path, &cache_key) {
+            crate::meta::cache_hit();
+            let results = filter_results(cached, &symbol);
+            return output_results(config, formatter, &results);
+        }
     }
 
     let url = url::Url::parse(&rpc_url)
         .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
-    let provider = Arc::new(JsonRpcClient::new(HttpTransport::new(url)));
+    let provider = Arc::new(JsonRpcClient::new(crate::api::rpc_transport(url)));
+
+    if let Some(interval) = watch {
+        let interval = parse_interval(&interval)?;
+        return watch_balances(
+            config,
+            formatter,
+            &provider,
+            account_address,
+            &storage_path,
+            &symbol,
+            interval,
+        )
+        .await;
+    }
+
+    let all_results = fetch_balances(config, formatter, &provider, account_address).await;
+
+    // Save to cache (all tokens, before filtering)
+    save_cache(&storage_path, &cache_key, &all_results);
+
+    let results = filter_results(all_results, &symbol);
+    output_results(config, formatter, &results)
+}
 
+/// Query every built-in and configured token concurrently, in token order. Tokens whose query
+/// fails are skipped with a warning.
+async fn fetch_balances(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    provider: &Arc<JsonRpcClient<RpcTransport>>,
+    account_address: Felt,
+) -> Vec<BalanceOutput> {
     // Build token list: built-in defaults + config overrides
     let mut tokens: BTreeMap<String, String> = BTreeMap::new();
     for (sym, info) in builtin_tokens() {
@@ -198,7 +290,7 @@ pub async fn execute(
             .find(|(s, _)| s.to_uppercase() == sym.to_uppercase())
             .map(|(_, info)| info.decimals);
 
-        let provider = Arc::clone(&provider);
+        let provider = Arc::clone(provider);
         let sym = sym.clone();
         handles.push(tokio::spawn(query_token_balance(
             provider,
@@ -226,16 +318,136 @@ pub async fn execute(
         }
     }
 
-    let all_results: Vec<BalanceOutput> = token_order
+    token_order
         .iter()
         .filter_map(|sym| result_map.remove(sym))
-        .collect();
+        .collect()
+}
 
-    // Save to cache (all tokens, before filtering)
-    save_cache(&storage_path, &cache_key, &all_results);
+/// Shortest `--watch` interval, to keep a forgotten watcher from hammering the RPC
+const MIN_WATCH_SECS: u64 = 2;
+
+/// Parse a `--watch` interval: plain seconds or a number with `s`, `min` or `hr`.
+pub fn parse_interval(value: &str) -> Result<std::time::Duration> {
+    let value = value.trim().to_lowercase();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (num, unit) = value.split_at(split);
+    let invalid = || {
+        CliError::InvalidInput(format!(
+            "Invalid watch interval '{value}'. Use seconds or a unit, e.g. 10, 30s, 5min"
+        ))
+    };
+    let num: u64 = num.parse().map_err(|_| invalid())?;
+    let secs = match unit {
+        "" | "s" | "sec" | "secs" => num,
+        "min" | "mins" => num * 60,
+        "hr" | "hrs" => num * 3600,
+        _ => return Err(invalid()),
+    };
+    if secs < MIN_WATCH_SECS {
+        return Err(CliError::InvalidInput(format!(
+            "Watch interval must be at least {MIN_WATCH_SECS}s"
+        )));
+    }
+    Ok(std::time::Duration::from_secs(secs))
+}
 
-    let results = filter_results(all_results, &symbol);
-    output_results(config, formatter, &results)
+/// A token whose balance changed between two refreshes, one NDJSON line in `--watch --json`
+#[derive(Debug, PartialEq, Serialize)]
+struct BalanceDelta {
+    timestamp: String,
+    token: String,
+    balance: String,
+    raw: String,
+    /// `None` on the first refresh, or when the token was skipped last time
+    previous_balance: Option<String>,
+    previous_raw: Option<String>,
+}
+
+fn balance_deltas(previous: &[BalanceOutput], current: &[BalanceOutput]) -> Vec<BalanceDelta> {
+    let timestamp = chrono::Utc::now().to_rfc3339();
+    current
+        .iter()
+        .filter_map(|now| {
+            let before = previous.iter().find(|p| p.token == now.token);
+            if before.is_some_and(|b| b.raw == now.raw) {
+                return None;
+            }
+            Some(BalanceDelta {
+                timestamp: timestamp.clone(),
+                token: now.token.clone(),
+                balance: now.balance.clone(),
+                raw: now.raw.clone(),
+                previous_balance: before.map(|b| b.balance.clone()),
+                previous_raw: before.map(|b| b.raw.clone()),
+            })
+        })
+        .collect()
+}
+
+/// Refresh balances every `interval` until Ctrl-C. Human output redraws the table in place;
+/// JSON output emits one line per changed balance (the first refresh reports every token).
+async fn watch_balances(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    provider: &Arc<JsonRpcClient<RpcTransport>>,
+    account_address: Felt,
+    storage_path: &std::path::Path,
+    symbol: &Option<String>,
+    interval: std::time::Duration,
+) -> Result<()> {
+    use std::io::IsTerminal;
+
+    let cache_key = format!("0x{account_address:x}");
+    let redraw = !config.cli.json_output && std::io::stdout().is_terminal();
+    let mut previous: Vec<BalanceOutput> = Vec::new();
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+
+        let balances = fetch_balances(config, formatter, provider, account_address).await;
+        save_cache(storage_path, &cache_key, &balances);
+        let current: Vec<BalanceOutput> = match symbol {
+            Some(sym) => filter_results(balances, &Some(sym.clone())),
+            None => balances,
+        };
+
+        let deltas = balance_deltas(&previous, &current);
+        if config.cli.json_output {
+            for delta in &deltas {
+                if let Ok(line) = serde_json::to_string(delta) {
+                    println!("{line}");
+                }
+            }
+        } else if previous.is_empty() || !deltas.is_empty() {
+            if redraw {
+                // Clear the screen and move to the top-left before redrawing
+                print!("\x1b[2J\x1b[H");
+            }
+            println!(
+                "Balances at {} (every {}s, Ctrl-C to stop)",
+                chrono::Local::now().format("%H:%M:%S"),
+                interval.as_secs()
+            );
+            for r in filter_results(current.clone(), symbol) {
+                let changed = deltas.iter().any(|d| d.token == r.token) && !previous.is_empty();
+                println!(
+                    "{} {}{}",
+                    r.balance,
+                    r.token,
+                    if changed { " *" } else { "" }
+                );
+            }
+        }
+        previous = current;
+    }
 }
 
 /// Filter results: by symbol if specified, and skip zero balances when querying all
@@ -280,6 +492,34 @@ fn cache_path(storage_path: &std::path::Path, account: &str) -> PathBuf {
     storage_path.join(format!("balance_cache_{account}.json"))
 }
 
+/// Cache files under `storage_path` whose balances are past the TTL, for `doctor` to clear
+pub fn expired_caches(storage_path: &std::path::Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(storage_path) else {
+        return Vec::new();
+    };
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("balance_cache_") && name.ends_with(".json"))
+        })
+        .filter(|path| {
+            std::fs::read_to_string(path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<BalanceCache>(&content).ok())
+                .map_or(true, |cache| {
+                    now.saturating_sub(cache.timestamp) > CACHE_TTL_SECS
+                })
+        })
+        .collect()
+}
+
 fn load_cache(storage_path: &std::path::Path, account: &str) -> Option<Vec<BalanceOutput>> {
     let path = cache_path(storage_path, account);
     let content = std::fs::read_to_string(&path).ok()?;
@@ -299,6 +539,96 @@ fn load_cache(storage_path: &std::path::Path, account: &str) -> Option<Vec<Balan
     }
 }
 
+/// Drop an account's cached balances after it submits a transaction that may have moved tokens.
+/// `moves_funds` marks transactions known to spend tokens regardless of their call targets
+/// (self-paid fees, purchases settled by a marketplace contract); otherwise the cache is only
+/// dropped when a call targets a known token contract.
+pub fn invalidate_cache_after_tx(
+    config: &Config,
+    storage_path: &std::path::Path,
+    account_address: Felt,
+    targets: &[Felt],
+    moves_funds: bool,
+) {
+    if let Some(path) =
+        invalidated_cache_path(config, storage_path, account_address, targets, moves_funds)
+    {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// The balance cache a transaction to `targets` invalidates, if any
+pub fn invalidated_cache_path(
+    config: &Config,
+    storage_path: &std::path::Path,
+    account_address: Felt,
+    targets: &[Felt],
+    moves_funds: bool,
+) -> Option<PathBuf> {
+    (moves_funds || touches_known_token(config, targets))
+        .then(|| cache_path(storage_path, &format!("0x{account_address:x}")))
+}
+
+/// Whether any call target is a built-in or configured token contract
+fn touches_known_token(config: &Config, targets: &[Felt]) -> bool {
+    builtin_tokens()
+        .iter()
+        .map(|(_, info)| info.address)
+        .chain(config.tokens.values().map(String::as_str))
+        .filter_map(|address| Felt::from_hex(address).ok())
+        .any(|token| targets.contains(&token))
+}
+
+/// Fail with `InsufficientFunds` before a self-paid transaction when the account's fee token
+/// balance doesn't cover `estimated_fee` plus the configured `fee-buffer`. If the balance can't be
+/// read, a warning is emitted and submission proceeds.
+pub async fn ensure_fee_balance<P: Provider + Sync>(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    provider: &P,
+    account_address: Felt,
+    estimated_fee: Felt,
+) -> Result<()> {
+    let Some((token, decimals)) = resolve_token(config, FEE_TOKEN) else {
+        return Ok(());
+    };
+    let call = FunctionCall {
+        contract_address: token,
+        entry_point_selector: starknet::core::utils::get_selector_from_name("balance_of").unwrap(),
+        calldata: vec![account_address],
+    };
+    let balance = match provider.call(call, BlockId::Tag(BlockTag::Latest)).await {
+        Ok(result) if !result.is_empty() => match result.get(1) {
+            Some(high) if *high != Felt::ZERO => u128::MAX,
+            _ => felt_to_u128(result[0]),
+        },
+        Ok(_) => return Ok(()),
+        Err(e) => {
+            formatter.warning(&format!(
+                "Could not check the {FEE_TOKEN} balance before submitting: {e}"
+            ));
+            return Ok(());
+        }
+    };
+
+    let fee = u128::try_from(estimated_fee).unwrap_or(u128::MAX);
+    match fee_shortfall(fee, config.cli.fee_buffer_percent, balance) {
+        Some((required, shortfall)) => Err(CliError::InsufficientFunds {
+            token: FEE_TOKEN.to_string(),
+            required: format_u128_balance(required, decimals),
+            available: format_u128_balance(balance, decimals),
+            shortfall: format_u128_balance(shortfall, decimals),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// (required, shortfall) when `balance` is below `fee` plus `buffer_percent`, in raw units
+fn fee_shortfall(fee: u128, buffer_percent: u64, balance: u128) -> Option<(u128, u128)> {
+    let required = fee.saturating_add(fee.saturating_mul(buffer_percent as u128) / 100);
+    (balance < required).then(|| (required, required - balance))
+}
+
 fn save_cache(storage_path: &std::path::Path, account: &str, balances: &[BalanceOutput]) {
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -311,7 +641,7 @@ fn save_cache(storage_path: &std::path::Path, account: &str, balances: &[Balance
     };
 
     if let Ok(json) = serde_json::to_string(&cache) {
-        let _ = std::fs::write(cache_path(storage_path, account), json);
+        let _ = super::storage::write_atomic(&cache_path(storage_path, account), json);
     }
 }
 
@@ -344,7 +674,7 @@ fn format_u256_balance(low: Felt, high: Felt, decimals: u8) -> String {
 }
 
 /// Format a u128 balance with the given number of decimals (up to 6 visible decimal places)
-fn format_u128_balance(value: u128, decimals: u8) -> String {
+pub fn format_u128_balance(value: u128, decimals: u8) -> String {
     if decimals == 0 {
         return value.to_string();
     }
@@ -360,33 +690,6 @@ fn format_u128_balance(value: u128, decimals: u8) -> String {
     format!("{whole}.{truncated}")
 }
 
-/// Resolve RPC URL from chain_id, explicit rpc_url, or config
-fn resolve_rpc_url(
-    chain_id: Option<String>,
-    rpc_url: Option<String>,
-    config: &Config,
-    formatter: &dyn OutputFormatter,
-) -> Result<String> {
-    if let Some(url) = rpc_url {
-        return Ok(url);
-    }
-
-    if let Some(chain) = chain_id {
-        match chain.as_str() {
-            "SN_MAIN" => Ok("https://api.cartridge.gg/x/starknet/mainnet".to_string()),
-            "SN_SEPOLIA" => Ok("https://api.cartridge.gg/x/starknet/sepolia".to_string()),
-            _ => Err(CliError::InvalidInput(format!(
-                "Unsupported chain ID '{chain}'. Supported chains: SN_MAIN, SN_SEPOLIA"
-            ))),
-        }
-    } else if !config.session.rpc_url.is_empty() {
-        Ok(config.session.rpc_url.clone())
-    } else {
-        formatter.warning("No --chain-id or --rpc-url specified, using SN_SEPOLIA by default");
-        Ok("https://api.cartridge.gg/x/starknet/sepolia".to_string())
-    }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct BalanceOutput {
     token: String,
@@ -394,3 +697,104 @@ struct BalanceOutput {
     raw: String,
     contract: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_fee_shortfall_with_buffer() {
+        assert_eq!(fee_shortfall(100, 20, 120), None);
+        assert_eq!(fee_shortfall(100, 20, 119), Some((120, 1)));
+        assert_eq!(fee_shortfall(100, 0, 50), Some((100, 50)));
+        assert_eq!(
+            fee_shortfall(u128::MAX, 20, 0),
+            Some((u128::MAX, u128::MAX))
+        );
+    }
+
+    #[test]
+    fn detects_calls_to_known_tokens() {
+        let mut config = Config::default();
+        let strk = Felt::from_hex_unchecked(
+            "0x04718f5a0Fc34cC1AF16A1cdee98fFB20C31f5cD61D6Ab07201858f4287c938D",
+        );
+        let game = Felt::from_hex_unchecked("0x123");
+
+        assert!(touches_known_token(&config, &[game, strk]));
+        assert!(!touches_known_token(&config, &[game]));
+
+        config
+            .tokens
+            .insert("GOLD".to_string(), "0x0123".to_string());
+        assert!(touches_known_token(&config, &[game]));
+    }
+
+    #[test]
+    fn invalidates_cache_after_token_transfer() {
+        let dir =
+            std::env::temp_dir().join(format!("controller-balance-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = Config::default();
+        let account = Felt::from_hex_unchecked("0xabc");
+        let game = Felt::from_hex_unchecked("0x123");
+
+        save_cache(&dir, "0xabc", &[]);
+        invalidate_cache_after_tx(&config, &dir, account, &[game], false);
+        assert!(load_cache(&dir, "0xabc").is_some());
+
+        invalidate_cache_after_tx(&config, &dir, account, &[game], true);
+        assert!(load_cache(&dir, "0xabc").is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn parses_token_amounts() {
+        assert_eq!(parse_token_amount("100", 6), Some(100_000_000));
+        assert_eq!(parse_token_amount("0.5", 18), Some(500_000_000_000_000_000));
+        assert_eq!(parse_token_amount(".25", 2), Some(25));
+        assert_eq!(parse_token_amount("1.234", 2), None);
+        assert_eq!(parse_token_amount("lots", 18), None);
+        assert_eq!(parse_token_amount("", 18), None);
+    }
+
+    #[test]
+    fn resolves_tokens_case_insensitively() {
+        let mut config = Config::default();
+        let (_, decimals) = resolve_token(&config, "usdc").unwrap();
+        assert_eq!(decimals, 6);
+        assert!(resolve_token(&config, "GOLD").is_none());
+
+        config
+            .tokens
+            .insert("GOLD".to_string(), "0x123".to_string());
+        assert_eq!(
+            resolve_token(&config, "gold"),
+            Some((Felt::from_hex_unchecked("0x123"), 18))
+        );
+    }
+
+    #[test]
+    fn watch_reports_only_changed_balances() {
+        let balance = |token: &str, raw: &str| BalanceOutput {
+            token: token.to_string(),
+            balance: raw.to_string(),
+            raw: raw.to_string(),
+            contract: "0x1".to_string(),
+        };
+        let first = vec![balance("ETH", "0x1"), balance("STRK", "0x2")];
+        assert_eq!(balance_deltas(&[], &first).len(), 2);
+
+        let second = vec![balance("ETH", "0x1"), balance("STRK", "0x0")];
+        let deltas = balance_deltas(&first, &second);
+        assert_eq!(deltas.len(), 1);
+        assert_eq!(deltas[0].token, "STRK");
+        assert_eq!(deltas[0].previous_raw.as_deref(), Some("0x2"));
+
+        assert_eq!(parse_interval("30").unwrap().as_secs(), 30);
+        assert_eq!(parse_interval("5min").unwrap().as_secs(), 300);
+        assert!(parse_interval("1s").is_err());
+        assert!(parse_interval("soon").is_err());
+    }
+}
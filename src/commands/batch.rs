@@ -0,0 +1,232 @@
+use crate::commands::audit::{self, AuditEntry, AUDIT_FILE};
+use crate::config::Config;
+use crate::error::{CliError, Result};
+use crate::output::OutputFormatter;
+use serde::Serialize;
+use starknet::core::types::{ExecutionResult, Felt, StarknetError};
+use starknet::providers::{JsonRpcClient, Provider, ProviderError};
+
+/// Seconds between receipt fetches for transactions still pending
+const RETRY_INTERVAL_SECS: u64 = 2;
+
+/// A new batch job ID, for runs that submit several transactions under one job.
+pub fn new_job_id() -> String {
+    format!(
+        "{:x}-{}",
+        chrono::Utc::now().timestamp_millis(),
+        std::process::id()
+    )
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchStatusOutput {
+    pub job_id: String,
+    pub command: String,
+    pub chain_id: String,
+    pub transactions: Vec<BatchTransaction>,
+    pub summary: BatchSummary,
+}
+
+/// State of one submission of the job: 'confirmed', 'reverted', 'failed' (never submitted) or
+/// 'pending' (no receipt yet)
+#[derive(Debug, Serialize)]
+pub struct BatchTransaction {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<String>,
+    pub calls: usize,
+    pub state: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct BatchSummary {
+    pub total: usize,
+    pub confirmed: usize,
+    pub reverted: usize,
+    pub failed: usize,
+    pub pending: usize,
+}
+
+/// Show the per-transaction state of a batch job from the audit log. Transactions the log has no
+/// outcome for are looked up on chain, retrying up to `retries` times while receipts are missing.
+pub async fn status(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    job_id: String,
+    chain_id: Option<String>,
+    rpc_url: Option<String>,
+    retries: u32,
+    account: Option<&str>,
+) -> Result<()> {
+    let entries: Vec<AuditEntry> =
+        audit::load(&config.resolve_storage_path(None).join(AUDIT_FILE))?
+            .into_iter()
+            .filter(|entry| entry.batch_id.as_deref() == Some(job_id.as_str()))
+            .collect();
+    let Some(first) = entries.first() else {
+        return Err(CliError::NotFoundError(format!(
+            "No batch job '{job_id}' in the audit log"
+        )));
+    };
+    let (command, audited_chain) = (first.command.clone(), first.chain_id.clone());
+    let mut transactions = transactions(&entries);
+
+    if transactions.iter().any(|tx| tx.state == "pending") {
+        let rpc_url = crate::chains::resolve_rpc_url(
+            config,
+            formatter,
+            chain_id.or_else(|| Some(audited_chain.clone())),
+            rpc_url,
+            account,
+        )?;
+        let url = url::Url::parse(&rpc_url)
+            .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
+        let provider = JsonRpcClient::new(crate::api::rpc_transport(url));
+
+        for attempt in 0..=retries {
+            if attempt > 0 {
+                tokio::time::sleep(std::time::Duration::from_secs(RETRY_INTERVAL_SECS)).await;
+            }
+            let mut pending = 0;
+            for tx in transactions.iter_mut().filter(|tx| tx.state == "pending") {
+                let Some(hash) = tx.transaction_hash.as_deref() else {
+                    continue;
+                };
+                let hash = Felt::from_hex(hash).map_err(|e| {
+                    CliError::Storage(format!(
+                        "Invalid transaction hash '{hash}' in audit log: {e}"
+                    ))
+                })?;
+                match provider.get_transaction_receipt(hash).await {
+                    Ok(receipt) => match receipt.receipt.execution_result() {
+                        ExecutionResult::Succeeded => tx.state = "confirmed".to_string(),
+                        ExecutionResult::Reverted { reason } => {
+                            tx.state = "reverted".to_string();
+                            tx.error = Some(reason.clone());
+                        }
+                    },
+                    Err(ProviderError::StarknetError(StarknetError::TransactionHashNotFound)) => {
+                        pending += 1
+                    }
+                    Err(e) => {
+                        return Err(CliError::ApiError(format!(
+                            "Failed to get transaction receipt: {e}"
+                        )))
+                    }
+                }
+            }
+            if pending == 0 {
+                break;
+            }
+            if attempt < retries {
+                formatter.info(&format!(
+                    "{pending} transaction(s) still pending, retrying ({}/{retries})...",
+                    attempt + 1
+                ));
+            }
+        }
+    }
+
+    let summary = summarize(&transactions);
+    formatter.success(&BatchStatusOutput {
+        job_id,
+        command,
+        chain_id: audited_chain,
+        transactions,
+        summary,
+    });
+    Ok(())
+}
+
+/// One transaction per submission of the job, in submission order, at its latest audited state.
+/// Submissions that failed before reaching the chain have no hash and are listed separately.
+fn transactions(entries: &[AuditEntry]) -> Vec<BatchTransaction> {
+    let mut transactions: Vec<BatchTransaction> = Vec::new();
+    for entry in entries {
+        let state = match entry.result.as_str() {
+            "submitted" => "pending",
+            result => result,
+        };
+        let existing = entry.transaction_hash.as_ref().and_then(|hash| {
+            transactions
+                .iter_mut()
+                .find(|tx| tx.transaction_hash.as_ref() == Some(hash))
+        });
+        match existing {
+            Some(tx) => {
+                tx.state = state.to_string();
+                tx.error = entry.error.clone();
+            }
+            None => transactions.push(BatchTransaction {
+                transaction_hash: entry.transaction_hash.clone(),
+                calls: entry.calls.len(),
+                state: state.to_string(),
+                error: entry.error.clone(),
+            }),
+        }
+    }
+    transactions
+}
+
+fn summarize(transactions: &[BatchTransaction]) -> BatchSummary {
+    let mut summary = BatchSummary {
+        total: transactions.len(),
+        ..Default::default()
+    };
+    for tx in transactions {
+        match tx.state.as_str() {
+            "confirmed" => summary.confirmed += 1,
+            "reverted" => summary.reverted += 1,
+            "failed" => summary.failed += 1,
+            _ => summary.pending += 1,
+        }
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(hash: Option<&str>, result: &str) -> AuditEntry {
+        let mut entry = AuditEntry::new(
+            "execute",
+            None,
+            Felt::from(0xabcu64),
+            "SN_SEPOLIA",
+            Vec::new(),
+            Vec::new(),
+            true,
+        );
+        entry.batch_id = Some("job".to_string());
+        entry.transaction_hash = hash.map(str::to_string);
+        entry.result = result.to_string();
+        entry
+    }
+
+    #[test]
+    fn folds_audit_lines_into_transaction_states() {
+        let entries = [
+            entry(Some("0x1"), "submitted"),
+            entry(Some("0x2"), "submitted"),
+            entry(Some("0x1"), "confirmed"),
+            entry(Some("0x3"), "submitted"),
+            entry(Some("0x3"), "reverted"),
+            entry(None, "failed"),
+        ];
+        let transactions = transactions(&entries);
+        let states: Vec<&str> = transactions.iter().map(|tx| tx.state.as_str()).collect();
+        assert_eq!(states, ["confirmed", "pending", "reverted", "failed"]);
+        assert_eq!(
+            summarize(&transactions),
+            BatchSummary {
+                total: 4,
+                confirmed: 1,
+                reverted: 1,
+                failed: 1,
+                pending: 1,
+            }
+        );
+    }
+}
@@ -0,0 +1,362 @@
+use crate::{
+    api::RpcTransport,
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use account_sdk::{
+    controller::Controller,
+    signers::{Owner, Signer},
+    storage::{filestorage::FileSystemBackend, StorageBackend, StorageValue},
+};
+use serde::{Deserialize, Serialize};
+use starknet::core::types::{Felt, TransactionReceipt};
+use starknet::providers::{JsonRpcClient, Provider};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Recorded benchmark results, relative to the base storage path (shared across accounts)
+pub const BENCH_FILE: &str = "bench_history.json";
+
+/// Results kept per action; older ones are dropped
+const HISTORY_LIMIT: usize = 50;
+
+/// Gas growth over the previous run, in percent, that is reported as a regression
+const REGRESSION_PERCENT: f64 = 5.0;
+
+/// Resources of one run of an action
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Measurement {
+    pub l1_gas: u64,
+    pub l1_data_gas: u64,
+    pub l2_gas: u64,
+    /// Overall fee in the fee token's base unit
+    pub fee: u128,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchRecord {
+    pub recorded_at: u64,
+    /// `simulate` (fee estimates) or `execute` (confirmed receipts); only the same mode is compared
+    pub mode: String,
+    pub chain_id: String,
+    pub runs: usize,
+    pub median: Measurement,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Delta {
+    pub l1_gas: i128,
+    pub l1_data_gas: i128,
+    pub l2_gas: i128,
+    pub fee: i128,
+    /// Change in L2 gas relative to the previous run
+    pub l2_gas_percent: f64,
+}
+
+#[derive(Serialize)]
+pub struct BenchOutput {
+    pub action: String,
+    pub mode: String,
+    pub chain_id: String,
+    pub runs: usize,
+    pub median: Measurement,
+    pub samples: Vec<Measurement>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous: Option<BenchRecord>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delta: Option<Delta>,
+    pub regression: bool,
+}
+
+/// Measure the resources of a call file `runs` times, record the median under `action` and
+/// compare it with the previous recorded run of the same mode on the same chain. `simulate`
+/// estimates each run through the session without submitting; otherwise each run is submitted
+/// through `execute` and read back from its receipt.
+#[allow(clippy::too_many_arguments)]
+pub async fn action(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    file: String,
+    name: Option<String>,
+    runs: usize,
+    simulate: bool,
+    rpc_url: Option<String>,
+    account: Option<&str>,
+) -> Result<()> {
+    let runs = runs.max(1);
+    let action = name.unwrap_or_else(|| {
+        Path::new(&file)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| file.clone())
+    });
+
+    let (specs, _) = crate::commands::execute::calls_from_args(None, None, None, Some(file))?;
+    let calls = crate::commands::execute::apply_calldata_prefixes(config, formatter, specs.clone());
+    let calls = crate::commands::execute::starknet_calls(&calls)?;
+
+    let storage_path = config.resolve_storage_path(account);
+    let backend = FileSystemBackend::new(storage_path.clone());
+    let controller_metadata = backend
+        .controller()
+        .map_err(|e| CliError::Storage(e.to_string()))?
+        .ok_or_else(|| {
+            CliError::InvalidSessionData(
+                "No controller metadata found. Run 'controller session auth' to create a session."
+                    .to_string(),
+            )
+        })?;
+    let chain_id = starknet::core::utils::parse_cairo_short_string(&controller_metadata.chain_id)
+        .unwrap_or_else(|_| format!("0x{:x}", controller_metadata.chain_id));
+
+    let effective_rpc_url = rpc_url
+        .or_else(|| match backend.get("session_rpc_url").ok().flatten() {
+            Some(StorageValue::String(url)) => Some(url),
+            _ => None,
+        })
+        .unwrap_or_else(|| config.session.rpc_url.clone());
+    let rpc_parsed = url::Url::parse(&effective_rpc_url)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
+
+    let mut samples = Vec::with_capacity(runs);
+    if simulate {
+        let session_key = format!(
+            "@cartridge/session/0x{:x}/0x{:x}",
+            controller_metadata.address, controller_metadata.chain_id
+        );
+        let credentials = backend
+            .session(&session_key)
+            .map_err(|e| CliError::Storage(e.to_string()))?
+            .ok_or(CliError::NoSession)?
+            .credentials
+            .ok_or_else(|| CliError::InvalidSessionData("No credentials found".to_string()))?;
+        let signing_key =
+            starknet::signers::SigningKey::from_secret_scalar(credentials.private_key);
+        let controller = Controller::new(
+            controller_metadata.username.clone(),
+            controller_metadata.class_hash,
+            rpc_parsed,
+            Owner::Signer(Signer::Starknet(signing_key)),
+            controller_metadata.address,
+            Some(backend),
+        )
+        .await
+        .map_err(|e| CliError::Storage(format!("Failed to create controller: {e}")))?;
+
+        for run in 1..=runs {
+            formatter.info(&format!("Simulating '{action}' ({run}/{runs})..."));
+            let estimate = controller
+                .estimate_invoke_fee(calls.clone())
+                .await
+                .map_err(|e| CliError::TransactionFailed(format!("Simulation failed: {e}")))?;
+            samples.push(Measurement {
+                l1_gas: estimate.l1_gas_consumed,
+                l1_data_gas: estimate.l1_data_gas_consumed,
+                l2_gas: estimate.l2_gas_consumed,
+                fee: estimate.overall_fee,
+            });
+        }
+    } else {
+        let provider = JsonRpcClient::new(crate::api::rpc_transport(rpc_parsed));
+        for run in 1..=runs {
+            formatter.info(&format!("Executing '{action}' ({run}/{runs})..."));
+            let transaction_hash = crate::commands::execute::execute_calls(
+                config,
+                formatter,
+                specs.clone(),
+                crate::commands::execute::ExecuteOptions {
+                    wait: true,
+                    ..Default::default()
+                },
+                account,
+            )
+            .await?
+            .into_submitted(&format!("Run {run} of '{action}'"))?
+            .transaction_hash;
+            samples.push(measure_receipt(&provider, &transaction_hash).await?);
+        }
+    }
+
+    let median = median(&samples);
+    let mode = if simulate { "simulate" } else { "execute" }.to_string();
+    let record = BenchRecord {
+        recorded_at: chrono::Utc::now().timestamp() as u64,
+        mode: mode.clone(),
+        chain_id: chain_id.clone(),
+        runs,
+        median,
+    };
+
+    // Shared across accounts, like the lookup cache: the action measures contracts, not sessions
+    let history_path = config.resolve_storage_path(None).join(BENCH_FILE);
+    let mut history = load(&history_path);
+    let previous = previous(&history, &action, &mode, &chain_id).cloned();
+    let delta = previous
+        .as_ref()
+        .map(|previous| delta(&previous.median, &median));
+    let regression = delta
+        .as_ref()
+        .is_some_and(|delta| delta.l2_gas_percent > REGRESSION_PERCENT || delta.l1_data_gas > 0);
+    if let Some(delta) = delta.as_ref().filter(|_| regression) {
+        formatter.warning(&format!(
+            "'{action}' costs more than its last run: L2 gas {:+} ({:+.1}%), L1 data gas {:+}",
+            delta.l2_gas, delta.l2_gas_percent, delta.l1_data_gas
+        ));
+    }
+    record_run(&mut history, &action, record);
+    save(&history_path, &history)?;
+
+    formatter.success(&BenchOutput {
+        action,
+        mode,
+        chain_id,
+        runs,
+        median,
+        samples,
+        previous,
+        delta,
+        regression,
+    });
+    Ok(())
+}
+
+async fn measure_receipt(
+    provider: &JsonRpcClient<RpcTransport>,
+    transaction_hash: &str,
+) -> Result<Measurement> {
+    let hash = Felt::from_hex(transaction_hash)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid transaction hash: {e}")))?;
+    let receipt = provider
+        .get_transaction_receipt(hash)
+        .await
+        .map_err(|e| CliError::ApiError(format!("Failed to get transaction receipt: {e}")))?
+        .receipt;
+    let (resources, fee) = match &receipt {
+        TransactionReceipt::Invoke(r) => (&r.execution_resources, r.actual_fee.amount),
+        _ => {
+            return Err(CliError::TransactionFailed(format!(
+                "{transaction_hash} is not an invoke transaction"
+            )))
+        }
+    };
+    Ok(Measurement {
+        l1_gas: resources.l1_gas,
+        l1_data_gas: resources.l1_data_gas,
+        l2_gas: resources.l2_gas,
+        fee: u128::try_from(fee).unwrap_or(u128::MAX),
+    })
+}
+
+/// Per-field median, so one noisy run doesn't move the recorded figure
+fn median(samples: &[Measurement]) -> Measurement {
+    fn mid<T: Copy + Ord>(mut values: Vec<T>) -> T {
+        values.sort_unstable();
+        values[values.len() / 2]
+    }
+    Measurement {
+        l1_gas: mid(samples.iter().map(|s| s.l1_gas).collect()),
+        l1_data_gas: mid(samples.iter().map(|s| s.l1_data_gas).collect()),
+        l2_gas: mid(samples.iter().map(|s| s.l2_gas).collect()),
+        fee: mid(samples.iter().map(|s| s.fee).collect()),
+    }
+}
+
+fn delta(previous: &Measurement, current: &Measurement) -> Delta {
+    let l2_gas = current.l2_gas as i128 - previous.l2_gas as i128;
+    Delta {
+        l1_gas: current.l1_gas as i128 - previous.l1_gas as i128,
+        l1_data_gas: current.l1_data_gas as i128 - previous.l1_data_gas as i128,
+        l2_gas,
+        fee: current.fee as i128 - previous.fee as i128,
+        l2_gas_percent: match previous.l2_gas {
+            0 => 0.0,
+            base => l2_gas as f64 * 100.0 / base as f64,
+        },
+    }
+}
+
+fn previous<'a>(
+    history: &'a BTreeMap<String, Vec<BenchRecord>>,
+    action: &str,
+    mode: &str,
+    chain_id: &str,
+) -> Option<&'a BenchRecord> {
+    history
+        .get(action)?
+        .iter()
+        .rev()
+        .find(|record| record.mode == mode && record.chain_id == chain_id)
+}
+
+fn record_run(history: &mut BTreeMap<String, Vec<BenchRecord>>, action: &str, record: BenchRecord) {
+    let records = history.entry(action.to_string()).or_default();
+    records.push(record);
+    if records.len() > HISTORY_LIMIT {
+        records.drain(..records.len() - HISTORY_LIMIT);
+    }
+}
+
+fn load(path: &Path) -> BTreeMap<String, Vec<BenchRecord>> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save(path: &Path, history: &BTreeMap<String, Vec<BenchRecord>>) -> Result<()> {
+    let json = serde_json::to_string_pretty(history)
+        .map_err(|e| CliError::Storage(format!("Failed to serialize bench history: {e}")))?;
+    crate::commands::storage::write_atomic(path, json).map_err(|e| CliError::FileError {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measurement(l2_gas: u64) -> Measurement {
+        Measurement {
+            l1_gas: 0,
+            l1_data_gas: 128,
+            l2_gas,
+            fee: l2_gas as u128 * 10,
+        }
+    }
+
+    fn record(mode: &str, l2_gas: u64) -> BenchRecord {
+        BenchRecord {
+            recorded_at: 0,
+            mode: mode.to_string(),
+            chain_id: "SN_SEPOLIA".to_string(),
+            runs: 3,
+            median: measurement(l2_gas),
+        }
+    }
+
+    #[test]
+    fn compares_with_previous_run_of_same_mode() {
+        let samples = [measurement(900), measurement(1_000_000), measurement(1000)];
+        assert_eq!(median(&samples).l2_gas, 1000);
+
+        let mut history = BTreeMap::new();
+        record_run(&mut history, "move", record("simulate", 1000));
+        record_run(&mut history, "move", record("execute", 2000));
+        let last = previous(&history, "move", "simulate", "SN_SEPOLIA").unwrap();
+        assert_eq!(last.median.l2_gas, 1000);
+        assert!(previous(&history, "move", "simulate", "SN_MAIN").is_none());
+        assert!(previous(&history, "attack", "simulate", "SN_SEPOLIA").is_none());
+
+        let change = delta(&last.median, &measurement(1100));
+        assert_eq!(change.l2_gas, 100);
+        assert_eq!(change.fee, 1000);
+        assert!((change.l2_gas_percent - 10.0).abs() < f64::EPSILON);
+
+        for _ in 0..HISTORY_LIMIT {
+            record_run(&mut history, "move", record("simulate", 1));
+        }
+        assert_eq!(history["move"].len(), HISTORY_LIMIT);
+    }
+}
@@ -1,10 +1,16 @@
-use crate::commands::calldata::parse_calldata_value;
+use crate::api::RpcTransport;
+use crate::commands::calldata::{parse_calldata_value, resolve_selector};
 use crate::config::Config;
 use crate::error::{CliError, Result};
 use crate::output::OutputFormatter;
+use futures::stream::{self, StreamExt};
 use serde::{Deserialize, Serialize};
-use starknet::core::types::{BlockId, BlockTag, Felt, FunctionCall};
-use starknet::providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider};
+use starknet::core::types::{
+    BlockId, BlockTag, Felt, FunctionCall, MaybePreConfirmedBlockWithTxHashes,
+};
+use starknet::providers::{JsonRpcClient, Provider, ProviderError};
+use std::time::Duration;
+use tokio::time::Instant;
 
 /// Execute a read-only call to a contract
 #[allow(clippy::too_many_arguments)]
@@ -18,14 +24,18 @@ pub async fn execute(
     chain_id: Option<String>,
     rpc_url: Option<String>,
     block_id: Option<String>,
+    block_range: Option<String>,
+    concurrency: usize,
+    rate: Option<String>,
+    account: Option<&str>,
 ) -> Result<()> {
     // Determine RPC URL
-    let rpc_url = resolve_rpc_url(chain_id, rpc_url, config, formatter)?;
+    let rpc_url = crate::chains::resolve_rpc_url(config, formatter, chain_id, rpc_url, account)?;
 
     // Build the provider
     let url = url::Url::parse(&rpc_url)
         .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
-    let provider = JsonRpcClient::new(HttpTransport::new(url));
+    let provider = JsonRpcClient::new(crate::api::rpc_transport(url));
 
     // Parse block ID (default to latest)
     let block_id = parse_block_id(block_id)?;
@@ -33,28 +43,15 @@ pub async fn execute(
     // Handle file input for multiple calls
     if let Some(file_path) = file {
         let calls = parse_calls_file(&file_path)?;
-        let mut results = Vec::new();
-
-        for call in calls {
-            match execute_single_call(&provider, &call, block_id).await {
-                Ok(result) => results.push(CallResult {
-                    contract: call.contract_address.clone(),
-                    entrypoint: call.entrypoint.clone(),
-                    success: true,
-                    result: Some(result),
-                    error: None,
-                }),
-                Err(e) => results.push(CallResult {
-                    contract: call.contract_address.clone(),
-                    entrypoint: call.entrypoint.clone(),
-                    success: false,
-                    result: None,
-                    error: Some(e.to_string()),
-                }),
-            }
+        let rate = rate.as_deref().map(parse_rate).transpose()?;
+        let output = execute_batch(&provider, &calls, block_id, concurrency, rate).await;
+        if output.summary.failed > 0 {
+            formatter.warning(&format!(
+                "{} of {} calls failed",
+                output.summary.failed, output.summary.total
+            ));
         }
-
-        formatter.success(&CallBatchOutput { calls: results });
+        formatter.success(&output);
         return Ok(());
     }
 
@@ -71,22 +68,37 @@ pub async fn execute(
         calldata: parse_calldata(calldata)?,
     };
 
+    if let Some(range) = block_range {
+        let range = BlockRange::parse(&range)?;
+        let series = execute_block_range(&provider, &call, &range).await;
+        formatter.success(&series);
+        return Ok(());
+    }
+
     let result = execute_single_call(&provider, &call, block_id).await?;
 
     formatter.success(&result);
     Ok(())
 }
 
-async fn execute_single_call(
-    provider: &JsonRpcClient<HttpTransport>,
+pub async fn execute_single_call(
+    provider: &JsonRpcClient<RpcTransport>,
     call: &ContractCall,
     block_id: BlockId,
 ) -> Result<Vec<String>> {
+    let result = provider
+        .call(function_call(call)?, block_id)
+        .await
+        .map_err(|e| CliError::TransactionFailed(format!("Call failed: {e}")))?;
+
+    Ok(format_result(&result))
+}
+
+fn function_call(call: &ContractCall) -> Result<FunctionCall> {
     let contract_address = Felt::from_hex(&call.contract_address)
         .map_err(|e| CliError::InvalidInput(format!("Invalid contract address: {e}")))?;
 
-    let selector = starknet::core::utils::get_selector_from_name(&call.entrypoint)
-        .map_err(|e| CliError::InvalidInput(format!("Invalid entrypoint name: {e}")))?;
+    let selector = resolve_selector(&call.entrypoint)?;
 
     let calldata: Vec<Felt> = call
         .calldata
@@ -97,21 +109,294 @@ async fn execute_single_call(
         .flatten()
         .collect();
 
-    let function_call = FunctionCall {
+    Ok(FunctionCall {
         contract_address,
         entry_point_selector: selector,
         calldata,
+    })
+}
+
+fn format_result(result: &[Felt]) -> Vec<String> {
+    result.iter().map(|f| format!("0x{f:x}")).collect()
+}
+
+/// Extra rounds given to calls that failed with a transient error (rate limits, transport)
+const BATCH_RETRIES: u32 = 2;
+
+/// Requests allowed per `interval`, parsed from `--rate` (e.g. '10/s', '300/m')
+#[derive(Debug, PartialEq)]
+struct RateBudget {
+    interval: Duration,
+}
+
+fn parse_rate(value: &str) -> Result<RateBudget> {
+    let invalid = || {
+        CliError::InvalidInput(format!(
+            "Invalid rate '{value}'. Expected requests per second or minute (e.g., 10/s, 300/m)"
+        ))
     };
+    let (count, unit) = value.split_once('/').unwrap_or((value, "s"));
+    let count = count.trim().parse::<u32>().map_err(|_| invalid())?;
+    let period = match unit.trim() {
+        "s" | "sec" => Duration::from_secs(1),
+        "m" | "min" => Duration::from_secs(60),
+        _ => return Err(invalid()),
+    };
+    if count == 0 {
+        return Err(invalid());
+    }
+    Ok(RateBudget {
+        interval: period / count,
+    })
+}
 
-    let result = provider
-        .call(function_call, block_id)
-        .await
-        .map_err(|e| CliError::TransactionFailed(format!("Call failed: {e}")))?;
+/// Spaces out request starts across all workers so the RPC sees at most the configured rate
+struct Pacer {
+    interval: Duration,
+    next: tokio::sync::Mutex<Instant>,
+}
+
+impl Pacer {
+    fn new(budget: &RateBudget) -> Self {
+        Pacer {
+            interval: budget.interval,
+            next: tokio::sync::Mutex::new(Instant::now()),
+        }
+    }
 
-    Ok(result.iter().map(|f| format!("0x{f:x}")).collect())
+    async fn wait(&self) {
+        let slot = {
+            let mut next = self.next.lock().await;
+            let slot = (*next).max(Instant::now());
+            *next = slot + self.interval;
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+enum Attempt {
+    Done(std::result::Result<Vec<String>, String>),
+    /// Worth retrying: the RPC was rate limiting or unreachable, not rejecting the call
+    Transient(String),
+}
+
+async fn attempt_call(
+    provider: &JsonRpcClient<RpcTransport>,
+    call: &ContractCall,
+    block_id: BlockId,
+    pacer: Option<&Pacer>,
+) -> Attempt {
+    let request = match function_call(call) {
+        Ok(request) => request,
+        Err(e) => return Attempt::Done(Err(e.to_string())),
+    };
+    if let Some(pacer) = pacer {
+        pacer.wait().await;
+    }
+    match provider.call(request, block_id).await {
+        Ok(result) => Attempt::Done(Ok(format_result(&result))),
+        Err(e @ (ProviderError::RateLimited | ProviderError::Other(_))) => {
+            Attempt::Transient(format!("Call failed: {e}"))
+        }
+        Err(e) => Attempt::Done(Err(format!("Call failed: {e}"))),
+    }
+}
+
+/// Run a calls file with up to `concurrency` calls in flight. A failing call never stops the
+/// batch; transient failures are queued and retried after the first pass.
+async fn execute_batch(
+    provider: &JsonRpcClient<RpcTransport>,
+    calls: &[ContractCall],
+    block_id: BlockId,
+    concurrency: usize,
+    rate: Option<RateBudget>,
+) -> CallBatchOutput {
+    let pacer = rate.as_ref().map(Pacer::new);
+    let mut outcomes: Vec<Option<std::result::Result<Vec<String>, String>>> =
+        calls.iter().map(|_| None).collect();
+    let mut attempts = vec![0u32; calls.len()];
+    let mut pending: Vec<usize> = (0..calls.len()).collect();
+
+    for round in 0..=BATCH_RETRIES {
+        if pending.is_empty() {
+            break;
+        }
+        if round > 0 {
+            tokio::time::sleep(Duration::from_millis(500 * 2u64.pow(round - 1))).await;
+            crate::meta::retries(pending.len() as u64);
+        }
+        let finished: Vec<(usize, Attempt)> = stream::iter(pending.iter().copied())
+            .map(|i| {
+                let pacer = pacer.as_ref();
+                async move { (i, attempt_call(provider, &calls[i], block_id, pacer).await) }
+            })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await;
+
+        pending.clear();
+        for (i, attempt) in finished {
+            attempts[i] += 1;
+            match attempt {
+                Attempt::Done(outcome) => outcomes[i] = Some(outcome),
+                Attempt::Transient(error) => {
+                    outcomes[i] = Some(Err(error));
+                    pending.push(i);
+                }
+            }
+        }
+    }
+
+    let results: Vec<CallResult> = calls
+        .iter()
+        .zip(outcomes)
+        .zip(attempts)
+        .map(|((call, outcome), attempts)| {
+            let (result, error) = match outcome {
+                Some(Ok(result)) => (Some(result), None),
+                Some(Err(error)) => (None, Some(error)),
+                None => (None, None),
+            };
+            CallResult {
+                contract: call.contract_address.clone(),
+                entrypoint: call.entrypoint.clone(),
+                success: result.is_some(),
+                result,
+                error,
+                attempts,
+            }
+        })
+        .collect();
+
+    let succeeded = results.iter().filter(|r| r.success).count();
+    CallBatchOutput {
+        summary: BatchSummary {
+            total: results.len(),
+            succeeded,
+            failed: results.len() - succeeded,
+            retried: results.iter().filter(|r| r.attempts > 1).count(),
+        },
+        calls: results,
+    }
 }
 
-fn parse_block_id(block_id: Option<String>) -> Result<BlockId> {
+/// Maximum number of blocks sampled by a single `--block-range` sweep
+const MAX_RANGE_SAMPLES: u64 = 1000;
+
+/// Inclusive `start:end[:step]` block range for time-series calls
+#[derive(Debug, PartialEq)]
+struct BlockRange {
+    start: u64,
+    end: u64,
+    step: u64,
+}
+
+impl BlockRange {
+    fn parse(value: &str) -> Result<Self> {
+        let invalid = || {
+            CliError::InvalidInput(format!(
+                "Invalid block range '{value}'. Expected start:end or start:end:step (e.g., 100000:100100:10)"
+            ))
+        };
+
+        let parts: Vec<&str> = value.split(':').collect();
+        let (start, end, step) = match parts.as_slice() {
+            [start, end] => (*start, *end, "1"),
+            [start, end, step] => (*start, *end, *step),
+            _ => return Err(invalid()),
+        };
+        let start = start.trim().parse::<u64>().map_err(|_| invalid())?;
+        let end = end.trim().parse::<u64>().map_err(|_| invalid())?;
+        let step = step.trim().parse::<u64>().map_err(|_| invalid())?;
+
+        if start > end || step == 0 {
+            return Err(invalid());
+        }
+
+        let range = BlockRange { start, end, step };
+        if range.samples() > MAX_RANGE_SAMPLES {
+            return Err(CliError::InvalidInput(format!(
+                "Block range covers {} samples (max {MAX_RANGE_SAMPLES}). Increase the step",
+                range.samples()
+            )));
+        }
+        Ok(range)
+    }
+
+    fn samples(&self) -> u64 {
+        (self.end - self.start) / self.step + 1
+    }
+
+    fn blocks(&self) -> impl Iterator<Item = u64> + '_ {
+        (self.start..=self.end).step_by(self.step as usize)
+    }
+}
+
+/// Evaluate a call at every block in the range. Failures are recorded per point so one missing
+/// block doesn't discard the rest of the series.
+async fn execute_block_range(
+    provider: &JsonRpcClient<RpcTransport>,
+    call: &ContractCall,
+    range: &BlockRange,
+) -> CallSeriesOutput {
+    let mut points = Vec::new();
+
+    for block_number in range.blocks() {
+        let block_id = BlockId::Number(block_number);
+        let timestamp = match provider.get_block_with_tx_hashes(block_id).await {
+            Ok(MaybePreConfirmedBlockWithTxHashes::Block(block)) => Some(block.timestamp),
+            Ok(MaybePreConfirmedBlockWithTxHashes::PreConfirmedBlock(block)) => {
+                Some(block.timestamp)
+            }
+            Err(_) => None,
+        };
+
+        match execute_single_call(provider, call, block_id).await {
+            Ok(result) => points.push(SeriesPoint {
+                block_number,
+                timestamp,
+                value: decode_value(&result),
+                result: Some(result),
+                error: None,
+            }),
+            Err(e) => points.push(SeriesPoint {
+                block_number,
+                timestamp,
+                value: None,
+                result: None,
+                error: Some(e.to_string()),
+            }),
+        }
+    }
+
+    CallSeriesOutput {
+        contract: call.contract_address.clone(),
+        entrypoint: call.entrypoint.clone(),
+        points,
+    }
+}
+
+/// Decode single-felt results as a decimal and two-felt results as a u256 (low, high).
+/// Other shapes are left to the caller via the raw result.
+pub fn decode_value(result: &[String]) -> Option<String> {
+    let felts: Vec<Felt> = result
+        .iter()
+        .map(|r| Felt::from_hex(r).ok())
+        .collect::<Option<_>>()?;
+    match felts.as_slice() {
+        [value] => Some(value.to_string()),
+        [low, high] if *high == Felt::ZERO => Some(low.to_string()),
+        [low, high] => {
+            let low: u128 = (*low).try_into().ok()?;
+            let high: u128 = (*high).try_into().ok()?;
+            Some(format!("0x{high:x}{low:032x}"))
+        }
+        _ => None,
+    }
+}
+
+pub fn parse_block_id(block_id: Option<String>) -> Result<BlockId> {
     match block_id.as_deref() {
         None | Some("latest") => Ok(BlockId::Tag(BlockTag::Latest)),
         Some(num) if num.starts_with("0x") => {
@@ -135,14 +420,14 @@ fn parse_calldata(calldata: Option<String>) -> Result<Vec<String>> {
     }
 }
 
-fn parse_calls_file(file_path: &str) -> Result<Vec<ContractCall>> {
+pub fn parse_calls_file(file_path: &str) -> Result<Vec<ContractCall>> {
     let content = std::fs::read_to_string(file_path).map_err(|e| CliError::FileError {
         path: file_path.to_string(),
         message: e.to_string(),
     })?;
 
-    let file: CallsFile = serde_json::from_str(&content)
-        .map_err(|e| CliError::InvalidInput(format!("Invalid JSON in calls file: {e}")))?;
+    let file: CallsFile = crate::commands::file_format::parse_structured(file_path, &content)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid calls file: {e}")))?;
 
     Ok(file.calls)
 }
@@ -153,11 +438,11 @@ struct CallsFile {
 }
 
 #[derive(Debug, Deserialize)]
-struct ContractCall {
+pub struct ContractCall {
     #[serde(rename = "contractAddress")]
-    contract_address: String,
-    entrypoint: String,
-    calldata: Vec<String>,
+    pub contract_address: String,
+    pub entrypoint: String,
+    pub calldata: Vec<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -167,40 +452,94 @@ struct CallResult {
     success: bool,
     result: Option<Vec<String>>,
     error: Option<String>,
+    attempts: u32,
 }
 
 #[derive(Debug, Serialize)]
 struct CallBatchOutput {
     calls: Vec<CallResult>,
+    summary: BatchSummary,
 }
 
-/// Resolve RPC URL from chain_id, explicit rpc_url, or config
-fn resolve_rpc_url(
-    chain_id: Option<String>,
-    rpc_url: Option<String>,
-    config: &Config,
-    formatter: &dyn OutputFormatter,
-) -> Result<String> {
-    // If explicit RPC URL provided, use it
-    if let Some(url) = rpc_url {
-        return Ok(url);
-    }
-
-    // If chain_id provided, map to known RPC URL
-    if let Some(chain) = chain_id {
-        match chain.as_str() {
-            "SN_MAIN" => Ok("https://api.cartridge.gg/x/starknet/mainnet".to_string()),
-            "SN_SEPOLIA" => Ok("https://api.cartridge.gg/x/starknet/sepolia".to_string()),
-            _ => Err(CliError::InvalidInput(format!(
-                "Unsupported chain ID '{chain}'. Supported chains: SN_MAIN, SN_SEPOLIA"
-            ))),
-        }
-    } else if !config.session.rpc_url.is_empty() {
-        // Fall back to config default
-        Ok(config.session.rpc_url.clone())
-    } else {
-        // No chain_id, no rpc_url, no config default - use Sepolia with warning
-        formatter.warning("No --chain-id or --rpc-url specified, using SN_SEPOLIA by default");
-        Ok("https://api.cartridge.gg/x/starknet/sepolia".to_string())
+#[derive(Debug, Serialize)]
+struct BatchSummary {
+    total: usize,
+    succeeded: usize,
+    failed: usize,
+    /// Calls that needed more than one attempt
+    retried: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct SeriesPoint {
+    block_number: u64,
+    timestamp: Option<u64>,
+    value: Option<String>,
+    result: Option<Vec<String>>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CallSeriesOutput {
+    contract: String,
+    entrypoint: String,
+    points: Vec<SeriesPoint>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_block_ranges() {
+        assert_eq!(
+            BlockRange::parse("100000:100100:10").unwrap(),
+            BlockRange {
+                start: 100000,
+                end: 100100,
+                step: 10
+            }
+        );
+        let range = BlockRange::parse("5:7").unwrap();
+        assert_eq!(range.blocks().collect::<Vec<_>>(), vec![5, 6, 7]);
+        assert_eq!(BlockRange::parse("0:10:4").unwrap().samples(), 3);
+
+        assert!(BlockRange::parse("10:5").is_err());
+        assert!(BlockRange::parse("1:10:0").is_err());
+        assert!(BlockRange::parse("latest").is_err());
+        assert!(BlockRange::parse("0:100000").is_err());
+    }
+
+    #[test]
+    fn parses_rate_budgets() {
+        assert_eq!(
+            parse_rate("10/s").unwrap().interval,
+            Duration::from_millis(100)
+        );
+        assert_eq!(
+            parse_rate("300/m").unwrap().interval,
+            Duration::from_millis(200)
+        );
+        assert_eq!(
+            parse_rate("4").unwrap().interval,
+            Duration::from_millis(250)
+        );
+        assert!(parse_rate("0/s").is_err());
+        assert!(parse_rate("10/h").is_err());
+        assert!(parse_rate("fast").is_err());
+    }
+
+    #[test]
+    fn decodes_felt_and_u256_values() {
+        assert_eq!(decode_value(&["0x64".to_string()]).as_deref(), Some("100"));
+        assert_eq!(
+            decode_value(&["0x64".to_string(), "0x0".to_string()]).as_deref(),
+            Some("100")
+        );
+        assert_eq!(
+            decode_value(&["0x1".to_string(), "0x1".to_string()]).as_deref(),
+            Some("0x100000000000000000000000000000001")
+        );
+        assert!(decode_value(&["0x1".to_string(), "0x2".to_string(), "0x3".to_string()]).is_none());
     }
 }
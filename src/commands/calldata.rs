@@ -1,7 +1,10 @@
 use cainome_cairo_serde::{ByteArray, Bytes31, CairoSerde};
 
 use crate::error::{CliError, Result};
-use starknet::core::{types::Felt, utils::cairo_short_string_to_felt};
+use starknet::core::{
+    types::Felt,
+    utils::{cairo_short_string_to_felt, get_selector_from_name},
+};
 
 /// Parse a calldata value, handling special prefixes (u256:, str:, bytearray:) and default felt
 /// parsing.
@@ -89,6 +92,18 @@ pub fn parse_calldata_value(value: &str) -> Result<Vec<Felt>> {
     }
 }
 
+/// Resolve an entrypoint to its selector. Accepts either a function name (hashed with
+/// `starknet_keccak`) or a raw selector written as `selector:0x...`.
+pub fn resolve_selector(entrypoint: &str) -> Result<Felt> {
+    if let Some(raw) = entrypoint.strip_prefix("selector:") {
+        Felt::from_hex(raw.trim())
+            .map_err(|e| CliError::InvalidInput(format!("Invalid raw selector '{raw}': {e}")))
+    } else {
+        get_selector_from_name(entrypoint)
+            .map_err(|e| CliError::InvalidInput(format!("Invalid entrypoint '{entrypoint}': {e}")))
+    }
+}
+
 /// Construct a `ByteArray` from raw bytes, chunking into 31-byte segments.
 fn byte_array_from_bytes(
     bytes: &[u8],
@@ -292,4 +307,18 @@ mod tests {
             vec![Felt::from(0_u128), Felt::ZERO, Felt::from(0_u128)]
         );
     }
+
+    #[test]
+    fn test_resolve_selector_by_name() {
+        let selector = resolve_selector("transfer").unwrap();
+        assert_eq!(selector, get_selector_from_name("transfer").unwrap());
+    }
+
+    #[test]
+    fn test_resolve_raw_selector() {
+        let transfer = get_selector_from_name("transfer").unwrap();
+        let raw = format!("selector:{transfer:#x}");
+        assert_eq!(resolve_selector(&raw).unwrap(), transfer);
+        assert!(resolve_selector("selector:not-hex").is_err());
+    }
 }
@@ -1,4 +1,8 @@
-use crate::{config::Config, error::Result, output::OutputFormatter};
+use crate::{
+    config::Config,
+    error::{InputRequest, Result},
+    output::OutputFormatter,
+};
 use account_sdk::storage::{filestorage::FileSystemBackend, StorageBackend};
 use serde::Serialize;
 
@@ -19,19 +23,13 @@ pub async fn execute(
 
     if !skip_confirm && !config.cli.json_output {
         // In human mode, ask for confirmation
-        println!(
-            "This will delete all stored session data at: {}",
-            storage_path.display()
-        );
-        println!("Are you sure? (y/N): ");
-
-        let mut input = String::new();
-        std::io::stdin().read_line(&mut input).ok();
-
-        if !input.trim().eq_ignore_ascii_case("y") && !input.trim().eq_ignore_ascii_case("yes") {
-            formatter.info("Cancelled.");
-            return Ok(());
-        }
+        return Err(InputRequest::confirm(
+            format!(
+                "This will delete all stored session data at {}. Are you sure?",
+                storage_path.display()
+            ),
+            "--yes",
+        ));
     }
 
     backend
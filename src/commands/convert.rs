@@ -0,0 +1,284 @@
+use crate::{
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use serde::Serialize;
+use starknet::core::types::Felt;
+use starknet::core::utils::{cairo_short_string_to_felt, parse_cairo_short_string};
+
+/// Representations `convert` reads and writes
+const FORMATS: &[&str] = &["felt", "hex", "dec", "shortstr", "u256"];
+
+/// A value of up to 256 bits, as four little-endian 64-bit limbs
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct U256([u64; 4]);
+
+impl U256 {
+    fn from_parts(low: u128, high: u128) -> Self {
+        U256([
+            low as u64,
+            (low >> 64) as u64,
+            high as u64,
+            (high >> 64) as u64,
+        ])
+    }
+
+    fn low(&self) -> u128 {
+        self.0[0] as u128 | (self.0[1] as u128) << 64
+    }
+
+    fn high(&self) -> u128 {
+        self.0[2] as u128 | (self.0[3] as u128) << 64
+    }
+
+    fn from_felt(felt: Felt) -> Self {
+        let bytes = felt.to_bytes_be();
+        let half = |range: std::ops::Range<usize>| {
+            bytes[range]
+                .iter()
+                .fold(0u128, |acc, byte| acc << 8 | *byte as u128)
+        };
+        Self::from_parts(half(16..32), half(0..16))
+    }
+
+    /// The felt with this value, if it is below the field prime
+    fn to_felt(self) -> Option<Felt> {
+        let mut bytes = [0u8; 32];
+        bytes[..16].copy_from_slice(&self.high().to_be_bytes());
+        bytes[16..].copy_from_slice(&self.low().to_be_bytes());
+        let felt = Felt::from_bytes_be(&bytes);
+        (felt.to_bytes_be() == bytes).then_some(felt)
+    }
+
+    fn parse_hex(value: &str) -> Option<Self> {
+        let digits = value
+            .strip_prefix("0x")
+            .or_else(|| value.strip_prefix("0X"))?;
+        if digits.is_empty() || digits.len() > 64 {
+            return None;
+        }
+        let split = digits.len().saturating_sub(32);
+        let high = match &digits[..split] {
+            "" => 0,
+            high => u128::from_str_radix(high, 16).ok()?,
+        };
+        let low = u128::from_str_radix(&digits[split..], 16).ok()?;
+        Some(Self::from_parts(low, high))
+    }
+
+    fn parse_dec(value: &str) -> Option<Self> {
+        if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let mut limbs = [0u64; 4];
+        for digit in value.bytes().map(|b| (b - b'0') as u128) {
+            let mut carry = digit;
+            for limb in limbs.iter_mut() {
+                let next = *limb as u128 * 10 + carry;
+                *limb = next as u64;
+                carry = next >> 64;
+            }
+            if carry != 0 {
+                return None;
+            }
+        }
+        Some(U256(limbs))
+    }
+
+    fn to_dec(self) -> String {
+        let mut limbs = self.0;
+        let mut digits = Vec::new();
+        loop {
+            let mut remainder = 0u128;
+            for limb in limbs.iter_mut().rev() {
+                let current = remainder << 64 | *limb as u128;
+                *limb = (current / 10) as u64;
+                remainder = current % 10;
+            }
+            digits.push(b'0' + remainder as u8);
+            if limbs.iter().all(|limb| *limb == 0) {
+                break;
+            }
+        }
+        digits.iter().rev().map(|d| *d as char).collect()
+    }
+
+    fn to_hex(self) -> String {
+        match self.high() {
+            0 => format!("{:#x}", self.low()),
+            high => format!("{high:#x}{:032x}", self.low()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct U256Parts {
+    pub low: String,
+    pub high: String,
+}
+
+/// Every reading of a value
+#[derive(Serialize)]
+pub struct ConvertOutput {
+    pub input: String,
+    pub from: String,
+    pub hex: String,
+    pub dec: String,
+    /// Zero-padded 64-digit form, when the value fits in a felt
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub felt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shortstr: Option<String>,
+    pub u256: U256Parts,
+}
+
+#[derive(Serialize)]
+pub struct ConvertToOutput {
+    pub input: String,
+    pub from: String,
+    pub to: String,
+    pub value: String,
+}
+
+/// Convert values between felt representations: hex, decimal, Cairo short string and u256
+/// low/high halves. `from` is guessed when not given: `low,high` pairs are u256, `0x` prefixes
+/// hex, digits decimal and anything else a short string.
+pub async fn execute(
+    formatter: &dyn OutputFormatter,
+    values: Vec<String>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<()> {
+    let mut outputs = Vec::new();
+    for input in values {
+        let from = from.clone().unwrap_or_else(|| detect(&input).to_string());
+        let value = parse(&input, &from)?;
+        let output = match &to {
+            Some(to) => serde_json::to_value(ConvertToOutput {
+                value: render(value, to).ok_or_else(|| {
+                    CliError::InvalidInput(format!("'{input}' has no {to} representation"))
+                })?,
+                input,
+                from,
+                to: to.clone(),
+            }),
+            None => serde_json::to_value(ConvertOutput {
+                hex: value.to_hex(),
+                dec: value.to_dec(),
+                felt: render(value, "felt"),
+                shortstr: render(value, "shortstr"),
+                u256: U256Parts {
+                    low: format!("{:#x}", value.low()),
+                    high: format!("{:#x}", value.high()),
+                },
+                input,
+                from,
+            }),
+        };
+        outputs.push(output.map_err(|e| CliError::Other(e.into()))?);
+    }
+
+    if outputs.len() == 1 {
+        formatter.success(&outputs[0]);
+    } else {
+        formatter.success(&outputs);
+    }
+    Ok(())
+}
+
+fn detect(input: &str) -> &'static str {
+    if input.contains(',') {
+        "u256"
+    } else if input.starts_with("0x") || input.starts_with("0X") {
+        "hex"
+    } else if !input.is_empty() && input.bytes().all(|b| b.is_ascii_digit()) {
+        "dec"
+    } else {
+        "shortstr"
+    }
+}
+
+fn parse(input: &str, from: &str) -> Result<U256> {
+    let number = |value: &str| U256::parse_hex(value).or_else(|| U256::parse_dec(value));
+    let parsed = match from {
+        "hex" => U256::parse_hex(input),
+        "dec" => U256::parse_dec(input),
+        "felt" => number(input).filter(|value| value.to_felt().is_some()),
+        "shortstr" => cairo_short_string_to_felt(input).ok().map(U256::from_felt),
+        "u256" => match input.split_once(',') {
+            Some((low, high)) => {
+                let half = |value: &str| {
+                    number(value.trim())
+                        .filter(|half| half.high() == 0)
+                        .map(|half| half.low())
+                };
+                half(low)
+                    .zip(half(high))
+                    .map(|(low, high)| U256::from_parts(low, high))
+            }
+            None => number(input),
+        },
+        _ => {
+            return Err(CliError::InvalidInput(format!(
+                "Unknown format '{from}'. Expected one of: {}",
+                FORMATS.join(", ")
+            )))
+        }
+    };
+    parsed.ok_or_else(|| CliError::InvalidInput(format!("'{input}' is not a valid {from} value")))
+}
+
+/// Write a value in one representation, if it has one: felts must be below the prime and short
+/// strings printable.
+fn render(value: U256, to: &str) -> Option<String> {
+    match to {
+        "hex" => Some(value.to_hex()),
+        "dec" => Some(value.to_dec()),
+        "felt" => value.to_felt().map(|felt| format!("0x{felt:064x}")),
+        "shortstr" => value
+            .to_felt()
+            .and_then(|felt| parse_cairo_short_string(&felt).ok())
+            .filter(|s| !s.is_empty() && s.chars().all(|c| c.is_ascii_graphic() || c == ' ')),
+        "u256" => Some(format!("{:#x},{:#x}", value.low(), value.high())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_between_representations() {
+        let hello = parse("hello", detect("hello")).unwrap();
+        assert_eq!(hello.to_hex(), "0x68656c6c6f");
+        assert_eq!(render(hello, "dec").as_deref(), Some("448378203247"));
+        assert_eq!(
+            render(parse("0x68656c6c6f", "hex").unwrap(), "shortstr").as_deref(),
+            Some("hello")
+        );
+
+        // 2^128 + 1, split and recombined
+        let big = parse("340282366920938463463374607431768211457", "dec").unwrap();
+        assert_eq!(render(big, "u256").as_deref(), Some("0x1,0x1"));
+        assert_eq!(parse("0x1,0x1", detect("0x1,0x1")).unwrap(), big);
+        assert_eq!(big.to_hex(), "0x100000000000000000000000000000001");
+
+        let max = U256::from_parts(u128::MAX, u128::MAX);
+        assert_eq!(U256::parse_dec(&max.to_dec()), Some(max));
+        assert!(render(max, "felt").is_none());
+        assert_eq!(
+            render(parse("1", "dec").unwrap(), "felt").unwrap().len(),
+            66
+        );
+
+        assert!(parse("0xzz", "hex").is_err());
+        assert!(parse("1,0x100000000000000000000000000000000", "u256").is_err());
+        assert!(parse("0x1", "base64").is_err());
+        assert!(parse(
+            "this string is far too long to be a short string",
+            "shortstr"
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,538 @@
+use crate::{
+    commands::{accounts, balance, queue, schedule, status},
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use account_sdk::storage::{filestorage::FileSystemBackend, StorageBackend};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style, Stylize},
+    text::Line,
+    widgets::{Block, List, Paragraph, Row, Table},
+    DefaultTerminal, Frame,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Entries shown in an account's history pane
+const HISTORY_LEN: usize = 10;
+
+#[derive(Serialize)]
+pub struct DashboardOutput {
+    pub accounts: Vec<AccountView>,
+}
+
+/// One account as the dashboard shows it
+#[derive(Serialize)]
+pub struct AccountView {
+    /// Account label, or 'default' for the default storage
+    pub account: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<SessionView>,
+    pub balances: Vec<BalanceView>,
+    /// Scheduled executions, scheduled revokes and queued executions still to run
+    pub pending: Vec<PendingItem>,
+    /// Submitted and finished executions, newest first
+    pub history: Vec<HistoryItem>,
+    /// Warnings and errors from gathering this account's state
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SessionView {
+    pub address: String,
+    pub chain_id: String,
+    pub expires_at: u64,
+    pub expires_in_seconds: i64,
+    pub is_expired: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BalanceView {
+    pub token: String,
+    pub balance: String,
+}
+
+#[derive(Serialize)]
+pub struct PendingItem {
+    /// 'scheduled', 'revoke' or 'queued'
+    pub kind: String,
+    pub summary: String,
+    /// When it runs, for scheduled executions and revokes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub at: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HistoryItem {
+    /// 'scheduled' or 'queued'
+    pub kind: String,
+    pub id: String,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<String>,
+    pub at: u64,
+}
+
+/// The parts of `status` output the dashboard uses
+#[derive(Deserialize)]
+struct StatusSnapshot {
+    session: Option<SessionView>,
+    #[serde(default)]
+    scheduled_revokes: Vec<RevokeSnapshot>,
+}
+
+#[derive(Deserialize)]
+struct RevokeSnapshot {
+    token: String,
+    spender: String,
+    revoke_at: u64,
+}
+
+/// Keeps a nested command's result and collects its warnings and errors, so nothing is printed
+/// over the dashboard
+#[derive(Default)]
+struct Collect {
+    result: Mutex<Option<serde_json::Value>>,
+    warnings: Mutex<Vec<String>>,
+}
+
+impl Collect {
+    fn take<T: for<'de> Deserialize<'de>>(&self) -> Option<T> {
+        let value = self.result.lock().ok()?.take()?;
+        serde_json::from_value(value).ok()
+    }
+
+    fn push(&self, message: String) {
+        if let Ok(mut warnings) = self.warnings.lock() {
+            warnings.push(message);
+        }
+    }
+
+    fn into_warnings(self) -> Vec<String> {
+        self.warnings.into_inner().unwrap_or_default()
+    }
+}
+
+impl OutputFormatter for Collect {
+    fn success(&self, data: &dyn erased_serde::Serialize) {
+        if let Ok(mut result) = self.result.lock() {
+            *result = serde_json::to_value(data).ok();
+        }
+    }
+
+    fn error(&self, error: &CliError) {
+        self.push(error.to_string());
+    }
+
+    fn info(&self, _message: &str) {}
+
+    fn warning(&self, message: &str) {
+        self.push(message.to_string());
+    }
+}
+
+/// Show session status, balances, pending work and recent history for the account (or every
+/// account with `all`), refreshed every `interval`. Outside a terminal, or with `--json`, one
+/// snapshot is printed instead.
+pub async fn execute(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    all: bool,
+    interval: String,
+    account: Option<&str>,
+) -> Result<()> {
+    use std::io::IsTerminal;
+
+    let interval = balance::parse_interval(&interval)?;
+    let labels = account_labels(config, all, account);
+    if labels.is_empty() {
+        return Err(CliError::NoSession);
+    }
+
+    if config.cli.json_output || !std::io::stdout().is_terminal() {
+        let output = DashboardOutput {
+            accounts: snapshot(config, &labels).await,
+        };
+        formatter.success(&output);
+        return Ok(());
+    }
+
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, config, &labels, interval).await;
+    ratatui::restore();
+    result
+}
+
+/// The accounts to show: the selected one, or with `all` the default storage (when it holds a
+/// controller) and every label
+fn account_labels(config: &Config, all: bool, account: Option<&str>) -> Vec<Option<String>> {
+    if !all {
+        return vec![account.map(str::to_string)];
+    }
+    let backend = FileSystemBackend::new(config.resolve_storage_path(None));
+    let default = backend.controller().ok().flatten().map(|_| None);
+    default
+        .into_iter()
+        .chain(accounts::labels(config).into_iter().map(Some))
+        .collect()
+}
+
+async fn snapshot(config: &Config, labels: &[Option<String>]) -> Vec<AccountView> {
+    let mut views = Vec::with_capacity(labels.len());
+    for label in labels {
+        views.push(account_view(config, label.as_deref()).await);
+    }
+    views
+}
+
+async fn account_view(config: &Config, account: Option<&str>) -> AccountView {
+    let mut nested = config.clone();
+    nested.cli.json_output = true;
+    let collect = Collect::default();
+
+    if let Err(e) = status::execute(&nested, &collect, account).await {
+        collect.push(e.to_string());
+    }
+    let status: Option<StatusSnapshot> = collect.take();
+
+    if let Err(e) =
+        balance::execute(&nested, &collect, None, None, None, false, None, account).await
+    {
+        collect.push(e.to_string());
+    }
+    let balances: Vec<BalanceView> = collect.take().unwrap_or_default();
+
+    let storage_path = config.resolve_storage_path(account);
+    let executions = schedule::load_schedule(&storage_path);
+    let queued = queue::load_queue(&storage_path);
+
+    let mut pending: Vec<PendingItem> = executions
+        .iter()
+        .filter(|e| e.status == "pending")
+        .map(|e| PendingItem {
+            kind: "scheduled".to_string(),
+            summary: format!("{} ({} call(s))", e.id, e.calls.len()),
+            at: Some(e.run_at),
+        })
+        .collect();
+    let (session, revokes) = match status {
+        Some(status) => (status.session, status.scheduled_revokes),
+        None => (None, Vec::new()),
+    };
+    pending.extend(revokes.into_iter().map(|r| PendingItem {
+        kind: "revoke".to_string(),
+        summary: format!("{} allowance for {}", r.token, r.spender),
+        at: Some(r.revoke_at),
+    }));
+    pending.extend(
+        queued
+            .iter()
+            .filter(|e| e.status == "pending")
+            .map(|e| PendingItem {
+                kind: "queued".to_string(),
+                summary: format!(
+                    "{} ({} call(s), priority {})",
+                    e.id,
+                    e.calls.len(),
+                    e.priority
+                ),
+                at: None,
+            }),
+    );
+
+    AccountView {
+        account: account.unwrap_or("default").to_string(),
+        session,
+        balances,
+        pending,
+        history: history(&executions, &queued),
+        warnings: collect.into_warnings(),
+    }
+}
+
+/// Finished scheduled and queued executions, newest first
+fn history(
+    executions: &[schedule::ScheduledExecution],
+    queued: &[queue::QueuedExecution],
+) -> Vec<HistoryItem> {
+    let scheduled = executions.iter().filter_map(|e| {
+        Some(HistoryItem {
+            kind: "scheduled".to_string(),
+            id: e.id.clone(),
+            status: e.status.clone(),
+            transaction_hash: e.transaction_hash.clone(),
+            at: e.submitted_at?,
+        })
+    });
+    let queued = queued.iter().filter_map(|e| {
+        Some(HistoryItem {
+            kind: "queued".to_string(),
+            id: e.id.clone(),
+            status: e.status.clone(),
+            transaction_hash: e.transaction_hash.clone(),
+            at: e.finished_at?,
+        })
+    });
+    let mut items: Vec<HistoryItem> = scheduled.chain(queued).collect();
+    items.sort_by(|a, b| b.at.cmp(&a.at));
+    items.truncate(HISTORY_LEN);
+    items
+}
+
+async fn run(
+    terminal: &mut DefaultTerminal,
+    config: &Config,
+    labels: &[Option<String>],
+    interval: Duration,
+) -> Result<()> {
+    let mut views: Vec<AccountView> = Vec::new();
+    let mut selected = 0;
+    let mut refreshed: Option<String> = None;
+    let mut next_refresh = Instant::now();
+
+    loop {
+        if Instant::now() >= next_refresh {
+            terminal
+                .draw(|frame| draw(frame, &views, selected, refreshed.as_deref(), interval))
+                .map_err(|e| CliError::Other(e.into()))?;
+            views = snapshot(config, labels).await;
+            refreshed = Some(chrono::Local::now().format("%H:%M:%S").to_string());
+            next_refresh = Instant::now() + interval;
+        }
+        terminal
+            .draw(|frame| draw(frame, &views, selected, refreshed.as_deref(), interval))
+            .map_err(|e| CliError::Other(e.into()))?;
+
+        if !event::poll(Duration::from_millis(200)).map_err(|e| CliError::Other(e.into()))? {
+            continue;
+        }
+        let Event::Key(key) = event::read().map_err(|e| CliError::Other(e.into()))? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            KeyCode::Char('r') => next_refresh = Instant::now(),
+            KeyCode::Down | KeyCode::Tab | KeyCode::Char('j') if !views.is_empty() => {
+                selected = (selected + 1) % views.len();
+            }
+            KeyCode::Up | KeyCode::BackTab | KeyCode::Char('k') if !views.is_empty() => {
+                selected = (selected + views.len() - 1) % views.len();
+            }
+            _ => {}
+        }
+    }
+}
+
+fn draw(
+    frame: &mut Frame,
+    views: &[AccountView],
+    selected: usize,
+    refreshed: Option<&str>,
+    interval: Duration,
+) {
+    let [header, table, details, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Length(views.len().max(1) as u16 + 3),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let title = match refreshed {
+        Some(at) => format!(
+            "Controller dashboard: refreshed {at}, every {}s (q quit, r refresh, ↑/↓ account)",
+            interval.as_secs()
+        ),
+        None => "Controller dashboard: loading...".to_string(),
+    };
+    frame.render_widget(Paragraph::new(title).style(Style::new().bold()), header);
+
+    let rows = views.iter().enumerate().map(|(i, view)| {
+        let (address, chain) = match &view.session {
+            Some(session) => (short_address(&session.address), session.chain_id.clone()),
+            None => ("-".to_string(), "-".to_string()),
+        };
+        let row = Row::new(vec![
+            view.account.clone(),
+            address,
+            chain,
+            session_label(view.session.as_ref()),
+            view.pending.len().to_string(),
+        ]);
+        if i == selected {
+            row.style(Style::new().add_modifier(Modifier::REVERSED))
+        } else {
+            row
+        }
+    });
+    let accounts = Table::new(
+        rows,
+        [
+            Constraint::Length(16),
+            Constraint::Length(14),
+            Constraint::Length(12),
+            Constraint::Min(18),
+            Constraint::Length(8),
+        ],
+    )
+    .header(
+        Row::new(vec!["Account", "Address", "Chain", "Session", "Pending"])
+            .style(Style::new().bold()),
+    )
+    .block(Block::bordered().title("Accounts"));
+    frame.render_widget(accounts, table);
+
+    let Some(view) = views.get(selected) else {
+        return;
+    };
+    let [balances, pending, history] = Layout::horizontal([
+        Constraint::Percentage(30),
+        Constraint::Percentage(35),
+        Constraint::Percentage(35),
+    ])
+    .areas(details);
+
+    let lines: Vec<Line> = view
+        .balances
+        .iter()
+        .map(|b| Line::from(format!("{} {}", b.balance, b.token)))
+        .collect();
+    frame.render_widget(
+        List::new(lines).block(Block::bordered().title(format!("Balances: {}", view.account))),
+        balances,
+    );
+
+    let lines: Vec<Line> = view
+        .pending
+        .iter()
+        .map(|p| {
+            let at =
+                p.at.map(|at| format!(" @ {}", format_time(at)))
+                    .unwrap_or_default();
+            Line::from(format!("[{}] {}{at}", p.kind, p.summary))
+        })
+        .collect();
+    frame.render_widget(
+        List::new(lines).block(Block::bordered().title("Pending")),
+        pending,
+    );
+
+    let lines: Vec<Line> = view
+        .history
+        .iter()
+        .map(|h| {
+            let tx = h
+                .transaction_hash
+                .as_deref()
+                .map(|hash| format!(" {}", short_address(hash)))
+                .unwrap_or_default();
+            Line::from(format!(
+                "{} [{}] {} {}{tx}",
+                format_time(h.at),
+                h.kind,
+                h.id,
+                h.status
+            ))
+        })
+        .collect();
+    frame.render_widget(
+        List::new(lines).block(Block::bordered().title("History")),
+        history,
+    );
+
+    if let Some(warning) = view.warnings.last() {
+        frame.render_widget(
+            Paragraph::new(warning.as_str()).style(Style::new().fg(Color::Yellow)),
+            footer,
+        );
+    }
+}
+
+/// 'expires in 3h 20m', 'expired' or 'no session'
+fn session_label(session: Option<&SessionView>) -> String {
+    let Some(session) = session else {
+        return "no session".to_string();
+    };
+    if session.is_expired || session.expires_in_seconds <= 0 {
+        return "expired".to_string();
+    }
+    let secs = session.expires_in_seconds;
+    let (days, hours, mins) = (secs / 86_400, secs % 86_400 / 3600, secs % 3600 / 60);
+    let remaining = if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {mins}m")
+    } else {
+        format!("{}m", mins.max(1))
+    };
+    format!("expires in {remaining}")
+}
+
+fn short_address(address: &str) -> String {
+    if address.len() <= 12 {
+        return address.to_string();
+    }
+    format!("{}…{}", &address[..6], &address[address.len() - 4..])
+}
+
+fn format_time(at: u64) -> String {
+    chrono::DateTime::from_timestamp(at as i64, 0)
+        .map(|dt| {
+            dt.with_timezone(&chrono::Local)
+                .format("%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(expires_in_seconds: i64) -> SessionView {
+        SessionView {
+            address: "0x1".to_string(),
+            chain_id: "SN_MAIN".to_string(),
+            expires_at: 0,
+            expires_in_seconds,
+            is_expired: expires_in_seconds <= 0,
+        }
+    }
+
+    #[test]
+    fn labels_sessions_and_orders_history() {
+        assert_eq!(session_label(None), "no session");
+        assert_eq!(session_label(Some(&session(-5))), "expired");
+        assert_eq!(session_label(Some(&session(30))), "expires in 1m");
+        assert_eq!(session_label(Some(&session(12_000))), "expires in 3h 20m");
+        assert_eq!(session_label(Some(&session(200_000))), "expires in 2d 7h");
+
+        let scheduled: Vec<schedule::ScheduledExecution> =
+            serde_json::from_value(serde_json::json!([
+                {"id": "s1", "calls": [], "run_at": 10, "created_at": 0, "status": "submitted",
+                 "submitted_at": 20, "transaction_hash": "0xabc"},
+                {"id": "s2", "calls": [], "run_at": 90, "created_at": 0, "status": "pending"}
+            ]))
+            .unwrap();
+        let queued: Vec<queue::QueuedExecution> = serde_json::from_value(serde_json::json!([
+            {"id": "q1", "calls": [], "created_at": 0, "max_attempts": 3, "status": "failed",
+             "finished_at": 30},
+            {"id": "q2", "calls": [], "created_at": 0, "max_attempts": 3, "status": "pending"}
+        ]))
+        .unwrap();
+
+        let items = history(&scheduled, &queued);
+        let ids: Vec<&str> = items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, ["q1", "s1"]);
+        assert_eq!(items[1].transaction_hash.as_deref(), Some("0xabc"));
+    }
+}
@@ -0,0 +1,484 @@
+use crate::error::{CliError, Result};
+use crate::output::OutputFormatter;
+use cainome_cairo_serde::{ByteArray, CairoSerde};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use starknet::core::types::Felt;
+use starknet::core::utils::{get_selector_from_name, parse_cairo_short_string};
+
+/// The plausible readings of a single felt
+#[derive(Serialize)]
+pub struct FeltInterpretation {
+    pub hex: String,
+    pub decimal: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub short_string: Option<String>,
+    /// Zero-padded form, only for values too large to be a plain integer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct DecodeCalldataOutput {
+    pub felts: Vec<FeltInterpretation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decoded: Option<Value>,
+}
+
+#[derive(Serialize)]
+pub struct DecodeEventOutput {
+    pub keys: Vec<FeltInterpretation>,
+    pub data: Vec<FeltInterpretation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decoded: Option<Value>,
+}
+
+/// Print each calldata felt's possible interpretations and, given an ABI and function name,
+/// the calldata decoded into the function's named parameters.
+pub async fn calldata(
+    formatter: &dyn OutputFormatter,
+    values: Vec<String>,
+    abi: Option<String>,
+    function: Option<String>,
+) -> Result<()> {
+    let felts = parse_felts(&values)?;
+
+    let decoded = match (abi, &function) {
+        (Some(path), Some(function)) => {
+            let abi = load_abi(&path)?;
+            let inputs = find_function(&abi, function)?;
+            let mut decoder = Decoder::new(&abi, &felts);
+            let decoded = decoder.members(inputs, "name")?;
+            if decoder.remaining() > 0 {
+                formatter.warning(&format!(
+                    "{} trailing felt(s) not consumed by '{function}'",
+                    decoder.remaining()
+                ));
+            }
+            Some(decoded)
+        }
+        _ => None,
+    };
+
+    formatter.success(&DecodeCalldataOutput {
+        felts: felts.iter().map(interpret).collect(),
+        function: decoded.as_ref().and(function),
+        decoded,
+    });
+    Ok(())
+}
+
+/// Print the interpretations of an event's keys and data and, given an ABI, the event matched by
+/// its selector (`keys[0]`) decoded into named members.
+pub async fn event(
+    formatter: &dyn OutputFormatter,
+    keys: String,
+    data: String,
+    abi: Option<String>,
+) -> Result<()> {
+    let keys = parse_felts(&[keys])?;
+    let data = parse_felts(&[data])?;
+
+    let (event, decoded) = match abi {
+        Some(path) => {
+            let abi = load_abi(&path)?;
+            let (name, decoded) = decode_event(&abi, &keys, &data)?;
+            (Some(name), Some(decoded))
+        }
+        None => (None, None),
+    };
+
+    formatter.success(&DecodeEventOutput {
+        keys: keys.iter().map(interpret).collect(),
+        data: data.iter().map(interpret).collect(),
+        event,
+        decoded,
+    });
+    Ok(())
+}
+
+/// Parse felts given as separate arguments and/or comma-separated, in hex or decimal.
+fn parse_felts(values: &[String]) -> Result<Vec<Felt>> {
+    values
+        .iter()
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| {
+            let felt = if value.starts_with("0x") || value.starts_with("0X") {
+                Felt::from_hex(value)
+            } else {
+                Felt::from_dec_str(value)
+            };
+            felt.map_err(|e| CliError::InvalidInput(format!("Invalid felt '{value}': {e}")))
+        })
+        .collect()
+}
+
+pub fn interpret(felt: &Felt) -> FeltInterpretation {
+    let short_string = parse_cairo_short_string(felt)
+        .ok()
+        .filter(|s| !s.is_empty() && s.chars().all(|c| c.is_ascii_graphic() || c == ' '));
+    let address = u128::try_from(*felt)
+        .is_err()
+        .then(|| format!("0x{felt:064x}"));
+    FeltInterpretation {
+        hex: format!("{felt:#x}"),
+        decimal: felt.to_string(),
+        short_string,
+        address,
+    }
+}
+
+/// Load an ABI from either a bare ABI array or a contract class (whose `abi` may itself be a
+/// JSON-encoded string, as in Sierra classes).
+pub fn load_abi(path: &str) -> Result<Vec<Value>> {
+    let content = std::fs::read_to_string(path).map_err(|e| CliError::FileError {
+        path: path.to_string(),
+        message: e.to_string(),
+    })?;
+    let value: Value = serde_json::from_str(&content)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid JSON in {path}: {e}")))?;
+    abi_items(value, path)
+}
+
+/// The ABI items of a bare ABI array or a contract class, read from `source`.
+pub fn abi_items(value: Value, source: &str) -> Result<Vec<Value>> {
+    let abi = match value {
+        Value::Object(mut class) => match class.remove("abi") {
+            Some(Value::String(abi)) => serde_json::from_str(&abi).map_err(|e| {
+                CliError::InvalidInput(format!("Invalid ABI string in {source}: {e}"))
+            })?,
+            Some(abi) => abi,
+            None => Value::Null,
+        },
+        abi => abi,
+    };
+
+    match abi {
+        Value::Array(items) => {
+            // Remembered so receipts and transactions can name these selectors later
+            let names: Vec<String> = items.iter().flat_map(super::selectors::names_in).collect();
+            super::selectors::learn(names.iter().map(String::as_str));
+            Ok(items)
+        }
+        _ => Err(CliError::InvalidInput(format!(
+            "{source} is not an ABI: expected a JSON array or a contract class with an 'abi' field"
+        ))),
+    }
+}
+
+/// Find a function's inputs, looking inside `interface` items as well as top-level functions.
+pub fn find_function<'a>(abi: &'a [Value], name: &str) -> Result<&'a [Value]> {
+    abi.iter()
+        .flat_map(|item| match item["type"].as_str() {
+            Some("interface") => item["items"].as_array().map(Vec::as_slice).unwrap_or(&[]),
+            _ => std::slice::from_ref(item),
+        })
+        .find(|item| {
+            matches!(item["type"].as_str(), Some("function" | "l1_handler")) && item["name"] == name
+        })
+        .and_then(|function| function["inputs"].as_array())
+        .map(Vec::as_slice)
+        .ok_or_else(|| CliError::InvalidInput(format!("Function '{name}' not found in the ABI")))
+}
+
+pub fn decode_event(abi: &[Value], keys: &[Felt], data: &[Felt]) -> Result<(String, Value)> {
+    let selector = keys
+        .first()
+        .ok_or_else(|| CliError::InvalidInput("Event has no keys".to_string()))?;
+
+    // The selector is the variant name in the contract's `Event` enum, which by convention
+    // matches the last path segment of the event struct
+    let event = abi
+        .iter()
+        .filter(|item| item["type"] == "event" && item["kind"] == "struct")
+        .find(|item| {
+            item["name"]
+                .as_str()
+                .and_then(|name| name.rsplit("::").next())
+                .and_then(|name| get_selector_from_name(name).ok())
+                .is_some_and(|s| s == *selector)
+        })
+        .ok_or_else(|| {
+            CliError::InvalidInput(format!(
+                "No event in the ABI matches selector {selector:#x}"
+            ))
+        })?;
+
+    let members = event["members"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+    let mut key_decoder = Decoder::new(abi, &keys[1..]);
+    let mut data_decoder = Decoder::new(abi, data);
+    let mut decoded = Map::new();
+    for member in members {
+        let decoder = if member["kind"] == "key" {
+            &mut key_decoder
+        } else {
+            &mut data_decoder
+        };
+        let name = member["name"].as_str().unwrap_or_default().to_string();
+        let value = decoder.decode(member["type"].as_str().unwrap_or_default())?;
+        decoded.insert(name, value);
+    }
+
+    let name = event["name"].as_str().unwrap_or_default().to_string();
+    Ok((name, Value::Object(decoded)))
+}
+
+/// Walks a felt slice, decoding Cairo types described by an ABI.
+struct Decoder<'a> {
+    abi: &'a [Value],
+    felts: &'a [Felt],
+    offset: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn new(abi: &'a [Value], felts: &'a [Felt]) -> Self {
+        Self {
+            abi,
+            felts,
+            offset: 0,
+        }
+    }
+
+    fn remaining(&self) -> usize {
+        self.felts.len() - self.offset
+    }
+
+    fn next(&mut self, ty: &str) -> Result<Felt> {
+        let felt = self.felts.get(self.offset).copied().ok_or_else(|| {
+            CliError::InvalidInput(format!("Ran out of felts while decoding '{ty}'"))
+        })?;
+        self.offset += 1;
+        Ok(felt)
+    }
+
+    /// Decode `[{name, type}]` entries (function inputs, struct members) into an object.
+    fn members(&mut self, members: &[Value], key: &str) -> Result<Value> {
+        let mut object = Map::new();
+        for member in members {
+            let name = member[key].as_str().unwrap_or_default().to_string();
+            let value = self.decode(member["type"].as_str().unwrap_or_default())?;
+            object.insert(name, value);
+        }
+        Ok(Value::Object(object))
+    }
+
+    fn decode(&mut self, ty: &str) -> Result<Value> {
+        match ty {
+            "()" => return Ok(Value::Null),
+            "core::felt252"
+            | "core::starknet::contract_address::ContractAddress"
+            | "core::starknet::class_hash::ClassHash"
+            | "core::starknet::eth_address::EthAddress" => {
+                return Ok(json!(format!("{:#x}", self.next(ty)?)))
+            }
+            "core::bool" => return Ok(json!(self.next(ty)? != Felt::ZERO)),
+            "core::integer::u256" => {
+                let low: u128 = self.integer(ty)?;
+                let high: u128 = self.integer(ty)?;
+                return Ok(if high == 0 {
+                    json!(low.to_string())
+                } else {
+                    json!(format!("0x{high:x}{low:032x}"))
+                });
+            }
+            "core::byte_array::ByteArray" => {
+                let byte_array = ByteArray::cairo_deserialize(self.felts, self.offset)
+                    .map_err(|e| CliError::InvalidInput(format!("Invalid ByteArray: {e}")))?;
+                self.offset += ByteArray::cairo_serialized_size(&byte_array);
+                let string = byte_array
+                    .to_string()
+                    .map_err(|e| CliError::InvalidInput(format!("Invalid ByteArray: {e}")))?;
+                return Ok(json!(string));
+            }
+            _ => {}
+        }
+
+        if let Some(bits) = ty.strip_prefix("core::integer::u") {
+            if bits.parse::<u8>().is_ok() || bits == "size" {
+                return Ok(json!(self.integer::<u128>(ty)?.to_string()));
+            }
+        }
+        if ty.starts_with("core::integer::i") {
+            let felt = self.next(ty)?;
+            // Negative values are stored as P - |x|
+            return Ok(match u128::try_from(felt) {
+                Ok(value) => json!(value.to_string()),
+                Err(_) => json!(format!("-{}", -felt)),
+            });
+        }
+        if let Some(inner) = ty
+            .strip_prefix("core::array::Array::<")
+            .or_else(|| ty.strip_prefix("core::array::Span::<"))
+            .and_then(|rest| rest.strip_suffix('>'))
+        {
+            let len: u128 = self.integer(ty)?;
+            return (0..len)
+                .map(|_| self.decode(inner))
+                .collect::<Result<Vec<_>>>()
+                .map(Value::Array);
+        }
+        if let Some(inner) = ty.strip_prefix('(').and_then(|rest| rest.strip_suffix(')')) {
+            return split_tuple(inner)
+                .into_iter()
+                .map(|element| self.decode(element))
+                .collect::<Result<Vec<_>>>()
+                .map(Value::Array);
+        }
+
+        let item = self
+            .abi
+            .iter()
+            .find(|item| {
+                item["name"] == ty && matches!(item["type"].as_str(), Some("struct" | "enum"))
+            })
+            .ok_or_else(|| CliError::InvalidInput(format!("Unsupported type '{ty}'")))?;
+        if item["type"] == "struct" {
+            let members = item["members"].as_array().map(Vec::as_slice).unwrap_or(&[]);
+            return self.members(members, "name");
+        }
+
+        let index: u128 = self.integer(ty)?;
+        let variant = item["variants"]
+            .as_array()
+            .and_then(|variants| variants.get(index as usize))
+            .ok_or_else(|| CliError::InvalidInput(format!("Invalid variant {index} for '{ty}'")))?;
+        let name = variant["name"].as_str().unwrap_or_default().to_string();
+        match variant["type"].as_str().unwrap_or("()") {
+            "()" => Ok(json!(name)),
+            inner => Ok(Value::Object(Map::from_iter([(name, self.decode(inner)?)]))),
+        }
+    }
+
+    fn integer<T: TryFrom<Felt>>(&mut self, ty: &str) -> Result<T> {
+        let felt = self.next(ty)?;
+        T::try_from(felt)
+            .map_err(|_| CliError::InvalidInput(format!("Value {felt:#x} out of range for '{ty}'")))
+    }
+}
+
+/// Split the inside of a tuple type on its top-level commas.
+pub fn split_tuple(inner: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0usize, 0);
+    for (i, c) in inner.char_indices() {
+        match c {
+            '<' | '(' => depth += 1,
+            '>' | ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(inner[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(inner[start..].trim());
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn abi() -> Vec<Value> {
+        serde_json::from_value(json!([
+            {
+                "type": "struct",
+                "name": "game::Position",
+                "members": [
+                    { "name": "x", "type": "core::integer::u32" },
+                    { "name": "y", "type": "core::integer::u32" }
+                ]
+            },
+            {
+                "type": "enum",
+                "name": "game::Direction",
+                "variants": [
+                    { "name": "Left", "type": "()" },
+                    { "name": "Right", "type": "()" }
+                ]
+            },
+            {
+                "type": "interface",
+                "name": "game::IActions",
+                "items": [{
+                    "type": "function",
+                    "name": "move",
+                    "inputs": [
+                        { "name": "to", "type": "game::Position" },
+                        { "name": "direction", "type": "game::Direction" },
+                        { "name": "path", "type": "core::array::Span::<core::felt252>" },
+                        { "name": "amount", "type": "core::integer::u256" }
+                    ],
+                    "outputs": [],
+                    "state_mutability": "external"
+                }]
+            },
+            {
+                "type": "event",
+                "name": "game::Moved",
+                "kind": "struct",
+                "members": [
+                    { "name": "player", "type": "core::starknet::contract_address::ContractAddress", "kind": "key" },
+                    { "name": "delta", "type": "core::integer::i32", "kind": "data" }
+                ]
+            }
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn interprets_felts() {
+        let felts = parse_felts(&["0x68656c6c6f,42".to_string()]).unwrap();
+        let hello = interpret(&felts[0]);
+        assert_eq!(hello.short_string.as_deref(), Some("hello"));
+        assert!(hello.address.is_none());
+        assert_eq!(interpret(&felts[1]).decimal, "42");
+
+        let address = interpret(
+            &Felt::from_hex("0x49d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7")
+                .unwrap(),
+        );
+        assert_eq!(address.address.unwrap().len(), 66);
+    }
+
+    #[test]
+    fn decodes_calldata_with_abi() {
+        let abi = abi();
+        let felts = parse_felts(&["3,4,1,2,0xa,0xb,100,0".to_string()]).unwrap();
+        let inputs = find_function(&abi, "move").unwrap();
+        let mut decoder = Decoder::new(&abi, &felts);
+        let decoded = decoder.members(inputs, "name").unwrap();
+
+        assert_eq!(decoded["to"], json!({ "x": "3", "y": "4" }));
+        assert_eq!(decoded["direction"], "Right");
+        assert_eq!(decoded["path"], json!(["0xa", "0xb"]));
+        assert_eq!(decoded["amount"], "100");
+        assert_eq!(decoder.remaining(), 0);
+
+        let mut short = Decoder::new(&abi, &felts[..3]);
+        assert!(short.members(inputs, "name").is_err());
+    }
+
+    #[test]
+    fn decodes_event_by_selector() {
+        let selector = get_selector_from_name("Moved").unwrap();
+        let keys = vec![selector, Felt::from(0x123u32)];
+        let data = vec![-Felt::from(5u32)];
+        let (name, decoded) = decode_event(&abi(), &keys, &data).unwrap();
+        assert_eq!(name, "game::Moved");
+        assert_eq!(decoded["player"], "0x123");
+        assert_eq!(decoded["delta"], "-5");
+
+        assert!(decode_event(&abi(), &[Felt::ONE], &[]).is_err());
+    }
+}
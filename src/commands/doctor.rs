@@ -0,0 +1,272 @@
+use crate::{
+    commands::{lookup::CacheMode, session::authorize::PolicyStorage},
+    config::Config,
+    error::Result,
+    output::OutputFormatter,
+};
+use account_sdk::storage::{filestorage::FileSystemBackend, StorageBackend, StorageValue};
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    /// Nothing to fix
+    Ok,
+    /// A problem was found; rerun with `--fix` to apply the remediation
+    NeedsFix,
+    /// The remediation was applied
+    Applied,
+    /// A problem was found but there is no safe automatic fix (or the fix failed)
+    Skipped,
+}
+
+#[derive(Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+}
+
+#[derive(Serialize)]
+pub struct DoctorReport {
+    pub fix: bool,
+    pub storage_path: String,
+    pub checks: Vec<Check>,
+}
+
+impl Check {
+    fn new(name: &'static str, status: Status, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+        }
+    }
+
+    /// Report a found problem: applied when `fix` succeeds, pending without `--fix`
+    fn remediate(
+        name: &'static str,
+        fix: bool,
+        problem: String,
+        apply: impl FnOnce() -> std::io::Result<()>,
+    ) -> Self {
+        if !fix {
+            return Self::new(name, Status::NeedsFix, problem);
+        }
+        match apply() {
+            Ok(()) => Self::new(name, Status::Applied, format!("Fixed: {problem}")),
+            Err(e) => Self::new(name, Status::Skipped, format!("{problem}; fix failed: {e}")),
+        }
+    }
+}
+
+/// Diagnose local config and storage, and with `fix` apply the safe remediations.
+pub async fn execute(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    fix: bool,
+    account: Option<&str>,
+) -> Result<()> {
+    let storage_path = config.resolve_storage_path(account);
+    // Order matters: the permissions check covers a storage directory created just before it
+    let checks = vec![
+        config_dir(fix),
+        storage_dir(&storage_path, fix),
+        permissions(&storage_path, fix),
+        stale_caches(&storage_path, fix),
+        storage_schema(&storage_path),
+        username_metadata(config, fix).await,
+    ];
+    for check in &checks {
+        let line = format!(
+            "{}: {} ({})",
+            check.name,
+            status_label(check.status),
+            check.detail
+        );
+        match check.status {
+            Status::Ok | Status::Applied => formatter.info(&line),
+            Status::NeedsFix | Status::Skipped => formatter.warning(&line),
+        }
+    }
+    if !fix && checks.iter().any(|c| c.status == Status::NeedsFix) {
+        formatter.info("Run 'controller doctor --fix' to apply the safe fixes");
+    }
+
+    formatter.success(&DoctorReport {
+        fix,
+        storage_path: storage_path.display().to_string(),
+        checks,
+    });
+    Ok(())
+}
+
+fn status_label(status: Status) -> &'static str {
+    match status {
+        Status::Ok => "ok",
+        Status::NeedsFix => "needs fix",
+        Status::Applied => "applied",
+        Status::Skipped => "skipped",
+    }
+}
+
+fn config_dir(fix: bool) -> Check {
+    let Some(dir) = Config::config_path()
+        .ok()
+        .and_then(|path| path.parent().map(Path::to_path_buf))
+    else {
+        return Check::new(
+            "config_dir",
+            Status::Skipped,
+            "Could not determine the config directory",
+        );
+    };
+    if dir.is_dir() {
+        return Check::new("config_dir", Status::Ok, dir.display().to_string());
+    }
+    Check::remediate(
+        "config_dir",
+        fix,
+        format!("{} is missing", dir.display()),
+        || std::fs::create_dir_all(&dir),
+    )
+}
+
+fn storage_dir(storage_path: &Path, fix: bool) -> Check {
+    if storage_path.is_dir() {
+        return Check::new(
+            "storage_dir",
+            Status::Ok,
+            storage_path.display().to_string(),
+        );
+    }
+    Check::remediate(
+        "storage_dir",
+        fix,
+        format!("{} is missing", storage_path.display()),
+        || std::fs::create_dir_all(storage_path),
+    )
+}
+
+fn permissions(storage_path: &Path, fix: bool) -> Check {
+    let loose = match crate::commands::storage::find_loose_permissions(storage_path) {
+        Ok(loose) => loose,
+        Err(e) => {
+            return Check::new(
+                "permissions",
+                Status::Skipped,
+                format!("Could not inspect {}: {e}", storage_path.display()),
+            )
+        }
+    };
+    if loose.is_empty() {
+        return Check::new("permissions", Status::Ok, "Storage is private to this user");
+    }
+    Check::remediate(
+        "permissions",
+        fix,
+        format!("{} path(s) readable by other users", loose.len()),
+        || crate::commands::storage::harden_paths(&loose),
+    )
+}
+
+/// Expired balance caches and submission locks left behind by crashed invocations
+fn stale_caches(storage_path: &Path, fix: bool) -> Check {
+    let mut stale = crate::commands::balance::expired_caches(storage_path);
+    let lock = storage_path.join(crate::commands::submit_queue::LOCK_FILE);
+    if lock.exists() && crate::commands::submit_queue::is_stale(&lock) {
+        stale.push(lock);
+    }
+    if stale.is_empty() {
+        return Check::new("stale_caches", Status::Ok, "No stale caches or locks");
+    }
+    let names = stale
+        .iter()
+        .filter_map(|path| path.file_name().and_then(|name| name.to_str()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Check::remediate("stale_caches", fix, format!("Stale: {names}"), || {
+        stale.iter().try_for_each(std::fs::remove_file)
+    })
+}
+
+/// Stored policies must parse with the current layout. There is no older layout to convert
+/// from, so a mismatch is reported and left for re-authorization rather than rewritten.
+fn storage_schema(storage_path: &Path) -> Check {
+    let backend = FileSystemBackend::new(storage_path.to_path_buf());
+    match backend.get("session_policies").ok().flatten() {
+        None => Check::new("storage_schema", Status::Ok, "No stored policies"),
+        Some(StorageValue::String(json))
+            if serde_json::from_str::<PolicyStorage>(&json).is_ok() =>
+        {
+            Check::new("storage_schema", Status::Ok, "Storage layout is current")
+        }
+        Some(_) => Check::new(
+            "storage_schema",
+            Status::Skipped,
+            "Stored policies use an unrecognized layout; re-authorize with 'controller session auth'",
+        ),
+    }
+}
+
+/// Refetch usernames whose lookup cache entries are past the TTL
+async fn username_metadata(config: &Config, fix: bool) -> Check {
+    let stale = crate::commands::lookup::stale_usernames(config);
+    if stale.is_empty() {
+        return Check::new(
+            "username_metadata",
+            Status::Ok,
+            "Cached usernames are fresh",
+        );
+    }
+    let problem = format!("{} cached username(s) are stale", stale.len());
+    if !fix {
+        return Check::new("username_metadata", Status::NeedsFix, problem);
+    }
+    match crate::commands::lookup::resolve(config, &stale, &[], CacheMode::Refresh).await {
+        Ok(resolved) => Check::new(
+            "username_metadata",
+            Status::Applied,
+            format!(
+                "Refreshed {} of {} cached username(s)",
+                resolved.len(),
+                stale.len()
+            ),
+        ),
+        Err(e) => Check::new(
+            "username_metadata",
+            Status::Skipped,
+            format!("{problem}; refresh failed: {e}"),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remediates_missing_storage_only_with_fix() {
+        let dir = std::env::temp_dir().join(format!("controller-doctor-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(storage_dir(&dir, false).status, Status::NeedsFix);
+        assert!(!dir.exists());
+        assert_eq!(storage_dir(&dir, true).status, Status::Applied);
+        assert_eq!(storage_dir(&dir, true).status, Status::Ok);
+
+        std::fs::write(
+            dir.join("balance_cache_0x1.json"),
+            r#"{"timestamp":0,"balances":[]}"#,
+        )
+        .unwrap();
+        assert_eq!(stale_caches(&dir, false).status, Status::NeedsFix);
+        assert_eq!(stale_caches(&dir, true).status, Status::Applied);
+        assert!(!dir.join("balance_cache_0x1.json").exists());
+        assert_eq!(stale_caches(&dir, true).status, Status::Ok);
+        assert_eq!(storage_schema(&dir).status, Status::Ok);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
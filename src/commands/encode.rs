@@ -0,0 +1,660 @@
+use crate::commands::{balance, calldata::parse_calldata_value, decode};
+use crate::config::Config;
+use crate::error::{CliError, Result};
+use crate::output::OutputFormatter;
+use account_sdk::storage::{filestorage::FileSystemBackend, StorageBackend, StorageValue};
+use serde_json::{Map, Value};
+use starknet::core::types::{BlockId, BlockTag, ContractClass, Felt};
+use starknet::providers::{JsonRpcClient, Provider};
+
+/// `--abi` value that reads the contract class from the RPC instead of a file
+pub const FETCH_ABI: &str = "fetch";
+
+/// Encode human arguments for `--abi` into raw calldata. The entrypoint may carry the arguments
+/// (`transfer(0xabc, 1.5strk)`); otherwise they come from the calldata argument. Returns the
+/// plain entrypoint name and the encoded calldata, comma-separated; without `--abi` both pass
+/// through unchanged.
+#[allow(clippy::too_many_arguments)]
+pub async fn encode_args(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    abi: Option<&str>,
+    contract: Option<&str>,
+    entrypoint: Option<String>,
+    calldata: Option<String>,
+    chain_id: Option<String>,
+    rpc_url: Option<String>,
+    account: Option<&str>,
+) -> Result<(Option<String>, Option<String>)> {
+    let Some(abi) = abi else {
+        return Ok((entrypoint, calldata));
+    };
+    let (Some(contract), Some(entrypoint)) = (contract, entrypoint) else {
+        return Err(CliError::InvalidInput(
+            "--abi needs a contract and an entrypoint".to_string(),
+        ));
+    };
+    let (function, args) = match entrypoint.split_once('(') {
+        Some((name, rest)) => {
+            let args = rest
+                .strip_suffix(')')
+                .ok_or_else(|| CliError::InvalidInput(format!("Missing ')' in '{entrypoint}'")))?;
+            if calldata.is_some() {
+                return Err(CliError::InvalidInput(
+                    "Pass arguments in the entrypoint or as calldata, not both".to_string(),
+                ));
+            }
+            (name.trim().to_string(), args.to_string())
+        }
+        None => (entrypoint, calldata.unwrap_or_default()),
+    };
+    if function.starts_with("selector:") {
+        return Err(CliError::InvalidInput(
+            "--abi needs the entrypoint's name, not a raw selector".to_string(),
+        ));
+    }
+
+    let abi = if abi == FETCH_ABI {
+        fetch_abi(config, formatter, contract, chain_id, rpc_url, account).await?
+    } else {
+        decode::load_abi(abi)?
+    };
+    let inputs = decode::find_function(&abi, &function)?;
+    let felts = Encoder::new(&abi, config).inputs(&function, inputs, parse_args(&args)?)?;
+
+    let calldata = felts
+        .iter()
+        .map(|felt| format!("{felt:#x}"))
+        .collect::<Vec<_>>()
+        .join(",");
+    Ok((Some(function), (!calldata.is_empty()).then_some(calldata)))
+}
+
+/// Read the contract's ABI from the RPC the command would use: flags, then the configured RPC,
+/// then the session's.
+pub async fn fetch_abi(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    contract: &str,
+    chain_id: Option<String>,
+    rpc_url: Option<String>,
+    account: Option<&str>,
+) -> Result<Vec<Value>> {
+    let address = Felt::from_hex(contract)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid contract address: {e}")))?;
+    let backend = FileSystemBackend::new(config.resolve_storage_path(account));
+    let rpc_url = match backend.get("session_rpc_url") {
+        Ok(Some(StorageValue::String(url)))
+            if chain_id.is_none()
+                && rpc_url.is_none()
+                && !config.session.rpc_url_explicitly_set =>
+        {
+            url
+        }
+        _ => crate::chains::resolve_rpc_url(config, formatter, chain_id, rpc_url, account)?,
+    };
+    let url = url::Url::parse(&rpc_url)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
+    let provider = JsonRpcClient::new(crate::api::rpc_transport(url));
+
+    let class = provider
+        .get_class_at(BlockId::Tag(BlockTag::Latest), address)
+        .await
+        .map_err(|e| CliError::Network(format!("Failed to fetch the class of {contract}: {e}")))?;
+    match class {
+        ContractClass::Sierra(class) => {
+            let abi: Value = serde_json::from_str(&class.abi).map_err(|e| {
+                CliError::InvalidInput(format!("Invalid ABI in the class of {contract}: {e}"))
+            })?;
+            decode::abi_items(abi, contract)
+        }
+        ContractClass::Legacy(_) => Err(CliError::InvalidInput(format!(
+            "{contract} is a Cairo 0 contract; --abi only supports Cairo 1 ABIs"
+        ))),
+    }
+}
+
+/// Parse human arguments: comma-separated values, `[a, b]` arrays, `(a, b)` tuples, `{x: 1}`
+/// structs, `Variant` / `Variant(value)` enums and `"quoted"` strings. JSON is accepted as is.
+/// Scalars are kept as strings and read according to their ABI type.
+fn parse_args(input: &str) -> Result<Vec<Value>> {
+    let mut parser = ArgParser {
+        chars: input.chars().collect(),
+        pos: 0,
+    };
+    parser.list(None)
+}
+
+struct ArgParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl ArgParser {
+    fn error(&self, message: &str) -> CliError {
+        CliError::InvalidInput(format!(
+            "Invalid arguments at character {}: {message}",
+            self.pos + 1
+        ))
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+        self.chars.get(self.pos).copied()
+    }
+
+    /// Comma-separated values up to `close`, or the end of input at the top level
+    fn list(&mut self, close: Option<char>) -> Result<Vec<Value>> {
+        let mut items = Vec::new();
+        if self.peek() == close {
+            self.pos += 1;
+            return Ok(items);
+        }
+        loop {
+            items.push(self.value()?);
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                next if next == close => {
+                    self.pos += 1;
+                    return Ok(items);
+                }
+                Some(c) => return Err(self.error(&format!("unexpected '{c}'"))),
+                None => return Err(self.error("unexpected end")),
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Value> {
+        match self.peek() {
+            Some('[') => {
+                self.pos += 1;
+                self.list(Some(']')).map(Value::Array)
+            }
+            Some('(') => {
+                self.pos += 1;
+                self.list(Some(')')).map(Value::Array)
+            }
+            Some('{') => {
+                self.pos += 1;
+                self.object()
+            }
+            Some('"') => self.quoted().map(Value::String),
+            _ => {
+                let token = self.token(&[',', '(', ')', '[', ']', '{', '}']);
+                if token.is_empty() {
+                    return Err(self.error("expected a value"));
+                }
+                if self.peek() != Some('(') {
+                    return Ok(Value::String(token));
+                }
+                // Enum variant with a payload
+                self.pos += 1;
+                let mut args = self.list(Some(')'))?;
+                let payload = match args.len() {
+                    0 => Value::Null,
+                    1 => args.remove(0),
+                    _ => Value::Array(args),
+                };
+                Ok(Value::Object(Map::from_iter([(token, payload)])))
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Value> {
+        let mut fields = Map::new();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(Value::Object(fields));
+        }
+        loop {
+            let key = match self.peek() {
+                Some('"') => self.quoted()?,
+                _ => self.token(&[':', ',', '}']),
+            };
+            if self.peek() != Some(':') {
+                return Err(self.error(&format!("expected ':' after '{key}'")));
+            }
+            self.pos += 1;
+            let value = self.value()?;
+            fields.insert(key, value);
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {
+                    self.pos += 1;
+                    return Ok(Value::Object(fields));
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn quoted(&mut self) -> Result<String> {
+        self.pos += 1;
+        let mut text = String::new();
+        while let Some(&c) = self.chars.get(self.pos) {
+            self.pos += 1;
+            match c {
+                '"' => return Ok(text),
+                '\\' => {
+                    if let Some(&escaped) = self.chars.get(self.pos) {
+                        self.pos += 1;
+                        text.push(escaped);
+                    }
+                }
+                c => text.push(c),
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    fn token(&mut self, stops: &[char]) -> String {
+        self.peek();
+        let start = self.pos;
+        while self.chars.get(self.pos).is_some_and(|c| !stops.contains(c)) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos]
+            .iter()
+            .collect::<String>()
+            .trim()
+            .to_string()
+    }
+}
+
+/// Serializes values into felts for Cairo types described by an ABI; the inverse of
+/// [`decode`]'s decoder.
+struct Encoder<'a> {
+    abi: &'a [Value],
+    config: &'a Config,
+    felts: Vec<Felt>,
+}
+
+impl<'a> Encoder<'a> {
+    fn new(abi: &'a [Value], config: &'a Config) -> Self {
+        Self {
+            abi,
+            config,
+            felts: Vec::new(),
+        }
+    }
+
+    /// Encode a function's inputs from positional values, or from a single object keyed by
+    /// input name.
+    fn inputs(mut self, function: &str, inputs: &[Value], args: Vec<Value>) -> Result<Vec<Felt>> {
+        fn field<'v>(input: &'v Value, key: &str) -> &'v str {
+            input[key].as_str().unwrap_or_default()
+        }
+        let signature = || {
+            let params: Vec<String> = inputs
+                .iter()
+                .map(|input| {
+                    format!(
+                        "{}: {}",
+                        field(input, "name"),
+                        short_type(field(input, "type"))
+                    )
+                })
+                .collect();
+            format!("{function}({})", params.join(", "))
+        };
+
+        let named: Option<Vec<Value>> = match args.as_slice() {
+            [Value::Object(named)]
+                if !inputs.is_empty()
+                    && named.len() == inputs.len()
+                    && inputs
+                        .iter()
+                        .all(|input| named.contains_key(field(input, "name"))) =>
+            {
+                Some(
+                    inputs
+                        .iter()
+                        .map(|input| named[field(input, "name")].clone())
+                        .collect(),
+                )
+            }
+            _ => None,
+        };
+        let args = match named {
+            Some(named) => named,
+            None if args.len() == inputs.len() => args,
+            None => {
+                return Err(CliError::InvalidInput(format!(
+                    "Expected {} argument(s), got {}: {}",
+                    inputs.len(),
+                    args.len(),
+                    signature()
+                )))
+            }
+        };
+
+        for (input, arg) in inputs.iter().zip(&args) {
+            self.encode(field(input, "type"), arg).map_err(|e| {
+                CliError::InvalidInput(format!(
+                    "Argument '{}' of {}: {e}",
+                    field(input, "name"),
+                    signature()
+                ))
+            })?;
+        }
+        Ok(self.felts)
+    }
+
+    fn encode(&mut self, ty: &str, value: &Value) -> Result<()> {
+        let invalid =
+            |expected: &str| CliError::InvalidInput(format!("expected {expected}, got {value}"));
+
+        match ty {
+            "()" => return Ok(()),
+            "core::felt252"
+            | "core::starknet::contract_address::ContractAddress"
+            | "core::starknet::class_hash::ClassHash"
+            | "core::starknet::eth_address::EthAddress"
+            | "core::bytes_31::bytes31" => {
+                let text = scalar(value).ok_or_else(|| invalid("a felt"))?;
+                let felt = match token_amount(self.config, &text)? {
+                    Some(raw) => Felt::from(raw),
+                    None => single_felt(&text)?,
+                };
+                self.felts.push(felt);
+                return Ok(());
+            }
+            "core::bool" => {
+                let flag = match scalar(value).as_deref() {
+                    Some("true" | "1") => Felt::ONE,
+                    Some("false" | "0") => Felt::ZERO,
+                    _ => return Err(invalid("true or false")),
+                };
+                self.felts.push(flag);
+                return Ok(());
+            }
+            // Amounts are written as one number; `{low, high}` falls through to the struct
+            "core::integer::u256" if !value.is_object() => {
+                let text = scalar(value).ok_or_else(|| invalid("an amount"))?;
+                let amount = match token_amount(self.config, &text)? {
+                    Some(raw) => raw.to_string(),
+                    None => text,
+                };
+                self.felts
+                    .extend(parse_calldata_value(&format!("u256:{amount}"))?);
+                return Ok(());
+            }
+            "core::byte_array::ByteArray" => {
+                let text = scalar(value).ok_or_else(|| invalid("a string"))?;
+                self.felts
+                    .extend(parse_calldata_value(&format!("bytearray:{text}"))?);
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        if let Some(bits) = ty.strip_prefix("core::integer::u") {
+            let bits = match bits {
+                "size" => Some(64),
+                bits => bits.parse::<u32>().ok(),
+            };
+            if let Some(bits) = bits {
+                let text = scalar(value).ok_or_else(|| invalid("an integer"))?;
+                let felt = match token_amount(self.config, &text)? {
+                    Some(raw) => Felt::from(raw),
+                    None => single_felt(&text)?,
+                };
+                let fits = u128::try_from(felt).is_ok_and(|v| bits >= 128 || v >> bits == 0);
+                if !fits {
+                    return Err(invalid(&format!("a u{bits}")));
+                }
+                self.felts.push(felt);
+                return Ok(());
+            }
+        }
+        if let Some(bits) = ty
+            .strip_prefix("core::integer::i")
+            .and_then(|bits| bits.parse::<u32>().ok())
+        {
+            let text = scalar(value).ok_or_else(|| invalid("an integer"))?;
+            let integer: i128 = text.parse().map_err(|_| invalid("an integer"))?;
+            let limit = 1u128 << (bits - 1);
+            let magnitude = integer.unsigned_abs();
+            if magnitude > limit || (integer >= 0 && magnitude == limit) {
+                return Err(invalid(&format!("an i{bits}")));
+            }
+            // Negative values are stored as P - |x|
+            self.felts.push(if integer < 0 {
+                -Felt::from(magnitude)
+            } else {
+                Felt::from(magnitude)
+            });
+            return Ok(());
+        }
+        if let Some(inner) = ty
+            .strip_prefix("core::array::Array::<")
+            .or_else(|| ty.strip_prefix("core::array::Span::<"))
+            .and_then(|rest| rest.strip_suffix('>'))
+        {
+            let items = value.as_array().ok_or_else(|| invalid("an array"))?;
+            self.felts.push(Felt::from(items.len()));
+            return items.iter().try_for_each(|item| self.encode(inner, item));
+        }
+        if let Some(inner) = ty
+            .strip_prefix("core::zeroable::NonZero::<")
+            .and_then(|rest| rest.strip_suffix('>'))
+        {
+            return self.encode(inner, value);
+        }
+        if let Some(inner) = ty.strip_prefix('(').and_then(|rest| rest.strip_suffix(')')) {
+            let elements = decode::split_tuple(inner);
+            let items = value
+                .as_array()
+                .filter(|items| items.len() == elements.len())
+                .ok_or_else(|| invalid(&format!("a tuple of {}", elements.len())))?;
+            return elements
+                .into_iter()
+                .zip(items)
+                .try_for_each(|(element, item)| self.encode(element, item));
+        }
+
+        let abi = self.abi;
+        let item = abi
+            .iter()
+            .find(|item| {
+                item["name"] == ty && matches!(item["type"].as_str(), Some("struct" | "enum"))
+            })
+            .ok_or_else(|| CliError::InvalidInput(format!("Unsupported type '{ty}'")))?;
+        if item["type"] == "struct" {
+            let members = item["members"].as_array().map(Vec::as_slice).unwrap_or(&[]);
+            let names: Vec<&str> = members
+                .iter()
+                .map(|member| member["name"].as_str().unwrap_or_default())
+                .collect();
+            let values: Vec<&Value> = match value {
+                Value::Object(fields) => names
+                    .iter()
+                    .map(|name| fields.get(*name))
+                    .collect::<Option<_>>()
+                    .ok_or_else(|| invalid(&format!("{{{}}}", names.join(", "))))?,
+                Value::Array(items) if items.len() == members.len() => items.iter().collect(),
+                _ => return Err(invalid(&format!("{{{}}}", names.join(", ")))),
+            };
+            return members.iter().zip(values).try_for_each(|(member, value)| {
+                self.encode(member["type"].as_str().unwrap_or_default(), value)
+            });
+        }
+
+        let variants = item["variants"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or(&[]);
+        let names: Vec<&str> = variants
+            .iter()
+            .map(|variant| variant["name"].as_str().unwrap_or_default())
+            .collect();
+        let selected = match value {
+            Value::String(name) => Some((name.as_str(), &Value::Null)),
+            Value::Object(object) if object.len() == 1 => object
+                .iter()
+                .next()
+                .map(|(name, payload)| (name.as_str(), payload)),
+            _ => None,
+        };
+        let (index, payload) = selected
+            .and_then(|(name, payload)| Some((names.iter().position(|n| *n == name)?, payload)))
+            .ok_or_else(|| invalid(&format!("one of {}", names.join(", "))))?;
+        self.felts.push(Felt::from(index));
+        match variants[index]["type"].as_str().unwrap_or("()") {
+            "()" => Ok(()),
+            inner => self.encode(inner, payload),
+        }
+    }
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(flag) => Some(flag.to_string()),
+        _ => None,
+    }
+}
+
+/// Read an amount with a token symbol (`1.5strk`, `20 USDC`) in the token's raw units. `None`
+/// when the value has no symbol.
+fn token_amount(config: &Config, text: &str) -> Result<Option<u128>> {
+    if text.starts_with("0x") || text.starts_with("0X") || text.contains(':') {
+        return Ok(None);
+    }
+    let Some(split) = text
+        .find(|c: char| c.is_ascii_alphabetic())
+        .filter(|&i| i > 0)
+    else {
+        return Ok(None);
+    };
+    let (amount, symbol) = text.split_at(split);
+    let (_, decimals) = balance::resolve_token(config, symbol).ok_or_else(|| {
+        CliError::InvalidInput(format!("Unknown token '{symbol}' in amount '{text}'"))
+    })?;
+    balance::parse_token_amount(amount.trim(), decimals)
+        .map(Some)
+        .ok_or_else(|| {
+            CliError::InvalidInput(format!(
+                "Invalid amount '{text}' ({symbol} has {decimals} decimals)"
+            ))
+        })
+}
+
+fn single_felt(text: &str) -> Result<Felt> {
+    match parse_calldata_value(text)?.as_slice() {
+        [felt] => Ok(*felt),
+        _ => Err(CliError::InvalidInput(format!(
+            "'{text}' doesn't fit in a felt"
+        ))),
+    }
+}
+
+/// Last path segment of every type name, e.g. `Span<u8>`, for signatures in errors
+fn short_type(ty: &str) -> String {
+    let mut short = String::new();
+    let mut segment = String::new();
+    for c in ty.chars().chain(std::iter::once(' ')) {
+        if matches!(c, '<' | '>' | '(' | ')' | ',' | ' ') {
+            let path = segment.trim_end_matches(':');
+            short.push_str(path.rsplit("::").next().unwrap_or(path));
+            segment.clear();
+            short.push(c);
+        } else {
+            segment.push(c);
+        }
+    }
+    short.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn encodes_arguments_with_abi() {
+        let abi: Vec<Value> = serde_json::from_value(json!([
+            {
+                "type": "struct",
+                "name": "game::Position",
+                "members": [
+                    { "name": "x", "type": "core::integer::u32" },
+                    { "name": "y", "type": "core::integer::i32" }
+                ]
+            },
+            {
+                "type": "enum",
+                "name": "core::option::Option::<game::Direction>",
+                "variants": [
+                    { "name": "Some", "type": "game::Direction" },
+                    { "name": "None", "type": "()" }
+                ]
+            },
+            {
+                "type": "enum",
+                "name": "game::Direction",
+                "variants": [
+                    { "name": "Left", "type": "()" },
+                    { "name": "Right", "type": "()" }
+                ]
+            },
+            {
+                "type": "interface",
+                "name": "game::IActions",
+                "items": [
+                    {
+                        "type": "function",
+                        "name": "transfer",
+                        "inputs": [
+                            { "name": "recipient", "type": "core::starknet::contract_address::ContractAddress" },
+                            { "name": "amount", "type": "core::integer::u256" }
+                        ]
+                    },
+                    {
+                        "type": "function",
+                        "name": "move",
+                        "inputs": [
+                            { "name": "to", "type": "game::Position" },
+                            { "name": "path", "type": "core::array::Span::<game::Direction>" },
+                            { "name": "then", "type": "core::option::Option::<game::Direction>" }
+                        ]
+                    }
+                ]
+            }
+        ]))
+        .unwrap();
+        let config = Config::default();
+        let encode = |function: &str, args: &str| {
+            let inputs = decode::find_function(&abi, function).unwrap();
+            Encoder::new(&abi, &config).inputs(function, inputs, parse_args(args)?)
+        };
+
+        let strk = Felt::from(1_500_000_000_000_000_000u128);
+        let transfer = encode("transfer", "0xabc, 1.5strk").unwrap();
+        assert_eq!(transfer, vec![Felt::from(0xabcu32), strk, Felt::ZERO]);
+        let named = encode("transfer", r#"{"amount": "1.5strk", "recipient": "0xabc"}"#).unwrap();
+        assert_eq!(named, transfer);
+
+        let moved = encode("move", "{x: 3, y: -1}, [Left, Right], Some(Right)").unwrap();
+        let expected = [3, -1, 2, 0, 1, 0, 1].map(|n: i32| match n {
+            n if n < 0 => -Felt::from(n.unsigned_abs()),
+            n => Felt::from(n.unsigned_abs()),
+        });
+        assert_eq!(moved, expected);
+        let positional = encode("move", "(3, -1), [], None").unwrap();
+        assert_eq!(positional[2..], [Felt::ZERO, Felt::ONE]);
+
+        assert!(encode("transfer", "0xabc").is_err());
+        assert!(encode("transfer", "0xabc, 1.5doge").is_err());
+        assert!(encode("move", "{x: -3, y: 0}, [], None").is_err());
+        assert!(encode("move", "{x: 3, y: 0}, [Up], None").is_err());
+        assert!(parse_args("[1, 2").is_err());
+        assert_eq!(
+            short_type("core::array::Span::<core::integer::u8>"),
+            "Span<u8>"
+        );
+    }
+}
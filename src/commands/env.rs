@@ -0,0 +1,118 @@
+use super::lookup::CacheMode;
+use crate::config::Config;
+use crate::error::{CliError, Result};
+use crate::output::OutputFormatter;
+use account_sdk::storage::{filestorage::FileSystemBackend, StorageBackend};
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct EnvOutput {
+    pub address: String,
+    pub chain: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+}
+
+impl EnvOutput {
+    /// `NAME=value` pairs in output order
+    fn variables(&self) -> Vec<(&'static str, &str)> {
+        let mut vars = vec![
+            ("CONTROLLER_ADDRESS", self.address.as_str()),
+            ("CONTROLLER_CHAIN", self.chain.as_str()),
+        ];
+        if let Some(username) = &self.username {
+            vars.push(("CONTROLLER_USERNAME", username));
+        }
+        if let Some(account) = &self.account {
+            vars.push(("CONTROLLER_ACCOUNT", account));
+        }
+        vars
+    }
+}
+
+/// Print the active session's account facts as shell `export` lines (or JSON with `--json`).
+pub async fn execute(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    account: Option<&str>,
+) -> Result<()> {
+    let backend = FileSystemBackend::new(config.resolve_storage_path(account));
+    let controller = backend
+        .controller()
+        .ok()
+        .flatten()
+        .ok_or(CliError::NoSession)?;
+
+    let address = format!("0x{:x}", controller.address);
+    let chain = starknet::core::utils::parse_cairo_short_string(&controller.chain_id)
+        .unwrap_or_else(|_| format!("0x{:x}", controller.chain_id));
+
+    // The username is a convenience: scripts still get the address if the lookup fails
+    let username = match super::lookup::resolve(
+        config,
+        &[],
+        std::slice::from_ref(&address),
+        CacheMode::Default,
+    )
+    .await
+    {
+        Ok(resolved) => resolved.into_iter().next().map(|e| e.username),
+        Err(e) => {
+            formatter.warning(&format!("Could not resolve username: {e}"));
+            None
+        }
+    };
+
+    let output = EnvOutput {
+        address,
+        chain,
+        username,
+        account: account.map(str::to_string),
+    };
+
+    if config.cli.json_output {
+        formatter.success(&output);
+    } else {
+        for (name, value) in output.variables() {
+            println!("export {name}={}", shell_quote(value));
+        }
+    }
+    Ok(())
+}
+
+/// Quote a value for POSIX shells, leaving plain identifiers and hex untouched
+fn shell_quote(value: &str) -> String {
+    if !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exports_quoted_variables() {
+        let output = EnvOutput {
+            address: "0x1234".to_string(),
+            chain: "SN_MAIN".to_string(),
+            username: Some("player one's".to_string()),
+            account: None,
+        };
+        let vars = output.variables();
+        assert_eq!(vars[0], ("CONTROLLER_ADDRESS", "0x1234"));
+        assert_eq!(vars.len(), 3);
+
+        assert_eq!(shell_quote("SN_MAIN"), "SN_MAIN");
+        assert_eq!(shell_quote("player one's"), r"'player one'\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+}
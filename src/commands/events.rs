@@ -0,0 +1,222 @@
+use super::selectors::SelectorDb;
+use crate::{
+    commands::{call::parse_block_id, decode, encode::FETCH_ABI},
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use serde::Serialize;
+use serde_json::Value;
+use starknet::core::types::{EmittedEvent, EventFilter, Felt};
+use starknet::core::utils::get_selector_from_name;
+use starknet::providers::{JsonRpcClient, Provider};
+
+#[derive(Serialize)]
+pub struct EventsOutput {
+    pub events: Vec<EventOutput>,
+    pub count: usize,
+    /// Set when --limit stopped the query early; pass it to --continuation-token to go on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuation_token: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct EventOutput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+    pub transaction_hash: String,
+    pub from_address: String,
+    /// Event name: the ABI's event when decoded, or a name known for the selector
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub keys: Vec<String>,
+    pub data: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decoded: Option<Value>,
+}
+
+/// Query events with `starknet_getEvents`, following continuation tokens until the block range
+/// is exhausted or `limit` events were read. With an ABI (a file, or `fetch` for the contract's
+/// class) events are decoded into named members.
+#[allow(clippy::too_many_arguments)]
+pub async fn execute(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    contract: Option<String>,
+    keys: Option<String>,
+    from_block: Option<String>,
+    to_block: Option<String>,
+    chunk_size: u64,
+    limit: Option<usize>,
+    continuation_token: Option<String>,
+    abi: Option<String>,
+    chain_id: Option<String>,
+    rpc_url: Option<String>,
+    account: Option<&str>,
+) -> Result<()> {
+    let address = contract
+        .as_deref()
+        .map(|contract| {
+            Felt::from_hex(contract)
+                .map_err(|e| CliError::InvalidInput(format!("Invalid contract address: {e}")))
+        })
+        .transpose()?;
+    let filter = EventFilter {
+        from_block: Some(parse_block_id(from_block)?),
+        to_block: Some(parse_block_id(to_block)?),
+        address,
+        keys: keys.as_deref().map(parse_keys).transpose()?,
+    };
+
+    let abi = match abi.as_deref() {
+        Some(FETCH_ABI) => {
+            let contract = contract.as_deref().ok_or_else(|| {
+                CliError::InvalidInput("--abi fetch needs --contract".to_string())
+            })?;
+            Some(
+                crate::commands::encode::fetch_abi(
+                    config,
+                    formatter,
+                    contract,
+                    chain_id.clone(),
+                    rpc_url.clone(),
+                    account,
+                )
+                .await?,
+            )
+        }
+        Some(path) => Some(decode::load_abi(path)?),
+        None => None,
+    };
+
+    let rpc_url = crate::chains::resolve_rpc_url(config, formatter, chain_id, rpc_url, account)?;
+    let url = url::Url::parse(&rpc_url)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
+    let provider = JsonRpcClient::new(crate::api::rpc_transport(url));
+    let selectors = SelectorDb::load();
+
+    let mut events = Vec::new();
+    let mut token = continuation_token;
+    let mut undecoded = 0;
+    loop {
+        let page = provider
+            .get_events(filter.clone(), token.take(), chunk_size.max(1))
+            .await
+            .map_err(|e| CliError::Network(format!("Failed to get events: {e}")))?;
+        if page.continuation_token.is_some() {
+            formatter.info(&format!(
+                "Read {} event(s)...",
+                events.len() + page.events.len()
+            ));
+        }
+
+        for event in page.events {
+            let (event, matched_abi) = event_output(event, abi.as_deref(), &selectors);
+            if !matched_abi {
+                undecoded += 1;
+            }
+            events.push(event);
+        }
+
+        token = page.continuation_token;
+        if token.is_none() || limit.is_some_and(|limit| events.len() >= limit) {
+            break;
+        }
+    }
+
+    if let Some(limit) = limit {
+        events.truncate(limit);
+    }
+    if undecoded > 0 {
+        formatter.warning(&format!(
+            "{undecoded} event(s) did not match the ABI and are shown raw"
+        ));
+    }
+    formatter.success(&EventsOutput {
+        count: events.len(),
+        events,
+        continuation_token: token,
+    });
+    Ok(())
+}
+
+/// An emitted event for output, named and decoded with `abi` when given (the bool is false if
+/// the event didn't match it), or named from the selector database otherwise.
+pub fn event_output(
+    event: EmittedEvent,
+    abi: Option<&[Value]>,
+    selectors: &SelectorDb,
+) -> (EventOutput, bool) {
+    let decoded = abi.map(|abi| decode::decode_event(abi, &event.keys, &event.data));
+    let (name, decoded, matched_abi) = match decoded {
+        Some(Ok((name, decoded))) => (Some(name), Some(decoded), true),
+        Some(Err(_)) => (None, None, false),
+        None => (
+            event
+                .keys
+                .first()
+                .and_then(|selector| selectors.name(*selector))
+                .map(str::to_string),
+            None,
+            true,
+        ),
+    };
+    let output = EventOutput {
+        block_number: event.block_number,
+        transaction_hash: format!("0x{:x}", event.transaction_hash),
+        from_address: format!("0x{:x}", event.from_address),
+        name,
+        keys: event.keys.iter().map(|f| format!("0x{f:x}")).collect(),
+        data: event.data.iter().map(|f| format!("0x{f:x}")).collect(),
+        decoded,
+    };
+    (output, matched_abi)
+}
+
+/// Parse a key filter: comma-separated positions, each `*` for any value or `|`-separated
+/// alternatives. Values are felts in hex or decimal, or event names hashed to their selector
+/// (e.g. 'Moved,*,0x123' or 'Moved|Attacked').
+fn parse_keys(keys: &str) -> Result<Vec<Vec<Felt>>> {
+    keys.split(',')
+        .map(str::trim)
+        .map(|position| {
+            if position == "*" || position.is_empty() {
+                return Ok(Vec::new());
+            }
+            position
+                .split('|')
+                .map(str::trim)
+                .map(|key| {
+                    let felt = if key.starts_with("0x") || key.starts_with("0X") {
+                        Felt::from_hex(key).ok()
+                    } else if key.bytes().all(|b| b.is_ascii_digit()) {
+                        Felt::from_dec_str(key).ok()
+                    } else {
+                        get_selector_from_name(key).ok()
+                    };
+                    felt.ok_or_else(|| CliError::InvalidInput(format!("Invalid event key '{key}'")))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_key_filters() {
+        let moved = get_selector_from_name("Moved").unwrap();
+        let attacked = get_selector_from_name("Attacked").unwrap();
+
+        let keys = parse_keys("Moved|Attacked, *, 0x123").unwrap();
+        assert_eq!(keys.len(), 3);
+        assert_eq!(keys[0], vec![moved, attacked]);
+        assert!(keys[1].is_empty());
+        assert_eq!(keys[2], vec![Felt::from(0x123u64)]);
+
+        assert_eq!(parse_keys("42").unwrap(), vec![vec![Felt::from(42u64)]]);
+        assert!(parse_keys("0xzz").is_err());
+    }
+}
@@ -1,7 +1,13 @@
 use crate::{
-    commands::{calldata::parse_calldata_value, session::authorize::PolicyStorage},
+    commands::{
+        audit::AuditEntry,
+        calldata::{parse_calldata_value, resolve_selector},
+        decode::{interpret, FeltInterpretation},
+        session::authorize::{ContractPolicy, MethodPolicy, PolicyFile, PolicyStorage},
+        submit_queue::{self, SubmissionLock},
+    },
     config::Config,
-    error::{CliError, Result},
+    error::{CliError, InputRequest, Result},
     output::OutputFormatter,
 };
 use account_sdk::{
@@ -11,27 +17,178 @@ use account_sdk::{
 };
 use serde::{Deserialize, Serialize};
 use starknet::{
-    core::types::{Call, Felt},
+    accounts::Account,
+    core::types::{
+        Call, FeeEstimate, Felt, InvokeTransactionResult, PriceUnit, TransactionReceipt,
+    },
     providers::Provider,
 };
+use std::collections::HashMap;
+
+/// Policy file written by `--auto-fix-policies`, relative to the account's storage path
+const AUTO_FIX_POLICY_FILE: &str = "auto_fix_policies.json";
+
+/// Headroom on estimated gas amounts and prices, as starknet-rs applies to its own estimates
+const GAS_MULTIPLIER: f64 = 1.5;
 
 #[derive(Debug, Deserialize)]
 struct CallFile {
+    /// Idempotency key for `--once`; when absent the file content is used
+    #[serde(default)]
+    id: Option<String>,
     calls: Vec<CallSpec>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-struct CallSpec {
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CallSpec {
     #[serde(rename = "contractAddress")]
-    contract_address: String,
-    entrypoint: String,
-    calldata: Vec<String>,
+    pub contract_address: String,
+    pub entrypoint: String,
+    pub calldata: Vec<String>,
 }
 
 #[derive(Serialize)]
 pub struct ExecuteOutput {
     pub transaction_hash: String,
     pub message: String,
+    pub chain_id: String,
+    pub explorer_url: Option<String>,
+    pub paymaster_used: bool,
+    /// RFC 3339 timestamp of when the transaction was submitted
+    pub submitted_at: String,
+    /// Tip paid per unit of L2 gas, in FRI (self-paid only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tip: Option<u64>,
+    /// The event `--wait-for-event` waited for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event: Option<crate::commands::events::EventOutput>,
+}
+
+/// What `execute` would do, printed instead of submitting under `--explain`
+#[derive(Serialize)]
+pub struct ExecutePlan {
+    pub chain_id: String,
+    pub rpc_url: String,
+    pub account: String,
+    pub calls: Vec<PlannedCall>,
+    /// 'paymaster' or 'self-pay'
+    pub fee_mode: String,
+    /// Approval thresholds exceeded; when non-empty a pending intent is written instead
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub approval_required: Vec<String>,
+    /// The `--only-if` condition, checked right before submitting
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub only_if: Option<String>,
+    pub storage_files: Vec<PlannedFile>,
+}
+
+#[derive(Serialize)]
+pub struct PlannedCall {
+    pub contract_address: String,
+    pub entrypoint: String,
+    pub selector: String,
+    pub calldata: Vec<FeltInterpretation>,
+    /// 'allowed', 'not_authorized', or 'unknown' when no policies are stored
+    pub policy: String,
+}
+
+#[derive(Serialize)]
+pub struct PlannedFile {
+    pub path: String,
+    pub purpose: String,
+}
+
+/// How `execute` submits the calls and what it checks. Callers set what they need and take the
+/// rest from `Default`, which matches the `execute` command's defaults.
+#[derive(Debug, Clone)]
+pub struct ExecuteOptions {
+    /// Wait for the transaction and check its receipt
+    pub wait: bool,
+    /// Seconds to wait with `wait`
+    pub timeout: u64,
+    pub chain_id: Option<String>,
+    pub rpc_url: Option<String>,
+    /// Pay fees from the account instead of the paymaster
+    pub no_paymaster: bool,
+    /// Submit self-paid transactions without taking the submission lock
+    pub no_queue: bool,
+    /// Approved intent to submit, instead of writing a new one past approval thresholds
+    pub intent: Option<String>,
+    /// Switch to the session's chain when the RPC is on another one
+    pub switch_chain: bool,
+    /// Re-authorize without asking when the session policies miss a call
+    pub auto_fix_policies: bool,
+    /// Ask an interactive caller whether to re-authorize, for commands that take
+    /// `--auto-fix-policies`
+    pub offer_policy_fix: bool,
+    /// Refuse a call file already submitted within the replay window
+    pub once: bool,
+    /// Submit even when the call file was already submitted
+    pub force: bool,
+    /// Events the receipt must contain (`--require-events`)
+    pub require_events: Vec<String>,
+    /// Event to wait for after the receipt (`--wait-for-event`)
+    pub wait_for_event: Option<String>,
+    /// Guard checked with a read call before submitting (`--only-if`)
+    pub only_if: Option<String>,
+}
+
+impl Default for ExecuteOptions {
+    fn default() -> Self {
+        Self {
+            wait: false,
+            timeout: 300,
+            chain_id: None,
+            rpc_url: None,
+            no_paymaster: false,
+            no_queue: false,
+            intent: None,
+            switch_chain: false,
+            auto_fix_policies: false,
+            offer_policy_fix: false,
+            once: false,
+            force: false,
+            require_events: Vec::new(),
+            wait_for_event: None,
+            only_if: None,
+        }
+    }
+}
+
+/// What `execute_calls` did with the calls
+pub enum Execution {
+    /// Submitted. With `wait`, `confirmation` is why waiting for the receipt, a required event
+    /// or an awaited event failed; the transaction was still sent, so it must not be resubmitted.
+    Submitted {
+        output: ExecuteOutput,
+        confirmation: Result<()>,
+    },
+    /// Over an approval threshold: written as a pending intent instead of submitting
+    PendingIntent(crate::commands::intent::IntentOutput),
+    /// Under `--explain`: what would be submitted
+    Planned(ExecutePlan),
+    /// Under `--simulate`: the simulated transaction
+    Simulated(crate::commands::simulate::SimulationOutput),
+}
+
+impl Execution {
+    /// The submitted transaction, for commands built on `execute_calls`. `what` names the
+    /// submission in the error when it was only planned, simulated or left for approval.
+    pub fn into_submitted(self, what: &str) -> Result<ExecuteOutput> {
+        match self {
+            Self::Submitted {
+                output,
+                confirmation,
+            } => confirmation.map(|()| output),
+            Self::PendingIntent(intent) => Err(CliError::TransactionFailed(format!(
+                "{what} requires a second approval (intent {})",
+                intent.intent_id
+            ))),
+            Self::Planned(_) | Self::Simulated(_) => Err(CliError::TransactionFailed(format!(
+                "{what} was not submitted"
+            ))),
+        }
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -42,45 +199,142 @@ pub async fn execute(
     entrypoint: Option<String>,
     calldata: Option<String>,
     file: Option<String>,
-    wait: bool,
-    timeout: u64,
-    chain_id: Option<String>,
-    rpc_url: Option<String>,
-    no_paymaster: bool,
+    options: ExecuteOptions,
     account: Option<&str>,
 ) -> Result<()> {
-    // Resolve --chain-id to RPC URL
-    let rpc_url = resolve_chain_id_to_rpc(chain_id, rpc_url)?;
-    // Parse calls from arguments or file
-    let calls = if let Some(file_path) = file {
-        // Load calls from JSON file
-        let file_content = std::fs::read_to_string(&file_path)
-            .map_err(|e| CliError::InvalidInput(format!("Failed to read file: {e}")))?;
+    if options.once && file.is_none() {
+        return Err(CliError::InvalidInput(
+            "--once only applies to call files passed with --file".to_string(),
+        ));
+    }
+    // Approved intents carry their own calls, loaded once the session is known
+    let (calls, call_file_identity) = if options.intent.is_some() {
+        (Vec::new(), None)
+    } else {
+        calls_from_args(contract, entrypoint, calldata, file)?
+    };
 
-        let call_file: CallFile = serde_json::from_str(&file_content)
-            .map_err(|e| CliError::InvalidInput(format!("Invalid file format: {e}")))?;
+    let execution = submit(
+        config,
+        formatter,
+        calls,
+        call_file_identity,
+        options,
+        account,
+    )
+    .await?;
+    report(config, formatter, execution)
+}
 
-        call_file.calls
-    } else if let (Some(contract_addr), Some(entry), Some(data)) = (contract, entrypoint, calldata)
-    {
-        // Single call from CLI arguments
-        vec![CallSpec {
-            contract_address: contract_addr,
-            entrypoint: entry,
-            calldata: data.split(',').map(|s| s.trim().to_string()).collect(),
-        }]
-    } else {
+/// Submit calls built in code, with the checks and bookkeeping of the `execute` command, and
+/// return what happened instead of printing it. Progress and warnings still go to `formatter`.
+pub async fn execute_calls(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    calls: Vec<CallSpec>,
+    options: ExecuteOptions,
+    account: Option<&str>,
+) -> Result<Execution> {
+    if options.once {
         return Err(CliError::InvalidInput(
-            "Either --file or all of contract, entrypoint, calldata arguments must be provided"
-                .to_string(),
+            "--once only applies to call files passed with --file".to_string(),
         ));
-    };
+    }
+    submit(config, formatter, calls, None, options, account).await
+}
 
-    formatter.info(&format!("Preparing to execute {} call(s)...", calls.len()));
+/// Print an execution the way the `execute` command does. Human mode already showed the
+/// transaction link while submitting.
+pub fn report(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    execution: Execution,
+) -> Result<()> {
+    match execution {
+        Execution::Submitted {
+            output,
+            confirmation,
+        } => {
+            if config.cli.json_output {
+                formatter.success(&output);
+            }
+            confirmation
+        }
+        Execution::PendingIntent(intent) => {
+            crate::commands::intent::report_created(config, formatter, &intent);
+            Ok(())
+        }
+        Execution::Planned(plan) => {
+            formatter.success(&plan);
+            Ok(())
+        }
+        Execution::Simulated(simulation) => {
+            if let Some(reason) = &simulation.revert_reason {
+                formatter.warning(&format!("Simulated transaction reverted: {reason}"));
+            }
+            formatter.success(&simulation);
+            Ok(())
+        }
+    }
+}
+
+/// `call_file_identity` is the call file's (id, content) for `--once`.
+async fn submit(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    calls: Vec<CallSpec>,
+    call_file_identity: Option<(Option<String>, String)>,
+    options: ExecuteOptions,
+    account: Option<&str>,
+) -> Result<Execution> {
+    // Kept to retry the same calls after re-authorizing with fixed policies
+    let original_calls = calls.clone();
+    let original_options = options.clone();
+    let ExecuteOptions {
+        wait,
+        timeout,
+        chain_id,
+        rpc_url,
+        no_paymaster,
+        no_queue,
+        intent,
+        switch_chain,
+        auto_fix_policies: _,
+        offer_policy_fix: _,
+        once,
+        force,
+        require_events,
+        wait_for_event,
+        only_if,
+    } = options;
+
+    // Resolve --chain-id to RPC URL
+    let rpc_url = resolve_chain_id_to_rpc(config, chain_id, rpc_url)?;
+    let required_events = require_events
+        .iter()
+        .map(|spec| EventRequirement::parse(spec, "--require-events"))
+        .collect::<Result<Vec<_>>>()?;
+    if !required_events.is_empty() && !wait {
+        return Err(CliError::InvalidInput(
+            "--require-events checks the receipt, so it needs --wait".to_string(),
+        ));
+    }
+    let awaited_event = wait_for_event
+        .as_deref()
+        .map(|spec| EventRequirement::parse(spec, "--wait-for-event"))
+        .transpose()?;
+    if awaited_event.is_some() && !wait {
+        return Err(CliError::InvalidInput(
+            "--wait-for-event polls after submitting, so it needs --wait".to_string(),
+        ));
+    }
+    let guard = only_if.as_deref().map(Guard::parse).transpose()?;
+
+    let calls = apply_calldata_prefixes(config, formatter, calls);
 
     // Load controller metadata first to get address and chain_id for session key
     let storage_path = config.resolve_storage_path(account);
-    let backend = FileSystemBackend::new(storage_path);
+    let backend = FileSystemBackend::new(storage_path.clone());
 
     let controller_metadata = backend
         .controller()
@@ -114,6 +368,49 @@ pub async fn execute(
         ));
     }
 
+    crate::commands::session::revocation::ensure_not_revoked(
+        config,
+        formatter,
+        &storage_path,
+        &session_key,
+    )
+    .await?;
+
+    // Fingerprint of the call file and session, checked before and recorded after submitting
+    let replay_fingerprint = match (&call_file_identity, once) {
+        (Some((id, content)), true) => {
+            let guid = match backend.get("session_key_guid").ok().flatten() {
+                Some(StorageValue::String(guid)) => guid,
+                _ => session_key.clone(),
+            };
+            let fingerprint = crate::commands::replay::fingerprint(id.as_deref(), content, &guid);
+            if force {
+                formatter.warning("--force: skipping the duplicate submission check");
+            } else {
+                crate::commands::replay::check(
+                    &storage_path,
+                    &fingerprint,
+                    config.cli.replay_window_seconds,
+                )?;
+            }
+            Some(fingerprint)
+        }
+        _ => None,
+    };
+
+    let approved_intent = intent
+        .as_deref()
+        .map(|intent_ref| {
+            crate::commands::intent::load_approved(config, intent_ref, controller_metadata.address)
+        })
+        .transpose()?;
+    let calls = match &approved_intent {
+        Some(approved) => approved.calls.clone(),
+        None => calls,
+    };
+
+    formatter.info(&format!("Preparing to execute {} call(s)...", calls.len()));
+
     let credentials = session_metadata
         .credentials
         .ok_or_else(|| CliError::InvalidSessionData("No credentials found".to_string()))?;
@@ -122,8 +419,13 @@ pub async fn execute(
     let signing_key = starknet::signers::SigningKey::from_secret_scalar(credentials.private_key);
     let owner = Owner::Signer(Signer::Starknet(signing_key));
 
+    let stored_rpc_url = backend.get("session_rpc_url").ok().and_then(|v| match v {
+        Some(StorageValue::String(url)) => Some(url),
+        _ => None,
+    });
+
     // Priority: CLI flag > config > stored session RPC
-    let effective_rpc_url = rpc_url
+    let mut effective_rpc_url = rpc_url
         .clone()
         .or_else(|| {
             if config.session.rpc_url_explicitly_set {
@@ -132,12 +434,7 @@ pub async fn execute(
                 None
             }
         })
-        .or_else(|| {
-            backend.get("session_rpc_url").ok().and_then(|v| match v {
-                Some(StorageValue::String(url)) => Some(url),
-                _ => None,
-            })
-        })
+        .or_else(|| stored_rpc_url.clone())
         .unwrap_or_else(|| config.session.rpc_url.clone());
 
     // Load stored policies for pre-execution validation
@@ -152,22 +449,16 @@ pub async fn execute(
 
     // If --rpc-url was provided, validate it's a Cartridge RPC endpoint
     if let Some(ref url) = rpc_url {
-        if !url.starts_with("https://api.cartridge.gg") {
-            return Err(CliError::InvalidInput(
-                "Only Cartridge RPC endpoints are supported. Use: https://api.cartridge.gg/x/starknet/mainnet or https://api.cartridge.gg/x/starknet/sepolia".to_string()
-            ));
-        }
+        crate::chains::ensure_cartridge_rpc(config, url)?;
     }
 
     // If --rpc-url was provided, validate it and check chain_id matches session
     if rpc_url.is_some() {
         formatter.info("Validating RPC endpoint...");
-        let provider = starknet::providers::jsonrpc::JsonRpcClient::new(
-            starknet::providers::jsonrpc::HttpTransport::new(
-                url::Url::parse(&effective_rpc_url)
-                    .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?,
-            ),
-        );
+        let provider = starknet::providers::jsonrpc::JsonRpcClient::new(crate::api::rpc_transport(
+            url::Url::parse(&effective_rpc_url)
+                .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?,
+        ));
 
         match starknet::providers::Provider::chain_id(&provider).await {
             Ok(rpc_chain_id) => {
@@ -181,9 +472,21 @@ pub async fn execute(
                     )
                     .unwrap_or_else(|_| format!("0x{:x}", controller_metadata.chain_id));
 
-                    return Err(CliError::InvalidInput(format!(
-                        "Chain ID mismatch: RPC endpoint is on {rpc_chain_name} but session is for {session_chain_name}"
-                    )));
+                    if !switch_chain {
+                        return Err(CliError::ChainMismatch {
+                            session_chain: session_chain_name,
+                            rpc_chain: rpc_chain_name,
+                        });
+                    }
+
+                    // Re-resolve the RPC from the session's chain instead of failing
+                    effective_rpc_url = match stored_rpc_url {
+                        Some(url) => url,
+                        None => crate::chains::rpc_url_for(config, &session_chain_name)?,
+                    };
+                    formatter.warning(&format!(
+                        "RPC endpoint is on {rpc_chain_name} but session is for {session_chain_name}; switching to {effective_rpc_url}"
+                    ));
                 }
                 // Validation successful, continue
             }
@@ -207,36 +510,164 @@ pub async fn execute(
     .await
     .map_err(|e| CliError::Storage(format!("Failed to create controller: {e}")))?;
 
-    // Convert CallSpec to starknet Call
-    let starknet_calls: Vec<Call> = calls
-        .iter()
-        .map(|call| {
-            let contract_address = Felt::from_hex(&call.contract_address)
-                .map_err(|e| CliError::InvalidInput(format!("Invalid contract address: {e}")))?;
+    let starknet_calls = starknet_calls(&calls)?;
 
-            let selector = starknet::core::utils::get_selector_from_name(&call.entrypoint)
-                .map_err(|e| CliError::InvalidInput(format!("Invalid entrypoint: {e}")))?;
-
-            let calldata: Vec<Felt> = call
-                .calldata
-                .iter()
-                .map(|data| parse_calldata_value(data.trim()))
-                .collect::<Result<Vec<Vec<Felt>>>>()?
-                .into_iter()
-                .flatten()
-                .collect();
+    if config.cli.explain {
+        let session_chain =
+            starknet::core::utils::parse_cairo_short_string(&controller_metadata.chain_id)
+                .unwrap_or_else(|_| format!("0x{:x}", controller_metadata.chain_id));
+        let planned_calls = calls
+            .iter()
+            .zip(&starknet_calls)
+            .map(|(spec, call)| PlannedCall {
+                contract_address: format!("0x{:x}", call.to),
+                entrypoint: spec.entrypoint.clone(),
+                selector: format!("{:#x}", call.selector),
+                calldata: call.calldata.iter().map(interpret).collect(),
+                policy: policy_status(spec, stored_policies.as_ref()).to_string(),
+            })
+            .collect();
+        let approval_required = match approved_intent {
+            Some(_) => Vec::new(),
+            None => crate::commands::intent::threshold_violations(config, &calls)?,
+        };
 
-            Ok(Call {
-                to: contract_address,
-                selector,
-                calldata,
+        let mut storage_files = Vec::new();
+        let mut touch = |path: std::path::PathBuf, purpose: &str| {
+            storage_files.push(PlannedFile {
+                path: path.display().to_string(),
+                purpose: purpose.to_string(),
             })
-        })
-        .collect::<Result<Vec<_>>>()?;
+        };
+        if !approval_required.is_empty() {
+            touch(
+                crate::commands::intent::intents_dir(config),
+                "pending intent written here instead of submitting",
+            );
+        } else {
+            if !no_queue {
+                touch(
+                    storage_path.join(submit_queue::LOCK_FILE),
+                    "submission lock, created and removed",
+                );
+                if no_paymaster {
+                    touch(
+                        storage_path.join(submit_queue::NONCE_FILE),
+                        "last self-paid nonce, updated",
+                    );
+                }
+            }
+            let targets: Vec<Felt> = starknet_calls.iter().map(|call| call.to).collect();
+            if let Some(path) = crate::commands::balance::invalidated_cache_path(
+                config,
+                &storage_path,
+                controller_metadata.address,
+                &targets,
+                no_paymaster,
+            ) {
+                touch(path, "balance cache, removed");
+            }
+            if let Some(intent_ref) = &intent {
+                touch(
+                    crate::commands::intent::resolve_path(config, intent_ref),
+                    "approved intent, removed once submitted",
+                );
+            }
+            if replay_fingerprint.is_some() {
+                touch(
+                    storage_path.join(crate::commands::replay::REPLAY_FILE),
+                    "submitted call files, updated for --once",
+                );
+            }
+        }
+
+        return Ok(Execution::Planned(ExecutePlan {
+            chain_id: session_chain,
+            rpc_url: effective_rpc_url,
+            account: format!("0x{:x}", controller_metadata.address),
+            calls: planned_calls,
+            fee_mode: if no_paymaster {
+                "self-pay"
+            } else {
+                "paymaster"
+            }
+            .to_string(),
+            approval_required,
+            only_if: guard.as_ref().map(|guard| guard.spec.clone()),
+            storage_files,
+        }));
+    }
 
     // Validate calls against registered session policies
     if let Some(ref policies) = stored_policies {
-        validate_calls_against_policies(&calls, policies)?;
+        if let Err(e) = validate_calls_against_policies(&calls, policies) {
+            if calls.is_empty() || !confirm_policy_fix(config, &original_options, &e)? {
+                return Err(e);
+            }
+            formatter.warning(&e.to_string());
+            remediate_policies(
+                config,
+                formatter,
+                &calls,
+                policies,
+                &storage_path,
+                &effective_rpc_url,
+                session_metadata.session.inner.expires_at,
+                account,
+            )
+            .await?;
+
+            formatter.info("Retrying execute with the new session...");
+            return Box::pin(submit(
+                config,
+                formatter,
+                original_calls,
+                call_file_identity,
+                ExecuteOptions {
+                    chain_id: None,
+                    rpc_url,
+                    auto_fix_policies: false,
+                    ..original_options
+                },
+                account,
+            ))
+            .await;
+        }
+    }
+
+    if config.cli.simulate {
+        let session_chain =
+            starknet::core::utils::parse_cairo_short_string(&controller_metadata.chain_id)
+                .unwrap_or_else(|_| format!("0x{:x}", controller_metadata.chain_id));
+        let timestamp = config
+            .cli
+            .simulate_timestamp
+            .as_deref()
+            .map(crate::commands::simulate::parse_timestamp)
+            .transpose()?;
+        let block = crate::commands::simulate::pin_block(
+            &controller.provider,
+            config.cli.simulate_at_block.clone(),
+            timestamp,
+        )
+        .await?;
+        formatter.info(&format!(
+            "Simulating {} call(s) on {session_chain}{}...",
+            starknet_calls.len(),
+            block
+                .as_ref()
+                .map(|block| format!(" at block {}", block.block_number))
+                .unwrap_or_default()
+        ));
+        let simulation = crate::commands::simulate::simulate(
+            &controller,
+            controller_metadata.address,
+            starknet_calls,
+            session_chain,
+            block,
+        )
+        .await?;
+        return Ok(Execution::Simulated(simulation));
     }
 
     let chain_name = match controller.provider.chain_id().await {
@@ -248,60 +679,185 @@ pub async fn execute(
                 .unwrap_or_else(|_| format!("0x{chain_id:x}"))
         }
     };
-    let is_mainnet = chain_name == "SN_MAIN";
 
-    // Execute based on paymaster preference
-    let result = if no_paymaster {
-        // Force self-pay: estimate fee and execute directly
-        formatter.info(&format!(
-            "Executing transaction on {chain_name} without paymaster..."
-        ));
-        let estimate = controller
-            .estimate_invoke_fee(starknet_calls.clone())
-            .await
-            .map_err(|e| CliError::TransactionFailed(format!("Fee estimation failed: {e}")))?;
-        controller
-            .execute(starknet_calls, Some(estimate), None)
-            .await
-            .map_err(|e| CliError::TransactionFailed(format!("Transaction failed: {e}")))?
-    } else {
-        // Try paymaster first, fail if unavailable (no fallback)
-        formatter.info(&format!("Executing transaction on {chain_name}..."));
-        match controller
-            .execute_from_outside_v3(starknet_calls, None)
-            .await
-        {
-            Ok(result) => result,
-            Err(e) => {
-                return Err(CliError::TransactionFailed(format!(
-                    "Paymaster execution failed: {e}\nUse --no-paymaster to force self-pay"
-                )));
-            }
+    if let Some(approved) = &approved_intent {
+        if approved.chain_id != chain_name {
+            return Err(CliError::InvalidInput(format!(
+                "Intent {} was created for {} but the session is on {chain_name}",
+                approved.id, approved.chain_id
+            )));
         }
+    }
+
+    // Large transfers/approvals wait for a second operator unless this is an approved intent
+    if approved_intent.is_none() {
+        let reasons = crate::commands::intent::threshold_violations(config, &calls)?;
+        if !reasons.is_empty() {
+            return crate::commands::intent::create(
+                config,
+                controller_metadata.address,
+                &chain_name,
+                calls,
+                reasons,
+            )
+            .map(Execution::PendingIntent);
+        }
+    }
+
+    let targets: Vec<Felt> = starknet_calls.iter().map(|call| call.to).collect();
+
+    // Serialize submissions from this account so concurrent invocations don't race on the nonce
+    let submission_lock = if no_queue {
+        None
+    } else {
+        Some(SubmissionLock::acquire(&storage_path, formatter).await?)
+    };
+    // Paymaster flows use outside-execution nonces; only self-pay consumes the account nonce
+    let queued_nonce = if submission_lock.is_some() && no_paymaster {
+        Some(
+            submit_queue::wait_for_nonce(
+                &controller.provider,
+                &storage_path,
+                controller_metadata.address,
+                formatter,
+            )
+            .await?,
+        )
+    } else {
+        None
     };
 
+    // Checked under the submission lock, as late as possible, so our own submissions can't race it
+    if let Some(guard) = &guard {
+        guard.check(&controller.provider).await?;
+    }
+
+    // Only a self-paid invoke carries a tip; the paymaster sets its own
+    let tip = if no_paymaster { config.cli.tip } else { 0 };
+
+    let mut audit = AuditEntry::new(
+        "execute",
+        account,
+        controller_metadata.address,
+        &chain_name,
+        calls.clone(),
+        matched_policies(&calls, stored_policies.as_ref()),
+        !no_paymaster,
+    );
+    audit.batch_id = config.cli.batch_id.clone();
+
+    // Execute based on paymaster preference
+    let submitted: Result<_> = async {
+        Ok(if no_paymaster {
+            // Force self-pay: estimate fee and execute directly
+            formatter.info(&format!(
+                "Executing transaction on {chain_name} without paymaster..."
+            ));
+            let estimate = controller
+                .estimate_invoke_fee(starknet_calls.clone())
+                .await
+                .map_err(|e| CliError::TransactionFailed(format!("Fee estimation failed: {e}")))?;
+            crate::commands::balance::ensure_fee_balance(
+                config,
+                formatter,
+                &controller.provider,
+                controller_metadata.address,
+                Felt::from(fee_with_tip(
+                    estimate.overall_fee,
+                    estimate.l2_gas_consumed,
+                    tip,
+                )),
+            )
+            .await?;
+            if tip == 0 {
+                controller
+                    .execute(starknet_calls, Some(estimate), None)
+                    .await
+                    .map_err(|e| CliError::TransactionFailed(format!("Transaction failed: {e}")))?
+            } else {
+                formatter.info(&format!(
+                    "Tipping {tip} FRI per unit of L2 gas (estimated fee with tip: {} FRI)",
+                    fee_with_tip(estimate.overall_fee, estimate.l2_gas_consumed, tip)
+                ));
+                execute_with_tip(&controller, starknet_calls, &estimate, tip).await?
+            }
+        } else {
+            // Try paymaster first, fail if unavailable (no fallback)
+            formatter.info(&format!("Executing transaction on {chain_name}..."));
+            match controller
+                .execute_from_outside_v3(starknet_calls, None)
+                .await
+            {
+                Ok(result) => result,
+                Err(e) => {
+                    return Err(CliError::TransactionFailed(format!(
+                        "Paymaster execution failed: {e}\nUse --no-paymaster to force self-pay"
+                    )));
+                }
+            }
+        })
+    }
+    .await;
+    audit.submitted(
+        config,
+        formatter,
+        submitted.as_ref().map(|result| result.transaction_hash),
+    );
+    let result = submitted?;
+
+    if let Some(nonce) = queued_nonce {
+        submit_queue::record_nonce(&storage_path, nonce);
+    }
+    drop(submission_lock);
+
+    crate::commands::balance::invalidate_cache_after_tx(
+        config,
+        &storage_path,
+        controller_metadata.address,
+        &targets,
+        no_paymaster,
+    );
+
+    if let Some(intent_ref) = &intent {
+        crate::commands::intent::consume(config, intent_ref);
+    }
+
     let transaction_hash = format!("0x{:x}", result.transaction_hash);
+    if let Some(fingerprint) = replay_fingerprint {
+        crate::commands::replay::record(
+            &storage_path,
+            fingerprint,
+            call_file_identity.and_then(|(id, _)| id),
+            transaction_hash.clone(),
+            config.cli.replay_window_seconds,
+        );
+    }
+    let explorer_url = crate::chains::explorer_tx_url(&chain_name, &transaction_hash);
 
-    let output = ExecuteOutput {
+    let mut output = ExecuteOutput {
         transaction_hash: transaction_hash.clone(),
-        message: if wait {
-            "Transaction submitted. Waiting for confirmation...".to_string()
-        } else {
-            "Transaction submitted successfully".to_string()
-        },
+        message: "Transaction submitted successfully".to_string(),
+        chain_id: chain_name,
+        explorer_url,
+        paymaster_used: !no_paymaster,
+        submitted_at: chrono::Utc::now().to_rfc3339(),
+        tip: (tip > 0).then_some(tip),
+        event: None,
     };
-    let voyager_subdomain = if is_mainnet { "" } else { "sepolia." };
 
-    if config.cli.json_output {
-        formatter.success(&output);
-    } else {
+    formatter.progress(&output);
+    if !config.cli.json_output {
         formatter.info(&format!(
-            "Transaction: https://{voyager_subdomain}voyager.online/tx/{transaction_hash}"
+            "Transaction: {}",
+            output.explorer_url.as_deref().unwrap_or(&transaction_hash)
         ));
     }
 
     // Wait for transaction confirmation if requested
-    if wait {
+    let confirmation = async {
+        if !wait {
+            return Ok(());
+        }
         formatter.info("Waiting for transaction confirmation...");
 
         let start = std::time::Instant::now();
@@ -320,9 +876,46 @@ pub async fn execute(
                 .get_transaction_receipt(result.transaction_hash)
                 .await
             {
-                Ok(_receipt) => {
+                Ok(receipt) => {
                     formatter.info("Transaction confirmed!");
-                    break;
+                    audit.confirmed(config, formatter, &receipt.receipt);
+                    if !output.paymaster_used {
+                        let fee = match &receipt.receipt {
+                            TransactionReceipt::Invoke(r) => &r.actual_fee,
+                            TransactionReceipt::Declare(r) => &r.actual_fee,
+                            TransactionReceipt::Deploy(r) => &r.actual_fee,
+                            TransactionReceipt::DeployAccount(r) => &r.actual_fee,
+                            TransactionReceipt::L1Handler(r) => &r.actual_fee,
+                        };
+                        crate::meta::fee_paid(
+                            fee.amount,
+                            match fee.unit {
+                                PriceUnit::Wei => "WEI",
+                                PriceUnit::Fri => "FRI",
+                            },
+                        );
+                    }
+                    let missing = missing_events(&required_events, receipt.receipt.events());
+                    if !missing.is_empty() {
+                        return Err(CliError::MissingEvents {
+                            transaction_hash: transaction_hash.clone(),
+                            missing,
+                        });
+                    }
+                    output.message = "Transaction confirmed".to_string();
+                    if let Some(awaited) = &awaited_event {
+                        let event = wait_for_event_after(
+                            &controller.provider,
+                            formatter,
+                            awaited,
+                            &receipt,
+                            timeout_duration.saturating_sub(start.elapsed()),
+                        )
+                        .await?;
+                        output.message = format!("Transaction confirmed and {} seen", awaited.spec);
+                        output.event = Some(event);
+                    }
+                    return Ok(());
                 }
                 Err(_) => {
                     // Transaction not yet confirmed, wait and retry
@@ -331,8 +924,421 @@ pub async fn execute(
             }
         }
     }
+    .await;
 
-    Ok(())
+    Ok(Execution::Submitted {
+        output,
+        confirmation,
+    })
+}
+
+/// Calls from a JSON, YAML or TOML call file or from the positional arguments, plus the file's
+/// (id, content) for `--once`.
+#[allow(clippy::type_complexity)]
+pub fn calls_from_args(
+    contract: Option<String>,
+    entrypoint: Option<String>,
+    calldata: Option<String>,
+    file: Option<String>,
+) -> Result<(Vec<CallSpec>, Option<(Option<String>, String)>)> {
+    if let Some(file_path) = file {
+        let file_content = std::fs::read_to_string(&file_path)
+            .map_err(|e| CliError::InvalidInput(format!("Failed to read file: {e}")))?;
+
+        let call_file: CallFile =
+            crate::commands::file_format::parse_structured(&file_path, &file_content)
+                .map_err(|e| CliError::InvalidInput(format!("Invalid file format: {e}")))?;
+
+        Ok((call_file.calls, Some((call_file.id, file_content))))
+    } else if let (Some(contract_addr), Some(entry), Some(data)) = (contract, entrypoint, calldata)
+    {
+        // Single call from CLI arguments
+        let call = CallSpec {
+            contract_address: contract_addr,
+            entrypoint: entry,
+            calldata: data.split(',').map(|s| s.trim().to_string()).collect(),
+        };
+        Ok((vec![call], None))
+    } else {
+        Err(CliError::InvalidInput(
+            "Either --file or all of contract, entrypoint, calldata arguments must be provided"
+                .to_string(),
+        ))
+    }
+}
+
+/// An event the confirmed transaction must emit, from `--require-events [<contract>:]<event>`.
+/// The event is a name (hashed like the ABI's event enum variants) or a raw `0x` selector.
+#[derive(Debug, PartialEq)]
+struct EventRequirement {
+    spec: String,
+    contract: Option<Felt>,
+    selector: Felt,
+}
+
+impl EventRequirement {
+    fn parse(spec: &str, flag: &str) -> Result<Self> {
+        let (contract, event) = match spec.split_once(':') {
+            Some((contract, event)) => {
+                let contract = Felt::from_hex(contract.trim()).map_err(|e| {
+                    CliError::InvalidInput(format!(
+                        "Invalid contract address in {flag} '{spec}': {e}"
+                    ))
+                })?;
+                (Some(contract), event.trim())
+            }
+            None => (None, spec.trim()),
+        };
+        let selector = if event.starts_with("0x") {
+            Felt::from_hex(event)
+        } else {
+            starknet::core::utils::get_selector_from_name(event).map_err(|e| e.to_string())
+        }
+        .map_err(|e| CliError::InvalidInput(format!("Invalid event in {flag} '{spec}': {e}")))?;
+        Ok(Self {
+            spec: spec.to_string(),
+            contract,
+            selector,
+        })
+    }
+
+    fn matches(&self, event: &starknet::core::types::Event) -> bool {
+        event.keys.first() == Some(&self.selector)
+            && self.contract.is_none_or(|c| c == event.from_address)
+    }
+}
+
+/// The first event matching `awaited`: from the transaction's receipt, or else from later blocks,
+/// polled with `starknet_getEvents` until `timeout` (for flows where another transaction, e.g. a
+/// game server's, emits the outcome).
+async fn wait_for_event_after<P: Provider + Sync>(
+    provider: &P,
+    formatter: &dyn OutputFormatter,
+    awaited: &EventRequirement,
+    receipt: &starknet::core::types::TransactionReceiptWithBlockInfo,
+    timeout: std::time::Duration,
+) -> Result<crate::commands::events::EventOutput> {
+    let name = awaited.spec.rsplit(':').next().map(str::to_string);
+    let transaction_hash = *receipt.receipt.transaction_hash();
+    if let Some(event) = receipt
+        .receipt
+        .events()
+        .iter()
+        .find(|event| awaited.matches(event))
+    {
+        return Ok(crate::commands::events::EventOutput {
+            block_number: Some(receipt.block.block_number()),
+            transaction_hash: format!("0x{transaction_hash:x}"),
+            from_address: format!("0x{:x}", event.from_address),
+            name,
+            keys: event.keys.iter().map(|f| format!("0x{f:x}")).collect(),
+            data: event.data.iter().map(|f| format!("0x{f:x}")).collect(),
+            decoded: None,
+        });
+    }
+
+    formatter.info(&format!(
+        "{} not in the receipt, watching later blocks...",
+        awaited.spec
+    ));
+    let filter = starknet::core::types::EventFilter {
+        from_block: Some(starknet::core::types::BlockId::Number(
+            receipt.block.block_number(),
+        )),
+        to_block: Some(starknet::core::types::BlockId::Tag(
+            starknet::core::types::BlockTag::Latest,
+        )),
+        address: awaited.contract,
+        keys: Some(vec![vec![awaited.selector]]),
+    };
+    let start = std::time::Instant::now();
+    let mut token = None;
+    loop {
+        if start.elapsed() > timeout {
+            return Err(CliError::TransactionFailed(format!(
+                "Transaction 0x{transaction_hash:x} confirmed, but no {} event within the timeout",
+                awaited.spec
+            )));
+        }
+        let page = provider
+            .get_events(filter.clone(), token.clone(), 100)
+            .await
+            .map_err(|e| CliError::Network(format!("Failed to get events: {e}")))?;
+        if let Some(event) = page.events.into_iter().next() {
+            return Ok(crate::commands::events::EventOutput {
+                block_number: event.block_number,
+                transaction_hash: format!("0x{:x}", event.transaction_hash),
+                from_address: format!("0x{:x}", event.from_address),
+                name,
+                keys: event.keys.iter().map(|f| format!("0x{f:x}")).collect(),
+                data: event.data.iter().map(|f| format!("0x{f:x}")).collect(),
+                decoded: None,
+            });
+        }
+        token = page.continuation_token;
+        if token.is_none() {
+            tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+        }
+    }
+}
+
+/// The `--require-events` specs no event in the receipt satisfies
+fn missing_events(
+    required: &[EventRequirement],
+    events: &[starknet::core::types::Event],
+) -> Vec<String> {
+    required
+        .iter()
+        .filter(|req| !events.iter().any(|event| req.matches(event)))
+        .map(|req| req.spec.clone())
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GuardOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// A view call that must hold before submitting, from
+/// `--only-if '<contract>:<entrypoint>:(<calldata>)<op><value>'`. `==` and `!=` compare the whole
+/// return value (`(a,b)` for several felts); `<`, `<=`, `>` and `>=` compare its first felt.
+#[derive(Debug)]
+struct Guard {
+    spec: String,
+    call: Call,
+    op: GuardOp,
+    expected: Vec<Felt>,
+}
+
+impl Guard {
+    fn parse(spec: &str) -> Result<Self> {
+        let invalid = |reason: &str| {
+            CliError::InvalidInput(format!(
+                "Invalid --only-if '{spec}': {reason}. Expected '<contract>:<entrypoint>:(<calldata>)==<value>'"
+            ))
+        };
+        let (target, rest) = spec
+            .split_once('(')
+            .ok_or_else(|| invalid("missing '(<calldata>)'"))?;
+        let (contract, entrypoint) = target
+            .trim()
+            .strip_suffix(':')
+            .and_then(|target| target.split_once(':'))
+            .ok_or_else(|| invalid("missing '<contract>:<entrypoint>:'"))?;
+        let (args, condition) = rest
+            .split_once(')')
+            .ok_or_else(|| invalid("unclosed '('"))?;
+
+        let to = Felt::from_hex(contract.trim()).map_err(|e| invalid(&e.to_string()))?;
+        let selector = resolve_selector(entrypoint.trim())?;
+        let calldata = parse_felts(args)?;
+
+        let condition = condition.trim();
+        let (op, value) = [
+            ("==", GuardOp::Eq),
+            ("!=", GuardOp::Ne),
+            ("<=", GuardOp::Le),
+            (">=", GuardOp::Ge),
+            ("<", GuardOp::Lt),
+            (">", GuardOp::Gt),
+        ]
+        .into_iter()
+        .find_map(|(symbol, op)| condition.strip_prefix(symbol).map(|value| (op, value)))
+        .ok_or_else(|| invalid("missing comparison (==, !=, <, <=, >, >=)"))?;
+        let value = value.trim();
+        let expected = parse_felts(
+            value
+                .strip_prefix('(')
+                .and_then(|v| v.strip_suffix(')'))
+                .unwrap_or(value),
+        )?;
+        if expected.is_empty() || (!matches!(op, GuardOp::Eq | GuardOp::Ne) && expected.len() > 1) {
+            return Err(invalid("ordering comparisons take a single value"));
+        }
+
+        Ok(Self {
+            spec: spec.to_string(),
+            call: Call {
+                to,
+                selector,
+                calldata,
+            },
+            op,
+            expected,
+        })
+    }
+
+    fn holds(&self, result: &[Felt]) -> bool {
+        let (Some(first), Some(expected)) = (result.first(), self.expected.first()) else {
+            return false;
+        };
+        match self.op {
+            GuardOp::Eq => result == self.expected.as_slice(),
+            GuardOp::Ne => result != self.expected.as_slice(),
+            GuardOp::Lt => first < expected,
+            GuardOp::Le => first <= expected,
+            GuardOp::Gt => first > expected,
+            GuardOp::Ge => first >= expected,
+        }
+    }
+
+    /// Run the view call against the pre-confirmed state and fail unless the condition holds.
+    async fn check<P: Provider + Sync>(&self, provider: &P) -> Result<()> {
+        let request = starknet::core::types::FunctionCall {
+            contract_address: self.call.to,
+            entry_point_selector: self.call.selector,
+            calldata: self.call.calldata.clone(),
+        };
+        let result = provider
+            .call(
+                request,
+                starknet::core::types::BlockId::Tag(starknet::core::types::BlockTag::PreConfirmed),
+            )
+            .await
+            .map_err(|e| CliError::TransactionFailed(format!("--only-if call failed: {e}")))?;
+        if self.holds(&result) {
+            return Ok(());
+        }
+        Err(CliError::ConditionNotMet {
+            condition: self.spec.clone(),
+            actual: result.iter().map(|felt| format!("{felt:#x}")).collect(),
+        })
+    }
+}
+
+/// Comma-separated calldata values, with the same encodings as positional calldata
+fn parse_felts(values: &str) -> Result<Vec<Felt>> {
+    Ok(values
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(parse_calldata_value)
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .flatten()
+        .collect())
+}
+
+/// Convert CallSpecs to starknet Calls, resolving selectors and encoding calldata.
+pub fn starknet_calls(calls: &[CallSpec]) -> Result<Vec<Call>> {
+    calls
+        .iter()
+        .map(|call| {
+            let contract_address = Felt::from_hex(&call.contract_address)
+                .map_err(|e| CliError::InvalidInput(format!("Invalid contract address: {e}")))?;
+
+            let selector = resolve_selector(&call.entrypoint)?;
+
+            let calldata: Vec<Felt> = call
+                .calldata
+                .iter()
+                .map(|data| parse_calldata_value(data.trim()))
+                .collect::<Result<Vec<Vec<Felt>>>>()?
+                .into_iter()
+                .flatten()
+                .collect();
+
+            Ok(Call {
+                to: contract_address,
+                selector,
+                calldata,
+            })
+        })
+        .collect()
+}
+
+/// Prepend the configured `calldata-prefix.<address>` values to calls to that contract.
+pub fn apply_calldata_prefixes(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    calls: Vec<CallSpec>,
+) -> Vec<CallSpec> {
+    calls
+        .into_iter()
+        .map(|mut call| {
+            let prefix = Felt::from_hex(&call.contract_address)
+                .ok()
+                .and_then(|address| config.calldata_prefix(address));
+            if let Some(prefix) = prefix {
+                formatter.info(&format!(
+                    "Prepending calldata prefix {prefix} to {} on {}",
+                    call.entrypoint, call.contract_address
+                ));
+                call.calldata = prefix
+                    .split(',')
+                    .map(|value| value.trim().to_string())
+                    .chain(call.calldata)
+                    .collect();
+            }
+            call
+        })
+        .collect()
+}
+
+/// Key of the policy registered for a contract, comparing addresses as felts so leading zeros
+/// don't matter (0x06f... == 0x6f...)
+fn policy_key<'a>(
+    contracts: &'a HashMap<String, ContractPolicy>,
+    address: &str,
+) -> Option<&'a String> {
+    let call_felt = Felt::from_hex(address).ok();
+    contracts
+        .keys()
+        .find(|addr| match (call_felt, Felt::from_hex(addr).ok()) {
+            (Some(a), Some(b)) => a == b,
+            _ => addr.to_lowercase() == address.to_lowercase(),
+        })
+}
+
+/// Compare by selector so raw `selector:0x...` entrypoints match named policies
+fn entrypoint_allowed(policy: &ContractPolicy, entrypoint: &str) -> bool {
+    let call_selector = resolve_selector(entrypoint).ok();
+    policy.methods.iter().any(|m| {
+        m.entrypoint == entrypoint
+            || (call_selector.is_some() && resolve_selector(&m.entrypoint).ok() == call_selector)
+    })
+}
+
+/// How the stored policies treat a call, for `--explain`
+fn policy_status(call: &CallSpec, policies: Option<&PolicyStorage>) -> &'static str {
+    let Some(policies) = policies else {
+        return "unknown";
+    };
+    match policy_key(&policies.contracts, &call.contract_address) {
+        Some(key) if entrypoint_allowed(&policies.contracts[key], &call.entrypoint) => "allowed",
+        _ => "not_authorized",
+    }
+}
+
+/// The stored policies that authorize the calls, as 'contract:entrypoint', for the audit log
+pub fn matched_policies(calls: &[CallSpec], policies: Option<&PolicyStorage>) -> Vec<String> {
+    let Some(policies) = policies else {
+        return Vec::new();
+    };
+    let mut matched: Vec<String> = Vec::new();
+    for call in calls {
+        let Some(key) = policy_key(&policies.contracts, &call.contract_address) else {
+            continue;
+        };
+        let call_selector = resolve_selector(&call.entrypoint).ok();
+        let method = policies.contracts[key].methods.iter().find(|m| {
+            m.entrypoint == call.entrypoint
+                || (call_selector.is_some()
+                    && resolve_selector(&m.entrypoint).ok() == call_selector)
+        });
+        if let Some(method) = method {
+            let policy = format!("{key}:{}", method.entrypoint);
+            if !matched.contains(&policy) {
+                matched.push(policy);
+            }
+        }
+    }
+    matched
 }
 
 /// Validates that all calls are permitted by the stored session policies.
@@ -345,16 +1351,9 @@ fn validate_calls_against_policies(calls: &[CallSpec], policies: &PolicyStorage)
     }
 
     for call in calls {
-        // Normalize by parsing as Felt to handle leading zeros (0x06f... == 0x6f...)
-        let call_felt = Felt::from_hex(&call.contract_address).ok();
-        let matching_contract = policies.contracts.iter().find(|(addr, _)| {
-            match (call_felt, Felt::from_hex(addr).ok()) {
-                (Some(a), Some(b)) => a == b,
-                _ => addr.to_lowercase() == call.contract_address.to_lowercase(),
-            }
-        });
-
-        match matching_contract {
+        match policy_key(&policies.contracts, &call.contract_address)
+            .map(|key| &policies.contracts[key])
+        {
             None => {
                 return Err(CliError::InvalidInput(format!(
                     "Contract {} is not authorized by the current session policies. \
@@ -362,13 +1361,8 @@ fn validate_calls_against_policies(calls: &[CallSpec], policies: &PolicyStorage)
                     call.contract_address
                 )));
             }
-            Some((_, contract_policy)) => {
-                let entrypoint_allowed = contract_policy
-                    .methods
-                    .iter()
-                    .any(|m| m.entrypoint == call.entrypoint);
-
-                if !entrypoint_allowed {
+            Some(contract_policy) => {
+                if !entrypoint_allowed(contract_policy, &call.entrypoint) {
                     let allowed: Vec<&str> = contract_policy
                         .methods
                         .iter()
@@ -388,12 +1382,217 @@ fn validate_calls_against_policies(calls: &[CallSpec], policies: &PolicyStorage)
     Ok(())
 }
 
+/// The stored policies plus the minimal additions that authorize every call.
+fn fill_policy_gaps(calls: &[CallSpec], policies: &PolicyStorage) -> PolicyFile {
+    let mut contracts = policies.contracts.clone();
+
+    for call in calls {
+        let method = MethodPolicy {
+            name: call.entrypoint.clone(),
+            entrypoint: call.entrypoint.clone(),
+            description: Some("Added by --auto-fix-policies".to_string()),
+            amount: None,
+            authorized: true,
+        };
+        match policy_key(&contracts, &call.contract_address).cloned() {
+            Some(key) => {
+                let policy = contracts.get_mut(&key).expect("key was just found");
+                if !entrypoint_allowed(policy, &call.entrypoint) {
+                    policy.methods.push(method);
+                }
+            }
+            None => {
+                contracts.insert(
+                    call.contract_address.clone(),
+                    ContractPolicy {
+                        name: None,
+                        methods: vec![method],
+                    },
+                );
+            }
+        }
+    }
+
+    PolicyFile {
+        contracts,
+        messages: None,
+    }
+}
+
+/// Whether to repair missing policies: always with `--auto-fix-policies`. An interactive caller
+/// is asked to re-run with the flag; otherwise the policy error stands.
+fn confirm_policy_fix(config: &Config, options: &ExecuteOptions, error: &CliError) -> Result<bool> {
+    if options.auto_fix_policies {
+        return Ok(true);
+    }
+    if !config.cli.interactive || !options.offer_policy_fix {
+        return Ok(false);
+    }
+    Err(InputRequest::confirm(
+        format!("{error}. Add the missing policies and re-authorize the session?"),
+        "--auto-fix-policies",
+    ))
+}
+
+/// Write the stored policies plus the missing entrypoints to a policy file and authorize a new
+/// session with it on the same RPC, expiring when the current session would have.
+#[allow(clippy::too_many_arguments)]
+async fn remediate_policies(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    calls: &[CallSpec],
+    policies: &PolicyStorage,
+    storage_path: &std::path::Path,
+    rpc_url: &str,
+    expires_at: u64,
+    account: Option<&str>,
+) -> Result<()> {
+    let policy_file = fill_policy_gaps(calls, policies);
+    let path = storage_path.join(AUTO_FIX_POLICY_FILE);
+    let json = serde_json::to_string_pretty(&policy_file)
+        .map_err(|e| CliError::Storage(format!("Failed to serialize policies: {e}")))?;
+    super::storage::write_atomic(&path, json).map_err(|e| CliError::FileError {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    })?;
+    formatter.info(&format!(
+        "Wrote updated policies to {}. Re-authorizing session...",
+        path.display()
+    ));
+
+    crate::commands::session::authorize::execute(
+        config,
+        formatter,
+        crate::commands::session::authorize::AuthorizeOptions {
+            file: Some(path.display().to_string()),
+            rpc_url: Some(rpc_url.to_string()),
+            overwrite: true,
+            expires: remaining_lifetime(expires_at, chrono::Utc::now().timestamp() as u64),
+            ..Default::default()
+        },
+        account,
+    )
+    .await
+}
+
+/// The time left until `expires_at`, as a duration `session auth` accepts
+fn remaining_lifetime(expires_at: u64, now: u64) -> String {
+    format!("{}s", expires_at.saturating_sub(now).max(1))
+}
+
+/// Submit a self-paid invoke bounded by `estimate`, tipping `tip` FRI per unit of L2 gas.
+/// account_sdk's `execute` builds the invoke without a tip, so this goes through the execution
+/// builder, which signs with the session the same way, with the bounds taken from the estimate
+/// instead of estimating again.
+async fn execute_with_tip(
+    controller: &Controller,
+    calls: Vec<Call>,
+    estimate: &FeeEstimate,
+    tip: u64,
+) -> Result<InvokeTransactionResult> {
+    controller
+        .execute_v3(calls)
+        .l1_gas(with_headroom(estimate.l1_gas_consumed))
+        .l1_gas_price(with_headroom(estimate.l1_gas_price))
+        .l2_gas(with_headroom(estimate.l2_gas_consumed))
+        .l2_gas_price(with_headroom(estimate.l2_gas_price))
+        .l1_data_gas(with_headroom(estimate.l1_data_gas_consumed))
+        .l1_data_gas_price(with_headroom(estimate.l1_data_gas_price))
+        .tip(tip)
+        .send()
+        .await
+        .map_err(|e| CliError::TransactionFailed(format!("Transaction failed: {e}")))
+}
+
+pub(crate) fn with_headroom<T: TryFrom<u128> + Into<u128> + Copy>(value: T) -> T {
+    T::try_from((value.into() as f64 * GAS_MULTIPLIER) as u128).unwrap_or(value)
+}
+
+/// The estimated fee plus the most the tip can add: it is paid on every unit of L2 gas
+fn fee_with_tip(overall_fee: u128, l2_gas: u64, tip: u64) -> u128 {
+    overall_fee.saturating_add((l2_gas as u128).saturating_mul(tip as u128))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::commands::session::authorize::{ContractPolicy, MethodPolicy, PolicyStorage};
     use std::collections::HashMap;
 
+    #[test]
+    fn reauthorizes_for_the_remaining_lifetime() {
+        assert_eq!(remaining_lifetime(1_700_003_600, 1_700_000_000), "3600s");
+        // Never zero, which `session auth` would turn into an already-expired session
+        assert_eq!(remaining_lifetime(1_700_000_000, 1_700_000_005), "1s");
+    }
+
+    #[test]
+    fn adds_the_tip_to_the_fee() {
+        assert_eq!(fee_with_tip(1_000, 50, 0), 1_000);
+        assert_eq!(fee_with_tip(1_000, 50, 3), 1_150);
+        assert_eq!(fee_with_tip(u128::MAX, 50, 3), u128::MAX);
+        assert_eq!(with_headroom(100u64), 150);
+        assert_eq!(with_headroom(u128::MAX), u128::MAX);
+    }
+
+    #[test]
+    fn parses_and_evaluates_only_if_guards() {
+        let guard = Guard::parse("0x123:get_state:(0x1, 7)==0x2").unwrap();
+        assert_eq!(guard.call.to, Felt::from(0x123u32));
+        assert_eq!(guard.call.calldata, vec![Felt::ONE, Felt::from(7u8)]);
+        assert!(guard.holds(&[Felt::TWO]));
+        assert!(!guard.holds(&[Felt::TWO, Felt::ONE]));
+
+        let guard = Guard::parse("0x123:selector:0x456:()>=10").unwrap();
+        assert_eq!(guard.call.selector, Felt::from(0x456u32));
+        assert!(guard.call.calldata.is_empty());
+        assert!(guard.holds(&[Felt::from(10u8)]));
+        assert!(!guard.holds(&[Felt::from(9u8)]));
+        assert!(!guard.holds(&[]));
+
+        let guard = Guard::parse("0x123:owner:()!=(0x0,0x0)").unwrap();
+        assert!(guard.holds(&[Felt::ONE, Felt::ZERO]));
+
+        assert!(Guard::parse("0x123:get_state==0x2").is_err());
+        assert!(Guard::parse("0x123:get_state:()~0x2").is_err());
+        assert!(Guard::parse("0x123:get_state:()<(1,2)").is_err());
+    }
+
+    #[test]
+    fn checks_required_events() {
+        let moved = starknet::core::utils::get_selector_from_name("MoveExecuted").unwrap();
+        let event = starknet::core::types::Event {
+            from_address: Felt::from_hex("0x123").unwrap(),
+            keys: vec![moved, Felt::ONE],
+            data: vec![],
+        };
+        let required: Vec<EventRequirement> = [
+            "MoveExecuted",
+            "0x0123:MoveExecuted",
+            &format!("{moved:#x}"),
+            "0x456:MoveExecuted",
+            "Died",
+        ]
+        .iter()
+        .map(|spec| EventRequirement::parse(spec, "--require-events").unwrap())
+        .collect();
+
+        assert_eq!(
+            missing_events(&required, std::slice::from_ref(&event)),
+            vec!["0x456:MoveExecuted", "Died"]
+        );
+        assert!(EventRequirement::parse("nothex:MoveExecuted", "--require-events").is_err());
+
+        let awaited = EventRequirement::parse("0x123:MoveExecuted", "--wait-for-event").unwrap();
+        assert!(awaited.matches(&event));
+        let Err(CliError::InvalidInput(message)) =
+            EventRequirement::parse("nothex:MoveExecuted", "--wait-for-event")
+        else {
+            panic!("expected an invalid --wait-for-event spec");
+        };
+        assert!(message.contains("--wait-for-event"));
+    }
+
     fn make_policies(contracts: Vec<(&str, Vec<&str>)>) -> PolicyStorage {
         let mut map = HashMap::new();
         for (addr, methods) in contracts {
@@ -414,7 +1613,10 @@ mod tests {
                 },
             );
         }
-        PolicyStorage { contracts: map }
+        PolicyStorage {
+            contracts: map,
+            messages: None,
+        }
     }
 
     fn make_call(contract: &str, entrypoint: &str) -> CallSpec {
@@ -438,6 +1640,26 @@ mod tests {
         assert!(validate_calls_against_policies(&calls, &policies).is_ok());
     }
 
+    #[test]
+    fn test_raw_selector_matches_named_policy() {
+        let policies = make_policies(vec![(
+            "0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7",
+            vec!["transfer"],
+        )]);
+        let selector = starknet::core::utils::get_selector_from_name("transfer").unwrap();
+        let calls = vec![make_call(
+            "0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7",
+            &format!("selector:{selector:#x}"),
+        )];
+        assert!(validate_calls_against_policies(&calls, &policies).is_ok());
+
+        let calls = vec![make_call(
+            "0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7",
+            "selector:0x1234",
+        )];
+        assert!(validate_calls_against_policies(&calls, &policies).is_err());
+    }
+
     #[test]
     fn test_unauthorized_contract_rejected() {
         let policies = make_policies(vec![(
@@ -541,21 +1763,49 @@ mod tests {
 
 /// Resolve --chain-id to an RPC URL, or pass through --rpc-url as-is.
 fn resolve_chain_id_to_rpc(
+    config: &Config,
     chain_id: Option<String>,
     rpc_url: Option<String>,
 ) -> Result<Option<String>> {
     match chain_id {
-        Some(chain) => match chain.as_str() {
-            "SN_MAIN" => Ok(Some(
-                "https://api.cartridge.gg/x/starknet/mainnet".to_string(),
-            )),
-            "SN_SEPOLIA" => Ok(Some(
-                "https://api.cartridge.gg/x/starknet/sepolia".to_string(),
-            )),
-            _ => Err(CliError::InvalidInput(format!(
-                "Unsupported chain ID '{chain}'. Supported chains: SN_MAIN, SN_SEPOLIA"
-            ))),
-        },
+        Some(chain) => crate::chains::rpc_url_for(config, &chain).map(Some),
         None => Ok(rpc_url),
     }
+
+    #[test]
+    fn fills_only_missing_policies() {
+        let policies = make_policies(vec![("0x0123", vec!["transfer"])]);
+        let calls = vec![
+            make_call("0x123", "transfer"),
+            make_call("0x123", "approve"),
+            make_call("0x456", "mint"),
+        ];
+        assert!(validate_calls_against_policies(&calls, &policies).is_err());
+
+        let fixed = fill_policy_gaps(&calls, &policies);
+        assert_eq!(fixed.contracts.len(), 2);
+        let entrypoints: Vec<&str> = fixed.contracts["0x0123"]
+            .methods
+            .iter()
+            .map(|m| m.entrypoint.as_str())
+            .collect();
+        assert_eq!(entrypoints, vec!["transfer", "approve"]);
+        assert_eq!(fixed.contracts["0x456"].methods[0].entrypoint, "mint");
+
+        let fixed = PolicyStorage {
+            contracts: fixed.contracts,
+            messages: None,
+        };
+        assert!(validate_calls_against_policies(&calls, &fixed).is_ok());
+    }
+
+    #[test]
+    fn reports_policy_status_for_explain() {
+        let policies = make_policies(vec![("0x0123", vec!["transfer"])]);
+        let allowed = make_call("0x123", "transfer");
+        let denied = make_call("0x123", "approve");
+        assert_eq!(policy_status(&allowed, Some(&policies)), "allowed");
+        assert_eq!(policy_status(&denied, Some(&policies)), "not_authorized");
+        assert_eq!(policy_status(&allowed, None), "unknown");
+    }
 }
@@ -0,0 +1,264 @@
+use crate::{
+    commands::{
+        batch,
+        execute::{CallSpec, Execution},
+    },
+    config::Config,
+    error::{CliError, HintContext, Result},
+    output::{error_document, OutputFormatter},
+};
+use futures::stream::{FuturesUnordered, StreamExt};
+use serde_json::{json, Value};
+use std::sync::Mutex;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+/// Options passed through to every `execute` of a batch
+pub struct StreamOptions {
+    pub wait: bool,
+    pub timeout: u64,
+    pub chain_id: Option<String>,
+    pub rpc_url: Option<String>,
+    pub no_paymaster: bool,
+    pub no_queue: bool,
+    pub require_events: Vec<String>,
+}
+
+/// Prints a batch's progress and result as NDJSON event lines. Progress messages are dropped and
+/// warnings ride along on the batch's next event: stdout belongs to the stream.
+struct LineFormatter {
+    line: usize,
+    id: Option<String>,
+    batch_id: Option<String>,
+    warnings: Mutex<Vec<String>>,
+}
+
+impl LineFormatter {
+    fn emit(&self, event: &str, fields: Value) {
+        let warnings: Vec<String> = self
+            .warnings
+            .lock()
+            .map(|mut w| w.drain(..).collect())
+            .unwrap_or_default();
+        let mut out = json!({ "line": self.line, "event": event });
+        if let Some(id) = &self.id {
+            out["id"] = json!(id);
+        }
+        if let Some(batch_id) = &self.batch_id {
+            out["batch_id"] = json!(batch_id);
+        }
+        if let (Some(out), Value::Object(fields)) = (out.as_object_mut(), fields) {
+            out.extend(fields);
+        }
+        if !warnings.is_empty() {
+            out["warnings"] = json!(warnings);
+        }
+        println!("{out}");
+    }
+}
+
+impl OutputFormatter for LineFormatter {
+    // The batch's result comes back from `execute_calls`; nothing it runs prints another
+    fn success(&self, _data: &dyn erased_serde::Serialize) {}
+
+    fn error(&self, _error: &CliError) {}
+
+    fn info(&self, _message: &str) {}
+
+    fn warning(&self, message: &str) {
+        if let Ok(mut warnings) = self.warnings.lock() {
+            warnings.push(message.to_string());
+        }
+    }
+
+    /// The transaction was sent; with `--wait` its confirmation follows as a separate event
+    fn progress(&self, data: &dyn erased_serde::Serialize) {
+        self.emit("submitted", serde_json::to_value(data).unwrap_or_default());
+    }
+}
+
+/// Read newline-delimited call batches from stdin and submit each through `execute`, up to
+/// `concurrency` at a time, printing one JSON event per line as batches are submitted, confirmed
+/// or fail. A line is a call file object (`{"calls": [...], "id": ...}`), an array of calls or a
+/// single call. Every batch is audited under one batch job (`--batch-id`, or a new one) for
+/// `controller batch status`. Fails at the end if any batch failed.
+pub async fn execute(
+    config: &Config,
+    options: StreamOptions,
+    concurrency: usize,
+    account: Option<&str>,
+) -> Result<()> {
+    let mut config = config.clone();
+    if config.cli.batch_id.is_none() && !config.cli.explain {
+        config.cli.batch_id = Some(batch::new_job_id());
+    }
+    let config = &config;
+    let options = &options;
+    let concurrency = concurrency.max(1);
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut in_flight = FuturesUnordered::new();
+    let mut stdin_open = true;
+    let (mut total, mut failed) = (0, 0);
+    let mut number = 0;
+
+    while stdin_open || !in_flight.is_empty() {
+        tokio::select! {
+            line = lines.next_line(), if stdin_open && in_flight.len() < concurrency => {
+                match line.map_err(|e| CliError::Other(e.into()))? {
+                    Some(line) => {
+                        number += 1;
+                        if !line.trim().is_empty() {
+                            total += 1;
+                            in_flight.push(run_batch(config, options, number, line, account));
+                        }
+                    }
+                    None => stdin_open = false,
+                }
+            }
+            Some(succeeded) = in_flight.next(), if !in_flight.is_empty() => {
+                if !succeeded {
+                    failed += 1;
+                }
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(CliError::TransactionFailed(format!(
+            "{failed} of {total} batch(es) from stdin failed"
+        )));
+    }
+    Ok(())
+}
+
+/// Submit one line's calls, returning whether it succeeded.
+async fn run_batch(
+    config: &Config,
+    options: &StreamOptions,
+    line: usize,
+    content: String,
+    account: Option<&str>,
+) -> bool {
+    let parsed = parse_batch(&content);
+    let formatter = LineFormatter {
+        line,
+        id: parsed
+            .as_ref()
+            .ok()
+            .and_then(|batch| batch["id"].as_str().map(str::to_string)),
+        batch_id: config.cli.batch_id.clone(),
+        warnings: Mutex::new(Vec::new()),
+    };
+
+    let result = match parsed {
+        Ok(batch) => submit(config, options, &formatter, batch, account).await,
+        Err(e) => Err(e),
+    };
+    let failure = match result {
+        Ok(Execution::Submitted {
+            confirmation: Ok(()),
+            ..
+        }) => {
+            if options.wait {
+                formatter.emit("confirmed", json!({}));
+            }
+            return true;
+        }
+        Ok(Execution::Submitted {
+            confirmation: Err(e),
+            ..
+        }) => e,
+        Ok(Execution::PendingIntent(intent)) => {
+            // Over an approval threshold, execute writes a pending intent instead
+            formatter.emit("pending_approval", json!(intent));
+            return true;
+        }
+        Ok(Execution::Planned(plan)) => {
+            formatter.emit("planned", json!(plan));
+            return true;
+        }
+        Ok(Execution::Simulated(simulation)) => {
+            formatter.emit("simulated", json!(simulation));
+            return true;
+        }
+        Err(e) => e,
+    };
+    let mut document = error_document(&failure, &HintContext::new(account), &[]);
+    if let Some(document) = document.as_object_mut() {
+        document.remove("status");
+        document.remove("warnings");
+    }
+    formatter.emit("failed", document);
+    false
+}
+
+async fn submit(
+    config: &Config,
+    options: &StreamOptions,
+    formatter: &LineFormatter,
+    mut batch: Value,
+    account: Option<&str>,
+) -> Result<Execution> {
+    let calls: Vec<CallSpec> = serde_json::from_value(batch["calls"].take())
+        .map_err(|e| CliError::InvalidInput(format!("Invalid call: {e}")))?;
+    crate::commands::execute::execute_calls(
+        config,
+        formatter,
+        calls,
+        crate::commands::execute::ExecuteOptions {
+            wait: options.wait,
+            timeout: options.timeout,
+            chain_id: options.chain_id.clone(),
+            rpc_url: options.rpc_url.clone(),
+            no_paymaster: options.no_paymaster,
+            no_queue: options.no_queue,
+            require_events: options.require_events.clone(),
+            ..Default::default()
+        },
+        account,
+    )
+    .await
+}
+
+/// Normalize a line to a call file object: `{"calls": [...]}` as is, an array of calls or a
+/// single call wrapped.
+fn parse_batch(line: &str) -> Result<Value> {
+    let value: Value = serde_json::from_str(line)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid JSON: {e}")))?;
+    let batch = match value {
+        Value::Object(ref object) if object.contains_key("calls") => value,
+        Value::Array(_) => json!({ "calls": value }),
+        Value::Object(_) => json!({ "calls": [value] }),
+        _ => {
+            return Err(CliError::InvalidInput(
+                "Expected a call, an array of calls or {\"calls\": [...]}".to_string(),
+            ))
+        }
+    };
+    if batch["calls"].as_array().is_none_or(Vec::is_empty) {
+        return Err(CliError::InvalidInput("Batch has no calls".to_string()));
+    }
+    Ok(batch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_batch_lines() {
+        let call = r#"{"contractAddress":"0x1","entrypoint":"move","calldata":["0x2"]}"#;
+        let single = parse_batch(call).unwrap();
+        assert_eq!(single["calls"][0]["entrypoint"], "move");
+
+        let array = parse_batch(&format!("[{call},{call}]")).unwrap();
+        assert_eq!(array["calls"].as_array().unwrap().len(), 2);
+
+        let file = parse_batch(&format!(r#"{{"id":"turn-1","calls":[{call}]}}"#)).unwrap();
+        assert_eq!(file["id"], "turn-1");
+
+        assert!(parse_batch("[]").is_err());
+        assert!(parse_batch("42").is_err());
+        assert!(parse_batch("not json").is_err());
+    }
+}
@@ -0,0 +1,212 @@
+use crate::{
+    chains,
+    commands::balance,
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use account_sdk::storage::{filestorage::FileSystemBackend, StorageBackend};
+use serde::{Deserialize, Serialize};
+use starknet::core::types::Felt;
+use starknet::providers::JsonRpcClient;
+
+#[derive(Serialize)]
+pub struct FaucetOutput {
+    pub chain_id: String,
+    pub recipient: String,
+    pub token: String,
+    pub amount: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<String>,
+}
+
+/// How funds are obtained on a chain
+#[derive(Debug, PartialEq)]
+enum FundingMethod {
+    /// POST to the configured `faucet.<CHAIN>` endpoint
+    Faucet(String),
+    /// Call the token's dev-only `mint` entrypoint (Katana/SLOT)
+    Mint,
+}
+
+fn funding_method(config: &Config, chain_id: &str) -> Result<FundingMethod> {
+    if chain_id == "SN_MAIN" {
+        return Err(CliError::InvalidInput(
+            "The faucet is only available on test chains".to_string(),
+        ));
+    }
+    if let Some(url) = config.faucets.get(chain_id) {
+        return Ok(FundingMethod::Faucet(url.clone()));
+    }
+    if chain_id == "SN_SEPOLIA" {
+        return Err(CliError::Config(format!(
+            "No faucet configured for {chain_id}. \
+             Set one with 'controller config set faucet.{chain_id} <url>'"
+        )));
+    }
+    Ok(FundingMethod::Mint)
+}
+
+/// Fund an account on a test chain, either through the chain's configured faucet or by minting
+/// on Katana/SLOT.
+#[allow(clippy::too_many_arguments)]
+pub async fn execute(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    token: String,
+    amount: String,
+    recipient: Option<String>,
+    chain_id: Option<String>,
+    rpc_url: Option<String>,
+    account: Option<&str>,
+) -> Result<()> {
+    let rpc_url = chains::resolve_rpc_url(config, formatter, chain_id, rpc_url, account)?;
+    let url = url::Url::parse(&rpc_url)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
+    let provider = JsonRpcClient::new(crate::api::rpc_transport(url));
+    let chain_name = chains::chain_name(&provider).await?;
+    let method = funding_method(config, &chain_name)?;
+
+    let (token_address, decimals) = balance::resolve_token(config, &token).ok_or_else(|| {
+        CliError::InvalidInput(format!(
+            "Unknown token '{token}'. Add it with 'controller config set token.{token} <address>'"
+        ))
+    })?;
+    let raw_amount = balance::parse_token_amount(&amount, decimals).ok_or_else(|| {
+        CliError::InvalidInput(format!(
+            "Invalid amount '{amount}': expected a token amount like '100' or '0.5'"
+        ))
+    })?;
+
+    let storage_path = config.resolve_storage_path(account);
+    let account_address = FileSystemBackend::new(storage_path.clone())
+        .controller()
+        .ok()
+        .flatten()
+        .map(|controller| controller.address);
+    let recipient = match recipient {
+        Some(recipient) => Felt::from_hex(&recipient)
+            .map_err(|e| CliError::InvalidInput(format!("Invalid recipient address: {e}")))?,
+        None => account_address.ok_or(CliError::NoSession)?,
+    };
+
+    match method {
+        FundingMethod::Faucet(faucet_url) => {
+            formatter.info(&format!(
+                "Requesting {amount} {token} for 0x{recipient:x} from the {chain_name} faucet..."
+            ));
+            let transaction_hash =
+                request_funds(&faucet_url, recipient, token_address, raw_amount).await?;
+            if let Some(address) = account_address {
+                balance::invalidate_cache_after_tx(
+                    config,
+                    &storage_path,
+                    address,
+                    &[token_address],
+                    address == recipient,
+                );
+            }
+            formatter.success(&FaucetOutput {
+                chain_id: chain_name,
+                recipient: format!("0x{recipient:x}"),
+                token: token.to_uppercase(),
+                amount,
+                transaction_hash,
+            });
+            Ok(())
+        }
+        FundingMethod::Mint => {
+            formatter.info(&format!(
+                "No faucet configured for {chain_name}, minting {amount} {token} via the token's mint entrypoint..."
+            ));
+            crate::commands::execute::execute(
+                config,
+                formatter,
+                Some(format!("0x{token_address:x}")),
+                Some("mint".to_string()),
+                Some(format!("0x{recipient:x},u256:{raw_amount}")),
+                None,
+                crate::commands::execute::ExecuteOptions {
+                    rpc_url: Some(rpc_url),
+                    ..Default::default()
+                },
+                account,
+            )
+            .await
+        }
+    }
+}
+
+async fn request_funds(
+    faucet_url: &str,
+    recipient: Felt,
+    token: Felt,
+    amount: u128,
+) -> Result<Option<String>> {
+    let client = crate::api::http_client(std::time::Duration::from_secs(30))?;
+
+    #[derive(Serialize)]
+    struct FaucetRequest {
+        address: String,
+        token: String,
+        amount: String,
+    }
+
+    #[derive(Deserialize)]
+    struct FaucetResponse {
+        #[serde(alias = "transactionHash", alias = "tx_hash")]
+        transaction_hash: Option<String>,
+    }
+
+    let response = crate::api::send(
+        client.post(faucet_url).json(&FaucetRequest {
+            address: format!("0x{recipient:x}"),
+            token: format!("0x{token:x}"),
+            amount: amount.to_string(),
+        }),
+        "Failed to reach faucet",
+    )
+    .await?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(CliError::ApiError(format!(
+            "Faucet returned error status {status}: {}",
+            body.trim()
+        )));
+    }
+
+    // Faucets differ in what they return; the transaction hash is informational only
+    Ok(response
+        .json::<FaucetResponse>()
+        .await
+        .ok()
+        .and_then(|r| r.transaction_hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn picks_funding_method_per_chain() {
+        let mut config = Config::default();
+        assert!(funding_method(&config, "SN_MAIN").is_err());
+        assert!(funding_method(&config, "SN_SEPOLIA").is_err());
+        assert_eq!(
+            funding_method(&config, "WP_MY_SLOT").unwrap(),
+            FundingMethod::Mint
+        );
+
+        config.faucets = BTreeMap::from([(
+            "SN_SEPOLIA".to_string(),
+            "https://faucet.example".to_string(),
+        )]);
+        assert_eq!(
+            funding_method(&config, "SN_SEPOLIA").unwrap(),
+            FundingMethod::Faucet("https://faucet.example".to_string())
+        );
+    }
+}
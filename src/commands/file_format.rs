@@ -0,0 +1,111 @@
+use serde::de::DeserializeOwned;
+use std::path::Path;
+
+/// Structured file formats accepted for policy and call files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl FileFormat {
+    /// Pick the format from the file extension, defaulting to JSON.
+    pub fn from_path(path: &str) -> Self {
+        match Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("yaml") | Some("yml") => FileFormat::Yaml,
+            Some("toml") => FileFormat::Toml,
+            _ => FileFormat::Json,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FileFormat::Json => "JSON",
+            FileFormat::Yaml => "YAML",
+            FileFormat::Toml => "TOML",
+        }
+    }
+}
+
+/// Deserialize `content` using the format implied by `path`'s extension (`.json`, `.yaml`/`.yml`,
+/// `.toml`). The schema is identical across formats.
+pub fn parse_structured<T: DeserializeOwned>(
+    path: &str,
+    content: &str,
+) -> std::result::Result<T, String> {
+    let format = FileFormat::from_path(path);
+    let parsed = match format {
+        FileFormat::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+        FileFormat::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+        FileFormat::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+    };
+    parsed.map_err(|e| format!("{} parse error: {e}", format.name()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Calls {
+        calls: Vec<Call>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    struct Call {
+        contract_address: String,
+        calldata: Vec<String>,
+    }
+
+    fn expected() -> Calls {
+        Calls {
+            calls: vec![Call {
+                contract_address: "0x1".to_string(),
+                calldata: vec!["0x2".to_string(), "u256:3".to_string()],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(FileFormat::from_path("calls.json"), FileFormat::Json);
+        assert_eq!(FileFormat::from_path("policy.YAML"), FileFormat::Yaml);
+        assert_eq!(FileFormat::from_path("policy.yml"), FileFormat::Yaml);
+        assert_eq!(FileFormat::from_path("calls.toml"), FileFormat::Toml);
+        assert_eq!(FileFormat::from_path("calls"), FileFormat::Json);
+    }
+
+    #[test]
+    fn test_parse_same_schema_across_formats() {
+        let json = r#"{"calls":[{"contractAddress":"0x1","calldata":["0x2","u256:3"]}]}"#;
+        let yaml = "calls:\n  - contractAddress: \"0x1\"\n    calldata: [\"0x2\", \"u256:3\"]\n";
+        let toml = "[[calls]]\ncontractAddress = \"0x1\"\ncalldata = [\"0x2\", \"u256:3\"]\n";
+
+        assert_eq!(
+            parse_structured::<Calls>("a.json", json).unwrap(),
+            expected()
+        );
+        assert_eq!(
+            parse_structured::<Calls>("a.yaml", yaml).unwrap(),
+            expected()
+        );
+        assert_eq!(
+            parse_structured::<Calls>("a.toml", toml).unwrap(),
+            expected()
+        );
+    }
+
+    #[test]
+    fn test_parse_error_names_format() {
+        let err = parse_structured::<Calls>("a.toml", "not = [valid").unwrap_err();
+        assert!(err.starts_with("TOML parse error"));
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{
+    chains,
+    config::Config,
+    error::Result,
+    output::OutputFormatter,
+    presets::{self, PresetConfig},
+};
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct GameListOutput {
+    pub games: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct GameInfoOutput {
+    pub preset: String,
+    pub origin: Vec<String>,
+    pub chains: Vec<GameChain>,
+}
+
+#[derive(Serialize)]
+pub struct GameChain {
+    pub chain_id: String,
+    pub contracts: Vec<GameContract>,
+    /// Starterpack registry on this chain, if known (see `controller starterpack`)
+    pub starterpack_contract: Option<String>,
+    /// Marketplace on this chain, if known (see `controller marketplace`)
+    pub marketplace_contract: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct GameContract {
+    pub address: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub entrypoints: Vec<String>,
+}
+
+/// List games with a published preset
+pub async fn list(config: &Config, formatter: &dyn OutputFormatter) -> Result<()> {
+    let games = presets::list_presets(config, formatter).await?;
+
+    if config.cli.json_output {
+        formatter.success(&GameListOutput { games });
+    } else {
+        for game in &games {
+            println!("{game}");
+        }
+        formatter.info("Run 'controller games info <preset>' for contracts and supported chains.");
+    }
+
+    Ok(())
+}
+
+/// Show a game's preset: supported chains, contracts and entrypoints, and where to buy
+/// starterpacks/items on each chain
+pub async fn info(config: &Config, formatter: &dyn OutputFormatter, preset: String) -> Result<()> {
+    let preset_config = presets::fetch_preset(config, formatter, &preset).await?;
+    formatter.success(&game_info(config, preset, preset_config));
+    Ok(())
+}
+
+fn game_info(config: &Config, preset: String, preset_config: PresetConfig) -> GameInfoOutput {
+    let mut chains: Vec<GameChain> = preset_config
+        .chains
+        .into_iter()
+        .map(|(chain_id, chain)| {
+            let mut contracts: Vec<GameContract> = chain
+                .policies
+                .contracts
+                .into_iter()
+                .map(|(address, contract)| GameContract {
+                    address,
+                    name: contract.name,
+                    description: contract.description,
+                    entrypoints: contract.methods.into_iter().map(|m| m.entrypoint).collect(),
+                })
+                .collect();
+            contracts.sort_by(|a, b| a.address.to_lowercase().cmp(&b.address.to_lowercase()));
+
+            let contract = |name| {
+                chains::resolve_contract(config, &chain_id, name)
+                    .ok()
+                    .map(|address| format!("0x{address:x}"))
+            };
+
+            GameChain {
+                starterpack_contract: contract(chains::STARTERPACK),
+                marketplace_contract: contract(chains::MARKETPLACE),
+                chain_id,
+                contracts,
+            }
+        })
+        .collect();
+    chains.sort_by(|a, b| a.chain_id.cmp(&b.chain_id));
+
+    GameInfoOutput {
+        preset,
+        origin: preset_config.origin,
+        chains,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn game_info_summarizes_chains_and_contracts() {
+        let preset_config: PresetConfig = serde_json::from_value(serde_json::json!({
+            "origin": ["https://game.example"],
+            "chains": {
+                "SN_MAIN": {
+                    "policies": {
+                        "contracts": {
+                            "0x2": { "name": "Items", "methods": [{ "name": "Buy", "entrypoint": "buy" }] },
+                            "0x1": { "name": "Game", "methods": [
+                                { "name": "Start", "entrypoint": "start" },
+                                { "name": "Move", "entrypoint": "move" }
+                            ] }
+                        }
+                    }
+                },
+                "WP_MY_SLOT": { "policies": { "contracts": {} } }
+            }
+        }))
+        .unwrap();
+
+        let info = game_info(&Config::default(), "example".to_string(), preset_config);
+        assert_eq!(info.chains.len(), 2);
+
+        let mainnet = &info.chains[0];
+        assert_eq!(mainnet.chain_id, "SN_MAIN");
+        assert_eq!(mainnet.contracts[0].address, "0x1");
+        assert_eq!(mainnet.contracts[0].entrypoints, vec!["start", "move"]);
+        assert!(mainnet.marketplace_contract.is_some());
+
+        // Unknown chains have no built-in starterpack/marketplace deployment
+        assert!(info.chains[1].starterpack_contract.is_none());
+    }
+}
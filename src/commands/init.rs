@@ -0,0 +1,182 @@
+use crate::{
+    chains,
+    config::Config,
+    error::{CliError, InputRequest, Result},
+    output::OutputFormatter,
+    presets,
+};
+
+/// Chain picked when the prompt is left blank
+const DEFAULT_CHAIN: &str = "SN_SEPOLIA";
+const DEFAULT_EXPIRES: &str = "7days";
+
+/// Answers to the setup wizard's questions (`--chain`, `--preset`, `--label`, `--expires`)
+#[derive(Debug, Clone, Default)]
+pub struct InitOptions {
+    pub chain: Option<String>,
+    pub preset: Option<String>,
+    /// Account label for the session; empty for the default storage
+    pub label: Option<String>,
+    pub expires: Option<String>,
+    /// Replace an active session without asking
+    pub overwrite: bool,
+}
+
+/// Guided first-time setup: choose a chain and preset, authorize a session, then verify it.
+/// Each choice not given in `options` is returned as `CliError::InputRequired`, for the
+/// front-end to ask and run the wizard again with the answer.
+pub async fn execute(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    options: InitOptions,
+    account: Option<&str>,
+) -> Result<()> {
+    if config.cli.json_output {
+        return Err(CliError::InvalidInput(
+            "'controller init' is interactive and does not support --json. Use 'controller session auth' for scripted setups".to_string(),
+        ));
+    }
+
+    // Step 1: chain
+    let chain_id = match options.chain {
+        Some(chain) => supported_chain(config, &chain)?,
+        None => {
+            formatter
+                .info("Welcome to Cartridge Controller! This wizard will set up a new session.");
+            return Err(select_chain(config));
+        }
+    };
+
+    // Step 2: preset (with search)
+    let preset = match options.preset {
+        Some(preset) => preset,
+        None => return Err(select_preset(config, formatter).await),
+    };
+
+    // Step 3: optional account label
+    let label = match (account, options.label) {
+        (Some(name), _) => Some(name.to_string()),
+        (None, Some(label)) if label.is_empty() => None,
+        (None, Some(label)) => {
+            Config::validate_account_name(&label).map_err(CliError::InvalidInput)?;
+            Some(label)
+        }
+        (None, None) => {
+            return Err(InputRequest::text(
+                "Account label for this session (optional, press Enter to skip)",
+                "--label",
+                Some(String::new()),
+            ))
+        }
+    };
+
+    // Step 4: expiration
+    let expires = match options.expires {
+        Some(expires) => expires,
+        None => {
+            return Err(InputRequest::text(
+                "Session expiration",
+                "--expires",
+                Some(DEFAULT_EXPIRES.to_string()),
+            ))
+        }
+    };
+
+    formatter.info(&format!(
+        "Authorizing preset '{preset}' on {chain_id}{}...",
+        label
+            .as_deref()
+            .map(|l| format!(" for account '{l}'"))
+            .unwrap_or_default()
+    ));
+
+    crate::commands::session::authorize::execute(
+        config,
+        formatter,
+        crate::commands::session::authorize::AuthorizeOptions {
+            preset_names: vec![preset],
+            chain_id: Some(chain_id.clone()),
+            overwrite: options.overwrite,
+            expires,
+            ..Default::default()
+        },
+        label.as_deref(),
+    )
+    .await?;
+
+    // Step 5: verify the new session
+    formatter.info("Verifying session...");
+    crate::commands::status::execute(config, formatter, label.as_deref()).await?;
+
+    formatter.info("Checking balances...");
+    if let Err(e) = crate::commands::balance::execute(
+        config,
+        formatter,
+        None,
+        Some(chain_id),
+        None,
+        true,
+        None,
+        label.as_deref(),
+    )
+    .await
+    {
+        formatter.warning(&format!("Balance check failed: {e}"));
+    }
+
+    let account_flag = label
+        .as_deref()
+        .map(|l| format!(" --account {l}"))
+        .unwrap_or_default();
+    formatter.info(&format!(
+        "Setup complete. Run 'controller execute <contract> <entrypoint> <calldata>{account_flag}' to send your first transaction."
+    ));
+
+    Ok(())
+}
+
+/// Choose among the built-in chains and the chain profiles configured with `chain.<CHAIN>.*`.
+fn select_chain(config: &Config) -> CliError {
+    let supported = chains::supported_ids(config);
+    let default = supported
+        .iter()
+        .find(|chain| **chain == DEFAULT_CHAIN)
+        .or(supported.first())
+        .map(|chain| chain.to_string());
+    InputRequest::choice(
+        "Select a chain",
+        "--chain",
+        supported.iter().map(|chain| chain.to_string()).collect(),
+        default,
+    )
+}
+
+fn supported_chain(config: &Config, chain: &str) -> Result<String> {
+    let supported = chains::supported_ids(config);
+    supported
+        .iter()
+        .find(|id| id.eq_ignore_ascii_case(chain))
+        .map(|id| id.to_string())
+        .ok_or_else(|| {
+            CliError::InvalidInput(format!(
+                "Unsupported chain '{chain}'. Use one of: {}",
+                supported.join(", ")
+            ))
+        })
+}
+
+/// Choose a preset, searching the published list, or name one directly when it can't be fetched.
+async fn select_preset(config: &Config, formatter: &dyn OutputFormatter) -> CliError {
+    match presets::list_presets(config, formatter).await {
+        Ok(names) if !names.is_empty() => {
+            InputRequest::choice("Select a preset (type to search)", "--preset", names, None)
+        }
+        Ok(_) => InputRequest::text("Preset name", "--preset", None),
+        Err(e) => {
+            formatter.warning(&format!(
+                "Could not fetch preset list ({e}). Enter a preset name directly."
+            ));
+            InputRequest::text("Preset name", "--preset", None)
+        }
+    }
+}
@@ -0,0 +1,578 @@
+use crate::{
+    commands::{
+        balance,
+        calldata::{parse_calldata_value, resolve_selector},
+        execute::CallSpec,
+    },
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use account_sdk::storage::{filestorage::FileSystemBackend, StorageBackend};
+use serde::{Deserialize, Serialize};
+use starknet::core::{
+    crypto::{ecdsa_verify, Signature},
+    types::Felt,
+};
+use std::path::{Path, PathBuf};
+
+/// How long a pending intent can wait for approval and submission
+const INTENT_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// Token entrypoints whose trailing u256 argument is the amount moved or approved
+const VALUE_ENTRYPOINTS: &[&str] = &[
+    "transfer",
+    "transfer_from",
+    "transferFrom",
+    "approve",
+    "increase_allowance",
+    "increaseAllowance",
+];
+
+/// An execute that exceeded an approval threshold, parked until a second operator approves it.
+///
+/// The ID is derived from the requester, chain, calls and timestamps, so any edit to the file
+/// after creation is detected before submission. The approver signs that hash with their session
+/// key, so an approval can't be written into the file by hand.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Intent {
+    pub id: String,
+    pub requester: String,
+    pub chain_id: String,
+    pub calls: Vec<CallSpec>,
+    pub reasons: Vec<String>,
+    pub created_at: u64,
+    pub expires_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approved_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approved_at: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval: Option<Approval>,
+}
+
+/// The approver's signature over the intent hash, their address and the approval time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Approval {
+    pub address: String,
+    pub public_key: String,
+    /// `[r, s]`
+    pub signature: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct IntentOutput {
+    pub intent_id: String,
+    pub path: String,
+    pub status: String,
+    pub reasons: Vec<String>,
+    pub expires_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approved_by: Option<String>,
+    pub message: String,
+}
+
+impl Intent {
+    fn compute_hash(
+        requester: &str,
+        chain_id: &str,
+        calls: &[CallSpec],
+        created_at: u64,
+        expires_at: u64,
+    ) -> Felt {
+        let payload = serde_json::to_vec(&(requester, chain_id, calls, created_at, expires_at))
+            .unwrap_or_default();
+        starknet::core::utils::starknet_keccak(&payload)
+    }
+
+    fn compute_id(
+        requester: &str,
+        chain_id: &str,
+        calls: &[CallSpec],
+        created_at: u64,
+        expires_at: u64,
+    ) -> String {
+        let hash =
+            Self::compute_hash(requester, chain_id, calls, created_at, expires_at).to_bytes_be();
+        hash[24..].iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    /// What the approver signs: the intent hash bound to the approver and the approval time
+    fn approval_digest(&self, approver: Felt, approved_at: u64) -> Felt {
+        starknet::core::crypto::poseidon_hash_many(&[
+            Self::compute_hash(
+                &self.requester,
+                &self.chain_id,
+                &self.calls,
+                self.created_at,
+                self.expires_at,
+            ),
+            approver,
+            Felt::from(approved_at),
+        ])
+    }
+
+    /// Reject approvals without a valid signature from an account other than the requester.
+    fn verify_approval(&self) -> Result<()> {
+        let invalid = |reason: &str| {
+            CliError::InvalidInput(format!(
+                "Intent {} has no valid approval: {reason}. Run 'controller approve-intent {}' as a second operator",
+                self.id, self.id
+            ))
+        };
+        let (Some(approval), Some(approved_at)) = (&self.approval, self.approved_at) else {
+            return Err(invalid("it is not signed"));
+        };
+        let parse = |value: &str| Felt::from_hex(value).map_err(|_| invalid("malformed signature"));
+        let approver = parse(&approval.address)?;
+        if Felt::from_hex(&self.requester).ok() == Some(approver) {
+            return Err(invalid("it was approved by the requester"));
+        }
+        let [r, s] = approval.signature.as_slice() else {
+            return Err(invalid("malformed signature"));
+        };
+        let signature = Signature {
+            r: parse(r)?,
+            s: parse(s)?,
+        };
+        match ecdsa_verify(
+            &parse(&approval.public_key)?,
+            &self.approval_digest(approver, approved_at),
+            &signature,
+        ) {
+            Ok(true) => Ok(()),
+            _ => Err(invalid("the signature doesn't match")),
+        }
+    }
+
+    fn output(&self, path: &Path, message: String) -> IntentOutput {
+        IntentOutput {
+            intent_id: self.id.clone(),
+            path: path.display().to_string(),
+            status: if self.approved_by.is_some() {
+                "approved".to_string()
+            } else {
+                "pending".to_string()
+            },
+            reasons: self.reasons.clone(),
+            expires_at: chrono::DateTime::from_timestamp(self.expires_at as i64, 0)
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_default(),
+            approved_by: self.approved_by.clone(),
+            message,
+        }
+    }
+
+    /// Reject intents that were edited after creation or have expired.
+    fn verify(&self, now: u64) -> Result<()> {
+        let expected = Self::compute_id(
+            &self.requester,
+            &self.chain_id,
+            &self.calls,
+            self.created_at,
+            self.expires_at,
+        );
+        if expected != self.id {
+            return Err(CliError::InvalidInput(format!(
+                "Intent {} has been modified since it was created",
+                self.id
+            )));
+        }
+        if now > self.expires_at {
+            return Err(CliError::InvalidInput(format!(
+                "Intent {} expired. Run the execute again to create a new intent",
+                self.id
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Check calls against the configured approval thresholds. Amounts are summed per token so a
+/// large transfer can't be split into several small calls to slip under the limit.
+pub fn threshold_violations(config: &Config, calls: &[CallSpec]) -> Result<Vec<String>> {
+    let mut reasons = Vec::new();
+    let value_selectors = VALUE_ENTRYPOINTS
+        .iter()
+        .map(|entrypoint| resolve_selector(entrypoint))
+        .collect::<Result<Vec<_>>>()?;
+
+    for (symbol, threshold) in &config.approval_thresholds {
+        let Some((token, decimals)) = balance::resolve_token(config, symbol) else {
+            return Err(CliError::Config(format!(
+                "approval-threshold.{symbol} is set but '{symbol}' is not a known token. \
+                 Add it with 'controller config set token.{symbol} <address>'"
+            )));
+        };
+        let limit = balance::parse_token_amount(threshold, decimals).ok_or_else(|| {
+            CliError::Config(format!(
+                "Invalid approval-threshold.{symbol} value '{threshold}'"
+            ))
+        })?;
+
+        let mut total: u128 = 0;
+        for call in calls {
+            let targets_token = Felt::from_hex(&call.contract_address)
+                .map(|address| address == token)
+                .unwrap_or(false);
+            // Compared by selector so a raw `selector:0x...` entrypoint can't slip past
+            if !targets_token || !value_selectors.contains(&resolve_selector(&call.entrypoint)?) {
+                continue;
+            }
+            total = total.saturating_add(call_amount(call)?);
+        }
+
+        if total > limit {
+            reasons.push(format!(
+                "{} {symbol} exceeds the approval threshold of {threshold} {symbol}",
+                balance::format_u128_balance(total, decimals)
+            ));
+        }
+    }
+
+    Ok(reasons)
+}
+
+/// The u256 amount (last two felts) of a token call. Amounts that don't fit in u128 saturate.
+fn call_amount(call: &CallSpec) -> Result<u128> {
+    let felts: Vec<Felt> = call
+        .calldata
+        .iter()
+        .map(|data| parse_calldata_value(data.trim()))
+        .collect::<Result<Vec<Vec<Felt>>>>()?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    if felts.len() < 3 {
+        return Ok(0);
+    }
+    let low = felts[felts.len() - 2];
+    let high = felts[felts.len() - 1];
+    if high != Felt::ZERO {
+        return Ok(u128::MAX);
+    }
+    Ok(low.try_into().unwrap_or(u128::MAX))
+}
+
+/// Park calls as a pending intent, returning where it was written.
+pub fn create(
+    config: &Config,
+    requester: Felt,
+    chain_id: &str,
+    calls: Vec<CallSpec>,
+    reasons: Vec<String>,
+) -> Result<IntentOutput> {
+    let created_at = now_secs();
+    let expires_at = created_at + INTENT_TTL_SECS;
+    let requester = format!("0x{requester:x}");
+    let intent = Intent {
+        id: Intent::compute_id(&requester, chain_id, &calls, created_at, expires_at),
+        requester,
+        chain_id: chain_id.to_string(),
+        calls,
+        reasons,
+        created_at,
+        expires_at,
+        approved_by: None,
+        approved_at: None,
+        approval: None,
+    };
+
+    let path = intents_dir(config).join(format!("{}.json", intent.id));
+    save(&intent, &path)?;
+
+    Ok(intent.output(
+        &path,
+        format!(
+            "Execution requires a second approval. Have another operator run 'controller approve-intent {}' (or pass the intent file path), then run 'controller execute --intent {}'",
+            intent.id, intent.id
+        ),
+    ))
+}
+
+/// Print an intent `create` wrote in place of submitting.
+pub fn report_created(config: &Config, formatter: &dyn OutputFormatter, output: &IntentOutput) {
+    if config.cli.json_output {
+        formatter.success(output);
+    } else {
+        for reason in &output.reasons {
+            formatter.warning(reason);
+        }
+        formatter.info(&format!(
+            "Intent {} written to {}",
+            output.intent_id, output.path
+        ));
+        formatter.info(&output.message);
+    }
+}
+
+/// Approve a pending intent as a second operator. The approver is identified by the controller
+/// stored for `account`, must differ from the requester and signs the approval with its session
+/// key.
+pub async fn approve(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    intent_ref: &str,
+    account: Option<&str>,
+) -> Result<()> {
+    let (mut intent, path) = load(config, intent_ref)?;
+    intent.verify(now_secs())?;
+
+    if let Some(approver) = &intent.approved_by {
+        return Err(CliError::InvalidInput(format!(
+            "Intent {} was already approved by {approver}",
+            intent.id
+        )));
+    }
+
+    let backend = FileSystemBackend::new(config.resolve_storage_path(account));
+    let approver = backend
+        .controller()
+        .ok()
+        .flatten()
+        .ok_or(CliError::NoSession)?;
+
+    let requester = Felt::from_hex(&intent.requester).map_err(|e| {
+        CliError::InvalidInput(format!("Invalid requester in intent {}: {e}", intent.id))
+    })?;
+    if approver.address == requester {
+        return Err(CliError::InvalidInput(
+            "An intent must be approved by a different account than the one that created it"
+                .to_string(),
+        ));
+    }
+
+    let session_key = format!(
+        "@cartridge/session/0x{:x}/0x{:x}",
+        approver.address, approver.chain_id
+    );
+    let credentials = backend
+        .session(&session_key)
+        .map_err(|e| CliError::Storage(e.to_string()))?
+        .and_then(|metadata| metadata.credentials)
+        .ok_or(CliError::NoSession)?;
+    let signing_key = starknet::signers::SigningKey::from_secret_scalar(credentials.private_key);
+    let approved_at = now_secs();
+    let signature = signing_key
+        .sign(&intent.approval_digest(approver.address, approved_at))
+        .map_err(|e| CliError::Other(e.into()))?;
+
+    intent.approved_by = Some(approver.username.clone());
+    intent.approved_at = Some(approved_at);
+    intent.approval = Some(Approval {
+        address: format!("0x{:x}", approver.address),
+        public_key: format!("0x{:x}", signing_key.verifying_key().scalar()),
+        signature: vec![
+            format!("0x{:x}", signature.r),
+            format!("0x{:x}", signature.s),
+        ],
+    });
+    save(&intent, &path)?;
+
+    formatter.success(&intent.output(
+        &path,
+        format!(
+            "Intent approved by {}. The requester can now run 'controller execute --intent {}'",
+            approver.username, intent.id
+        ),
+    ));
+
+    Ok(())
+}
+
+/// Load an approved intent for submission by its requester.
+pub fn load_approved(config: &Config, intent_ref: &str, requester: Felt) -> Result<Intent> {
+    let (intent, _) = load(config, intent_ref)?;
+    intent.verify(now_secs())?;
+
+    if intent.approved_by.is_none() {
+        return Err(CliError::InvalidInput(format!(
+            "Intent {} has not been approved yet. Run 'controller approve-intent {}' as a second operator",
+            intent.id, intent.id
+        )));
+    }
+    intent.verify_approval()?;
+    if Felt::from_hex(&intent.requester).ok() != Some(requester) {
+        return Err(CliError::InvalidInput(format!(
+            "Intent {} was created by {}, not the active session account",
+            intent.id, intent.requester
+        )));
+    }
+
+    Ok(intent)
+}
+
+/// Remove a submitted intent so it can't be replayed.
+pub fn consume(config: &Config, intent_ref: &str) {
+    let _ = std::fs::remove_file(resolve_path(config, intent_ref));
+}
+
+/// Intents are shared across accounts so a second local account can approve them
+pub fn intents_dir(config: &Config) -> PathBuf {
+    config.resolve_storage_path(None).join("intents")
+}
+
+/// An intent reference is either an ID in the local intents directory or a path to an intent
+/// file (e.g., one copied from another machine).
+pub fn resolve_path(config: &Config, intent_ref: &str) -> PathBuf {
+    let path = PathBuf::from(intent_ref);
+    if path.is_file() {
+        path
+    } else {
+        intents_dir(config).join(format!("{intent_ref}.json"))
+    }
+}
+
+fn load(config: &Config, intent_ref: &str) -> Result<(Intent, PathBuf)> {
+    let path = resolve_path(config, intent_ref);
+    let content = std::fs::read_to_string(&path)
+        .map_err(|_| CliError::NotFoundError(format!("Intent '{intent_ref}' not found")))?;
+    let intent = serde_json::from_str(&content).map_err(|e| CliError::FileError {
+        path: path.display().to_string(),
+        message: format!("Invalid intent file: {e}"),
+    })?;
+    Ok((intent, path))
+}
+
+fn save(intent: &Intent, path: &Path) -> Result<()> {
+    let to_file_error = |e: std::io::Error| CliError::FileError {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    };
+    let json = serde_json::to_string_pretty(intent)
+        .map_err(|e| CliError::InvalidInput(format!("Failed to serialize intent: {e}")))?;
+    super::storage::write_atomic(path, json).map_err(to_file_error)
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    const STRK: &str = "0x04718f5a0Fc34cC1AF16A1cdee98fFB20C31f5cD61D6Ab07201858f4287c938D";
+
+    fn transfer(amount: &str) -> CallSpec {
+        CallSpec {
+            contract_address: STRK.to_string(),
+            entrypoint: "transfer".to_string(),
+            calldata: vec!["0xabc".to_string(), format!("u256:{amount}")],
+        }
+    }
+
+    fn config_with_threshold(threshold: &str) -> Config {
+        let mut config = Config::default();
+        config.approval_thresholds = BTreeMap::from([("STRK".to_string(), threshold.to_string())]);
+        config
+    }
+
+    #[test]
+    fn split_transfers_are_summed_against_threshold() {
+        let config = config_with_threshold("1");
+        let half = "500000000000000000";
+
+        assert!(threshold_violations(&config, &[transfer(half)])
+            .unwrap()
+            .is_empty());
+        assert!(
+            threshold_violations(&config, &[transfer(half), transfer(half)])
+                .unwrap()
+                .is_empty()
+        );
+
+        let reasons =
+            threshold_violations(&config, &[transfer(half), transfer(half), transfer(half)])
+                .unwrap();
+        assert_eq!(reasons.len(), 1);
+        assert!(reasons[0].contains("1.500000 STRK"));
+    }
+
+    #[test]
+    fn non_value_calls_are_ignored() {
+        let config = config_with_threshold("0");
+        let call = CallSpec {
+            contract_address: STRK.to_string(),
+            entrypoint: "balance_of".to_string(),
+            calldata: vec!["0xabc".to_string(), "0x1".to_string(), "0x0".to_string()],
+        };
+        assert!(threshold_violations(&config, &[call]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn tampered_or_expired_intents_are_rejected() {
+        let calls = vec![transfer("1")];
+        let mut intent = Intent {
+            id: Intent::compute_id("0x1", "SN_MAIN", &calls, 100, 200),
+            requester: "0x1".to_string(),
+            chain_id: "SN_MAIN".to_string(),
+            calls,
+            reasons: vec![],
+            created_at: 100,
+            expires_at: 200,
+            approved_by: None,
+            approved_at: None,
+            approval: None,
+        };
+        assert!(intent.verify(150).is_ok());
+        assert!(intent.verify(201).is_err());
+
+        intent.calls[0].calldata[1] = "u256:1000".to_string();
+        assert!(intent.verify(150).is_err());
+    }
+
+    #[test]
+    fn raw_selectors_count_against_threshold() {
+        let config = config_with_threshold("1");
+        let selector = starknet::core::utils::get_selector_from_name("transfer").unwrap();
+        let mut call = transfer("2000000000000000000");
+        call.entrypoint = format!("selector:0x{selector:x}");
+        assert_eq!(threshold_violations(&config, &[call]).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn approvals_need_a_signature_from_another_account() {
+        let calls = vec![transfer("1")];
+        let mut intent = Intent {
+            id: Intent::compute_id("0x1", "SN_MAIN", &calls, 100, 200),
+            requester: "0x1".to_string(),
+            chain_id: "SN_MAIN".to_string(),
+            calls,
+            reasons: vec![],
+            created_at: 100,
+            expires_at: 200,
+            approved_by: Some("bob".to_string()),
+            approved_at: Some(150),
+            approval: None,
+        };
+        assert!(intent.verify_approval().is_err());
+
+        let key = starknet::signers::SigningKey::from_secret_scalar(Felt::from(7u8));
+        let sign = |intent: &Intent, approver: Felt| {
+            let signature = key.sign(&intent.approval_digest(approver, 150)).unwrap();
+            Some(Approval {
+                address: format!("0x{approver:x}"),
+                public_key: format!("0x{:x}", key.verifying_key().scalar()),
+                signature: vec![
+                    format!("0x{:x}", signature.r),
+                    format!("0x{:x}", signature.s),
+                ],
+            })
+        };
+        intent.approval = sign(&intent, Felt::from(2u8));
+        assert!(intent.verify_approval().is_ok());
+
+        // Approved by the requester itself, or signed for another approver
+        intent.approval = sign(&intent, Felt::ONE);
+        assert!(intent.verify_approval().is_err());
+        intent.approval = sign(&intent, Felt::from(2u8));
+        intent.approval.as_mut().unwrap().address = "0x3".to_string();
+        assert!(intent.verify_approval().is_err());
+    }
+}
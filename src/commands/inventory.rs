@@ -0,0 +1,381 @@
+use crate::{
+    api::RpcTransport,
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use account_sdk::storage::{filestorage::FileSystemBackend, StorageBackend};
+use cainome_cairo_serde::{ByteArray, CairoSerde};
+use serde::{Deserialize, Serialize};
+use starknet::core::types::{BlockId, BlockTag, Felt, FunctionCall};
+use starknet::providers::{JsonRpcClient, Provider};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+#[derive(Serialize)]
+pub struct InventoryOutput {
+    pub address: String,
+    pub chain_id: String,
+    pub collections: Vec<CollectionOutput>,
+}
+
+#[derive(Serialize)]
+pub struct CollectionOutput {
+    pub address: String,
+    /// 'ERC721' or 'ERC1155'
+    pub standard: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    pub tokens: Vec<OwnedToken>,
+}
+
+#[derive(Serialize)]
+pub struct OwnedToken {
+    /// Decimal token ID
+    pub token_id: String,
+    /// Copies held (always 1 for ERC721)
+    pub amount: String,
+    /// `token_uri` (ERC721) or `uri` (ERC1155) read from the collection
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata_uri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+}
+
+/// A row of the Torii SQL query
+#[derive(Deserialize)]
+struct BalanceRow {
+    contract_address: String,
+    /// '<contract>:<token id>'
+    token_id: String,
+    balance: String,
+    contract_type: String,
+    name: Option<String>,
+    symbol: Option<String>,
+    /// Token metadata JSON fetched by Torii
+    metadata: Option<String>,
+}
+
+/// List the NFTs (ERC721 and ERC1155) the account owns, grouped by collection, from the chain's
+/// Torii indexer. Metadata URIs are read from the collections on-chain.
+pub async fn execute(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    collection: Option<String>,
+    chain_id: Option<String>,
+    rpc_url: Option<String>,
+    torii_url: Option<String>,
+    account: Option<&str>,
+) -> Result<()> {
+    let backend = FileSystemBackend::new(config.resolve_storage_path(account));
+    let controller = backend
+        .controller()
+        .ok()
+        .flatten()
+        .ok_or(CliError::NoSession)?;
+    let collection = collection
+        .map(|c| {
+            Felt::from_hex(&c)
+                .map_err(|e| CliError::InvalidInput(format!("Invalid collection address: {e}")))
+        })
+        .transpose()?;
+
+    let rpc_url = crate::chains::resolve_rpc_url(config, formatter, chain_id, rpc_url, account)?;
+    let url = url::Url::parse(&rpc_url)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
+    let provider = Arc::new(JsonRpcClient::new(crate::api::rpc_transport(url)));
+    let chain = crate::chains::chain_name(&*provider).await?;
+
+    let torii_url = torii_url
+        .or_else(|| {
+            config
+                .chains
+                .get(&chain)
+                .and_then(|profile| profile.torii_url.clone())
+        })
+        .ok_or_else(|| {
+            CliError::InvalidInput(format!(
+                "No Torii indexer configured for {chain}. Pass --torii-url, or save it with 'controller config set chain.{chain}.torii-url <url>'"
+            ))
+        })?;
+
+    formatter.info(&format!(
+        "Querying NFTs owned by 0x{:x} on {chain}...",
+        controller.address
+    ));
+    let rows = query_torii(&torii_url, controller.address, collection).await?;
+    let mut collections = group(rows);
+
+    // Metadata URIs are read concurrently, one call per token
+    let mut handles = Vec::new();
+    for (c, collection) in collections.iter().enumerate() {
+        let Ok(address) = Felt::from_hex(&collection.address) else {
+            continue;
+        };
+        for (t, token) in collection.tokens.iter().enumerate() {
+            let Ok(token_id) = Felt::from_dec_str(&token.token_id) else {
+                continue;
+            };
+            handles.push((
+                c,
+                t,
+                tokio::spawn(metadata_uri(
+                    Arc::clone(&provider),
+                    address,
+                    collection.standard == "ERC1155",
+                    token_id,
+                )),
+            ));
+        }
+    }
+    for (c, t, handle) in handles {
+        collections[c].tokens[t].metadata_uri = handle.await.ok().flatten();
+    }
+
+    if !config.cli.json_output {
+        if collections.is_empty() {
+            formatter.info("No NFTs found");
+        }
+        for collection in &collections {
+            formatter.info(&format!(
+                "{} ({}, {}): {} token(s)",
+                collection
+                    .name
+                    .as_deref()
+                    .unwrap_or(collection.address.as_str()),
+                collection.standard,
+                collection.address,
+                collection.tokens.len()
+            ));
+            for token in &collection.tokens {
+                let amount = if collection.standard == "ERC1155" {
+                    format!(" x{}", token.amount)
+                } else {
+                    String::new()
+                };
+                let name = token
+                    .name
+                    .as_deref()
+                    .map(|name| format!(" {name}"))
+                    .unwrap_or_default();
+                formatter.info(&format!("  #{}{amount}{name}", token.token_id));
+            }
+        }
+    }
+    formatter.success(&InventoryOutput {
+        address: format!("0x{:x}", controller.address),
+        chain_id: chain,
+        collections,
+    });
+    Ok(())
+}
+
+async fn query_torii(
+    torii_url: &str,
+    owner: Felt,
+    collection: Option<Felt>,
+) -> Result<Vec<BalanceRow>> {
+    // Torii stores addresses zero-padded; both values are formatted from felts, so the query
+    // can't be injected into
+    let mut sql = format!(
+        "SELECT tb.contract_address, tb.token_id, tb.balance, c.contract_type, t.name, t.symbol, t.metadata \
+         FROM token_balances tb \
+         JOIN contracts c ON c.contract_address = tb.contract_address \
+         LEFT JOIN tokens t ON t.id = tb.token_id \
+         WHERE tb.account_address = '0x{owner:064x}' AND c.contract_type IN ('ERC721', 'ERC1155')"
+    );
+    if let Some(collection) = collection {
+        sql.push_str(&format!(" AND tb.contract_address = '0x{collection:064x}'"));
+    }
+
+    let client = crate::api::http_client(std::time::Duration::from_secs(30))?;
+    let url = format!("{}/sql", torii_url.trim_end_matches('/'));
+    let response = crate::api::send(
+        client.get(url).query(&[("query", sql)]),
+        "Torii request failed",
+    )
+    .await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(CliError::ApiError(format!(
+            "Torii returned {status}: {body}"
+        )));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| CliError::ApiError(format!("Failed to parse the Torii response: {e}")))
+}
+
+/// Group balance rows by collection, dropping zero balances. Collections and tokens are sorted
+/// by address and token ID.
+fn group(rows: Vec<BalanceRow>) -> Vec<CollectionOutput> {
+    let mut collections: BTreeMap<Felt, CollectionOutput> = BTreeMap::new();
+    for row in rows {
+        let (Ok(address), Some(Ok(token_id)), Ok(amount)) = (
+            Felt::from_hex(&row.contract_address),
+            row.token_id.rsplit(':').next().map(Felt::from_hex),
+            Felt::from_hex(&row.balance),
+        ) else {
+            continue;
+        };
+        if amount == Felt::ZERO {
+            continue;
+        }
+        let metadata: Option<serde_json::Value> = row
+            .metadata
+            .as_deref()
+            .and_then(|m| serde_json::from_str(m).ok());
+        let field = |key: &str| {
+            metadata
+                .as_ref()
+                .and_then(|m| m[key].as_str())
+                .map(str::to_string)
+        };
+
+        let collection = collections
+            .entry(address)
+            .or_insert_with(|| CollectionOutput {
+                address: format!("0x{address:x}"),
+                standard: row.contract_type.clone(),
+                name: None,
+                symbol: None,
+                tokens: Vec::new(),
+            });
+        collection.name = collection
+            .name
+            .take()
+            .or(row.name.filter(|n| !n.is_empty()));
+        collection.symbol = collection
+            .symbol
+            .take()
+            .or(row.symbol.filter(|s| !s.is_empty()));
+        collection.tokens.push(OwnedToken {
+            token_id: token_id.to_string(),
+            amount: amount.to_string(),
+            metadata_uri: None,
+            name: field("name"),
+            image: field("image"),
+        });
+    }
+
+    collections
+        .into_values()
+        .map(|mut collection| {
+            collection
+                .tokens
+                .sort_by_key(|t| Felt::from_dec_str(&t.token_id).unwrap_or(Felt::ZERO));
+            collection
+        })
+        .collect()
+}
+
+/// The token's metadata URI: `token_uri` for ERC721, `uri` for ERC1155 (camelCase tried next)
+async fn metadata_uri(
+    provider: Arc<JsonRpcClient<RpcTransport>>,
+    collection: Felt,
+    erc1155: bool,
+    token_id: Felt,
+) -> Option<String> {
+    let (low, high) = super::marketplace::encode_u256(&format!("0x{token_id:x}")).ok()?;
+    let entrypoints: &[&str] = if erc1155 {
+        &["uri"]
+    } else {
+        &["token_uri", "tokenURI"]
+    };
+    for entrypoint in entrypoints {
+        let selector = starknet::core::utils::get_selector_from_name(entrypoint).ok()?;
+        let result = provider
+            .call(
+                FunctionCall {
+                    contract_address: collection,
+                    entry_point_selector: selector,
+                    calldata: vec![low, high],
+                },
+                BlockId::Tag(BlockTag::Latest),
+            )
+            .await;
+        if let Ok(result) = result {
+            return decode_uri(&result);
+        }
+    }
+    None
+}
+
+/// A URI returned as a ByteArray, or as a legacy array of short strings (length first)
+fn decode_uri(result: &[Felt]) -> Option<String> {
+    if let Ok(byte_array) = ByteArray::cairo_deserialize(result, 0) {
+        if ByteArray::cairo_serialized_size(&byte_array) == result.len() {
+            if let Ok(uri) = byte_array.to_string() {
+                return Some(uri);
+            }
+        }
+    }
+    let (len, parts) = result.split_first()?;
+    if usize::try_from(*len).ok()? != parts.len() {
+        return None;
+    }
+    parts
+        .iter()
+        .map(|part| starknet::core::utils::parse_cairo_short_string(part).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(contract: &str, token_id: &str, balance: &str, metadata: Option<&str>) -> BalanceRow {
+        BalanceRow {
+            contract_address: contract.to_string(),
+            token_id: format!("{contract}:{token_id}"),
+            balance: balance.to_string(),
+            contract_type: "ERC721".to_string(),
+            name: Some("Beasts".to_string()),
+            symbol: Some("BEAST".to_string()),
+            metadata: metadata.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn groups_tokens_and_decodes_uris() {
+        let collections = group(vec![
+            row(
+                "0x0b",
+                "0x0a",
+                "0x01",
+                Some(r#"{"name":"Wolf","image":"ipfs://w"}"#),
+            ),
+            row("0x0b", "0x02", "0x01", None),
+            row("0x0b", "0x03", "0x00", None),
+            row("0x0a", "0x01", "0x01", Some("not json")),
+        ]);
+        assert_eq!(collections.len(), 2);
+        assert_eq!(collections[0].address, "0xa");
+        let ids: Vec<&str> = collections[1]
+            .tokens
+            .iter()
+            .map(|t| t.token_id.as_str())
+            .collect();
+        assert_eq!(ids, ["2", "10"]);
+        assert_eq!(collections[1].tokens[1].name.as_deref(), Some("Wolf"));
+        assert_eq!(collections[1].tokens[1].image.as_deref(), Some("ipfs://w"));
+        assert_eq!(collections[0].symbol.as_deref(), Some("BEAST"));
+
+        let uri = "ipfs://bafybeigdyrztxyz/metadata/1.json";
+        let serialized = ByteArray::cairo_serialize(&ByteArray::from_string(uri).unwrap());
+        assert_eq!(decode_uri(&serialized).as_deref(), Some(uri));
+        let legacy = vec![
+            Felt::TWO,
+            starknet::core::utils::cairo_short_string_to_felt("https://x.io/").unwrap(),
+            starknet::core::utils::cairo_short_string_to_felt("7").unwrap(),
+        ];
+        assert_eq!(decode_uri(&legacy).as_deref(), Some("https://x.io/7"));
+        assert_eq!(decode_uri(&[]), None);
+    }
+}
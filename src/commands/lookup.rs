@@ -4,8 +4,18 @@ use crate::{
     output::OutputFormatter,
 };
 use serde::{Deserialize, Serialize};
+use starknet::core::types::Felt;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
-const LOOKUP_URL: &str = "https://api.cartridge.gg/accounts/lookup";
+/// Accounts lookup endpoint, next to the configured GraphQL `api-url`
+fn lookup_url(api_url: &str) -> String {
+    let api_base = api_url.trim_end_matches("/query").trim_end_matches('/');
+    format!("{api_base}/accounts/lookup")
+}
+
+/// How long a username↔address mapping is trusted before it is refetched
+const CACHE_TTL_SECS: u64 = 24 * 60 * 60;
 
 #[derive(Serialize)]
 struct LookupRequest {
@@ -26,11 +36,31 @@ struct LookupResponse {
     results: Vec<LookupEntry>,
 }
 
+/// A resolved username and its (first) controller address
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedAccount {
+    pub username: String,
+    pub address: String,
+}
+
+/// Whether to consult the local lookup cache, bypass it, or avoid the network entirely
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheMode {
+    /// Use fresh cache entries, fetch the rest
+    Default,
+    /// Ignore the cache and refetch everything (results are still written back)
+    Refresh,
+    /// Only use the cache, regardless of age; never hit the network
+    CachedOnly,
+}
+
 pub async fn execute(
-    _config: &Config,
+    config: &Config,
     formatter: &dyn OutputFormatter,
     usernames: Option<String>,
     addresses: Option<String>,
+    refresh: bool,
+    cached_only: bool,
 ) -> Result<()> {
     let usernames_list = usernames.map(|s| {
         s.split(',')
@@ -52,23 +82,110 @@ pub async fn execute(
         ));
     }
 
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .map_err(|e| CliError::ApiError(format!("Failed to build HTTP client: {e}")))?;
+    let mode = if cached_only {
+        CacheMode::CachedOnly
+    } else if refresh {
+        CacheMode::Refresh
+    } else {
+        CacheMode::Default
+    };
+
+    let resolved = resolve(
+        config,
+        usernames_list.as_deref().unwrap_or_default(),
+        addresses_list.as_deref().unwrap_or_default(),
+        mode,
+    )
+    .await?;
+
+    // Flatten into "username:address" entries
+    let entries: Vec<String> = resolved
+        .iter()
+        .map(|entry| format!("{}:{}", entry.username, entry.address))
+        .collect();
+
+    formatter.success(&entries);
+
+    Ok(())
+}
+
+/// Resolve usernames and/or addresses, serving fresh entries from the local cache and fetching
+/// only what is missing. Unknown names/addresses are omitted from the result.
+pub async fn resolve(
+    config: &Config,
+    usernames: &[String],
+    addresses: &[String],
+    mode: CacheMode,
+) -> Result<Vec<ResolvedAccount>> {
+    let path = cache_path(config);
+    let mut cache = LookupCache::load(&path);
+    let now = now_secs();
+
+    let mut resolved = Vec::new();
+    let mut missing_usernames = Vec::new();
+    let mut missing_addresses = Vec::new();
+
+    for username in usernames {
+        match cache.by_username(username, now, mode) {
+            Some(entry) => {
+                crate::meta::cache_hit();
+                resolved.push(entry)
+            }
+            None => missing_usernames.push(username.clone()),
+        }
+    }
+    for address in addresses {
+        match cache.by_address(address, now, mode) {
+            Some(entry) => {
+                crate::meta::cache_hit();
+                resolved.push(entry)
+            }
+            None => missing_addresses.push(address.clone()),
+        }
+    }
+
+    if mode == CacheMode::CachedOnly
+        || (missing_usernames.is_empty() && missing_addresses.is_empty())
+    {
+        return Ok(resolved);
+    }
+
+    let fetched = fetch(
+        &config.session.api_url,
+        (!missing_usernames.is_empty()).then_some(missing_usernames),
+        (!missing_addresses.is_empty()).then_some(missing_addresses),
+    )
+    .await?;
+
+    for entry in &fetched {
+        cache.insert(entry.clone(), now);
+    }
+    cache.save(&path);
+
+    resolved.extend(fetched);
+    Ok(resolved)
+}
+
+async fn fetch(
+    api_url: &str,
+    usernames: Option<Vec<String>>,
+    addresses: Option<Vec<String>>,
+) -> Result<Vec<ResolvedAccount>> {
+    let client = crate::api::http_client(std::time::Duration::from_secs(10))?;
 
     let request = LookupRequest {
-        usernames: usernames_list,
-        addresses: addresses_list,
+        usernames,
+        addresses,
     };
 
-    let response = client
-        .post(LOOKUP_URL)
-        .header("Content-Type", "application/json")
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| CliError::ApiError(format!("Lookup request failed: {e}")))?;
+    let response = crate::api::send(
+        client
+            .post(lookup_url(api_url))
+            .header("Content-Type", "application/json")
+            .json(&request),
+        "Lookup request failed",
+    )
+    .await?;
 
     if !response.status().is_success() {
         let status = response.status();
@@ -86,19 +203,193 @@ pub async fn execute(
         .await
         .map_err(|e| CliError::ApiError(format!("Failed to parse lookup response: {e}")))?;
 
-    // Flatten into "username:address" entries (use first address per result)
-    let entries: Vec<String> = lookup_response
+    // Use first address per result
+    Ok(lookup_response
         .results
-        .iter()
+        .into_iter()
         .filter_map(|entry| {
             entry
                 .addresses
-                .first()
-                .map(|addr| format!("{}:{}", entry.username, addr))
+                .into_iter()
+                .next()
+                .map(|address| ResolvedAccount {
+                    username: entry.username,
+                    address,
+                })
         })
-        .collect();
+        .collect())
+}
 
-    formatter.success(&entries);
+/// Usernames whose cache entries are older than the TTL, for `doctor` to refresh
+pub fn stale_usernames(config: &Config) -> Vec<String> {
+    let now = now_secs();
+    LookupCache::load(&cache_path(config))
+        .entries
+        .values()
+        .filter(|e| !e.usable(now, CacheMode::Default))
+        .map(|e| e.username.clone())
+        .collect()
+}
 
-    Ok(())
+// --- Cache ---
+
+#[derive(Default, Serialize, Deserialize)]
+struct LookupCache {
+    /// Keyed by lowercased username
+    entries: BTreeMap<String, CachedAccount>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct CachedAccount {
+    username: String,
+    address: String,
+    fetched_at: u64,
+}
+
+impl CachedAccount {
+    fn usable(&self, now: u64, mode: CacheMode) -> bool {
+        match mode {
+            CacheMode::Default => now.saturating_sub(self.fetched_at) <= CACHE_TTL_SECS,
+            CacheMode::Refresh => false,
+            CacheMode::CachedOnly => true,
+        }
+    }
+
+    fn resolved(&self) -> ResolvedAccount {
+        ResolvedAccount {
+            username: self.username.clone(),
+            address: self.address.clone(),
+        }
+    }
+}
+
+impl LookupCache {
+    fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, path: &Path) {
+        if let Ok(json) = serde_json::to_string(self) {
+            let _ = super::storage::write_atomic(path, json);
+        }
+    }
+
+    fn insert(&mut self, account: ResolvedAccount, now: u64) {
+        self.entries.insert(
+            account.username.to_lowercase(),
+            CachedAccount {
+                username: account.username,
+                address: account.address,
+                fetched_at: now,
+            },
+        );
+    }
+
+    fn by_username(&self, username: &str, now: u64, mode: CacheMode) -> Option<ResolvedAccount> {
+        self.entries
+            .get(&username.to_lowercase())
+            .filter(|e| e.usable(now, mode))
+            .map(CachedAccount::resolved)
+    }
+
+    fn by_address(&self, address: &str, now: u64, mode: CacheMode) -> Option<ResolvedAccount> {
+        // Compare as felts so leading zeros don't matter
+        let wanted = Felt::from_hex(address).ok();
+        self.entries
+            .values()
+            .find(|e| match (wanted, Felt::from_hex(&e.address).ok()) {
+                (Some(a), Some(b)) => a == b,
+                _ => e.address.eq_ignore_ascii_case(address),
+            })
+            .filter(|e| e.usable(now, mode))
+            .map(CachedAccount::resolved)
+    }
+}
+
+/// The cache is shared across accounts, so it lives in the base storage path
+fn cache_path(config: &Config) -> PathBuf {
+    config.resolve_storage_path(None).join("lookup_cache.json")
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(username: &str, address: &str) -> ResolvedAccount {
+        ResolvedAccount {
+            username: username.to_string(),
+            address: address.to_string(),
+        }
+    }
+
+    #[test]
+    fn cache_respects_ttl_and_mode() {
+        let mut cache = LookupCache::default();
+        cache.insert(account("Shinobi", "0x0123"), 1_000);
+
+        let fresh = 1_000 + CACHE_TTL_SECS;
+        let stale = fresh + 1;
+
+        assert!(cache
+            .by_username("shinobi", fresh, CacheMode::Default)
+            .is_some());
+        assert!(cache
+            .by_username("shinobi", stale, CacheMode::Default)
+            .is_none());
+        assert!(cache
+            .by_username("shinobi", stale, CacheMode::CachedOnly)
+            .is_some());
+        assert!(cache
+            .by_username("shinobi", fresh, CacheMode::Refresh)
+            .is_none());
+    }
+
+    #[test]
+    fn cache_matches_addresses_ignoring_leading_zeros() {
+        let mut cache = LookupCache::default();
+        cache.insert(account("shinobi", "0x0123"), 1_000);
+
+        let hit = cache
+            .by_address("0x123", 1_000, CacheMode::Default)
+            .unwrap();
+        assert_eq!(hit.username, "shinobi");
+        assert!(cache
+            .by_address("0x456", 1_000, CacheMode::Default)
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn cached_only_never_hits_network() {
+        let dir =
+            std::env::temp_dir().join(format!("controller-lookup-test-{}", std::process::id()));
+        let mut config = Config::default();
+        config.session.storage_path = dir.to_string_lossy().to_string();
+
+        let mut cache = LookupCache::default();
+        cache.insert(account("shinobi", "0x123"), 0);
+        cache.save(&cache_path(&config));
+
+        let resolved = resolve(
+            &config,
+            &["shinobi".to_string(), "unknown".to_string()],
+            &[],
+            CacheMode::CachedOnly,
+        )
+        .await
+        .unwrap();
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].address, "0x123");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
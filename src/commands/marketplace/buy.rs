@@ -1,3 +1,4 @@
+use crate::commands::audit::AuditEntry;
 use crate::commands::session::authorize::PolicyStorage;
 use crate::config::Config;
 use crate::error::{CliError, Result};
@@ -9,14 +10,19 @@ use account_sdk::{
 };
 use serde::Serialize;
 use starknet::core::types::{BlockId, BlockTag, Call, Felt, FunctionCall};
-use starknet::providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider};
+use starknet::providers::{JsonRpcClient, Provider};
 
-use super::{build_execute_calldata, encode_u256, resolve_chain_id_to_rpc, MARKETPLACE_CONTRACT};
+use super::{build_execute_calldata, encode_u256, resolve_chain_id_to_rpc};
 
 #[derive(Serialize)]
 struct BuyOutput {
     transaction_hash: String,
     message: String,
+    chain_id: String,
+    explorer_url: Option<String>,
+    paymaster_used: bool,
+    /// RFC 3339 timestamp of when the transaction was submitted
+    submitted_at: String,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -46,11 +52,11 @@ pub async fn execute(
     };
 
     // Resolve RPC URL
-    let rpc_url = resolve_chain_id_to_rpc(chain_id, rpc_url.clone())?;
+    let rpc_url = resolve_chain_id_to_rpc(config, chain_id, rpc_url.clone())?;
 
     // Load controller metadata
     let storage_path = config.resolve_storage_path(account);
-    let backend = FileSystemBackend::new(storage_path);
+    let backend = FileSystemBackend::new(storage_path.clone());
 
     let controller_metadata = backend
         .controller()
@@ -108,17 +114,17 @@ pub async fn execute(
 
     // Validate Cartridge RPC endpoint
     if let Some(ref url) = rpc_url {
-        if !url.starts_with("https://api.cartridge.gg") {
-            return Err(CliError::InvalidInput(
-                "Only Cartridge RPC endpoints are supported. Use: https://api.cartridge.gg/x/starknet/mainnet or https://api.cartridge.gg/x/starknet/sepolia".to_string()
-            ));
-        }
+        crate::chains::ensure_cartridge_rpc(config, url)?;
     }
 
     let rpc_parsed = url::Url::parse(&effective_rpc_url)
         .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
 
-    let provider = JsonRpcClient::new(HttpTransport::new(rpc_parsed.clone()));
+    let provider = JsonRpcClient::new(crate::api::rpc_transport(rpc_parsed.clone()));
+
+    let chain_name = crate::chains::chain_name(&provider).await?;
+    let marketplace_contract =
+        crate::chains::resolve_contract(config, &chain_name, crate::chains::MARKETPLACE)?;
 
     // First, check order validity
     formatter.info("Checking order validity...");
@@ -129,7 +135,7 @@ pub async fn execute(
     let validity_result = provider
         .call(
             FunctionCall {
-                contract_address: MARKETPLACE_CONTRACT,
+                contract_address: marketplace_contract,
                 entry_point_selector: validity_selector,
                 calldata: vec![
                     Felt::from(order_id),
@@ -173,7 +179,7 @@ pub async fn execute(
             _ => None,
         });
 
-    validate_marketplace_policies(&stored_policies)?;
+    validate_marketplace_policies(&stored_policies, marketplace_contract)?;
 
     // Build execute call
     let execute_selector = starknet::core::utils::get_selector_from_name("execute")
@@ -193,7 +199,7 @@ pub async fn execute(
     );
 
     let calls = vec![Call {
-        to: MARKETPLACE_CONTRACT,
+        to: marketplace_contract,
         selector: execute_selector,
         calldata: execute_calldata,
     }];
@@ -214,50 +220,86 @@ pub async fn execute(
     .await
     .map_err(|e| CliError::Storage(format!("Failed to create controller: {e}")))?;
 
-    let chain_name = match controller.provider.chain_id().await {
-        Ok(felt) => starknet::core::utils::parse_cairo_short_string(&felt)
-            .unwrap_or_else(|_| format!("0x{felt:x}")),
-        Err(_) => starknet::core::utils::parse_cairo_short_string(&controller_metadata.chain_id)
-            .unwrap_or_else(|_| format!("0x{:x}", controller_metadata.chain_id)),
-    };
-    let is_mainnet = chain_name == "SN_MAIN";
+    let specs = crate::commands::audit::call_specs(&calls);
+    let policies_matched =
+        crate::commands::execute::matched_policies(&specs, stored_policies.as_ref());
+    let mut audit = AuditEntry::new(
+        "marketplace buy",
+        account,
+        controller_metadata.address,
+        &chain_name,
+        specs,
+        policies_matched,
+        !no_paymaster,
+    );
 
     // Execute
-    let result = if no_paymaster {
-        formatter.info(&format!(
-            "Purchasing order #{order_id} on {chain_name} without paymaster..."
-        ));
-        let estimate = controller
-            .estimate_invoke_fee(calls.clone())
-            .await
-            .map_err(|e| CliError::TransactionFailed(format!("Fee estimation failed: {e}")))?;
-        controller
-            .execute(calls, Some(estimate), None)
-            .await
-            .map_err(|e| CliError::TransactionFailed(format!("Transaction failed: {e}")))?
-    } else {
-        formatter.info(&format!("Purchasing order #{order_id} on {chain_name}..."));
-        controller
-            .execute_from_outside_v3(calls, None)
-            .await
-            .map_err(|e| {
-                CliError::TransactionFailed(format!(
-                    "Paymaster execution failed: {e}\nUse --no-paymaster to force self-pay"
-                ))
-            })?
-    };
+    let submitted: Result<_> = async {
+        Ok(if no_paymaster {
+            formatter.info(&format!(
+                "Purchasing order #{order_id} on {chain_name} without paymaster..."
+            ));
+            let estimate = controller
+                .estimate_invoke_fee(calls.clone())
+                .await
+                .map_err(|e| CliError::TransactionFailed(format!("Fee estimation failed: {e}")))?;
+            crate::commands::balance::ensure_fee_balance(
+                config,
+                formatter,
+                &controller.provider,
+                controller_metadata.address,
+                Felt::from(estimate.overall_fee),
+            )
+            .await?;
+            controller
+                .execute(calls, Some(estimate), None)
+                .await
+                .map_err(|e| CliError::TransactionFailed(format!("Transaction failed: {e}")))?
+        } else {
+            formatter.info(&format!("Purchasing order #{order_id} on {chain_name}..."));
+            controller
+                .execute_from_outside_v3(calls, None)
+                .await
+                .map_err(|e| {
+                    CliError::TransactionFailed(format!(
+                        "Paymaster execution failed: {e}\nUse --no-paymaster to force self-pay"
+                    ))
+                })?
+        })
+    }
+    .await;
+    audit.submitted(
+        config,
+        formatter,
+        submitted.as_ref().map(|result| result.transaction_hash),
+    );
+    let result = submitted?;
+
+    // The marketplace settles payment in tokens, so balances always change
+    crate::commands::balance::invalidate_cache_after_tx(
+        config,
+        &storage_path,
+        controller_metadata.address,
+        &[],
+        true,
+    );
 
     let transaction_hash = format!("0x{:x}", result.transaction_hash);
-    let voyager_subdomain = if is_mainnet { "" } else { "sepolia." };
+    let explorer_url = crate::chains::explorer_tx_url(&chain_name, &transaction_hash);
 
     if config.cli.json_output {
         formatter.success(&BuyOutput {
             transaction_hash: transaction_hash.clone(),
             message: "Marketplace purchase executed successfully".to_string(),
+            chain_id: chain_name,
+            explorer_url,
+            paymaster_used: !no_paymaster,
+            submitted_at: chrono::Utc::now().to_rfc3339(),
         });
     } else {
         formatter.info(&format!(
-            "Transaction: https://{voyager_subdomain}voyager.online/tx/{transaction_hash}"
+            "Transaction: {}",
+            explorer_url.as_deref().unwrap_or(&transaction_hash)
         ));
     }
 
@@ -280,8 +322,9 @@ pub async fn execute(
                 .get_transaction_receipt(result.transaction_hash)
                 .await
             {
-                Ok(_) => {
+                Ok(receipt) => {
                     formatter.info("Transaction confirmed!");
+                    audit.confirmed(config, formatter, &receipt.receipt);
                     break;
                 }
                 Err(_) => {
@@ -295,23 +338,26 @@ pub async fn execute(
 }
 
 /// Validate that the session policies include `execute` on the marketplace contract
-fn validate_marketplace_policies(policies: &Option<PolicyStorage>) -> Result<()> {
+fn validate_marketplace_policies(
+    policies: &Option<PolicyStorage>,
+    marketplace_contract: Felt,
+) -> Result<()> {
     let mut missing = Vec::new();
 
     match policies {
         None => {
             missing.push(format!(
-                "execute on marketplace contract (0x{MARKETPLACE_CONTRACT:x})"
+                "execute on marketplace contract (0x{marketplace_contract:x})"
             ));
         }
         Some(policies) => {
             let has_execute = policies.contracts.iter().any(|(addr, policy)| {
-                Felt::from_hex(addr).ok() == Some(MARKETPLACE_CONTRACT)
+                Felt::from_hex(addr).ok() == Some(marketplace_contract)
                     && policy.methods.iter().any(|m| m.entrypoint == "execute")
             });
             if !has_execute {
                 missing.push(format!(
-                    "execute on marketplace contract (0x{MARKETPLACE_CONTRACT:x})"
+                    "execute on marketplace contract (0x{marketplace_contract:x})"
                 ));
             }
         }
@@ -3,9 +3,7 @@ use crate::error::{CliError, Result};
 use crate::output::OutputFormatter;
 use serde::Serialize;
 use starknet::core::types::{BlockId, BlockTag, Felt, FunctionCall};
-use starknet::providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider};
-
-use super::{resolve_chain_id_to_rpc, MARKETPLACE_CONTRACT};
+use starknet::providers::{JsonRpcClient, Provider};
 
 #[derive(Serialize)]
 pub struct OrderInfo {
@@ -21,6 +19,7 @@ struct InfoOutput {
     order: OrderInfo,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn execute(
     config: &Config,
     formatter: &dyn OutputFormatter,
@@ -29,21 +28,18 @@ pub async fn execute(
     token_id: String,
     chain_id: Option<String>,
     rpc_url: Option<String>,
+    account: Option<&str>,
 ) -> Result<()> {
     // Resolve RPC URL
-    let rpc_url = resolve_chain_id_to_rpc(chain_id.clone(), rpc_url)?
-        .or_else(|| {
-            if !config.session.rpc_url.is_empty() {
-                Some(config.session.rpc_url.clone())
-            } else {
-                None
-            }
-        })
-        .unwrap_or_else(|| "https://api.cartridge.gg/x/starknet/sepolia".to_string());
+    let rpc_url = crate::chains::resolve_rpc_url(config, formatter, chain_id, rpc_url, account)?;
 
     let url = url::Url::parse(&rpc_url)
         .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
-    let provider = JsonRpcClient::new(HttpTransport::new(url));
+    let provider = JsonRpcClient::new(crate::api::rpc_transport(url));
+
+    let chain_name = crate::chains::chain_name(&provider).await?;
+    let marketplace_contract =
+        crate::chains::resolve_contract(config, &chain_name, crate::chains::MARKETPLACE)?;
 
     // Parse collection address
     let collection_felt = Felt::from_hex(&collection)
@@ -63,7 +59,7 @@ pub async fn execute(
     let result = provider
         .call(
             FunctionCall {
-                contract_address: MARKETPLACE_CONTRACT,
+                contract_address: marketplace_contract,
                 entry_point_selector: selector,
                 calldata: vec![
                     Felt::from(order_id),
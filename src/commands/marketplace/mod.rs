@@ -1,13 +1,10 @@
 pub mod buy;
 pub mod info;
 
+use crate::config::Config;
 use crate::error::{CliError, Result};
 use starknet::core::types::Felt;
 
-/// Marketplace contract address (same on mainnet and sepolia)
-pub const MARKETPLACE_CONTRACT: Felt =
-    Felt::from_hex_unchecked("0x057b4ca2f7b58e1b940eb89c4376d6e166abc640abf326512b0c77091f3f9652");
-
 /// STRK token address (for reference in future features)
 #[allow(dead_code)]
 pub const STRK_TOKEN: Felt =
@@ -73,21 +70,12 @@ pub fn build_execute_calldata(
 
 /// Resolve chain_id to an RPC URL, or pass through rpc_url as-is
 pub fn resolve_chain_id_to_rpc(
+    config: &Config,
     chain_id: Option<String>,
     rpc_url: Option<String>,
 ) -> Result<Option<String>> {
     match chain_id {
-        Some(chain) => match chain.as_str() {
-            "SN_MAIN" => Ok(Some(
-                "https://api.cartridge.gg/x/starknet/mainnet".to_string(),
-            )),
-            "SN_SEPOLIA" => Ok(Some(
-                "https://api.cartridge.gg/x/starknet/sepolia".to_string(),
-            )),
-            _ => Err(CliError::InvalidInput(format!(
-                "Unsupported chain ID '{chain}'. Supported chains: SN_MAIN, SN_SEPOLIA"
-            ))),
-        },
+        Some(chain) => crate::chains::rpc_url_for(config, &chain).map(Some),
         None => Ok(rpc_url),
     }
 }
@@ -140,7 +128,8 @@ mod tests {
 
     #[test]
     fn test_resolve_chain_id_mainnet() {
-        let result = resolve_chain_id_to_rpc(Some("SN_MAIN".to_string()), None).unwrap();
+        let result =
+            resolve_chain_id_to_rpc(&Config::default(), Some("SN_MAIN".to_string()), None).unwrap();
         assert_eq!(
             result,
             Some("https://api.cartridge.gg/x/starknet/mainnet".to_string())
@@ -149,7 +138,9 @@ mod tests {
 
     #[test]
     fn test_resolve_chain_id_sepolia() {
-        let result = resolve_chain_id_to_rpc(Some("SN_SEPOLIA".to_string()), None).unwrap();
+        let result =
+            resolve_chain_id_to_rpc(&Config::default(), Some("SN_SEPOLIA".to_string()), None)
+                .unwrap();
         assert_eq!(
             result,
             Some("https://api.cartridge.gg/x/starknet/sepolia".to_string())
@@ -158,13 +149,18 @@ mod tests {
 
     #[test]
     fn test_resolve_chain_id_invalid() {
-        let result = resolve_chain_id_to_rpc(Some("INVALID".to_string()), None);
+        let result = resolve_chain_id_to_rpc(&Config::default(), Some("INVALID".to_string()), None);
         assert!(result.is_err());
     }
 
     #[test]
     fn test_resolve_rpc_url_passthrough() {
-        let result = resolve_chain_id_to_rpc(None, Some("https://custom.rpc".to_string())).unwrap();
+        let result = resolve_chain_id_to_rpc(
+            &Config::default(),
+            None,
+            Some("https://custom.rpc".to_string()),
+        )
+        .unwrap();
         assert_eq!(result, Some("https://custom.rpc".to_string()));
     }
 }
@@ -1,14 +1,53 @@
+pub mod accounts;
+pub mod api_cmd;
+pub mod approve_for;
+pub mod audit;
 pub mod balance;
+pub mod batch;
+pub mod bench;
 pub mod call;
 pub mod calldata;
 pub mod clear;
 pub mod config_cmd;
+pub mod convert;
+pub mod dashboard;
+pub mod decode;
+pub mod doctor;
+pub mod encode;
+pub mod env;
+pub mod events;
 pub mod execute;
+pub mod execute_stdin;
+pub mod faucet;
+pub mod file_format;
+pub mod games;
+pub mod init;
+pub mod intent;
+pub mod inventory;
 pub mod lookup;
 pub mod marketplace;
+pub mod policy;
+pub mod presets_cmd;
+pub mod queue;
 pub mod receipt;
+pub mod replay;
+pub mod schedule;
+pub mod selector_of;
+pub mod selectors;
+pub mod serve;
 pub mod session;
+pub mod sign_message;
+pub mod simulate;
+pub mod slot;
+pub mod snapshot;
 pub mod starterpack;
 pub mod status;
+pub mod storage;
+pub mod submit_queue;
+pub mod tail;
 pub mod transaction;
+pub mod transfer;
+pub mod transfer_batch;
 pub mod username;
+pub mod vault;
+pub mod version_cmd;
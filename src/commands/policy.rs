@@ -0,0 +1,561 @@
+use crate::api::RpcTransport;
+use crate::commands::session::authorize::{self, ContractPolicy, PolicyFile, PolicyStorage};
+use crate::config::Config;
+use crate::error::{CliError, Result};
+use crate::output::OutputFormatter;
+use account_sdk::storage::{filestorage::FileSystemBackend, StorageBackend, StorageValue};
+use serde::Serialize;
+use serde_json::Value;
+use starknet::core::types::{BlockId, BlockTag, ContractClass, Felt, StarknetError};
+use starknet::core::utils::get_selector_from_name;
+use starknet::providers::{JsonRpcClient, Provider, ProviderError};
+use std::collections::HashMap;
+
+#[derive(Debug, Serialize)]
+pub struct PolicyIssue {
+    /// 'error' blocks authorization; 'warning' is worth a look
+    pub severity: &'static str,
+    pub contract: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entrypoint: Option<String>,
+    pub message: String,
+}
+
+#[derive(Serialize)]
+struct ValidateOutput {
+    file: String,
+    valid: bool,
+    contracts: usize,
+    methods: usize,
+    /// The session's chain the contracts were checked on, with `--against-session`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chain_id: Option<String>,
+    issues: Vec<PolicyIssue>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct MethodRef {
+    pub contract: String,
+    pub entrypoint: String,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct PolicyDiff {
+    /// In the policies but not in the session: authorizing them would add these
+    pub added_contracts: Vec<String>,
+    pub removed_contracts: Vec<String>,
+    /// Includes the methods of added and removed contracts
+    pub added_methods: Vec<MethodRef>,
+    pub removed_methods: Vec<MethodRef>,
+    /// Same entrypoint with a different `authorized` flag or spending `amount`
+    pub changed_methods: Vec<MethodRef>,
+    pub added_messages: usize,
+    pub removed_messages: usize,
+    pub identical: bool,
+}
+
+#[derive(Serialize)]
+struct DiffOutput {
+    address: String,
+    chain_id: String,
+    #[serde(flatten)]
+    diff: PolicyDiff,
+}
+
+fn issue(
+    severity: &'static str,
+    contract: &str,
+    entrypoint: Option<&str>,
+    message: String,
+) -> PolicyIssue {
+    PolicyIssue {
+        severity,
+        contract: contract.to_string(),
+        entrypoint: entrypoint.map(str::to_string),
+        message,
+    }
+}
+
+/// Cairo identifiers: what an entrypoint name must look like to hash to a real selector
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Problems visible in the file alone: addresses that aren't felts, the same contract under two
+/// spellings, repeated or malformed entrypoints, and amounts that aren't numbers.
+pub fn lint(policy_file: &PolicyFile) -> Vec<PolicyIssue> {
+    let mut issues = Vec::new();
+    let mut seen: HashMap<Felt, &str> = HashMap::new();
+
+    let mut contracts: Vec<_> = policy_file.contracts.iter().collect();
+    contracts.sort_by(|(a, _), (b, _)| a.to_lowercase().cmp(&b.to_lowercase()));
+    for (address, contract) in contracts {
+        match Felt::from_hex(address) {
+            Err(e) => issues.push(issue(
+                "error",
+                address,
+                None,
+                format!("Invalid contract address: {e}"),
+            )),
+            Ok(felt) if felt == Felt::ZERO => issues.push(issue(
+                "error",
+                address,
+                None,
+                "Contract address is zero".to_string(),
+            )),
+            Ok(felt) => {
+                if let Some(other) = seen.insert(felt, address) {
+                    issues.push(issue(
+                        "error",
+                        address,
+                        None,
+                        format!("Same contract as {other}; merge the two entries"),
+                    ));
+                }
+            }
+        }
+        if contract.methods.is_empty() {
+            issues.push(issue(
+                "warning",
+                address,
+                None,
+                "No methods: the contract grants nothing".to_string(),
+            ));
+        }
+
+        let mut entrypoints: Vec<&str> = Vec::new();
+        for method in &contract.methods {
+            let entrypoint = method.entrypoint.as_str();
+            if entrypoints.contains(&entrypoint) {
+                issues.push(issue(
+                    "error",
+                    address,
+                    Some(entrypoint),
+                    "Duplicate entrypoint".to_string(),
+                ));
+                continue;
+            }
+            entrypoints.push(entrypoint);
+
+            if entrypoint.starts_with("selector:") {
+                issues.push(issue(
+                    "error",
+                    address,
+                    Some(entrypoint),
+                    "Policies need the entrypoint's name, not a raw selector".to_string(),
+                ));
+            } else if !is_identifier(entrypoint) || get_selector_from_name(entrypoint).is_err() {
+                issues.push(issue(
+                    "error",
+                    address,
+                    Some(entrypoint),
+                    "Not a valid entrypoint name".to_string(),
+                ));
+            }
+            if let Some(amount) = &method.amount {
+                let parsed = if amount.starts_with("0x") {
+                    Felt::from_hex(amount).is_ok()
+                } else {
+                    Felt::from_dec_str(amount).is_ok()
+                };
+                if !parsed {
+                    issues.push(issue(
+                        "error",
+                        address,
+                        Some(entrypoint),
+                        format!("Amount '{amount}' is not a number"),
+                    ));
+                }
+            }
+        }
+    }
+    issues
+}
+
+/// Names of the external functions of a Cairo 1 ABI, including those inside interfaces
+fn abi_functions(abi: &[Value]) -> Vec<&str> {
+    abi.iter()
+        .flat_map(|item| match item["type"].as_str() {
+            Some("interface") => item["items"].as_array().map(Vec::as_slice).unwrap_or(&[]),
+            _ => std::slice::from_ref(item),
+        })
+        .filter(|item| item["type"] == "function")
+        .filter_map(|item| item["name"].as_str())
+        .collect()
+}
+
+/// Check each contract is deployed on the session's chain and exposes the entrypoints.
+async fn check_onchain(
+    provider: &JsonRpcClient<RpcTransport>,
+    chain_name: &str,
+    policy_file: &PolicyFile,
+    issues: &mut Vec<PolicyIssue>,
+) {
+    for (address, contract) in &policy_file.contracts {
+        let Ok(felt) = Felt::from_hex(address) else {
+            continue;
+        };
+        let abi = match provider
+            .get_class_at(BlockId::Tag(BlockTag::Latest), felt)
+            .await
+        {
+            Ok(ContractClass::Sierra(class)) => {
+                serde_json::from_str::<Vec<Value>>(&class.abi).unwrap_or_default()
+            }
+            Ok(ContractClass::Legacy(_)) => {
+                issues.push(issue(
+                    "warning",
+                    address,
+                    None,
+                    "Cairo 0 contract: entrypoints were not checked".to_string(),
+                ));
+                continue;
+            }
+            Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => {
+                issues.push(issue(
+                    "error",
+                    address,
+                    None,
+                    format!("No contract deployed at this address on {chain_name}"),
+                ));
+                continue;
+            }
+            Err(e) => {
+                issues.push(issue(
+                    "warning",
+                    address,
+                    None,
+                    format!("Could not fetch the contract class: {e}"),
+                ));
+                continue;
+            }
+        };
+        let functions = abi_functions(&abi);
+        for method in &contract.methods {
+            if is_identifier(&method.entrypoint) && !functions.contains(&method.entrypoint.as_str())
+            {
+                issues.push(issue(
+                    "error",
+                    address,
+                    Some(&method.entrypoint),
+                    format!(
+                        "Unknown selector: the contract on {chain_name} has no such entrypoint"
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// The stored session's controller and the RPC of its chain
+struct Session {
+    address: Felt,
+    chain_name: String,
+    rpc_url: String,
+    backend: FileSystemBackend,
+}
+
+impl Session {
+    fn load(
+        config: &Config,
+        formatter: &dyn OutputFormatter,
+        account: Option<&str>,
+    ) -> Result<Self> {
+        let backend = FileSystemBackend::new(config.resolve_storage_path(account));
+        let controller = backend
+            .controller()
+            .map_err(|e| CliError::Storage(e.to_string()))?
+            .ok_or(CliError::NoSession)?;
+        let rpc_url = match backend.get("session_rpc_url") {
+            Ok(Some(StorageValue::String(url))) => url,
+            _ => crate::chains::resolve_rpc_url(config, formatter, None, None, account)?,
+        };
+        Ok(Self {
+            address: controller.address,
+            chain_name: starknet::core::utils::parse_cairo_short_string(&controller.chain_id)
+                .unwrap_or_else(|_| format!("0x{:x}", controller.chain_id)),
+            rpc_url,
+            backend,
+        })
+    }
+
+    fn provider(&self) -> Result<JsonRpcClient<RpcTransport>> {
+        let url = url::Url::parse(&self.rpc_url)
+            .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
+        Ok(JsonRpcClient::new(crate::api::rpc_transport(url)))
+    }
+
+    fn policies(&self) -> Result<PolicyStorage> {
+        match self.backend.get("session_policies").ok().flatten() {
+            Some(StorageValue::String(json)) => serde_json::from_str(&json).map_err(|e| {
+                CliError::InvalidSessionData(format!("Invalid stored policies: {e}"))
+            }),
+            _ => Err(CliError::InvalidSessionData(
+                "The session's policies are not stored. Re-authorize with 'controller session auth'"
+                    .to_string(),
+            )),
+        }
+    }
+}
+
+/// Lint a policy file before authorizing it; with `--against-session`, also check its contracts
+/// and entrypoints exist on the session's chain. Fails when any error is found.
+pub async fn validate(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    file: String,
+    against_session: bool,
+    account: Option<&str>,
+) -> Result<()> {
+    let content = std::fs::read_to_string(&file).map_err(|e| CliError::FileError {
+        path: file.clone(),
+        message: e.to_string(),
+    })?;
+    let policy_file: PolicyFile =
+        crate::commands::file_format::parse_structured(&file, &content)
+            .map_err(|e| CliError::InvalidInput(format!("Invalid policy file format: {e}")))?;
+
+    let mut issues = lint(&policy_file);
+    let chain_id = if against_session {
+        let session = Session::load(config, formatter, account)?;
+        formatter.info(&format!("Checking contracts on {}...", session.chain_name));
+        check_onchain(
+            &session.provider()?,
+            &session.chain_name,
+            &policy_file,
+            &mut issues,
+        )
+        .await;
+        Some(session.chain_name)
+    } else {
+        None
+    };
+
+    let errors = issues.iter().filter(|i| i.severity == "error").count();
+    formatter.success(&ValidateOutput {
+        file: file.clone(),
+        valid: errors == 0,
+        contracts: policy_file.contracts.len(),
+        methods: policy_file
+            .contracts
+            .values()
+            .map(|c| c.methods.len())
+            .sum(),
+        chain_id,
+        issues,
+    });
+    if errors > 0 {
+        return Err(CliError::InvalidInput(format!(
+            "{errors} problem(s) in {file}; see 'issues' in the output"
+        )));
+    }
+    Ok(())
+}
+
+fn find_contract<'a>(
+    contracts: &'a HashMap<String, ContractPolicy>,
+    address: &str,
+) -> Option<&'a ContractPolicy> {
+    let felt = Felt::from_hex(address).ok()?;
+    contracts
+        .iter()
+        .find(|(other, _)| Felt::from_hex(other).ok() == Some(felt))
+        .map(|(_, contract)| contract)
+}
+
+/// Compare requested policies with the session's. Addresses compare as felts, methods by
+/// entrypoint.
+pub fn diff(requested: &PolicyFile, session: &PolicyStorage) -> PolicyDiff {
+    let method_ref = |contract: &str, entrypoint: &str| MethodRef {
+        contract: contract.to_string(),
+        entrypoint: entrypoint.to_string(),
+    };
+
+    let mut diff = PolicyDiff::default();
+    for (address, contract) in &requested.contracts {
+        let granted = find_contract(&session.contracts, address);
+        if granted.is_none() {
+            diff.added_contracts.push(address.clone());
+        }
+        for method in &contract.methods {
+            match granted.and_then(|g| g.methods.iter().find(|m| m.entrypoint == method.entrypoint))
+            {
+                None => diff
+                    .added_methods
+                    .push(method_ref(address, &method.entrypoint)),
+                Some(m) if m.authorized != method.authorized || m.amount != method.amount => {
+                    diff.changed_methods
+                        .push(method_ref(address, &method.entrypoint));
+                }
+                Some(_) => {}
+            }
+        }
+    }
+    for (address, contract) in &session.contracts {
+        let wanted = find_contract(&requested.contracts, address);
+        if wanted.is_none() {
+            diff.removed_contracts.push(address.clone());
+        }
+        for method in &contract.methods {
+            if !wanted.is_some_and(|w| w.methods.iter().any(|m| m.entrypoint == method.entrypoint))
+            {
+                diff.removed_methods
+                    .push(method_ref(address, &method.entrypoint));
+            }
+        }
+    }
+
+    let requested_messages = requested.messages.as_deref().unwrap_or_default();
+    let session_messages = session.messages.as_deref().unwrap_or_default();
+    diff.added_messages = requested_messages
+        .iter()
+        .filter(|m| !session_messages.contains(m))
+        .count();
+    diff.removed_messages = session_messages
+        .iter()
+        .filter(|m| !requested_messages.contains(m))
+        .count();
+
+    diff.added_contracts.sort();
+    diff.removed_contracts.sort();
+    for methods in [
+        &mut diff.added_methods,
+        &mut diff.removed_methods,
+        &mut diff.changed_methods,
+    ] {
+        methods.sort_by(|a, b| (&a.contract, &a.entrypoint).cmp(&(&b.contract, &b.entrypoint)));
+    }
+    diff.identical = diff.added_methods.is_empty()
+        && diff.removed_methods.is_empty()
+        && diff.changed_methods.is_empty()
+        && diff.added_contracts.is_empty()
+        && diff.removed_contracts.is_empty()
+        && diff.added_messages == 0
+        && diff.removed_messages == 0;
+    diff
+}
+
+/// Compare a policy file and/or presets (resolved for the session's chain) with the policies of
+/// the currently authorized session.
+pub async fn diff_session(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    presets: Vec<String>,
+    file: Option<String>,
+    account: Option<&str>,
+) -> Result<()> {
+    if presets.is_empty() && file.is_none() {
+        return Err(CliError::InvalidInput(
+            "Pass --file and/or --preset to compare with the session".to_string(),
+        ));
+    }
+    let session = Session::load(config, formatter, account)?;
+    let requested = authorize::resolve_policies(
+        config,
+        formatter,
+        &presets,
+        file.as_deref(),
+        None,
+        &session.rpc_url,
+    )
+    .await?;
+
+    let diff = diff(&requested, &session.policies()?);
+    if diff.identical {
+        formatter.info("The policies match the session");
+    }
+    formatter.success(&DiffOutput {
+        address: format!("0x{:x}", session.address),
+        chain_id: session.chain_name,
+        diff,
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::session::authorize::MethodPolicy;
+
+    fn contract(methods: &[&str]) -> ContractPolicy {
+        ContractPolicy {
+            name: None,
+            methods: methods
+                .iter()
+                .map(|entrypoint| MethodPolicy {
+                    name: entrypoint.to_string(),
+                    entrypoint: entrypoint.to_string(),
+                    description: None,
+                    amount: None,
+                    authorized: true,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn lints_and_diffs_policies() {
+        let file = PolicyFile {
+            contracts: HashMap::from([
+                (
+                    "0x0abc".to_string(),
+                    contract(&["move", "move", "bad name"]),
+                ),
+                ("0xABC".to_string(), contract(&["attack"])),
+                ("0xnope".to_string(), contract(&["selector:0x1"])),
+            ]),
+            messages: None,
+        };
+        let messages: Vec<(String, Option<String>)> = lint(&file)
+            .into_iter()
+            .map(|i| (i.message, i.entrypoint))
+            .collect();
+        assert!(messages.contains(&("Duplicate entrypoint".to_string(), Some("move".to_string()))));
+        assert!(messages.contains(&(
+            "Not a valid entrypoint name".to_string(),
+            Some("bad name".to_string())
+        )));
+        assert!(messages
+            .iter()
+            .any(|(m, _)| m.starts_with("Same contract as")));
+        assert!(messages
+            .iter()
+            .any(|(m, _)| m.starts_with("Invalid contract address")));
+        assert!(messages.iter().any(|(m, _)| m.contains("raw selector")));
+
+        let requested = PolicyFile {
+            contracts: HashMap::from([
+                ("0x0abc".to_string(), contract(&["move", "attack"])),
+                ("0x2".to_string(), contract(&["claim"])),
+            ]),
+            messages: None,
+        };
+        let session = PolicyStorage {
+            contracts: HashMap::from([
+                ("0xabc".to_string(), contract(&["move", "flee"])),
+                ("0x3".to_string(), contract(&["sell"])),
+            ]),
+            messages: None,
+        };
+        let diff = diff(&requested, &session);
+        let refs = |methods: &[MethodRef]| -> Vec<String> {
+            methods
+                .iter()
+                .map(|m| format!("{}:{}", m.contract, m.entrypoint))
+                .collect()
+        };
+        assert_eq!(diff.added_contracts, vec!["0x2"]);
+        assert_eq!(diff.removed_contracts, vec!["0x3"]);
+        assert_eq!(
+            refs(&diff.added_methods),
+            vec!["0x0abc:attack", "0x2:claim"]
+        );
+        assert_eq!(refs(&diff.removed_methods), vec!["0x3:sell", "0xabc:flee"]);
+        assert!(diff.changed_methods.is_empty());
+        assert!(!diff.identical);
+    }
+}
@@ -0,0 +1,356 @@
+use crate::{
+    commands::session::authorize::{self, PolicyFile, PolicyReview},
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+    presets::{self, ChainConfig, ContractConfig, MethodConfig, PoliciesConfig, PresetConfig},
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize)]
+pub struct ScaffoldOutput {
+    pub name: String,
+    pub path: String,
+    pub chains: Vec<String>,
+    pub contracts: usize,
+    /// Fields filled with placeholders that should be edited before opening a PR
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub todo: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct PresetListOutput {
+    pub presets: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct PresetShowOutput {
+    pub preset: String,
+    pub origin: Vec<String>,
+    pub chains: Vec<PresetChain>,
+}
+
+#[derive(Serialize)]
+pub struct PresetChain {
+    pub chain_id: String,
+    /// What `session auth --preset` grants on this chain, sorted as it will be hashed
+    #[serde(flatten)]
+    pub policies: PolicyReview,
+}
+
+/// List the presets published in cartridge-gg/presets
+pub async fn list(config: &Config, formatter: &dyn OutputFormatter) -> Result<()> {
+    let presets = presets::list_presets(config, formatter).await?;
+
+    if config.cli.json_output {
+        formatter.success(&PresetListOutput { presets });
+    } else {
+        for preset in &presets {
+            println!("{preset}");
+        }
+        formatter.info("Run 'controller presets show <name>' for the policies it authorizes.");
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+pub struct PresetRefreshOutput {
+    pub refreshed: Vec<String>,
+}
+
+/// Re-download the preset index and the named presets (every cached one by default)
+pub async fn refresh(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    names: Vec<String>,
+) -> Result<()> {
+    if config.cli.offline {
+        return Err(CliError::InvalidInput(
+            "'presets refresh' needs the network; drop --offline".to_string(),
+        ));
+    }
+    let refreshed = presets::refresh(config, formatter, names).await?;
+    formatter.success(&PresetRefreshOutput { refreshed });
+    Ok(())
+}
+
+/// Show the contracts and methods a preset authorizes, with each method's selector and risk,
+/// on every chain it supports or only `chain_id`.
+pub async fn show(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    name: String,
+    chain_id: Option<String>,
+) -> Result<()> {
+    let preset = presets::fetch_preset(config, formatter, &name).await?;
+    formatter.success(&preset_chains(name, preset, chain_id.as_deref())?);
+    Ok(())
+}
+
+fn preset_chains(
+    name: String,
+    preset: PresetConfig,
+    chain_id: Option<&str>,
+) -> Result<PresetShowOutput> {
+    if let Some(chain_id) = chain_id {
+        // Fails listing the supported chains
+        presets::extract_chain_policies(&preset, chain_id, &name)?;
+    }
+    let mut chains = preset
+        .chains
+        .into_iter()
+        .filter(|(id, _)| chain_id.is_none_or(|wanted| wanted == id))
+        .map(|(chain_id, chain)| {
+            let policy_file = authorize::preset_policy_file(chain.policies);
+            Ok(PresetChain {
+                chain_id,
+                policies: authorize::review_policies(&policy_file)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    chains.sort_by(|a, b| a.chain_id.cmp(&b.chain_id));
+
+    Ok(PresetShowOutput {
+        preset: name,
+        origin: preset.origin,
+        chains,
+    })
+}
+
+/// Generate `configs/<name>/config.json` in the cartridge-gg/presets layout from a local policy
+/// file, ready to be copied into a checkout of the presets repository.
+pub async fn scaffold(
+    formatter: &dyn OutputFormatter,
+    from_policy: String,
+    name: String,
+    chains: Vec<String>,
+    origins: Vec<String>,
+    out: PathBuf,
+) -> Result<()> {
+    validate_preset_name(&name)?;
+
+    let content = std::fs::read_to_string(&from_policy).map_err(|e| CliError::FileError {
+        path: from_policy.clone(),
+        message: e.to_string(),
+    })?;
+    let policy_file: PolicyFile =
+        crate::commands::file_format::parse_structured(&from_policy, &content)
+            .map_err(|e| CliError::InvalidInput(format!("Invalid policy file format: {e}")))?;
+    if policy_file.contracts.is_empty() {
+        return Err(CliError::InvalidInput(format!(
+            "Policy file '{from_policy}' has no contracts"
+        )));
+    }
+
+    let (preset, todo) = build_preset(policy_file, &chains, origins);
+    let dir = out.join("configs").join(&name);
+    let path = dir.join("config.json");
+    if path.exists() {
+        return Err(CliError::InvalidInput(format!(
+            "{} already exists",
+            path.display()
+        )));
+    }
+    write_preset(&dir, &path, &preset)?;
+
+    for field in &todo {
+        formatter.warning(&format!(
+            "{field} is a placeholder; edit it before submitting"
+        ));
+    }
+    formatter.success(&ScaffoldOutput {
+        name,
+        path: path.display().to_string(),
+        chains,
+        contracts: preset
+            .chains
+            .values()
+            .next()
+            .map(|chain| chain.policies.contracts.len())
+            .unwrap_or_default(),
+        todo,
+    });
+    Ok(())
+}
+
+/// Preset directory names are kebab-case, like the existing configs
+fn validate_preset_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('-')
+        && !name.ends_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(CliError::InvalidInput(format!(
+            "Invalid preset name '{name}': use lowercase letters, digits and dashes (e.g. 'my-game')"
+        )))
+    }
+}
+
+/// Convert a policy file into a preset with the same policies on every chain. Returns the
+/// preset and the fields that had to be filled with placeholders.
+fn build_preset(
+    policy_file: PolicyFile,
+    chains: &[String],
+    origins: Vec<String>,
+) -> (PresetConfig, Vec<String>) {
+    let mut todo = Vec::new();
+    let mut addresses: Vec<&String> = policy_file.contracts.keys().collect();
+    addresses.sort();
+
+    let mut contracts = HashMap::new();
+    for address in addresses {
+        let policy = &policy_file.contracts[address];
+        let name = policy.name.clone().unwrap_or_else(|| {
+            todo.push(format!("name of contract {address}"));
+            address.clone()
+        });
+        let methods = policy
+            .methods
+            .iter()
+            .filter(|m| m.authorized)
+            .map(|m| MethodConfig {
+                name: m.name.clone(),
+                entrypoint: m.entrypoint.clone(),
+                description: m.description.clone().or_else(|| {
+                    todo.push(format!("description of {address}.{}", m.entrypoint));
+                    Some(format!("TODO: describe {}", m.entrypoint))
+                }),
+            })
+            .collect();
+        contracts.insert(
+            address.clone(),
+            ContractConfig {
+                name,
+                description: None,
+                methods,
+            },
+        );
+    }
+
+    if origins.is_empty() {
+        todo.push("origin".to_string());
+    }
+    let origin = if origins.is_empty() {
+        vec!["example.com".to_string()]
+    } else {
+        origins
+    };
+
+    let policies = PoliciesConfig {
+        contracts,
+        messages: policy_file.messages,
+    };
+    let preset = PresetConfig {
+        origin,
+        chains: chains
+            .iter()
+            .map(|chain| {
+                (
+                    chain.clone(),
+                    ChainConfig {
+                        policies: policies.clone(),
+                    },
+                )
+            })
+            .collect(),
+        theme: None,
+    };
+    (preset, todo)
+}
+
+fn write_preset(dir: &Path, path: &Path, preset: &PresetConfig) -> Result<()> {
+    std::fs::create_dir_all(dir).map_err(|e| CliError::FileError {
+        path: dir.display().to_string(),
+        message: e.to_string(),
+    })?;
+    let json = serde_json::to_string_pretty(preset)
+        .map_err(|e| CliError::InvalidInput(format!("Failed to serialize preset: {e}")))?;
+    std::fs::write(path, format!("{json}\n")).map_err(|e| CliError::FileError {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_preset_and_flags_placeholders() {
+        let policy_file: PolicyFile = serde_json::from_value(serde_json::json!({
+            "contracts": {
+                "0x123": {
+                    "name": "Game",
+                    "methods": [
+                        { "name": "Spawn", "entrypoint": "spawn", "description": "Start a run" },
+                        { "name": "Move", "entrypoint": "move" },
+                        { "name": "Admin", "entrypoint": "admin", "authorized": false }
+                    ]
+                },
+                "0x456": { "methods": [{ "name": "Claim", "entrypoint": "claim", "description": "Claim" }] }
+            }
+        }))
+        .unwrap();
+
+        let (preset, todo) = build_preset(
+            policy_file,
+            &["SN_MAIN".to_string(), "SN_SEPOLIA".to_string()],
+            vec!["my-game.xyz".to_string()],
+        );
+        assert_eq!(preset.origin, vec!["my-game.xyz"]);
+        assert_eq!(preset.chains.len(), 2);
+        let game = &preset.chains["SN_MAIN"].policies.contracts["0x123"];
+        assert_eq!(game.methods.len(), 2);
+        assert_eq!(
+            todo,
+            vec!["description of 0x123.move", "name of contract 0x456"]
+        );
+
+        assert!(validate_preset_name("my-game").is_ok());
+        assert!(validate_preset_name("My Game").is_err());
+        assert!(validate_preset_name("-game").is_err());
+    }
+
+    #[test]
+    fn shows_preset_policies_per_chain() {
+        let preset: PresetConfig = serde_json::from_value(serde_json::json!({
+            "origin": ["game.example"],
+            "chains": {
+                "SN_SEPOLIA": { "policies": { "contracts": {
+                    "0x2": { "name": "Game", "methods": [{ "name": "Start", "entrypoint": "start" }] }
+                } } },
+                "SN_MAIN": { "policies": { "contracts": {
+                    "0x1": { "name": "Token", "methods": [
+                        { "name": "Transfer", "entrypoint": "transfer" },
+                        { "name": "Approve", "entrypoint": "approve" }
+                    ] }
+                } } }
+            }
+        }))
+        .unwrap();
+        let shown = preset_chains("game".to_string(), preset, None).unwrap();
+        let chains: Vec<&str> = shown.chains.iter().map(|c| c.chain_id.as_str()).collect();
+        assert_eq!(chains, vec!["SN_MAIN", "SN_SEPOLIA"]);
+        let methods: Vec<&str> = shown.chains[0].policies.contracts[0]
+            .methods
+            .iter()
+            .map(|m| m.entrypoint.as_str())
+            .collect();
+        assert_eq!(methods, vec!["approve", "transfer"]);
+        assert!(shown.chains[0].policies.contracts[0].methods[0]
+            .risk
+            .is_high());
+
+        let preset: PresetConfig = serde_json::from_value(serde_json::json!({
+            "origin": [], "chains": { "SN_MAIN": { "policies": { "contracts": {} } } }
+        }))
+        .unwrap();
+        assert!(preset_chains("game".to_string(), preset, Some("SN_SEPOLIA")).is_err());
+    }
+}
@@ -0,0 +1,809 @@
+use crate::{
+    commands::execute::{CallSpec, Execution},
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use serde::{Deserialize, Serialize};
+use starknet::core::types::{ExecutionResult, Felt};
+use starknet::providers::{JsonRpcClient, Provider};
+use std::path::{Path, PathBuf};
+
+/// Queued executions and their results, relative to the account's storage path
+const QUEUE_FILE: &str = "execution_queue.json";
+/// Held while `queue run` processes the queue, so two runs never submit the same entry
+const QUEUE_LOCK_FILE: &str = "queue.lock";
+/// Finished executions kept in the queue file as history
+const MAX_FINISHED: usize = 50;
+/// Delay before the first retry, doubled after each further failure
+const RETRY_BASE_SECS: u64 = 10;
+
+/// An execution waiting in the queue, and what happened when it ran.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedExecution {
+    pub id: String,
+    pub calls: Vec<CallSpec>,
+    /// Higher runs first; ties run in the order they were added
+    #[serde(default)]
+    pub priority: i64,
+    /// IDs of executions that must be confirmed before this one runs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub after: Vec<String>,
+    pub created_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rpc_url: Option<String>,
+    #[serde(default)]
+    pub no_paymaster: bool,
+    pub max_attempts: u32,
+    #[serde(default)]
+    pub attempts: u32,
+    /// Earliest time of the next attempt after a failed one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_at: Option<u64>,
+    /// 'pending', 'confirmed', 'failed', 'skipped' (a dependency failed) or 'invalidated'
+    /// (confirmed, then reverted or dropped by a reorg)
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct QueuedOutput {
+    pub id: String,
+    pub priority: i64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub after: Vec<String>,
+    pub calls: usize,
+    /// Executions pending in the queue, including this one
+    pub pending: usize,
+}
+
+#[derive(Serialize)]
+pub struct QueueListOutput {
+    pub executions: Vec<QueuedExecution>,
+}
+
+#[derive(Serialize)]
+pub struct QueueRunOutput {
+    /// Executions attempted in this run, with their results
+    pub ran: Vec<QueuedExecution>,
+    /// Executions still pending (retrying later or waiting on a dependency)
+    pub pending: usize,
+}
+
+#[derive(Serialize)]
+pub struct QueueVerifyOutput {
+    /// Recorded transactions looked up, from the queue and the audit log
+    pub checked: usize,
+    /// Executions whose transaction is no longer found, or now reverted
+    pub invalidated: Vec<QueuedExecution>,
+    /// Failed executions whose transaction turned out to be confirmed, now marked confirmed
+    pub landed: Vec<String>,
+    /// Audit log submissions whose last recorded result does not match the chain
+    pub audit: Vec<AuditMismatch>,
+    /// Pending executions skipped because a dependency did not confirm
+    pub skipped: Vec<String>,
+    /// Confirmed executions that ran after an invalidated dependency, to be re-evaluated
+    pub dependents: Vec<String>,
+}
+
+/// An audited transaction the chain disagrees with.
+#[derive(Serialize)]
+pub struct AuditMismatch {
+    pub transaction_hash: String,
+    pub command: String,
+    /// Last result in the audit log: 'submitted', 'confirmed' or 'reverted'
+    pub recorded: String,
+    /// 'confirmed', 'reverted' or 'missing'
+    pub onchain: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+pub fn load_queue(storage_path: &Path) -> Vec<QueuedExecution> {
+    std::fs::read_to_string(storage_path.join(QUEUE_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_queue(storage_path: &Path, queue: &[QueuedExecution]) -> Result<()> {
+    let path = storage_path.join(QUEUE_FILE);
+    let content = serde_json::to_string_pretty(queue)
+        .map_err(|e| CliError::Storage(format!("Failed to serialize the queue: {e}")))?;
+    super::storage::write_atomic(&path, content).map_err(|e| CliError::FileError {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    })
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Add calls to the account's queue. Nothing is submitted until `controller queue run`.
+#[allow(clippy::too_many_arguments)]
+pub async fn add(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    calls: Vec<CallSpec>,
+    id: Option<String>,
+    priority: i64,
+    after: Vec<String>,
+    max_attempts: u32,
+    chain_id: Option<String>,
+    rpc_url: Option<String>,
+    no_paymaster: bool,
+    account: Option<&str>,
+) -> Result<()> {
+    if max_attempts == 0 {
+        return Err(CliError::InvalidInput(
+            "--max-attempts must be at least 1".to_string(),
+        ));
+    }
+    let storage_path = config.resolve_storage_path(account);
+    let mut queue = load_queue(&storage_path);
+
+    let created_at = now();
+    let id = id.unwrap_or_else(|| format!("{created_at:x}-{}", std::process::id()));
+    if queue.iter().any(|e| e.id == id) {
+        return Err(CliError::InvalidInput(format!(
+            "The queue already has an execution '{id}'"
+        )));
+    }
+    if let Some(missing) = after
+        .iter()
+        .find(|dep| !queue.iter().any(|e| &e.id == *dep))
+    {
+        return Err(CliError::InvalidInput(format!(
+            "--after '{missing}' is not in the queue. Add it first; 'controller queue list' shows IDs"
+        )));
+    }
+
+    queue.push(QueuedExecution {
+        id: id.clone(),
+        calls,
+        priority,
+        after: after.clone(),
+        created_at,
+        chain_id,
+        rpc_url,
+        no_paymaster,
+        max_attempts,
+        attempts: 0,
+        retry_at: None,
+        status: "pending".to_string(),
+        transaction_hash: None,
+        error: None,
+        finished_at: None,
+    });
+    let output = QueuedOutput {
+        id,
+        priority,
+        after,
+        calls: queue.last().map(|e| e.calls.len()).unwrap_or_default(),
+        pending: queue.iter().filter(|e| e.status == "pending").count(),
+    };
+    save_queue(&storage_path, &queue)?;
+
+    formatter.success(&output);
+    Ok(())
+}
+
+/// Show the queue in the order `queue run` will take it, then finished executions.
+pub async fn list(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    account: Option<&str>,
+) -> Result<()> {
+    let mut executions = load_queue(&config.resolve_storage_path(account));
+    // Stable sort: pending first by priority, history after in the order it happened
+    executions.sort_by_key(|e| {
+        (
+            e.status != "pending",
+            if e.status == "pending" {
+                -e.priority
+            } else {
+                0
+            },
+        )
+    });
+    if executions.is_empty() {
+        formatter.info("The queue is empty");
+    }
+    formatter.success(&QueueListOutput { executions });
+    Ok(())
+}
+
+/// Held for the duration of `queue run`
+struct QueueLock(PathBuf);
+
+impl QueueLock {
+    fn acquire(storage_path: &Path) -> Result<Self> {
+        let path = storage_path.join(QUEUE_LOCK_FILE);
+        std::fs::create_dir_all(storage_path).map_err(|e| CliError::FileError {
+            path: storage_path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        match std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Ok(_) => Ok(Self(path)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                Err(CliError::InvalidInput(format!(
+                    "Another 'queue run' is processing this queue. If none is running, delete {}",
+                    path.display()
+                )))
+            }
+            Err(e) => Err(CliError::FileError {
+                path: path.display().to_string(),
+                message: e.to_string(),
+            }),
+        }
+    }
+}
+
+impl Drop for QueueLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Submit runnable executions one at a time, highest priority first, waiting for each to be
+/// confirmed. Failures before anything was submitted, and reverts, are retried with backoff up
+/// to each execution's attempt limit; executions depending on one that failed are skipped.
+/// Stops when nothing is runnable, or after `max` executions.
+pub async fn run(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    max: Option<usize>,
+    account: Option<&str>,
+) -> Result<()> {
+    let storage_path = config.resolve_storage_path(account);
+    let _lock = QueueLock::acquire(&storage_path)?;
+    let mut queue = load_queue(&storage_path);
+
+    let mut ran: Vec<usize> = Vec::new();
+    loop {
+        skip_blocked(&mut queue, now());
+        if max.is_some_and(|max| ran.len() >= max) {
+            break;
+        }
+        let Some(i) = next_runnable(&queue, now()) else {
+            break;
+        };
+        let execution = queue[i].clone();
+        formatter.info(&format!(
+            "Running queued execution {} ({} call(s), attempt {}/{})...",
+            execution.id,
+            execution.calls.len(),
+            execution.attempts + 1,
+            execution.max_attempts
+        ));
+        let outcome = submit(config, formatter, &execution, account).await;
+
+        let entry = &mut queue[i];
+        entry.attempts += 1;
+        match outcome {
+            Outcome::Confirmed(hash) => {
+                entry.status = "confirmed".to_string();
+                entry.transaction_hash = Some(hash);
+                entry.error = None;
+                entry.finished_at = Some(now());
+            }
+            Outcome::Retryable(hash, error) => {
+                formatter.warning(&format!("{}: {error}", entry.id));
+                entry.transaction_hash = hash.or(entry.transaction_hash.take());
+                entry.error = Some(error);
+                if entry.attempts >= entry.max_attempts {
+                    entry.status = "failed".to_string();
+                    entry.finished_at = Some(now());
+                } else {
+                    entry.retry_at = Some(now() + retry_delay(entry.attempts));
+                }
+            }
+            Outcome::Failed(hash, error) => {
+                entry.transaction_hash = hash;
+                entry.error = Some(error);
+                entry.status = "failed".to_string();
+                entry.finished_at = Some(now());
+            }
+        }
+        if !ran.contains(&i) {
+            ran.push(i);
+        }
+        // Saved after each attempt so an interrupted run doesn't submit twice
+        save_queue(&storage_path, &queue)?;
+    }
+
+    let ran: Vec<QueuedExecution> = ran.iter().map(|&i| queue[i].clone()).collect();
+    let pending = queue.iter().filter(|e| e.status == "pending").count();
+    save_queue(&storage_path, &prune(queue))?;
+
+    let failed = ran.iter().filter(|e| e.status == "failed").count();
+    formatter.success(&QueueRunOutput { ran, pending });
+    if failed > 0 {
+        return Err(CliError::TransactionFailed(format!(
+            "{failed} queued execution(s) failed; see 'error' in the output"
+        )));
+    }
+    Ok(())
+}
+
+/// Re-check every recorded transaction against the chain: the queue's and the account's audit
+/// log entries. A confirmed execution whose transaction is gone (a reorg) or now reverted is
+/// marked 'invalidated' with a warning; pending executions depending on it are skipped, and
+/// confirmed ones that ran after it are reported for re-evaluation. A failed execution whose
+/// transaction did land is marked confirmed. Audited submissions whose last recorded result
+/// differs from the chain are reported. Lookups that fail for other reasons change nothing.
+pub async fn verify(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    account: Option<&str>,
+) -> Result<()> {
+    let storage_path = config.resolve_storage_path(account);
+    let _lock = QueueLock::acquire(&storage_path)?;
+    let mut queue = load_queue(&storage_path);
+
+    let mut checked: Vec<Felt> = Vec::new();
+    let mut invalidated: Vec<usize> = Vec::new();
+    let mut landed: Vec<String> = Vec::new();
+    for i in 0..queue.len() {
+        let entry = &queue[i];
+        let Some(hash) = entry.transaction_hash.clone() else {
+            continue;
+        };
+        let state = onchain_state(
+            config,
+            formatter,
+            entry.chain_id.clone(),
+            entry.rpc_url.clone(),
+            &hash,
+            account,
+        )
+        .await;
+        if let Ok(felt) = Felt::from_hex(&hash) {
+            checked.push(felt);
+        }
+        let error = match (entry.status.as_str(), state) {
+            (_, Err(e)) => {
+                formatter.warning(&format!("{}: could not verify {hash}: {e}", entry.id));
+                continue;
+            }
+            ("failed", Ok(Onchain::Succeeded)) => {
+                formatter.warning(&format!(
+                    "{} ({hash}): recorded as failed, but the transaction was confirmed",
+                    entry.id
+                ));
+                landed.push(entry.id.clone());
+                let entry = &mut queue[i];
+                entry.status = "confirmed".to_string();
+                entry.error = None;
+                continue;
+            }
+            ("confirmed", Ok(Onchain::Reverted(reason))) => {
+                format!("Reverted after confirmation: {reason}")
+            }
+            ("confirmed", Ok(Onchain::Missing)) => {
+                "Transaction no longer found on chain (dropped by a reorg?)".to_string()
+            }
+            _ => continue,
+        };
+        formatter.warning(&format!("{} ({hash}): {error}", entry.id));
+        let entry = &mut queue[i];
+        entry.status = "invalidated".to_string();
+        entry.error = Some(error);
+        entry.finished_at = Some(now());
+        invalidated.push(i);
+    }
+
+    let mut audit = Vec::new();
+    for entry in audited_transactions(config, account)? {
+        let Some(hash) = entry.transaction_hash.clone() else {
+            continue;
+        };
+        let Ok(felt) = Felt::from_hex(&hash) else {
+            continue;
+        };
+        if checked.contains(&felt) {
+            continue;
+        }
+        let state = onchain_state(
+            config,
+            formatter,
+            Some(entry.chain_id.clone()),
+            None,
+            &hash,
+            account,
+        )
+        .await;
+        let state = match state {
+            Ok(state) => state,
+            Err(e) => {
+                formatter.warning(&format!("{}: could not verify {hash}: {e}", entry.command));
+                continue;
+            }
+        };
+        checked.push(felt);
+        if let Some(mismatch) = audit_mismatch(&entry, &hash, state) {
+            formatter.warning(&format!(
+                "{} ({hash}): audit log says {}, chain says {}",
+                mismatch.command, mismatch.recorded, mismatch.onchain
+            ));
+            audit.push(mismatch);
+        }
+    }
+
+    let before: Vec<String> = queue.iter().map(|e| e.status.clone()).collect();
+    skip_blocked(&mut queue, now());
+    let skipped: Vec<String> = queue
+        .iter()
+        .zip(&before)
+        .filter(|(e, status)| e.status != **status)
+        .map(|(e, _)| e.id.clone())
+        .collect();
+    let invalid_ids: Vec<String> = invalidated.iter().map(|&i| queue[i].id.clone()).collect();
+    let dependents = confirmed_dependents(&queue, &invalid_ids);
+    if !dependents.is_empty() {
+        formatter.warning(&format!(
+            "Confirmed after an invalidated dependency, re-check their effects: {}",
+            dependents.join(", ")
+        ));
+    }
+
+    let invalidated: Vec<QueuedExecution> = invalidated.iter().map(|&i| queue[i].clone()).collect();
+    save_queue(&storage_path, &queue)?;
+
+    formatter.success(&QueueVerifyOutput {
+        checked: checked.len(),
+        invalidated,
+        landed,
+        audit,
+        skipped,
+        dependents,
+    });
+    Ok(())
+}
+
+/// The account's audited transactions, one per hash with its last recorded result
+fn audited_transactions(
+    config: &Config,
+    account: Option<&str>,
+) -> Result<Vec<crate::commands::audit::AuditEntry>> {
+    use crate::commands::audit;
+
+    let mut latest: Vec<audit::AuditEntry> = Vec::new();
+    for entry in audit::load(&config.resolve_storage_path(None).join(audit::AUDIT_FILE))? {
+        if entry.account.as_deref() != account || entry.transaction_hash.is_none() {
+            continue;
+        }
+        match latest
+            .iter_mut()
+            .find(|e| e.transaction_hash == entry.transaction_hash)
+        {
+            Some(previous) => *previous = entry,
+            None => latest.push(entry),
+        }
+    }
+    Ok(latest)
+}
+
+/// How an audited transaction's recorded result differs from the chain, if it does
+fn audit_mismatch(
+    entry: &crate::commands::audit::AuditEntry,
+    hash: &str,
+    state: Onchain,
+) -> Option<AuditMismatch> {
+    let (onchain, error) = match state {
+        Onchain::Succeeded => ("confirmed", None),
+        Onchain::Reverted(reason) => ("reverted", Some(reason)),
+        Onchain::Missing => ("missing", None),
+    };
+    (entry.result != onchain).then(|| AuditMismatch {
+        transaction_hash: hash.to_string(),
+        command: entry.command.clone(),
+        recorded: entry.result.clone(),
+        onchain: onchain.to_string(),
+        error,
+    })
+}
+
+/// Confirmed executions depending, directly or through other executions, on one of `ids`
+fn confirmed_dependents(queue: &[QueuedExecution], ids: &[String]) -> Vec<String> {
+    let mut affected: Vec<String> = ids.to_vec();
+    let mut dependents = Vec::new();
+    loop {
+        let next = queue.iter().find(|e| {
+            !affected.contains(&e.id) && e.after.iter().any(|dep| affected.contains(dep))
+        });
+        let Some(next) = next else {
+            return dependents;
+        };
+        affected.push(next.id.clone());
+        if next.status == "confirmed" {
+            dependents.push(next.id.clone());
+        }
+    }
+}
+
+enum Outcome {
+    Confirmed(String),
+    /// Nothing changed on chain (not submitted, or reverted), so running it again is safe
+    Retryable(Option<String>, String),
+    /// Submitted but not known to be confirmed; retrying could submit it twice
+    Failed(Option<String>, String),
+}
+
+async fn submit(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    queued: &QueuedExecution,
+    account: Option<&str>,
+) -> Outcome {
+    let execution = crate::commands::execute::execute_calls(
+        config,
+        formatter,
+        queued.calls.clone(),
+        crate::commands::execute::ExecuteOptions {
+            wait: true,
+            chain_id: queued.chain_id.clone(),
+            rpc_url: queued.rpc_url.clone(),
+            no_paymaster: queued.no_paymaster,
+            ..Default::default()
+        },
+        account,
+    )
+    .await;
+
+    match execution {
+        Err(e) => Outcome::Retryable(None, e.to_string()),
+        Ok(Execution::Submitted {
+            output,
+            confirmation: Err(e),
+        }) => Outcome::Failed(Some(output.transaction_hash), e.to_string()),
+        Ok(Execution::Submitted { output, .. }) => {
+            let hash = output.transaction_hash;
+            match revert_reason(
+                config,
+                formatter,
+                queued.chain_id.clone(),
+                queued.rpc_url.clone(),
+                &hash,
+                account,
+            )
+            .await
+            {
+                Ok(None) => Outcome::Confirmed(hash),
+                Ok(Some(reason)) => Outcome::Retryable(Some(hash), format!("Reverted: {reason}")),
+                Err(e) => Outcome::Failed(Some(hash), e.to_string()),
+            }
+        }
+        Ok(Execution::PendingIntent(intent)) => Outcome::Failed(
+            None,
+            format!("Requires a second approval (intent {})", intent.intent_id),
+        ),
+        Ok(Execution::Planned(_) | Execution::Simulated(_)) => {
+            Outcome::Failed(None, "Not submitted".to_string())
+        }
+    }
+}
+
+enum Onchain {
+    Succeeded,
+    Reverted(String),
+    Missing,
+}
+
+/// What the chain currently says about a recorded transaction
+async fn onchain_state(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    chain_id: Option<String>,
+    rpc_url: Option<String>,
+    hash: &str,
+    account: Option<&str>,
+) -> Result<Onchain> {
+    match revert_reason(config, formatter, chain_id, rpc_url, hash, account).await {
+        Ok(None) => Ok(Onchain::Succeeded),
+        Ok(Some(reason)) => Ok(Onchain::Reverted(reason)),
+        Err(CliError::NotFoundError(_)) => Ok(Onchain::Missing),
+        Err(e) => Err(e),
+    }
+}
+
+/// The revert reason of a confirmed transaction, if it reverted
+async fn revert_reason(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    chain_id: Option<String>,
+    rpc_url: Option<String>,
+    hash: &str,
+    account: Option<&str>,
+) -> Result<Option<String>> {
+    let rpc_url = crate::chains::resolve_rpc_url(config, formatter, chain_id, rpc_url, account)?;
+    let url = url::Url::parse(&rpc_url)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
+    let provider = JsonRpcClient::new(crate::api::rpc_transport(url));
+    let hash = Felt::from_hex(hash)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid transaction hash: {e}")))?;
+    let receipt = provider
+        .get_transaction_receipt(hash)
+        .await
+        .map_err(|e| match e {
+            starknet::providers::ProviderError::StarknetError(
+                starknet::core::types::StarknetError::TransactionHashNotFound,
+            ) => CliError::NotFoundError(format!("Transaction 0x{hash:x} not found")),
+            e => CliError::Network(format!("Failed to get the receipt: {e}")),
+        })?;
+    Ok(match receipt.receipt.execution_result() {
+        ExecutionResult::Succeeded => None,
+        ExecutionResult::Reverted { reason } => Some(reason.clone()),
+    })
+}
+
+fn retry_delay(attempts: u32) -> u64 {
+    RETRY_BASE_SECS.saturating_mul(1 << attempts.saturating_sub(1).min(10))
+}
+
+/// The pending execution to run next: its dependencies confirmed and any retry delay over,
+/// highest priority first, then oldest.
+fn next_runnable(queue: &[QueuedExecution], now: u64) -> Option<usize> {
+    let confirmed = |id: &String| queue.iter().any(|e| &e.id == id && e.status == "confirmed");
+    (0..queue.len())
+        .filter(|&i| {
+            let e = &queue[i];
+            e.status == "pending"
+                && e.retry_at.is_none_or(|at| at <= now)
+                && e.after.iter().all(confirmed)
+        })
+        .min_by_key(|&i| (-queue[i].priority, queue[i].created_at, i))
+}
+
+/// Mark pending executions whose dependency failed, was skipped or is gone as skipped.
+fn skip_blocked(queue: &mut [QueuedExecution], now: u64) {
+    loop {
+        let blocked = queue.iter().enumerate().find_map(|(i, e)| {
+            if e.status != "pending" {
+                return None;
+            }
+            e.after
+                .iter()
+                .find(|dep| {
+                    queue.iter().find(|d| &d.id == *dep).is_none_or(|d| {
+                        matches!(d.status.as_str(), "failed" | "skipped" | "invalidated")
+                    })
+                })
+                .map(|dep| (i, dep.clone()))
+        });
+        let Some((i, dep)) = blocked else {
+            return;
+        };
+        queue[i].status = "skipped".to_string();
+        queue[i].error = Some(format!("Dependency '{dep}' did not confirm"));
+        queue[i].finished_at = Some(now);
+    }
+}
+
+/// Keep pending executions and the most recent finished ones.
+fn prune(queue: Vec<QueuedExecution>) -> Vec<QueuedExecution> {
+    let finished = queue.iter().filter(|e| e.status != "pending").count();
+    let mut skip = finished.saturating_sub(MAX_FINISHED);
+    queue
+        .into_iter()
+        .filter(|e| {
+            if e.status != "pending" && skip > 0 {
+                skip -= 1;
+                return false;
+            }
+            true
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn execution(id: &str, priority: i64, after: &[&str]) -> QueuedExecution {
+        QueuedExecution {
+            id: id.to_string(),
+            calls: Vec::new(),
+            priority,
+            after: after.iter().map(|s| s.to_string()).collect(),
+            created_at: 0,
+            chain_id: None,
+            rpc_url: None,
+            no_paymaster: false,
+            max_attempts: 3,
+            attempts: 0,
+            retry_at: None,
+            status: "pending".to_string(),
+            transaction_hash: None,
+            error: None,
+            finished_at: None,
+        }
+    }
+
+    #[test]
+    fn orders_by_priority_and_dependencies() {
+        let mut queue = vec![
+            execution("a", 0, &[]),
+            execution("b", 5, &["a"]),
+            execution("c", 1, &[]),
+        ];
+        // b outranks c but waits for a
+        assert_eq!(next_runnable(&queue, 100), Some(2));
+        queue[2].status = "confirmed".to_string();
+        assert_eq!(next_runnable(&queue, 100), Some(0));
+        queue[0].status = "confirmed".to_string();
+        assert_eq!(next_runnable(&queue, 100), Some(1));
+
+        queue[1].retry_at = Some(200);
+        assert_eq!(next_runnable(&queue, 100), None);
+        assert_eq!(next_runnable(&queue, 200), Some(1));
+
+        let mut queue = vec![
+            execution("a", 0, &[]),
+            execution("b", 0, &["a"]),
+            execution("c", 0, &["b"]),
+        ];
+        queue[0].status = "failed".to_string();
+        skip_blocked(&mut queue, 100);
+        assert_eq!(queue[1].status, "skipped");
+        assert_eq!(queue[2].status, "skipped");
+        assert_eq!(next_runnable(&queue, 100), None);
+
+        let mut queue = vec![
+            execution("a", 0, &[]),
+            execution("b", 0, &["a"]),
+            execution("c", 0, &["b"]),
+            execution("d", 0, &["c"]),
+        ];
+        queue[0].status = "invalidated".to_string();
+        queue[1].status = "confirmed".to_string();
+        queue[2].status = "confirmed".to_string();
+        skip_blocked(&mut queue, 100);
+        assert_eq!(queue[3].status, "skipped");
+        assert_eq!(confirmed_dependents(&queue, &["a".to_string()]), ["b", "c"]);
+
+        assert_eq!(retry_delay(1), RETRY_BASE_SECS);
+        assert_eq!(retry_delay(3), RETRY_BASE_SECS * 4);
+    }
+
+    #[test]
+    fn flags_audited_transactions_the_chain_disagrees_with() {
+        let mut entry = crate::commands::audit::AuditEntry::new(
+            "execute",
+            None,
+            Felt::from(0xabcu64),
+            "SN_SEPOLIA",
+            Vec::new(),
+            Vec::new(),
+            true,
+        );
+        entry.result = "confirmed".to_string();
+        assert!(audit_mismatch(&entry, "0x1", Onchain::Succeeded).is_none());
+        let mismatch = audit_mismatch(&entry, "0x1", Onchain::Missing).unwrap();
+        assert_eq!(mismatch.onchain, "missing");
+        let mismatch = audit_mismatch(&entry, "0x1", Onchain::Reverted("boom".into())).unwrap();
+        assert_eq!(mismatch.error.as_deref(), Some("boom"));
+
+        entry.result = "submitted".to_string();
+        let mismatch = audit_mismatch(&entry, "0x1", Onchain::Succeeded).unwrap();
+        assert_eq!(
+            (mismatch.recorded.as_str(), mismatch.onchain.as_str()),
+            ("submitted", "confirmed")
+        );
+    }
+}
@@ -1,11 +1,13 @@
+use crate::api::RpcTransport;
 use crate::config::Config;
 use crate::error::{CliError, Result};
 use crate::output::OutputFormatter;
 use serde::Serialize;
 use starknet::core::types::Felt;
-use starknet::providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider};
+use starknet::providers::{JsonRpcClient, Provider};
 
 /// Get transaction receipt
+#[allow(clippy::too_many_arguments)]
 pub async fn execute(
     config: &Config,
     formatter: &dyn OutputFormatter,
@@ -14,12 +16,13 @@ pub async fn execute(
     rpc_url: Option<String>,
     wait: bool,
     timeout: u64,
+    account: Option<&str>,
 ) -> Result<()> {
-    let rpc_url = resolve_rpc_url(chain_id, rpc_url, config, formatter)?;
+    let rpc_url = crate::chains::resolve_rpc_url(config, formatter, chain_id, rpc_url, account)?;
 
     let url = url::Url::parse(&rpc_url)
         .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
-    let provider = JsonRpcClient::new(HttpTransport::new(url));
+    let provider = JsonRpcClient::new(crate::api::rpc_transport(url));
 
     let tx_hash = Felt::from_hex(&hash)
         .map_err(|e| CliError::InvalidInput(format!("Invalid transaction hash: {e}")))?;
@@ -64,7 +67,7 @@ pub async fn execute(
 }
 
 async fn get_receipt(
-    provider: &JsonRpcClient<HttpTransport>,
+    provider: &JsonRpcClient<RpcTransport>,
     tx_hash: Felt,
 ) -> Result<Option<ReceiptOutput>> {
     let result = provider.get_transaction_receipt(tx_hash).await;
@@ -120,10 +123,16 @@ async fn get_receipt(
             })
             .collect();
 
+            let selectors = super::selectors::SelectorDb::load();
             let events: Vec<EventOutput> = receipt
                 .events()
                 .iter()
                 .map(|e| EventOutput {
+                    name: e
+                        .keys
+                        .first()
+                        .and_then(|selector| selectors.name(*selector))
+                        .map(str::to_string),
                     from_address: format!("0x{:x}", e.from_address),
                     keys: e.keys.iter().map(|f| format!("0x{f:x}")).collect(),
                     data: e.data.iter().map(|f| format!("0x{f:x}")).collect(),
@@ -219,6 +228,9 @@ struct MessageOutput {
 
 #[derive(Debug, Serialize)]
 struct EventOutput {
+    /// Event name from the selector database, when `keys[0]` is known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
     from_address: String,
     keys: Vec<String>,
     data: Vec<String>,
@@ -230,30 +242,3 @@ struct ExecutionResourcesOutput {
     l1_data_gas: u64,
     l2_gas: u64,
 }
-
-/// Resolve RPC URL from chain_id, explicit rpc_url, or config
-fn resolve_rpc_url(
-    chain_id: Option<String>,
-    rpc_url: Option<String>,
-    config: &Config,
-    formatter: &dyn OutputFormatter,
-) -> Result<String> {
-    if let Some(url) = rpc_url {
-        return Ok(url);
-    }
-
-    if let Some(chain) = chain_id {
-        match chain.as_str() {
-            "SN_MAIN" => Ok("https://api.cartridge.gg/x/starknet/mainnet".to_string()),
-            "SN_SEPOLIA" => Ok("https://api.cartridge.gg/x/starknet/sepolia".to_string()),
-            _ => Err(CliError::InvalidInput(format!(
-                "Unsupported chain ID '{chain}'. Supported chains: SN_MAIN, SN_SEPOLIA"
-            ))),
-        }
-    } else if !config.session.rpc_url.is_empty() {
-        Ok(config.session.rpc_url.clone())
-    } else {
-        formatter.warning("No --chain-id or --rpc-url specified, using SN_SEPOLIA by default");
-        Ok("https://api.cartridge.gg/x/starknet/sepolia".to_string())
-    }
-}
@@ -0,0 +1,159 @@
+use crate::error::{CliError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Call files submitted with `execute --once`, relative to the account's storage path
+pub const REPLAY_FILE: &str = "submitted_call_files.json";
+
+/// A call file submission remembered to refuse identical re-submissions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Submission {
+    pub fingerprint: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub transaction_hash: String,
+    pub submitted_at: u64,
+}
+
+/// Identify a call file for the current session. A file `id` is used as the idempotency key
+/// when present, so regenerated files for the same action still match; otherwise the raw
+/// content is hashed.
+pub fn fingerprint(id: Option<&str>, content: &str, session_guid: &str) -> String {
+    let payload = match id {
+        Some(id) => format!("id:{id}\n{session_guid}"),
+        None => format!("content:{content}\n{session_guid}"),
+    };
+    format!(
+        "0x{:x}",
+        starknet::core::utils::starknet_keccak(payload.as_bytes())
+    )
+}
+
+/// Read the recorded submissions. Unlike the caches, a corrupt record is an error rather than
+/// an empty list: treating it as missing would let `--once` re-submit a call file it was meant
+/// to refuse.
+fn load(storage_path: &Path) -> Result<Vec<Submission>> {
+    let path = storage_path.join(REPLAY_FILE);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(CliError::FileError {
+                path: path.display().to_string(),
+                message: e.to_string(),
+            })
+        }
+    };
+    serde_json::from_str(&content).map_err(|e| CliError::FileError {
+        path: path.display().to_string(),
+        message: format!(
+            "Corrupt submission record, refusing to check --once against it ({e}). \
+             Inspect it against 'controller audit show' before deleting it"
+        ),
+    })
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Refuse a call file already submitted within the last `window` seconds.
+pub fn check(storage_path: &Path, fingerprint: &str, window: u64) -> Result<()> {
+    match find_recent(&load(storage_path)?, fingerprint, window, now()) {
+        Some(previous) => Err(CliError::DuplicateSubmission {
+            transaction_hash: previous.transaction_hash.clone(),
+            submitted_at: chrono::DateTime::from_timestamp(previous.submitted_at as i64, 0)
+                .unwrap_or_else(chrono::Utc::now)
+                .format("%Y-%m-%d %H:%M:%S UTC")
+                .to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+fn find_recent<'a>(
+    submissions: &'a [Submission],
+    fingerprint: &str,
+    window: u64,
+    now: u64,
+) -> Option<&'a Submission> {
+    submissions
+        .iter()
+        .rev()
+        .find(|s| s.fingerprint == fingerprint && now.saturating_sub(s.submitted_at) < window)
+}
+
+/// Remember a submitted call file, dropping records older than the window. Best effort: a
+/// failure to write only loses the protection for the next run, and a corrupt record is left
+/// in place so the next `check` still fails on it.
+pub fn record(
+    storage_path: &Path,
+    fingerprint: String,
+    id: Option<String>,
+    transaction_hash: String,
+    window: u64,
+) {
+    let now = now();
+    let Ok(mut submissions) = load(storage_path) else {
+        return;
+    };
+    submissions.retain(|s| now.saturating_sub(s.submitted_at) < window);
+    submissions.push(Submission {
+        fingerprint,
+        id,
+        transaction_hash,
+        submitted_at: now,
+    });
+    if let Ok(json) = serde_json::to_string_pretty(&submissions) {
+        let _ = super::storage::write_atomic(&storage_path.join(REPLAY_FILE), json);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprints_by_id_or_content_per_session() {
+        let a = fingerprint(None, "{\"calls\":[]}", "0xguid");
+        assert_eq!(a, fingerprint(None, "{\"calls\":[]}", "0xguid"));
+        assert_ne!(a, fingerprint(None, "{\"calls\":[]}", "0xother"));
+        assert_ne!(a, fingerprint(None, "{\"calls\":[1]}", "0xguid"));
+        assert_eq!(
+            fingerprint(Some("turn-7"), "{\"calls\":[]}", "0xguid"),
+            fingerprint(Some("turn-7"), "{\"calls\":[1]}", "0xguid")
+        );
+
+        let submissions = vec![Submission {
+            fingerprint: a.clone(),
+            id: None,
+            transaction_hash: "0x1".to_string(),
+            submitted_at: 1_000,
+        }];
+        assert!(find_recent(&submissions, &a, 3600, 1_500).is_some());
+        assert!(find_recent(&submissions, &a, 3600, 5_000).is_none());
+        assert!(find_recent(&submissions, "0x2", 3600, 1_500).is_none());
+    }
+
+    #[test]
+    fn corrupt_record_fails_the_check() {
+        let dir =
+            std::env::temp_dir().join(format!("controller-replay-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(check(&dir, "0x1", 3600).is_ok());
+
+        std::fs::write(dir.join(REPLAY_FILE), "[{\"fingerprint\":").unwrap();
+        assert!(matches!(
+            check(&dir, "0x1", 3600),
+            Err(CliError::FileError { .. })
+        ));
+        record(&dir, "0x1".to_string(), None, "0xabc".to_string(), 3600);
+        assert!(check(&dir, "0x1", 3600).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
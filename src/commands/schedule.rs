@@ -0,0 +1,412 @@
+use crate::{
+    commands::{
+        execute::{CallSpec, ExecuteOptions},
+        session::authorize::parse_expiration,
+    },
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use account_sdk::storage::{filestorage::FileSystemBackend, StorageBackend};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Scheduled executions and their results, relative to the account's storage path
+const SCHEDULE_FILE: &str = "scheduled_executions.json";
+/// Finished executions kept in the schedule file as history
+const MAX_FINISHED: usize = 50;
+
+/// An `execute` to submit once `run_at` has passed, and what happened when it was.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledExecution {
+    pub id: String,
+    pub calls: Vec<CallSpec>,
+    pub run_at: u64,
+    pub created_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rpc_url: Option<String>,
+    #[serde(default)]
+    pub wait: bool,
+    #[serde(default)]
+    pub no_paymaster: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub only_if: Option<String>,
+    /// Seconds to wait with `wait`
+    #[serde(default = "default_timeout")]
+    pub timeout: u64,
+    #[serde(default)]
+    pub no_queue: bool,
+    /// Tip per unit of L2 gas for self-paid runs, as configured or passed when scheduled
+    #[serde(default)]
+    pub tip: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch_id: Option<String>,
+    #[serde(default)]
+    pub switch_chain: bool,
+    #[serde(default)]
+    pub auto_fix_policies: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub require_events: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wait_for_event: Option<String>,
+    /// 'pending', 'submitted' or 'failed'
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub submitted_at: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ScheduledExecution {
+    pub fn is_due(&self, now: u64) -> bool {
+        self.status == "pending" && now >= self.run_at
+    }
+
+    /// The `execute` options the run was scheduled with
+    fn options(&self) -> ExecuteOptions {
+        ExecuteOptions {
+            wait: self.wait,
+            timeout: self.timeout,
+            chain_id: self.chain_id.clone(),
+            rpc_url: self.rpc_url.clone(),
+            no_paymaster: self.no_paymaster,
+            no_queue: self.no_queue,
+            switch_chain: self.switch_chain,
+            auto_fix_policies: self.auto_fix_policies,
+            require_events: self.require_events.clone(),
+            wait_for_event: self.wait_for_event.clone(),
+            only_if: self.only_if.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+fn default_timeout() -> u64 {
+    ExecuteOptions::default().timeout
+}
+
+#[derive(Serialize)]
+pub struct ScheduledOutput {
+    pub id: String,
+    pub run_at: u64,
+    pub run_at_formatted: String,
+    pub calls: usize,
+    pub message: String,
+}
+
+#[derive(Serialize)]
+pub struct ExecuteDueOutput {
+    /// Executions attempted in this run, with their results
+    pub ran: Vec<ScheduledExecution>,
+    /// Executions still waiting for their time
+    pub pending: usize,
+}
+
+pub fn load_schedule(storage_path: &Path) -> Vec<ScheduledExecution> {
+    std::fs::read_to_string(storage_path.join(SCHEDULE_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_schedule(storage_path: &Path, schedule: &[ScheduledExecution]) -> Result<()> {
+    let path = storage_path.join(SCHEDULE_FILE);
+    let content = serde_json::to_string_pretty(schedule)
+        .map_err(|e| CliError::Storage(format!("Failed to serialize scheduled executions: {e}")))?;
+    super::storage::write_atomic(&path, content).map_err(|e| CliError::FileError {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    })
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn format_time(timestamp: u64) -> String {
+    DateTime::from_timestamp(timestamp as i64, 0)
+        .unwrap_or_else(Utc::now)
+        .format("%Y-%m-%d %H:%M:%S UTC")
+        .to_string()
+}
+
+/// The unix time for `--at` (RFC 3339, or 'YYYY-MM-DDTHH:MM[:SS]' in UTC) or `--in` (a duration
+/// like '2h' or '30min').
+pub fn parse_run_at(at: Option<&str>, delay: Option<&str>) -> Result<u64> {
+    let run_at = match (at, delay) {
+        (Some(at), _) => {
+            let at = at.trim();
+            let parsed = DateTime::parse_from_rfc3339(at)
+                .map(|dt| dt.timestamp())
+                .or_else(|_| {
+                    let naive = at.trim_end_matches('Z');
+                    ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"]
+                        .iter()
+                        .find_map(|format| NaiveDateTime::parse_from_str(naive, format).ok())
+                        .map(|dt| dt.and_utc().timestamp())
+                        .ok_or(())
+                })
+                .map_err(|_| {
+                    CliError::InvalidInput(format!(
+                        "Invalid --at '{at}': use RFC 3339 like 2025-01-01T00:00:00Z"
+                    ))
+                })?;
+            u64::try_from(parsed).unwrap_or(0)
+        }
+        (None, Some(delay)) => parse_expiration(delay)?,
+        (None, None) => {
+            return Err(CliError::InvalidInput(
+                "Pass --at <time> or --in <duration>".to_string(),
+            ))
+        }
+    };
+    if run_at <= now() {
+        return Err(CliError::InvalidInput(format!(
+            "Scheduled time {} is in the past",
+            format_time(run_at)
+        )));
+    }
+    Ok(run_at)
+}
+
+/// Persist calls to submit at `run_at` with `options`, and the tip and batch ID in effect now.
+/// Warns when the session expires first. Nothing runs in the background: `controller
+/// execute-due` submits what is due.
+pub async fn create(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    calls: Vec<CallSpec>,
+    run_at: u64,
+    options: ExecuteOptions,
+    account: Option<&str>,
+) -> Result<()> {
+    if options.once || options.intent.is_some() {
+        return Err(CliError::InvalidInput(
+            "--once and --intent can't be scheduled".to_string(),
+        ));
+    }
+    let storage_path = config.resolve_storage_path(account);
+    let backend = FileSystemBackend::new(storage_path.clone());
+    let controller = backend
+        .controller()
+        .ok()
+        .flatten()
+        .ok_or(CliError::NoSession)?;
+    let session_key = format!(
+        "@cartridge/session/0x{:x}/0x{:x}",
+        controller.address, controller.chain_id
+    );
+    let expires_at = backend
+        .session(&session_key)
+        .ok()
+        .flatten()
+        .map(|metadata| metadata.session.inner.expires_at)
+        .ok_or(CliError::NoSession)?;
+    if expires_at <= run_at {
+        formatter.warning(&format!(
+            "The session expires at {}, before the scheduled time. Re-authorize with \
+             'controller session auth' before then or the execution will fail",
+            format_time(expires_at)
+        ));
+    }
+
+    let created_at = now();
+    let execution = ScheduledExecution {
+        id: format!("{created_at:x}-{}", std::process::id()),
+        calls,
+        run_at,
+        created_at,
+        chain_id: options.chain_id,
+        rpc_url: options.rpc_url,
+        wait: options.wait,
+        no_paymaster: options.no_paymaster,
+        only_if: options.only_if,
+        timeout: options.timeout,
+        no_queue: options.no_queue,
+        tip: config.cli.tip,
+        batch_id: config.cli.batch_id.clone(),
+        switch_chain: options.switch_chain,
+        auto_fix_policies: options.auto_fix_policies,
+        require_events: options.require_events,
+        wait_for_event: options.wait_for_event,
+        status: "pending".to_string(),
+        submitted_at: None,
+        transaction_hash: None,
+        error: None,
+    };
+    let output = ScheduledOutput {
+        id: execution.id.clone(),
+        run_at,
+        run_at_formatted: format_time(run_at),
+        calls: execution.calls.len(),
+        message: format!(
+            "Scheduled for {}. Run 'controller execute-due' after then (e.g. from cron).",
+            format_time(run_at)
+        ),
+    };
+
+    if config.cli.explain {
+        formatter.info(&format!(
+            "Would schedule {} call(s) for {}",
+            output.calls, output.run_at_formatted
+        ));
+        return Ok(());
+    }
+
+    let mut schedule = load_schedule(&storage_path);
+    schedule.push(execution);
+    save_schedule(&storage_path, &schedule)?;
+
+    formatter.success(&output);
+    Ok(())
+}
+
+/// Submit every scheduled execution whose time has come, recording each result in the schedule
+/// file. Fails at the end if any of them failed.
+pub async fn execute_due(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    account: Option<&str>,
+) -> Result<()> {
+    let storage_path = config.resolve_storage_path(account);
+    let mut schedule = load_schedule(&storage_path);
+    let started = now();
+    let due: Vec<usize> = (0..schedule.len())
+        .filter(|&i| schedule[i].is_due(started))
+        .collect();
+
+    for &i in &due {
+        let execution = schedule[i].clone();
+        formatter.info(&format!(
+            "Submitting scheduled execution {} ({} call(s))...",
+            execution.id,
+            execution.calls.len()
+        ));
+        let result = submit(config, formatter, &execution, account).await;
+        let entry = &mut schedule[i];
+        entry.submitted_at = Some(now());
+        match result {
+            Ok(transaction_hash) => {
+                entry.status = "submitted".to_string();
+                entry.transaction_hash = Some(transaction_hash);
+            }
+            Err(e) => {
+                entry.status = "failed".to_string();
+                entry.error = Some(e.to_string());
+            }
+        }
+        // Saved after each submission so an interrupted run doesn't submit twice
+        save_schedule(&storage_path, &schedule)?;
+    }
+
+    let ran: Vec<ScheduledExecution> = due.iter().map(|&i| schedule[i].clone()).collect();
+    let pending = schedule.iter().filter(|e| e.status == "pending").count();
+    save_schedule(&storage_path, &prune(schedule))?;
+
+    let failed = ran.iter().filter(|e| e.status == "failed").count();
+    formatter.success(&ExecuteDueOutput { ran, pending });
+    if failed > 0 {
+        return Err(CliError::TransactionFailed(format!(
+            "{failed} scheduled execution(s) failed; see 'error' in the output"
+        )));
+    }
+    Ok(())
+}
+
+async fn submit(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    scheduled: &ScheduledExecution,
+    account: Option<&str>,
+) -> Result<String> {
+    let mut config = config.clone();
+    config.cli.tip = scheduled.tip;
+    config.cli.batch_id = scheduled.batch_id.clone();
+    let execution = crate::commands::execute::execute_calls(
+        &config,
+        formatter,
+        scheduled.calls.clone(),
+        scheduled.options(),
+        account,
+    )
+    .await?;
+    Ok(execution
+        .into_submitted("Scheduled execution")?
+        .transaction_hash)
+}
+
+/// Keep pending executions and the most recent finished ones.
+fn prune(schedule: Vec<ScheduledExecution>) -> Vec<ScheduledExecution> {
+    let finished = schedule.iter().filter(|e| e.status != "pending").count();
+    let mut skip = finished.saturating_sub(MAX_FINISHED);
+    schedule
+        .into_iter()
+        .filter(|e| {
+            if e.status != "pending" && skip > 0 {
+                skip -= 1;
+                return false;
+            }
+            true
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn execution(status: &str, run_at: u64) -> ScheduledExecution {
+        ScheduledExecution {
+            id: format!("{run_at}"),
+            calls: Vec::new(),
+            run_at,
+            created_at: 0,
+            chain_id: None,
+            rpc_url: None,
+            wait: false,
+            no_paymaster: false,
+            only_if: None,
+            timeout: 300,
+            no_queue: false,
+            tip: 0,
+            batch_id: None,
+            switch_chain: false,
+            auto_fix_policies: false,
+            require_events: Vec::new(),
+            wait_for_event: None,
+            status: status.to_string(),
+            submitted_at: None,
+            transaction_hash: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn schedules_and_prunes_executions() {
+        assert!(execution("pending", 100).is_due(100));
+        assert!(!execution("pending", 101).is_due(100));
+        assert!(!execution("submitted", 50).is_due(100));
+
+        let mut schedule: Vec<_> = (0..MAX_FINISHED as u64 + 2)
+            .map(|i| execution("submitted", i))
+            .collect();
+        schedule.push(execution("pending", 1000));
+        let pruned = prune(schedule);
+        assert_eq!(pruned.len(), MAX_FINISHED + 1);
+        assert_eq!(pruned[0].run_at, 2);
+
+        assert!(parse_run_at(Some("2000-01-01T00:00Z"), None).is_err());
+        assert!(parse_run_at(Some("2999-01-01T00:00Z"), None).is_ok());
+        assert!(parse_run_at(Some("2999-01-01T00:00:00+02:00"), None).is_ok());
+        assert!(parse_run_at(Some("tomorrow"), None).is_err());
+        assert!(parse_run_at(None, Some("2h")).unwrap() > now());
+    }
+}
@@ -0,0 +1,35 @@
+use crate::{
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use serde::Serialize;
+use starknet::core::utils::get_selector_from_name;
+
+#[derive(Serialize)]
+pub struct SelectorOutput {
+    pub name: String,
+    pub selector: String,
+}
+
+/// Print the selector (`starknet_keccak`) of one or more entrypoint names.
+pub async fn execute(formatter: &dyn OutputFormatter, names: Vec<String>) -> Result<()> {
+    let outputs = names
+        .into_iter()
+        .map(|name| {
+            let selector = get_selector_from_name(&name)
+                .map_err(|e| CliError::InvalidInput(format!("Invalid entrypoint '{name}': {e}")))?;
+            Ok(SelectorOutput {
+                name,
+                selector: format!("{selector:#x}"),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if outputs.len() == 1 {
+        formatter.success(&outputs[0]);
+    } else {
+        formatter.success(&outputs);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,264 @@
+use crate::{
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use serde::Serialize;
+use serde_json::Value;
+use starknet::core::types::Felt;
+use starknet::core::utils::get_selector_from_name;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Entrypoints and events of the token and account standards, known without any setup
+const STANDARD_NAMES: &[&str] = &[
+    "__execute__",
+    "__validate__",
+    "__validate_declare__",
+    "__validate_deploy__",
+    "transfer",
+    "transfer_from",
+    "transferFrom",
+    "approve",
+    "increase_allowance",
+    "increaseAllowance",
+    "decrease_allowance",
+    "decreaseAllowance",
+    "set_approval_for_all",
+    "setApprovalForAll",
+    "safe_transfer_from",
+    "safeTransferFrom",
+    "safe_batch_transfer_from",
+    "safeBatchTransferFrom",
+    "balance_of",
+    "balanceOf",
+    "allowance",
+    "owner_of",
+    "ownerOf",
+    "name",
+    "symbol",
+    "decimals",
+    "total_supply",
+    "totalSupply",
+    "mint",
+    "burn",
+    "Transfer",
+    "Approval",
+    "ApprovalForAll",
+    "TransferSingle",
+    "TransferBatch",
+];
+
+/// Selector → name mappings learned locally, on top of [`STANDARD_NAMES`].
+#[derive(Debug, Default)]
+pub struct SelectorDb {
+    names: BTreeMap<Felt, String>,
+}
+
+impl SelectorDb {
+    /// Standard names plus everything added, imported or learned on this machine. A missing or
+    /// unreadable database just means fewer names.
+    pub fn load() -> Self {
+        let mut db = Self::default();
+        for name in STANDARD_NAMES {
+            db.insert(name);
+        }
+        let stored: BTreeMap<String, String> = db_path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        for (selector, name) in stored {
+            if let Ok(selector) = Felt::from_hex(&selector) {
+                db.names.insert(selector, name);
+            }
+        }
+        db
+    }
+
+    pub fn name(&self, selector: Felt) -> Option<&str> {
+        self.names.get(&selector).map(String::as_str)
+    }
+
+    /// Add a name, returning its selector if it was new.
+    fn insert(&mut self, name: &str) -> Option<Felt> {
+        let selector = get_selector_from_name(name).ok()?;
+        (self.names.insert(selector, name.to_string()).is_none()).then_some(selector)
+    }
+
+    /// Persist everything that isn't a standard name
+    fn save(&self) -> Result<()> {
+        let standard: Vec<Felt> = STANDARD_NAMES
+            .iter()
+            .filter_map(|name| get_selector_from_name(name).ok())
+            .collect();
+        let stored: BTreeMap<String, &String> = self
+            .names
+            .iter()
+            .filter(|(selector, _)| !standard.contains(selector))
+            .map(|(selector, name)| (format!("{selector:#x}"), name))
+            .collect();
+        let path = db_path()
+            .ok_or_else(|| CliError::Storage("Could not determine config directory".to_string()))?;
+        let json = serde_json::to_string_pretty(&stored)
+            .map_err(|e| CliError::Storage(format!("Failed to serialize selectors: {e}")))?;
+        super::storage::write_atomic(&path, json).map_err(|e| CliError::FileError {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })
+    }
+}
+
+fn db_path() -> Option<PathBuf> {
+    crate::config::config_dir().map(|p| p.join("selectors.json"))
+}
+
+/// Remember entrypoint or event names seen elsewhere (session policies, ABIs). Best effort.
+pub fn learn<'a>(names: impl IntoIterator<Item = &'a str>) {
+    let mut db = SelectorDb::load();
+    let added = names
+        .into_iter()
+        .filter(|name| !name.starts_with("selector:"))
+        .filter_map(|name| db.insert(name))
+        .count();
+    if added > 0 {
+        let _ = db.save();
+    }
+}
+
+/// Function and event names in an ABI, policy file or preset: `function`/`l1_handler` items
+/// (including those nested in interfaces), struct events by their last path segment, and any
+/// `entrypoint` field.
+pub fn names_in(value: &Value) -> Vec<String> {
+    let mut names = Vec::new();
+    collect_names(value, &mut names);
+    names.sort();
+    names.dedup();
+    names
+}
+
+fn collect_names(value: &Value, names: &mut Vec<String>) {
+    match value {
+        Value::Array(items) => items.iter().for_each(|item| collect_names(item, names)),
+        Value::Object(object) => {
+            let name = object.get("name").and_then(Value::as_str);
+            match (object.get("type").and_then(Value::as_str), name) {
+                (Some("function" | "l1_handler"), Some(name)) => names.push(name.to_string()),
+                (Some("event"), Some(name))
+                    if object.get("kind").is_some_and(|k| k == "struct") =>
+                {
+                    names.push(name.rsplit("::").next().unwrap_or(name).to_string())
+                }
+                _ => {}
+            }
+            if let Some(entrypoint) = object.get("entrypoint").and_then(Value::as_str) {
+                names.push(entrypoint.to_string());
+            }
+            for (key, child) in object {
+                // Sierra classes carry the ABI as a JSON string
+                if key == "abi" {
+                    if let Some(abi) = child
+                        .as_str()
+                        .and_then(|abi| serde_json::from_str::<Value>(abi).ok())
+                    {
+                        collect_names(&abi, names);
+                        continue;
+                    }
+                }
+                collect_names(child, names);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[derive(Serialize)]
+pub struct SelectorsOutput {
+    pub added: Vec<SelectorName>,
+    /// Names given that were already known
+    pub known: usize,
+}
+
+#[derive(Serialize)]
+pub struct SelectorName {
+    pub name: String,
+    pub selector: String,
+}
+
+/// Add entrypoint or event names to the local selector database.
+pub async fn add(formatter: &dyn OutputFormatter, names: Vec<String>) -> Result<()> {
+    for name in &names {
+        get_selector_from_name(name)
+            .map_err(|e| CliError::InvalidInput(format!("Invalid entrypoint '{name}': {e}")))?;
+    }
+    store(formatter, names)
+}
+
+/// Import every function, event and policy entrypoint name from an ABI, contract class, policy
+/// file or preset config.
+pub async fn import(formatter: &dyn OutputFormatter, file: String) -> Result<()> {
+    let content = std::fs::read_to_string(&file).map_err(|e| CliError::FileError {
+        path: file.clone(),
+        message: e.to_string(),
+    })?;
+    let value: Value = serde_json::from_str(&content)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid JSON in {file}: {e}")))?;
+    let names = names_in(&value);
+    if names.is_empty() {
+        return Err(CliError::InvalidInput(format!(
+            "No function, event or entrypoint names found in {file}"
+        )));
+    }
+    store(formatter, names)
+}
+
+fn store(formatter: &dyn OutputFormatter, names: Vec<String>) -> Result<()> {
+    let mut db = SelectorDb::load();
+    let total = names.len();
+    let added: Vec<SelectorName> = names
+        .into_iter()
+        .filter_map(|name| {
+            db.insert(&name).map(|selector| SelectorName {
+                name,
+                selector: format!("{selector:#x}"),
+            })
+        })
+        .collect();
+    if !added.is_empty() {
+        db.save()?;
+    }
+    formatter.success(&SelectorsOutput {
+        known: total - added.len(),
+        added,
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn collects_names_from_abis_and_policies() {
+        let abi = json!([
+            {
+                "type": "interface",
+                "name": "game::IActions",
+                "items": [{ "type": "function", "name": "move", "inputs": [] }]
+            },
+            { "type": "event", "name": "game::actions::Moved", "kind": "struct", "members": [] },
+            { "type": "event", "name": "game::actions::Event", "kind": "enum", "variants": [] }
+        ]);
+        assert_eq!(names_in(&abi), vec!["Moved", "move"]);
+
+        let class = json!({ "abi": abi.to_string() });
+        assert_eq!(names_in(&class), vec!["Moved", "move"]);
+
+        let policies =
+            json!({ "contracts": { "0x1": { "methods": [{ "entrypoint": "spawn" }] } } });
+        assert_eq!(names_in(&policies), vec!["spawn"]);
+
+        let db = SelectorDb::load();
+        let transfer = get_selector_from_name("transfer").unwrap();
+        assert_eq!(db.name(transfer), Some("transfer"));
+    }
+}
@@ -0,0 +1,402 @@
+use crate::{
+    config::Config,
+    error::{CliError, HintContext, Result},
+    output::{self, OutputFormatter},
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Mutex;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+/// Protocol revision answered when the client doesn't ask for one
+const MCP_PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// JSON-RPC 2.0 request or notification (no `id`) read from stdin, one per line
+#[derive(Debug, Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// Collects a tool's result and warnings instead of printing them: stdout belongs to the protocol.
+struct ToolFormatter {
+    result: Mutex<Option<Value>>,
+    warnings: Mutex<Vec<String>>,
+}
+
+impl ToolFormatter {
+    fn new() -> Self {
+        Self {
+            result: Mutex::new(None),
+            warnings: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl OutputFormatter for ToolFormatter {
+    fn success(&self, data: &dyn erased_serde::Serialize) {
+        if let Ok(mut result) = self.result.lock() {
+            *result = serde_json::to_value(data).ok();
+        }
+    }
+
+    fn error(&self, _error: &CliError) {}
+
+    fn info(&self, _message: &str) {}
+
+    fn warning(&self, message: &str) {
+        if let Ok(mut warnings) = self.warnings.lock() {
+            warnings.push(message.to_string());
+        }
+    }
+}
+
+/// Serve session status, execute, call, balance and lookup as MCP tools over stdio until stdin
+/// closes. Tool results are the same `success`/`error` documents `--json` prints.
+pub async fn mcp(config: &Config, account: Option<&str>) -> Result<()> {
+    // Commands print tables in human mode; tools always want the structured result
+    let mut config = config.clone();
+    config.cli.json_output = true;
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+    while let Some(line) = lines
+        .next_line()
+        .await
+        .map_err(|e| CliError::Other(e.into()))?
+    {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => handle(&config, account, request).await,
+            Err(e) => Some(rpc_error(Value::Null, -32700, &format!("Parse error: {e}"))),
+        };
+        if let Some(response) = response {
+            let mut out = response.to_string();
+            out.push('\n');
+            stdout
+                .write_all(out.as_bytes())
+                .await
+                .map_err(|e| CliError::Other(e.into()))?;
+            stdout
+                .flush()
+                .await
+                .map_err(|e| CliError::Other(e.into()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Answer one message. Notifications get no response.
+async fn handle(config: &Config, account: Option<&str>, request: Request) -> Option<Value> {
+    let id = request.id?;
+    let result = match request.method.as_str() {
+        "initialize" => json!({
+            "protocolVersion": request.params["protocolVersion"]
+                .as_str()
+                .unwrap_or(MCP_PROTOCOL_VERSION),
+            "capabilities": { "tools": {} },
+            "serverInfo": {
+                "name": "controller-cli",
+                "version": crate::version::CURRENT_VERSION
+            }
+        }),
+        "ping" => json!({}),
+        "tools/list" => json!({ "tools": tool_definitions() }),
+        "tools/call" => {
+            let name = request.params["name"].as_str().unwrap_or_default();
+            let arguments = &request.params["arguments"];
+            call_tool(config, account, name, arguments).await
+        }
+        method => return Some(rpc_error(id, -32601, &format!("Unknown method: {method}"))),
+    };
+    Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+}
+
+fn rpc_error(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message }
+    })
+}
+
+fn tool_definitions() -> Value {
+    let chain = json!({
+        "chain_id": { "type": "string", "description": "Chain ID, e.g. SN_MAIN, SN_SEPOLIA or a chain configured with chain.<name>.rpc-url" },
+        "rpc_url": { "type": "string", "description": "RPC URL (instead of chain_id)" }
+    });
+    let with_chain = |properties: Value| {
+        let mut merged = chain.clone();
+        if let (Some(merged), Some(extra)) = (merged.as_object_mut(), properties.as_object()) {
+            merged.extend(extra.clone());
+        }
+        merged
+    };
+    let calldata = json!({
+        "type": "array",
+        "items": { "type": "string" },
+        "description": "Calldata values (hex, decimal, u256:, str: ... as on the command line)"
+    });
+
+    json!([
+        {
+            "name": "session_status",
+            "description": "Whether a session exists, its address, chain, expiry and policies",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "balance",
+            "description": "ERC20 balances of the session account (all non-zero, or one token)",
+            "inputSchema": {
+                "type": "object",
+                "properties": with_chain(json!({
+                    "symbol": { "type": "string", "description": "Token symbol, e.g. STRK" },
+                    "fresh": { "type": "boolean", "description": "Bypass the 30s cache" }
+                }))
+            }
+        },
+        {
+            "name": "call",
+            "description": "Read-only contract call",
+            "inputSchema": {
+                "type": "object",
+                "properties": with_chain(json!({
+                    "contract": { "type": "string" },
+                    "entrypoint": { "type": "string" },
+                    "calldata": calldata,
+                    "block_id": { "type": "string", "description": "latest, a number or a hash" }
+                })),
+                "required": ["contract", "entrypoint"]
+            }
+        },
+        {
+            "name": "execute",
+            "description": "Submit a transaction with the session account. Only calls allowed by the session policies succeed",
+            "inputSchema": {
+                "type": "object",
+                "properties": with_chain(json!({
+                    "contract": { "type": "string" },
+                    "entrypoint": { "type": "string" },
+                    "calldata": calldata,
+                    "wait": { "type": "boolean", "description": "Wait for confirmation" },
+                    "timeout": { "type": "integer", "description": "Seconds to wait (default 300)" },
+                    "no_paymaster": { "type": "boolean", "description": "Pay fees from the account" }
+                })),
+                "required": ["contract", "entrypoint"]
+            }
+        },
+        {
+            "name": "lookup",
+            "description": "Resolve Controller usernames to addresses and back",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "usernames": { "type": "array", "items": { "type": "string" } },
+                    "addresses": { "type": "array", "items": { "type": "string" } }
+                }
+            }
+        }
+    ])
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ToolArguments {
+    chain_id: Option<String>,
+    rpc_url: Option<String>,
+    symbol: Option<String>,
+    fresh: bool,
+    contract: Option<String>,
+    entrypoint: Option<String>,
+    calldata: Vec<String>,
+    block_id: Option<String>,
+    wait: bool,
+    timeout: Option<u64>,
+    no_paymaster: bool,
+    usernames: Vec<String>,
+    addresses: Vec<String>,
+}
+
+/// Run a tool and wrap its `--json` document as MCP text content.
+async fn call_tool(config: &Config, account: Option<&str>, name: &str, arguments: &Value) -> Value {
+    let formatter = ToolFormatter::new();
+    let outcome = match serde_json::from_value::<ToolArguments>(match arguments {
+        Value::Null => json!({}),
+        arguments => arguments.clone(),
+    }) {
+        Ok(args) => run_tool(config, &formatter, account, name, args).await,
+        Err(e) => Err(CliError::InvalidInput(format!(
+            "Invalid arguments for {name}: {e}"
+        ))),
+    };
+
+    let warnings = formatter
+        .warnings
+        .lock()
+        .map(|w| w.clone())
+        .unwrap_or_default();
+    let (document, is_error) = match outcome {
+        Ok(()) => {
+            let data = formatter.result.lock().ok().and_then(|mut r| r.take());
+            (output::success_document(&data, &warnings), false)
+        }
+        Err(e) => (
+            output::error_document(&e, &HintContext::new(account), &warnings),
+            true,
+        ),
+    };
+    json!({
+        "content": [{ "type": "text", "text": document.to_string() }],
+        "isError": is_error
+    })
+}
+
+async fn run_tool(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    account: Option<&str>,
+    name: &str,
+    args: ToolArguments,
+) -> Result<()> {
+    if name == "execute" && config.cli.read_only {
+        return Err(CliError::ReadOnly(
+            "this command signs or submits transactions".to_string(),
+        ));
+    }
+    let reads_credentials = matches!(name, "session_status" | "balance" | "execute");
+    if reads_credentials {
+        let storage_path = config.resolve_storage_path(account);
+        super::storage::check_permissions(&storage_path, false, formatter);
+        super::storage::check_integrity(&storage_path, formatter);
+    }
+    let calldata = (!args.calldata.is_empty()).then(|| args.calldata.join(","));
+
+    match name {
+        "session_status" => super::status::execute(config, formatter, account).await,
+        "balance" => {
+            super::balance::execute(
+                config,
+                formatter,
+                args.symbol,
+                args.chain_id,
+                args.rpc_url,
+                args.fresh,
+                None,
+                account,
+            )
+            .await
+        }
+        "call" => {
+            super::call::execute(
+                config,
+                formatter,
+                args.contract,
+                args.entrypoint,
+                calldata,
+                None,
+                args.chain_id,
+                args.rpc_url,
+                args.block_id,
+                None,
+                1,
+                None,
+                account,
+            )
+            .await
+        }
+        "execute" => {
+            super::execute::execute(
+                config,
+                formatter,
+                args.contract,
+                args.entrypoint,
+                calldata,
+                None,
+                super::execute::ExecuteOptions {
+                    wait: args.wait,
+                    timeout: args.timeout.unwrap_or(300),
+                    chain_id: args.chain_id,
+                    rpc_url: args.rpc_url,
+                    no_paymaster: args.no_paymaster,
+                    ..Default::default()
+                },
+                account,
+            )
+            .await
+        }
+        "lookup" => {
+            let join = |values: Vec<String>| (!values.is_empty()).then(|| values.join(","));
+            super::lookup::execute(
+                config,
+                formatter,
+                join(args.usernames),
+                join(args.addresses),
+                false,
+                false,
+            )
+            .await
+        }
+        _ => Err(CliError::InvalidInput(format!("Unknown tool: {name}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn answers_protocol_requests() {
+        let config = Config::default();
+        let request = |line: &str| serde_json::from_str::<Request>(line).unwrap();
+
+        let init = handle(
+            &config,
+            None,
+            request(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#),
+        )
+        .await
+        .unwrap();
+        assert_eq!(init["result"]["protocolVersion"], MCP_PROTOCOL_VERSION);
+
+        let notification = request(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#);
+        assert!(handle(&config, None, notification).await.is_none());
+
+        let tools = handle(
+            &config,
+            None,
+            request(r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#),
+        )
+        .await
+        .unwrap();
+        let names: Vec<&str> = tools["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| tool["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            ["session_status", "balance", "call", "execute", "lookup"]
+        );
+
+        let mut read_only = Config::default();
+        read_only.cli.read_only = true;
+        let refused = call_tool(
+            &read_only,
+            None,
+            "execute",
+            &json!({ "contract": "0x1", "entrypoint": "transfer" }),
+        )
+        .await;
+        assert_eq!(refused["isError"], true);
+        assert!(refused["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("ReadOnlyMode"));
+    }
+}
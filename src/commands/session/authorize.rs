@@ -1,7 +1,8 @@
+use super::risk::{self, Risk};
 use crate::{
     api,
     config::Config,
-    error::{CliError, Result},
+    error::{CliError, InputRequest, Result},
     output::OutputFormatter,
     presets,
 };
@@ -11,6 +12,7 @@ use account_sdk::storage::{
 use serde::{Deserialize, Serialize};
 use starknet::signers::SigningKey;
 use std::fmt::Display;
+use std::io::IsTerminal;
 use std::time::{SystemTime, UNIX_EPOCH};
 use url::Url;
 
@@ -25,6 +27,9 @@ pub struct PolicyFile {
 #[derive(Serialize, Deserialize, Clone)]
 pub struct PolicyStorage {
     pub contracts: std::collections::HashMap<String, ContractPolicy>,
+    /// SNIP-12 typed data policies, checked by `sign-message`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub messages: Option<Vec<serde_json::Value>>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -50,9 +55,9 @@ fn default_authorized() -> bool {
     true
 }
 
-/// Parse a duration string like "1min", "1hr", "7days", "1week", "1year"
-/// and return the corresponding unix timestamp (now + duration).
-fn parse_expiration(duration: &str) -> Result<u64> {
+/// Parse a duration string like "1min", "1hr", "7days", "1week", "1year" (or "30s", "5m", "2h",
+/// "1d") and return the corresponding unix timestamp (now + duration).
+pub fn parse_expiration(duration: &str) -> Result<u64> {
     let duration = duration.trim().to_lowercase();
 
     let (num_str, unit) = duration
@@ -65,15 +70,16 @@ fn parse_expiration(duration: &str) -> Result<u64> {
         .map_err(|_| CliError::InvalidInput(format!("Invalid number in duration: {num_str}")))?;
 
     let seconds = match unit {
-        "min" | "mins" => num * 60,
-        "hr" | "hrs" => num * 3600,
-        "day" | "days" => num * 86400,
+        "s" | "sec" | "secs" => num,
+        "m" | "min" | "mins" => num * 60,
+        "h" | "hr" | "hrs" => num * 3600,
+        "d" | "day" | "days" => num * 86400,
         "week" | "weeks" => num * 604800,
         "year" | "years" => num * 31536000,
         _ => {
             return Err(CliError::InvalidInput(format!(
-                "Unknown duration unit: '{unit}'. Use min, hr, day/days, week/weeks, or year/years"
-            )))
+            "Unknown duration unit: '{unit}'. Use s, min, hr, day/days, week/weeks, or year/years"
+        )))
         }
     };
 
@@ -91,6 +97,9 @@ pub struct AuthorizeOutput {
     pub short_url: Option<String>,
     pub public_key: String,
     pub expires: String,
+    /// With --headless, the short code shown next to the URL (the end of the session key)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
     pub message: String,
 }
 
@@ -118,70 +127,447 @@ where
     }
 }
 
-#[allow(clippy::too_many_arguments)]
+/// No browser can be opened here: an SSH session, a container, or Linux without a display.
+fn browser_unavailable() -> bool {
+    let env = |name: &str| std::env::var_os(name).is_some_and(|value| !value.is_empty());
+    env("SSH_CONNECTION")
+        || env("SSH_TTY")
+        || std::path::Path::new("/.dockerenv").exists()
+        || (cfg!(target_os = "linux") && !env("DISPLAY") && !env("WAYLAND_DISPLAY"))
+}
+
+/// A short code for the session key, like a device-flow user code (e.g. 'A1B2-C3D4'). The
+/// authorization URL carries the full key, so the code lets the user check on the other device
+/// that they are approving the key this terminal is waiting on.
+fn device_code(public_key: &str) -> String {
+    let hex = public_key.trim_start_matches("0x").to_uppercase();
+    let tail = &hex[hex.len().saturating_sub(8)..];
+    let tail = format!("{tail:0>8}");
+    format!("{}-{}", &tail[..4], &tail[4..])
+}
+
+/// "1m 05s"
+fn format_elapsed(secs: u64) -> String {
+    format!("{}m {:02}s", secs / 60, secs % 60)
+}
+
+/// The URL as a QR code drawn with half-block characters (light modules on dark, which scans
+/// on the usual dark terminal), or None when it won't fit in `columns`.
+fn render_qr(url: &str, columns: Option<usize>) -> Option<String> {
+    use qrcode::render::unicode::Dense1x2;
+
+    let code = qrcode::QrCode::new(url.as_bytes()).ok()?;
+    let rendered = code
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .build();
+    let width = rendered.lines().map(|l| l.chars().count()).max()?;
+    columns.map_or(true, |c| width <= c).then_some(rendered)
+}
+
+/// GUID of a Starknet session signer: poseidon_hash("Starknet Signer", public_key)
+pub fn session_key_guid(public_key: starknet::core::types::Felt) -> starknet::core::types::Felt {
+    use starknet::macros::short_string;
+    starknet_crypto::poseidon_hash(short_string!("Starknet Signer"), public_key)
+}
+
+/// Contracts sorted by address (case-insensitive) and methods sorted by entrypoint, matching the
+/// frontend's toWasmPolicies() canonical ordering used to build the policy Merkle tree.
+fn canonical_order(policy_file: &PolicyFile) -> Vec<(&str, &ContractPolicy, Vec<&MethodPolicy>)> {
+    let mut contracts: Vec<_> = policy_file.contracts.iter().collect();
+    contracts.sort_by(|(a, _), (b, _)| a.to_lowercase().cmp(&b.to_lowercase()));
+
+    contracts
+        .into_iter()
+        .map(|(address, contract)| {
+            let mut methods: Vec<_> = contract.methods.iter().collect();
+            methods.sort_by(|a, b| a.entrypoint.cmp(&b.entrypoint));
+            (address.as_str(), contract, methods)
+        })
+        .collect()
+}
+
+#[derive(Serialize)]
+pub struct PolicyReview {
+    pub contracts: Vec<ReviewedContract>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub messages: Option<Vec<serde_json::Value>>,
+}
+
+#[derive(Serialize)]
+pub struct ReviewedContract {
+    pub address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub methods: Vec<ReviewedMethod>,
+}
+
+#[derive(Serialize)]
+pub struct ReviewedMethod {
+    pub entrypoint: String,
+    pub selector: String,
+    pub authorized: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub risk: Risk,
+}
+
+/// The resolved policy set exactly as it will be hashed, for `--print-policies`.
+pub fn review_policies(policy_file: &PolicyFile) -> Result<PolicyReview> {
+    let contracts = canonical_order(policy_file)
+        .into_iter()
+        .map(|(address, contract, methods)| {
+            let methods = methods
+                .into_iter()
+                .map(|method| {
+                    let selector = starknet::core::utils::get_selector_from_name(
+                        &method.entrypoint,
+                    )
+                    .map_err(|e| {
+                        CliError::InvalidInput(format!(
+                            "Invalid entrypoint name {}: {}",
+                            method.entrypoint, e
+                        ))
+                    })?;
+                    Ok(ReviewedMethod {
+                        entrypoint: method.entrypoint.clone(),
+                        selector: format!("0x{selector:x}"),
+                        authorized: method.authorized,
+                        description: method.description.clone(),
+                        risk: risk::classify(selector),
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(ReviewedContract {
+                address: address.to_string(),
+                name: contract.name.clone(),
+                methods,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(PolicyReview {
+        contracts,
+        messages: policy_file.messages.clone(),
+    })
+}
+
+fn print_policy_review(config: &Config, formatter: &dyn OutputFormatter, review: &PolicyReview) {
+    if config.cli.json_output {
+        formatter.success(review);
+        return;
+    }
+
+    formatter.info("Policies to be granted:");
+    for contract in &review.contracts {
+        match &contract.name {
+            Some(name) => println!("  {} ({name})", contract.address),
+            None => println!("  {}", contract.address),
+        }
+        for method in &contract.methods {
+            let note = if !method.authorized {
+                " [not authorized]".to_string()
+            } else if method.risk.is_high() {
+                format!(" [{}]", method.risk.label())
+            } else {
+                String::new()
+            };
+            println!("    - {}{note}", method.entrypoint);
+        }
+    }
+    if let Some(messages) = &review.messages {
+        println!("  + {} typed message policies", messages.len());
+    }
+}
+
+/// Summarize the authorized grants by risk category and call out the high-risk ones, so they
+/// are visible before the browser opens.
+fn print_risk_summary(formatter: &dyn OutputFormatter, review: &PolicyReview) {
+    let granted: Vec<(&ReviewedContract, &ReviewedMethod)> = review
+        .contracts
+        .iter()
+        .flat_map(|contract| {
+            contract
+                .methods
+                .iter()
+                .map(move |method| (contract, method))
+        })
+        .filter(|(_, method)| method.authorized)
+        .collect();
+    let count = |risk: Risk| granted.iter().filter(|(_, m)| m.risk == risk).count();
+    formatter.info(&format!(
+        "Risk summary: {} value transfer, {} approval, {} NFT transfer, {} unknown",
+        count(Risk::ValueTransfer),
+        count(Risk::Approval),
+        count(Risk::NftTransfer),
+        count(Risk::Unknown)
+    ));
+    for (contract, method) in granted.iter().filter(|(_, m)| m.risk.is_high()) {
+        formatter.warning(&format!(
+            "High-risk grant: {} on {} ({})",
+            method.entrypoint,
+            contract.name.as_deref().unwrap_or(&contract.address),
+            method.risk.label()
+        ));
+    }
+}
+
+/// Merge policies from several presets/files into one policy set. Contracts are matched by
+/// address value (so '0x0abc' and '0xABC' are the same contract) and methods by entrypoint;
+/// duplicates are dropped, but the same method with a different `authorized` flag or spending
+/// `amount` across sources is rejected rather than silently picking one.
+fn merge_policy_files(sources: Vec<(String, PolicyFile)>) -> Result<PolicyFile> {
+    let mut merged: std::collections::HashMap<String, ContractPolicy> =
+        std::collections::HashMap::new();
+    // Normalized address -> (key in `merged`, source that introduced each entrypoint)
+    let mut keys: std::collections::HashMap<starknet::core::types::Felt, String> =
+        std::collections::HashMap::new();
+    let mut method_sources: std::collections::HashMap<(String, String), String> =
+        std::collections::HashMap::new();
+    let mut messages: Vec<serde_json::Value> = Vec::new();
+
+    for (source, policy_file) in sources {
+        for (address, contract) in policy_file.contracts {
+            let normalized = starknet::core::types::Felt::from_hex(&address).map_err(|e| {
+                CliError::InvalidInput(format!(
+                    "Invalid contract address {address} in {source}: {e}"
+                ))
+            })?;
+            let key = keys
+                .entry(normalized)
+                .or_insert_with(|| address.clone())
+                .clone();
+            let entry = merged.entry(key.clone()).or_insert_with(|| ContractPolicy {
+                name: contract.name.clone(),
+                methods: Vec::new(),
+            });
+            if entry.name.is_none() {
+                entry.name = contract.name.clone();
+            }
+
+            for method in contract.methods {
+                let method_key = (key.clone(), method.entrypoint.clone());
+                match entry
+                    .methods
+                    .iter()
+                    .find(|m| m.entrypoint == method.entrypoint)
+                {
+                    Some(existing)
+                        if existing.authorized != method.authorized
+                            || existing.amount != method.amount =>
+                    {
+                        return Err(CliError::InvalidInput(format!(
+                            "Conflicting policies for {} on {key}: {} and {source} disagree",
+                            method.entrypoint, method_sources[&method_key]
+                        )));
+                    }
+                    Some(_) => {}
+                    None => {
+                        method_sources.insert(method_key, source.clone());
+                        entry.methods.push(method);
+                    }
+                }
+            }
+        }
+
+        for message in policy_file.messages.unwrap_or_default() {
+            if !messages.contains(&message) {
+                messages.push(message);
+            }
+        }
+    }
+
+    Ok(PolicyFile {
+        contracts: merged,
+        messages: (!messages.is_empty()).then_some(messages),
+    })
+}
+
+/// A preset's policies for one chain as a policy file; every method is authorized.
+pub fn preset_policy_file(chain_policies: presets::PoliciesConfig) -> PolicyFile {
+    let contracts = chain_policies
+        .contracts
+        .into_iter()
+        .map(|(addr, contract)| {
+            (
+                addr,
+                ContractPolicy {
+                    name: Some(contract.name),
+                    methods: contract
+                        .methods
+                        .into_iter()
+                        .map(|m| MethodPolicy {
+                            name: m.name,
+                            entrypoint: m.entrypoint,
+                            description: m.description,
+                            amount: None,
+                            authorized: true,
+                        })
+                        .collect(),
+                },
+            )
+        })
+        .collect();
+    PolicyFile {
+        contracts,
+        messages: chain_policies.messages,
+    }
+}
+
+/// Load the policies of every preset (for the chain behind `rpc_url`), the policy file and the
+/// template, merged into one policy set.
+pub async fn resolve_policies(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    preset_names: &[String],
+    file: Option<&str>,
+    template: Option<(String, Vec<String>)>,
+    rpc_url: &str,
+) -> Result<PolicyFile> {
+    let mut sources: Vec<(String, PolicyFile)> = Vec::new();
+
+    if !preset_names.is_empty() {
+        let provider = starknet::providers::jsonrpc::JsonRpcClient::new(crate::api::rpc_transport(
+            url::Url::parse(rpc_url)
+                .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?,
+        ));
+
+        let chain_id = starknet::providers::Provider::chain_id(&provider)
+            .await
+            .map_err(|e| {
+                CliError::InvalidInput(format!("Failed to query chain_id from RPC: {e}"))
+            })?;
+
+        let chain_name = starknet::core::utils::parse_cairo_short_string(&chain_id)
+            .unwrap_or_else(|_| format!("0x{chain_id:x}"));
+
+        for preset_name in preset_names {
+            // Fetch preset from GitHub
+            let preset_config = presets::fetch_preset(config, formatter, preset_name).await?;
+
+            // Extract chain-specific policies
+            let chain_policies =
+                presets::extract_chain_policies(&preset_config, &chain_name, preset_name)?;
+
+            sources.push((
+                format!("preset '{preset_name}'"),
+                preset_policy_file(chain_policies),
+            ));
+        }
+    }
+
+    if let Some(file_path) = file {
+        // Load from local file
+        let policy_content = std::fs::read_to_string(file_path)
+            .map_err(|e| CliError::InvalidInput(format!("Failed to read policy file: {e}")))?;
+
+        let policy_file: PolicyFile =
+            crate::commands::file_format::parse_structured(file_path, &policy_content)
+                .map_err(|e| CliError::InvalidInput(format!("Invalid policy file format: {e}")))?;
+        sources.push((format!("file '{file_path}'"), policy_file));
+    }
+
+    if let Some((template_name, contracts)) = template {
+        let policy_file = super::templates::expand(&template_name, &contracts)?;
+        sources.push((format!("template '{template_name}'"), policy_file));
+    }
+
+    merge_policy_files(sources)
+}
+
+/// Where `session auth` takes its policies from and how it runs. Callers set what they need and
+/// take the rest from `Default`, which matches the `session auth` command's defaults.
+#[derive(Debug, Clone)]
+pub struct AuthorizeOptions {
+    /// Presets whose policies are merged in (`--preset`)
+    pub preset_names: Vec<String>,
+    /// Local policy file merged with the presets (`--file`)
+    pub file: Option<String>,
+    /// Built-in template and the contracts it is applied to (`--template`, `--contracts`)
+    pub template: Option<(String, Vec<String>)>,
+    pub chain_id: Option<String>,
+    pub rpc_url: Option<String>,
+    /// Replace a stored session without asking
+    pub overwrite: bool,
+    /// Print the resolved policies and ask before opening the browser
+    pub print_policies: bool,
+    /// Authorize the printed policies without asking
+    pub yes: bool,
+    /// Also draw the authorization URL as a QR code
+    pub qr: bool,
+    /// Don't try to open a browser
+    pub headless: bool,
+    /// Webhook that receives progress events
+    pub notify_url: Option<String>,
+    /// Session lifetime, e.g. '7days'
+    pub expires: String,
+}
+
+impl Default for AuthorizeOptions {
+    fn default() -> Self {
+        Self {
+            preset_names: Vec::new(),
+            file: None,
+            template: None,
+            chain_id: None,
+            rpc_url: None,
+            overwrite: false,
+            print_policies: false,
+            yes: false,
+            qr: false,
+            headless: false,
+            notify_url: None,
+            expires: "7days".to_string(),
+        }
+    }
+}
+
 pub async fn execute(
     config: &Config,
     formatter: &dyn OutputFormatter,
-    preset: Option<String>,
-    file: Option<String>,
-    chain_id: Option<String>,
-    rpc_url: Option<String>,
-    overwrite: bool,
+    options: AuthorizeOptions,
     account: Option<&str>,
-    expires: &str,
 ) -> Result<()> {
+    let AuthorizeOptions {
+        preset_names,
+        file,
+        template,
+        chain_id,
+        rpc_url,
+        overwrite,
+        print_policies,
+        yes,
+        qr,
+        headless,
+        notify_url,
+        expires,
+    } = options;
     // Validate that either preset or file is provided
-    if preset.is_none() && file.is_none() {
+    if preset_names.is_empty() && file.is_none() && template.is_none() {
         return Err(CliError::InvalidInput(
-            "Session policies are required. Use --preset <name> to load a preset policy or --file <path> to provide a local policy JSON file".to_string(),
+            "Session policies are required. Use --preset <name> to load a preset policy, --file <path> to provide a local policy JSON file, or --template <name> --contracts <addresses>".to_string(),
         ));
     }
+    let notifier = notify_url.as_deref().map(Notifier::new).transpose()?;
 
     if let Some(name) = account {
         // Look up the account to verify it resolves to a controller address
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(10))
-            .build()
-            .map_err(|e| CliError::ApiError(format!("Failed to build HTTP client: {e}")))?;
-
-        let response = client
-            .post("https://api.cartridge.gg/accounts/lookup")
-            .header("Content-Type", "application/json")
-            .json(&serde_json::json!({ "usernames": [name] }))
-            .send()
-            .await
-            .map_err(|e| CliError::ApiError(format!("Account lookup failed: {e}")))?;
-
-        if !response.status().is_success() {
-            return Err(CliError::ApiError(format!(
-                "Account lookup failed with status: {}",
-                response.status()
-            )));
-        }
-
-        #[derive(Deserialize)]
-        struct LookupEntry {
-            addresses: Vec<String>,
-        }
-        #[derive(Deserialize)]
-        struct LookupResponse {
-            results: Vec<LookupEntry>,
-        }
-
-        let lookup: LookupResponse = response
-            .json()
-            .await
-            .map_err(|e| CliError::ApiError(format!("Failed to parse lookup response: {e}")))?;
-
-        let address = lookup
-            .results
-            .first()
-            .and_then(|entry| entry.addresses.first())
-            .ok_or_else(|| {
-                CliError::InvalidInput(format!(
-                    "Account '{name}' not found. Verify the account exists on Cartridge."
-                ))
-            })?;
+        let address = crate::commands::lookup::resolve(
+            config,
+            &[name.to_string()],
+            &[],
+            crate::commands::lookup::CacheMode::Default,
+        )
+        .await?
+        .into_iter()
+        .next()
+        .map(|entry| entry.address)
+        .ok_or_else(|| {
+            CliError::InvalidInput(format!(
+                "Account '{name}' not found. Verify the account exists on Cartridge."
+            ))
+        })?;
 
         formatter.info(&format!(
             "Authorizing session for account {name} ({address})"
@@ -200,34 +586,17 @@ pub async fn execute(
         );
         if let Ok(Some(metadata)) = backend.session(&session_key) {
             if !metadata.session.is_expired() && !overwrite {
-                formatter.warning(
-                    "An active session already exists. Authorizing a new session will replace it.",
-                );
-                eprint!("Continue? [y/N] ");
-                let mut input = String::new();
-                std::io::stdin()
-                    .read_line(&mut input)
-                    .map_err(|e| CliError::InvalidInput(format!("Failed to read input: {e}")))?;
-                if !matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
-                    formatter.info("Aborted.");
-                    return Ok(());
-                }
+                return Err(InputRequest::confirm(
+                    "An active session already exists. Authorizing a new session will replace it. Continue?",
+                    "--overwrite",
+                ));
             }
         }
     }
 
     // Map chain_id to RPC URL if provided
     let resolved_rpc_url = if let Some(ref chain_id_str) = chain_id {
-        match chain_id_str.as_str() {
-            "SN_MAIN" => Some("https://api.cartridge.gg/x/starknet/mainnet".to_string()),
-            "SN_SEPOLIA" => Some("https://api.cartridge.gg/x/starknet/sepolia".to_string()),
-            _ => {
-                return Err(CliError::InvalidInput(format!(
-                    "Unsupported chain ID '{chain_id_str}'. Supported chains: SN_MAIN, SN_SEPOLIA. \
-                     For Cartridge SLOT or other chains, use --rpc-url to specify your Katana endpoint."
-                )));
-            }
-        }
+        Some(crate::chains::rpc_url_for(config, chain_id_str)?)
     } else if rpc_url.is_some() {
         rpc_url.clone()
     } else if config.session.rpc_url_explicitly_set {
@@ -243,14 +612,54 @@ pub async fn execute(
     let public_key = format!("0x{:x}", verifying_key.scalar());
     let private_key = signing_key.secret_scalar();
 
-    // Re-open storage as mutable for writes (ensure directory exists for named accounts)
+    // Load policies from every preset and the file, then merge them into one session. Use the
+    // resolved RPC URL or fall back to config default for preset chain detection
+    let preset_rpc_url = resolved_rpc_url.as_ref().unwrap_or(&config.session.rpc_url);
+    let policy_file = resolve_policies(
+        config,
+        formatter,
+        &preset_names,
+        file.as_deref(),
+        template,
+        preset_rpc_url,
+    )
+    .await?;
+
+    let total_contracts = policy_file.contracts.len();
+    let total_entrypoints: usize = policy_file
+        .contracts
+        .values()
+        .map(|c| c.methods.len())
+        .sum();
+    formatter.info(&format!(
+        "Policies loaded: {total_contracts} contracts, {total_entrypoints} entrypoints"
+    ));
+    formatter.info(&format!("Session expiration: {expires}"));
+
+    let review = review_policies(&policy_file)?;
+    print_risk_summary(formatter, &review);
+    crate::commands::selectors::learn(
+        review
+            .contracts
+            .iter()
+            .flat_map(|c| c.methods.iter().map(|m| m.entrypoint.as_str())),
+    );
+
+    if print_policies {
+        print_policy_review(config, formatter, &review);
+        if !yes {
+            return Err(InputRequest::confirm("Authorize these policies?", "--yes"));
+        }
+    }
+
+    // Store the keypair only once the policies are settled, so an aborted review leaves any
+    // existing session untouched. Ensure the directory exists for named accounts.
     if account.is_some() {
         std::fs::create_dir_all(&storage_path)
             .map_err(|e| CliError::Storage(format!("Failed to create account directory: {e}")))?;
     }
     let mut backend = FileSystemBackend::new(storage_path.clone());
 
-    // Store the keypair as session credentials
     let credentials = Credentials {
         private_key,
         authorization: vec![],
@@ -259,90 +668,11 @@ pub async fn execute(
     let credentials_json =
         serde_json::to_string(&credentials).map_err(|e| CliError::InvalidInput(e.to_string()))?;
 
-    backend
-        .set("session_signer", &StorageValue::String(credentials_json))
-        .map_err(|e| CliError::Storage(e.to_string()))?;
-
-    // Load policies from preset or file
-    let policy_file: PolicyFile = if let Some(preset_name) = preset {
-        // Fetch preset from GitHub
-        let preset_config = presets::fetch_preset(&preset_name).await?;
-
-        // Use resolved RPC URL or fall back to config default for preset chain detection
-        let preset_rpc_url = resolved_rpc_url.as_ref().unwrap_or(&config.session.rpc_url);
-        {
-            let rpc_url_str = preset_rpc_url;
-            let provider = starknet::providers::jsonrpc::JsonRpcClient::new(
-                starknet::providers::jsonrpc::HttpTransport::new(
-                    url::Url::parse(rpc_url_str)
-                        .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?,
-                ),
-            );
-
-            let chain_id = starknet::providers::Provider::chain_id(&provider)
-                .await
-                .map_err(|e| {
-                    CliError::InvalidInput(format!("Failed to query chain_id from RPC: {e}"))
-                })?;
-
-            let chain_name = starknet::core::utils::parse_cairo_short_string(&chain_id)
-                .unwrap_or_else(|_| format!("0x{chain_id:x}"));
-
-            // Extract chain-specific policies
-            let chain_policies =
-                presets::extract_chain_policies(&preset_config, &chain_name, &preset_name)?;
-
-            // Convert to PolicyFile format
-            let contracts: std::collections::HashMap<String, ContractPolicy> = chain_policies
-                .contracts
-                .into_iter()
-                .map(|(addr, contract)| {
-                    (
-                        addr,
-                        ContractPolicy {
-                            name: Some(contract.name),
-                            methods: contract
-                                .methods
-                                .into_iter()
-                                .map(|m| MethodPolicy {
-                                    name: m.name,
-                                    entrypoint: m.entrypoint,
-                                    description: m.description,
-                                    amount: None,
-                                    authorized: true,
-                                })
-                                .collect(),
-                        },
-                    )
-                })
-                .collect();
-
-            PolicyFile {
-                contracts,
-                messages: chain_policies.messages,
-            }
-        }
-    } else if let Some(file_path) = file {
-        // Load from local file
-        let policy_content = std::fs::read_to_string(&file_path)
-            .map_err(|e| CliError::InvalidInput(format!("Failed to read policy file: {e}")))?;
-
-        serde_json::from_str(&policy_content)
-            .map_err(|e| CliError::InvalidInput(format!("Invalid policy file format: {e}")))?
-    } else {
-        unreachable!("Either preset or file must be provided");
-    };
-
-    let total_contracts = policy_file.contracts.len();
-    let total_entrypoints: usize = policy_file
-        .contracts
-        .values()
-        .map(|c| c.methods.len())
-        .sum();
-    formatter.info(&format!(
-        "Policies loaded: {total_contracts} contracts, {total_entrypoints} entrypoints"
-    ));
-    formatter.info(&format!("Session expiration: {expires}"));
+    crate::commands::storage::write_value(
+        &storage_path,
+        "session_signer",
+        &StorageValue::String(credentials_json),
+    )?;
 
     // Convert to the format expected by the keychain
     let mut policies = serde_json::json!({
@@ -359,11 +689,7 @@ pub async fn execute(
     if let Some(contracts) = policies.as_object_mut() {
         if let Some(contracts_obj) = contracts.get_mut("contracts") {
             if let Some(contracts_map) = contracts_obj.as_object_mut() {
-                // Sort contracts by address (case-insensitive) to match toWasmPolicies
-                let mut sorted_contracts: Vec<_> = policy_file.contracts.iter().collect();
-                sorted_contracts.sort_by(|(a, _), (b, _)| a.to_lowercase().cmp(&b.to_lowercase()));
-
-                for (address, contract) in &sorted_contracts {
+                for (address, contract, sorted_methods) in canonical_order(&policy_file) {
                     contracts_map.insert(
                         address.to_string(),
                         serde_json::json!({
@@ -379,11 +705,7 @@ pub async fn execute(
                             ))
                         })?;
 
-                    // Sort methods by entrypoint name to match toWasmPolicies
-                    let mut sorted_methods = contract.methods.clone();
-                    sorted_methods.sort_by(|a, b| a.entrypoint.cmp(&b.entrypoint));
-
-                    for method in &sorted_methods {
+                    for method in sorted_methods {
                         // Compute selector from entrypoint name
                         let selector =
                             starknet::core::utils::get_selector_from_name(&method.entrypoint)
@@ -407,7 +729,7 @@ pub async fn execute(
         }
     }
 
-    if let Some(messages) = policy_file.messages {
+    if let Some(messages) = &policy_file.messages {
         policies["messages"] = serde_json::json!(messages);
     }
 
@@ -420,20 +742,14 @@ pub async fn execute(
 
     // If --rpc-url or --chain-id was provided, validate it's a Cartridge RPC endpoint
     if let Some(ref url) = resolved_rpc_url {
-        if !url.starts_with("https://api.cartridge.gg") {
-            return Err(CliError::InvalidInput(
-                "Only Cartridge RPC endpoints are supported. Use: https://api.cartridge.gg/x/starknet/mainnet or https://api.cartridge.gg/x/starknet/sepolia".to_string()
-            ));
-        }
+        crate::chains::ensure_cartridge_rpc(config, url)?;
     }
 
     // Query chain_id from the RPC endpoint to display in authorization URL
-    let provider = starknet::providers::jsonrpc::JsonRpcClient::new(
-        starknet::providers::jsonrpc::HttpTransport::new(
-            url::Url::parse(effective_rpc_url)
-                .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?,
-        ),
-    );
+    let provider = starknet::providers::jsonrpc::JsonRpcClient::new(crate::api::rpc_transport(
+        url::Url::parse(effective_rpc_url)
+            .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?,
+    ));
 
     let detected_chain_name = match starknet::providers::Provider::chain_id(&provider).await {
         Ok(chain_id_felt) => {
@@ -454,7 +770,7 @@ pub async fn execute(
     };
 
     // Parse expiration duration
-    let expires_at = parse_expiration(expires)?;
+    let expires_at = parse_expiration(&expires)?;
 
     // Build the authorization URL
     let mut url = Url::parse(&format!("{}/session", config.session.keychain_url))
@@ -464,7 +780,7 @@ pub async fn execute(
         let mut pairs = url.query_pairs_mut();
         pairs
             .append_pair("public_key", &public_key)
-            .append_pair("redirect_uri", "https://x.cartridge.gg")
+            .append_pair("redirect_uri", &config.session.keychain_url)
             .append_pair("policies", &policies_json)
             .append_pair("rpc_url", effective_rpc_url)
             .append_pair("expires_at", &expires_at.to_string())
@@ -484,20 +800,40 @@ pub async fn execute(
 
     // Show URL and start polling
     let display_url = short_url.as_deref().unwrap_or(&authorization_url);
-    try_open_authorization_url(formatter, display_url);
+    let headless = headless || browser_unavailable();
+    if !headless {
+        try_open_authorization_url(formatter, display_url);
+    }
+    let code = headless.then(|| device_code(&public_key));
 
     let output = AuthorizeOutput {
         authorization_url: authorization_url.clone(),
         short_url: short_url.clone(),
         public_key: public_key.clone(),
-        expires: expires.to_string(),
-        message:
+        expires,
+        code: code.clone(),
+        message: if headless {
+            "Open this URL on any device with a browser; its public_key ends in the code. Waiting for authorization..."
+                .to_string()
+        } else {
             "Open this URL in your browser to authorize the session. Waiting for authorization..."
-                .to_string(),
+                .to_string()
+        },
     };
 
     if config.cli.json_output {
         formatter.success(&output);
+    } else if let Some(code) = &code {
+        let chain = detected_chain_name
+            .map(|name| format!(" ({name})"))
+            .unwrap_or_default();
+        formatter.info(&format!(
+            "No browser here. On any device with a browser, open{chain}:"
+        ));
+        println!("\n  {display_url}\n");
+        formatter.info(&format!(
+            "Code: {code} (the end of the session key in the URL; check it before approving)\n"
+        ));
     } else {
         if let Some(chain_name) = detected_chain_name {
             formatter.info(&format!("Authorization URL ({chain_name}):"));
@@ -505,33 +841,80 @@ pub async fn execute(
             formatter.info("Authorization URL:");
         }
         println!("\n{display_url}\n");
+    }
+    // With --json the document owns stdout, so a requested code goes to stderr
+    let (terminal, term) = if config.cli.json_output {
+        (std::io::stderr().is_terminal(), console::Term::stderr())
+    } else {
+        (std::io::stdout().is_terminal(), console::Term::stdout())
+    };
+    if qr || (terminal && !config.cli.json_output) {
+        let columns = term.size_checked().map(|(_, columns)| columns as usize);
+        match render_qr(display_url, columns) {
+            Some(code) if terminal && config.cli.json_output => eprintln!("{code}"),
+            Some(code) if terminal => println!("{code}"),
+            _ if qr => formatter.warning(
+                "Terminal too small or not a terminal, skipping the QR code. Open the URL above instead.",
+            ),
+            _ => {}
+        }
+    }
+    if !config.cli.json_output {
         formatter.info("Waiting for authorization...");
     }
 
     // Calculate session_key_guid for long-polling query
     // GUID = poseidon_hash("Starknet Signer", public_key)
     let session_key_guid = {
-        use starknet::macros::short_string;
-        use starknet_crypto::poseidon_hash;
-
         let pubkey_felt = starknet::core::types::Felt::from_hex(&public_key)
             .map_err(|e| CliError::InvalidInput(format!("Invalid public key: {e}")))?;
-
-        let guid = poseidon_hash(short_string!("Starknet Signer"), pubkey_felt);
-        format!("0x{guid:x}")
+        format!("0x{:x}", session_key_guid(pubkey_felt))
     };
+    let event = serde_json::json!({
+        "account": account,
+        "public_key": public_key,
+        "session_key_guid": session_key_guid,
+        "authorization_url": authorization_url,
+        "short_url": short_url,
+        "expires_at": expires_at,
+    });
+    if let Some(notifier) = &notifier {
+        notifier.send(formatter, "url_generated", &event).await;
+    }
 
     // Query with long-polling (backend holds connection for ~2 minutes)
     // Retry if backend times out without finding session
     let max_attempts = 3; // 3 attempts × 2min = ~6 minutes total
     let mut attempts = 0;
+    let started = std::time::Instant::now();
 
     loop {
         attempts += 1;
 
-        match api::query_session_info(&config.session.api_url, &session_key_guid).await? {
+        let query = api::query_session_info(&config.session.api_url, &session_key_guid);
+        let result = if headless {
+            with_progress(query, config, formatter, started, max_attempts * 120).await
+        } else {
+            query.await
+        };
+        let session_info = match result {
+            // Keep waiting for the user rather than losing the pending authorization
+            Err(CliError::RateLimited { retry_after }) if attempts < max_attempts => {
+                let delay = retry_after.unwrap_or(10);
+                formatter.warning(&format!(
+                    "Rate limited while waiting for authorization, retrying in {delay}s"
+                ));
+                tokio::time::sleep(std::time::Duration::from_secs(delay)).await;
+                crate::meta::retries(1);
+                continue;
+            }
+            result => result?,
+        };
+
+        match session_info {
             Some(session_info) => {
                 let chain_id = session_info.chain_id.clone();
+                let address = session_info.controller.address.clone();
 
                 // Store the session with policies
                 store_session_from_api(
@@ -555,12 +938,15 @@ pub async fn execute(
                 // Store policies for display in status command
                 let policies_storage = PolicyStorage {
                     contracts: policy_file.contracts.clone(),
+                    messages: policy_file.messages.clone(),
                 };
                 let policies_json = serde_json::to_string(&policies_storage)
                     .map_err(|e| CliError::Storage(format!("Failed to serialize policies: {e}")))?;
-                backend
-                    .set("session_policies", &StorageValue::String(policies_json))
-                    .map_err(|e| CliError::Storage(e.to_string()))?;
+                crate::commands::storage::write_value(
+                    &storage_path,
+                    "session_policies",
+                    &StorageValue::String(policies_json),
+                )?;
                 backend
                     .set(
                         "session_key_guid",
@@ -577,12 +963,21 @@ pub async fn execute(
                 } else {
                     formatter.info("Session authorized and stored successfully.");
                 }
+                if let Some(notifier) = &notifier {
+                    let mut event = event.clone();
+                    event["address"] = serde_json::json!(address);
+                    event["chain_id"] = serde_json::json!(chain_id);
+                    notifier.send(formatter, "authorized", &event).await;
+                }
 
                 return Ok(());
             }
             None => {
                 // Backend timed out without finding session
                 if attempts >= max_attempts {
+                    if let Some(notifier) = &notifier {
+                        notifier.send(formatter, "timeout", &event).await;
+                    }
                     return Err(CliError::CallbackTimeout(max_attempts * 120)); // ~6 minutes
                 }
                 // Backend will retry automatically on next call
@@ -592,6 +987,86 @@ pub async fn execute(
     }
 }
 
+/// Await `query`, reporting every PROGRESS_SECS how long authorization has been pending, so a
+/// headless session over SSH or in `docker logs` visibly isn't stuck.
+async fn with_progress<T>(
+    query: impl std::future::Future<Output = T>,
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    started: std::time::Instant,
+    timeout_secs: u64,
+) -> T {
+    const PROGRESS_SECS: u64 = 15;
+    tokio::pin!(query);
+    let period = std::time::Duration::from_secs(PROGRESS_SECS);
+    let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+    loop {
+        tokio::select! {
+            result = &mut query => return result,
+            _ = ticker.tick() => {
+                let elapsed = started.elapsed().as_secs();
+                let message = format!(
+                    "Still waiting for authorization ({} elapsed, times out in {})",
+                    format_elapsed(elapsed),
+                    format_elapsed(timeout_secs.saturating_sub(elapsed)),
+                );
+                // With --json the document owns stdout
+                if config.cli.json_output {
+                    eprintln!("{message}");
+                } else {
+                    formatter.info(&message);
+                }
+            }
+        }
+    }
+}
+
+/// POSTs `session auth` progress to `--notify-url`, so an orchestrator provisioning many accounts
+/// can tell which still wait for a human. Delivery is best effort: failures only warn.
+struct Notifier {
+    url: Url,
+    client: reqwest::Client,
+}
+
+impl Notifier {
+    fn new(url: &str) -> Result<Self> {
+        let url = Url::parse(url)
+            .ok()
+            .filter(|url| matches!(url.scheme(), "http" | "https"))
+            .ok_or_else(|| {
+                CliError::InvalidInput(format!(
+                    "Invalid --notify-url '{url}': expected an http(s) URL"
+                ))
+            })?;
+        let client = api::http_client(std::time::Duration::from_secs(10))?;
+        Ok(Self { url, client })
+    }
+
+    /// Send `{event, timestamp, ...fields}`
+    async fn send(&self, formatter: &dyn OutputFormatter, event: &str, fields: &serde_json::Value) {
+        let mut body = serde_json::json!({
+            "event": event,
+            "timestamp": SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+        });
+        if let (Some(body), Some(fields)) = (body.as_object_mut(), fields.as_object()) {
+            body.extend(fields.clone());
+        }
+        let sent = self
+            .client
+            .post(self.url.clone())
+            .json(&body)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        if let Err(e) = sent {
+            formatter.warning(&format!("Failed to notify {} of '{event}': {e}", self.url));
+        }
+    }
+}
+
 /// Store session credentials from API response
 fn store_session_from_api(
     backend: &mut FileSystemBackend,
@@ -706,6 +1181,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn derives_device_codes_from_the_session_key() {
+        assert_eq!(device_code("0x4b1d3e5f7a9c0012ab34cd56"), "AB34-CD56");
+        assert_eq!(device_code("0x1f"), "0000-001F");
+        assert_eq!(format_elapsed(65), "1m 05s");
+    }
+
+    #[test]
+    fn renders_qr_only_when_it_fits() {
+        let url = "https://x.cartridge.gg/s/abc123";
+        let code = render_qr(url, None).unwrap();
+        let width = code.lines().next().unwrap().chars().count();
+        assert!(code.lines().all(|line| line.chars().count() == width));
+        assert!(render_qr(url, Some(width)).is_some());
+        assert!(render_qr(url, Some(width - 1)).is_none());
+    }
+
+    #[test]
+    fn accepts_only_http_notify_urls() {
+        assert!(Notifier::new("https://orchestrator.example/hooks/auth").is_ok());
+        assert!(Notifier::new("http://localhost:8080").is_ok());
+        assert!(Notifier::new("ftp://example.com").is_err());
+        assert!(Notifier::new("not a url").is_err());
+    }
+
     #[test]
     fn opens_authorization_url_when_opener_succeeds() {
         let formatter = TestFormatter::default();
@@ -740,4 +1240,121 @@ mod tests {
         assert_eq!(warnings.len(), 1);
         assert!(warnings[0].contains("Could not open browser automatically: mock failure"));
     }
+
+    fn method(entrypoint: &str) -> MethodPolicy {
+        MethodPolicy {
+            name: entrypoint.to_string(),
+            entrypoint: entrypoint.to_string(),
+            description: None,
+            amount: None,
+            authorized: true,
+        }
+    }
+
+    #[test]
+    fn review_uses_canonical_hash_order() {
+        let policy_file = PolicyFile {
+            contracts: std::collections::HashMap::from([
+                (
+                    "0xBB".to_string(),
+                    ContractPolicy {
+                        name: None,
+                        methods: vec![method("transfer"), method("approve")],
+                    },
+                ),
+                (
+                    "0xaa".to_string(),
+                    ContractPolicy {
+                        name: Some("Game".to_string()),
+                        methods: vec![method("spawn")],
+                    },
+                ),
+            ]),
+            messages: None,
+        };
+
+        let review = review_policies(&policy_file).unwrap();
+        let addresses: Vec<_> = review
+            .contracts
+            .iter()
+            .map(|c| c.address.as_str())
+            .collect();
+        assert_eq!(addresses, vec!["0xaa", "0xBB"]);
+
+        let entrypoints: Vec<_> = review.contracts[1]
+            .methods
+            .iter()
+            .map(|m| m.entrypoint.as_str())
+            .collect();
+        assert_eq!(entrypoints, vec!["approve", "transfer"]);
+        assert_eq!(review.contracts[1].methods[0].risk, Risk::Approval);
+        assert_eq!(review.contracts[0].methods[0].risk, Risk::Unknown);
+        assert_eq!(
+            review.contracts[1].methods[1].selector,
+            format!(
+                "0x{:x}",
+                starknet::core::utils::get_selector_from_name("transfer").unwrap()
+            )
+        );
+    }
+
+    fn policy_file(contracts: Vec<(&str, Vec<MethodPolicy>)>) -> PolicyFile {
+        PolicyFile {
+            contracts: contracts
+                .into_iter()
+                .map(|(address, methods)| {
+                    (
+                        address.to_string(),
+                        ContractPolicy {
+                            name: None,
+                            methods,
+                        },
+                    )
+                })
+                .collect(),
+            messages: None,
+        }
+    }
+
+    #[test]
+    fn merges_presets_and_deduplicates_methods() {
+        let merged = merge_policy_files(vec![
+            (
+                "preset 'a'".to_string(),
+                policy_file(vec![("0x0abc", vec![method("spawn"), method("move")])]),
+            ),
+            (
+                "preset 'b'".to_string(),
+                policy_file(vec![
+                    ("0xABC", vec![method("move"), method("attack")]),
+                    ("0xdef", vec![method("transfer")]),
+                ]),
+            ),
+        ])
+        .unwrap();
+
+        assert_eq!(merged.contracts.len(), 2);
+        let game = &merged.contracts["0x0abc"];
+        let entrypoints: Vec<_> = game.methods.iter().map(|m| m.entrypoint.as_str()).collect();
+        assert_eq!(entrypoints, vec!["spawn", "move", "attack"]);
+    }
+
+    #[test]
+    fn rejects_conflicting_method_policies() {
+        let mut denied = method("transfer");
+        denied.authorized = false;
+
+        let err = merge_policy_files(vec![
+            (
+                "preset 'a'".to_string(),
+                policy_file(vec![("0x1", vec![method("transfer")])]),
+            ),
+            (
+                "file 'p.json'".to_string(),
+                policy_file(vec![("0x01", vec![denied])]),
+            ),
+        ])
+        .unwrap_err();
+        assert!(err.to_string().contains("preset 'a' and file 'p.json'"));
+    }
 }
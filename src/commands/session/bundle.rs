@@ -0,0 +1,410 @@
+use crate::{
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use account_sdk::storage::{filestorage::FileSystemBackend, StorageBackend};
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Prefix of exported bundles, so a pasted blob is recognized (and versioned)
+const BLOB_PREFIX: &str = "controller-session-v1:";
+
+/// scrypt cost for passphrase-encrypted bundles (N = 2^15, r = 8, p = 1)
+const SCRYPT_LOG_N: u8 = 15;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+
+/// The session's storage files plus what they hold, for display before importing
+#[derive(Serialize, Deserialize)]
+struct Bundle {
+    address: String,
+    chain_id: String,
+    expires_at: u64,
+    exported_at: String,
+    /// Storage file name to content
+    files: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    /// Set when the bundle is encrypted with a passphrase
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kdf: Option<Kdf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nonce: Option<String>,
+    /// Base64 of the bundle JSON, or of its AES-256-GCM ciphertext
+    data: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Kdf {
+    salt: String,
+    log_n: u8,
+    r: u32,
+    p: u32,
+}
+
+#[derive(Serialize)]
+pub struct ExportOutput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blob: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub encrypted: bool,
+    pub address: String,
+    pub chain_id: String,
+    pub expires_at: u64,
+}
+
+#[derive(Serialize)]
+pub struct ImportOutput {
+    pub address: String,
+    pub chain_id: String,
+    pub expires_at: u64,
+    pub storage_path: String,
+    pub files: usize,
+}
+
+/// Whether a storage file belongs to the session: the signer, policies and chain/RPC keys
+/// (`session_*`) and the SDK's controller and session metadata (`@cartridge/...` keys). Caches,
+/// history and the config file that share the directory stay behind.
+fn is_session_file(name: &str) -> bool {
+    !name.contains(['/', '\\'])
+        && name.ends_with(".json")
+        && (name.starts_with("session") || name.contains("cartridge"))
+}
+
+/// Serialize the current session into a single blob, encrypted when a passphrase is given, for
+/// `session import` on another machine.
+pub async fn export(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    passphrase: Option<String>,
+    out: Option<String>,
+    account: Option<&str>,
+) -> Result<()> {
+    let storage_path = config.resolve_storage_path(account);
+    let backend = FileSystemBackend::new(storage_path.clone());
+    let controller = backend
+        .controller()
+        .map_err(|e| CliError::Storage(e.to_string()))?
+        .ok_or(CliError::NoSession)?;
+    let session_key = format!(
+        "@cartridge/session/0x{:x}/0x{:x}",
+        controller.address, controller.chain_id
+    );
+    let session = backend
+        .session(&session_key)
+        .map_err(|e| CliError::Storage(e.to_string()))?
+        .ok_or(CliError::NoSession)?;
+
+    let mut files = BTreeMap::new();
+    let entries = std::fs::read_dir(&storage_path).map_err(|e| CliError::FileError {
+        path: storage_path.display().to_string(),
+        message: e.to_string(),
+    })?;
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !entry.path().is_file() || !is_session_file(&name) {
+            continue;
+        }
+        let content = std::fs::read_to_string(entry.path()).map_err(|e| CliError::FileError {
+            path: entry.path().display().to_string(),
+            message: e.to_string(),
+        })?;
+        files.insert(name, content);
+    }
+
+    let bundle = Bundle {
+        address: format!("0x{:x}", controller.address),
+        chain_id: starknet::core::utils::parse_cairo_short_string(&controller.chain_id)
+            .unwrap_or_else(|_| format!("0x{:x}", controller.chain_id)),
+        expires_at: session.session.inner.expires_at,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        files,
+    };
+    let encrypted = passphrase.is_some();
+    let blob = seal(&bundle, passphrase.as_deref())?;
+    if !encrypted {
+        formatter.warning(
+            "The export is not encrypted: anyone holding it can use the session until it expires. \
+             Pass --encrypt <passphrase> to protect it",
+        );
+    }
+
+    let path = match &out {
+        Some(path) => {
+            super::super::storage::write_atomic(Path::new(path), format!("{blob}\n")).map_err(
+                |e| CliError::FileError {
+                    path: path.clone(),
+                    message: e.to_string(),
+                },
+            )?;
+            Some(path.clone())
+        }
+        None if !config.cli.json_output => {
+            // Only the blob on stdout, so it can be redirected or piped to `session import`
+            println!("{blob}");
+            return Ok(());
+        }
+        None => None,
+    };
+
+    formatter.success(&ExportOutput {
+        blob: path.is_none().then_some(blob),
+        path,
+        encrypted,
+        address: bundle.address,
+        chain_id: bundle.chain_id,
+        expires_at: bundle.expires_at,
+    });
+    Ok(())
+}
+
+/// Restore a session exported with `session export` into this machine's storage. The bundle is
+/// checked in a staging directory before anything is replaced.
+#[allow(clippy::too_many_arguments)]
+pub async fn import(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    blob: Option<String>,
+    file: Option<String>,
+    passphrase: Option<String>,
+    overwrite: bool,
+    account: Option<&str>,
+) -> Result<()> {
+    let blob = match (blob, file) {
+        (Some(blob), _) => blob,
+        (None, Some(file)) => std::fs::read_to_string(&file).map_err(|e| CliError::FileError {
+            path: file.clone(),
+            message: e.to_string(),
+        })?,
+        (None, None) => {
+            let mut blob = String::new();
+            std::io::Read::read_to_string(&mut std::io::stdin(), &mut blob)
+                .map_err(|e| CliError::InvalidInput(format!("Failed to read stdin: {e}")))?;
+            blob
+        }
+    };
+    let bundle = open(&blob, passphrase.as_deref())?;
+    if let Some(name) = bundle.files.keys().find(|name| !is_session_file(name)) {
+        return Err(CliError::InvalidInput(format!(
+            "Session bundle contains an unexpected file '{name}'"
+        )));
+    }
+
+    let storage_path = config.resolve_storage_path(account);
+    let backend = FileSystemBackend::new(storage_path.clone());
+    if !overwrite {
+        if let Some(existing) = backend.controller().ok().flatten() {
+            return Err(CliError::InvalidInput(format!(
+                "A session for 0x{:x} is already stored. Pass --overwrite to replace it",
+                existing.address
+            )));
+        }
+    }
+
+    let staging = storage_path.join(format!(".import-{}", std::process::id()));
+    let staged = stage(&staging, &bundle);
+    let _ = std::fs::remove_dir_all(&staging);
+    staged?;
+
+    // Drop the previous session's files so none of them outlive the import
+    if let Ok(entries) = std::fs::read_dir(&storage_path) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if is_session_file(&name) && !bundle.files.contains_key(&name) {
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+    }
+    for (name, content) in &bundle.files {
+        let path = storage_path.join(name);
+        super::super::storage::write_atomic(&path, content).map_err(|e| CliError::FileError {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+    }
+
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    if bundle.expires_at <= now {
+        formatter.warning("The imported session has expired; run 'controller session auth'");
+    }
+    formatter.success(&ImportOutput {
+        address: bundle.address,
+        chain_id: bundle.chain_id,
+        expires_at: bundle.expires_at,
+        storage_path: storage_path.display().to_string(),
+        files: bundle.files.len(),
+    });
+    Ok(())
+}
+
+/// Write the bundle to `staging` and check the SDK can load its controller and session
+fn stage(staging: &Path, bundle: &Bundle) -> Result<()> {
+    for (name, content) in &bundle.files {
+        let path = staging.join(name);
+        super::super::storage::write_atomic(&path, content).map_err(|e| CliError::FileError {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+    }
+    let backend = FileSystemBackend::new(staging.to_path_buf());
+    let controller = backend.controller().ok().flatten().ok_or_else(|| {
+        CliError::InvalidSessionData("Session bundle has no controller metadata".to_string())
+    })?;
+    let session_key = format!(
+        "@cartridge/session/0x{:x}/0x{:x}",
+        controller.address, controller.chain_id
+    );
+    backend
+        .session(&session_key)
+        .ok()
+        .flatten()
+        .and_then(|session| session.credentials)
+        .ok_or_else(|| {
+            CliError::InvalidSessionData("Session bundle has no session credentials".to_string())
+        })?;
+    Ok(())
+}
+
+fn seal(bundle: &Bundle, passphrase: Option<&str>) -> Result<String> {
+    let json = serde_json::to_vec(bundle).map_err(|e| CliError::Other(e.into()))?;
+    let envelope = match passphrase {
+        None => Envelope {
+            kdf: None,
+            nonce: None,
+            data: STANDARD.encode(json),
+        },
+        Some(passphrase) => {
+            let mut salt = [0u8; 16];
+            let mut nonce = [0u8; 12];
+            rand::thread_rng().fill_bytes(&mut salt);
+            rand::thread_rng().fill_bytes(&mut nonce);
+            let kdf = Kdf {
+                salt: STANDARD.encode(salt),
+                log_n: SCRYPT_LOG_N,
+                r: SCRYPT_R,
+                p: SCRYPT_P,
+            };
+            let cipher = cipher(passphrase, &salt, &kdf)?;
+            let data = cipher
+                .encrypt(Nonce::from_slice(&nonce), json.as_slice())
+                .map_err(|_| CliError::Other(anyhow::anyhow!("Failed to encrypt the bundle")))?;
+            Envelope {
+                kdf: Some(kdf),
+                nonce: Some(STANDARD.encode(nonce)),
+                data: STANDARD.encode(data),
+            }
+        }
+    };
+    let envelope = serde_json::to_vec(&envelope).map_err(|e| CliError::Other(e.into()))?;
+    Ok(format!("{BLOB_PREFIX}{}", STANDARD.encode(envelope)))
+}
+
+fn open(blob: &str, passphrase: Option<&str>) -> Result<Bundle> {
+    let invalid = || {
+        CliError::InvalidInput("Not a session bundle from 'controller session export'".to_string())
+    };
+    let encoded = blob.trim().strip_prefix(BLOB_PREFIX).ok_or_else(invalid)?;
+    let envelope: Envelope = STANDARD
+        .decode(encoded)
+        .ok()
+        .and_then(|json| serde_json::from_slice(&json).ok())
+        .ok_or_else(invalid)?;
+    let data = STANDARD.decode(&envelope.data).map_err(|_| invalid())?;
+
+    let json = match (&envelope.kdf, envelope.nonce.as_deref()) {
+        (None, _) => data,
+        (Some(kdf), Some(nonce)) => {
+            let passphrase = passphrase.ok_or_else(|| {
+                CliError::InvalidInput(
+                    "The session bundle is encrypted. Pass --passphrase".to_string(),
+                )
+            })?;
+            // The parameters come from the bundle: never spend more memory or CPU than `seal`
+            if kdf.log_n > SCRYPT_LOG_N || kdf.r > SCRYPT_R || kdf.p > SCRYPT_P {
+                return Err(CliError::InvalidInput(format!(
+                    "Session bundle asks for scrypt parameters above log_n={SCRYPT_LOG_N}, r={SCRYPT_R}, p={SCRYPT_P}"
+                )));
+            }
+            let salt = STANDARD.decode(&kdf.salt).map_err(|_| invalid())?;
+            let nonce = STANDARD.decode(nonce).map_err(|_| invalid())?;
+            if nonce.len() != 12 {
+                return Err(invalid());
+            }
+            cipher(passphrase, &salt, kdf)?
+                .decrypt(Nonce::from_slice(&nonce), data.as_slice())
+                .map_err(|_| {
+                    CliError::InvalidInput(
+                        "Wrong passphrase, or the session bundle was modified".to_string(),
+                    )
+                })?
+        }
+        (Some(_), None) => return Err(invalid()),
+    };
+    serde_json::from_slice(&json).map_err(|_| invalid())
+}
+
+fn cipher(passphrase: &str, salt: &[u8], kdf: &Kdf) -> Result<Aes256Gcm> {
+    let params = scrypt::Params::new(kdf.log_n, kdf.r, kdf.p).map_err(|e| {
+        CliError::InvalidInput(format!("Invalid bundle encryption parameters: {e}"))
+    })?;
+    let mut key = [0u8; 32];
+    scrypt::scrypt(passphrase.as_bytes(), salt, &params, &mut key)
+        .map_err(|e| CliError::Other(anyhow::anyhow!("Failed to derive the bundle key: {e}")))?;
+    Aes256Gcm::new_from_slice(&key)
+        .map_err(|e| CliError::Other(anyhow::anyhow!("Failed to derive the bundle key: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundle() -> Bundle {
+        Bundle {
+            address: "0x123".to_string(),
+            chain_id: "SN_SEPOLIA".to_string(),
+            expires_at: 1_900_000_000,
+            exported_at: "2026-01-01T00:00:00Z".to_string(),
+            files: BTreeMap::from([(
+                "session_signer.json".to_string(),
+                "{\"private_key\":\"0x1\"}".to_string(),
+            )]),
+        }
+    }
+
+    #[test]
+    fn seals_and_opens_bundles() {
+        let plain = seal(&bundle(), None).unwrap();
+        assert!(plain.starts_with(BLOB_PREFIX));
+        assert_eq!(open(&plain, None).unwrap().files, bundle().files);
+
+        let sealed = seal(&bundle(), Some("hunter2")).unwrap();
+        let opened = open(&format!("  {sealed}\n"), Some("hunter2")).unwrap();
+        assert_eq!(opened.files, bundle().files);
+        assert!(open(&sealed, Some("wrong")).is_err());
+        assert!(open(&sealed, None).is_err());
+        assert!(open("not a bundle", None).is_err());
+
+        let envelope = sealed.strip_prefix(BLOB_PREFIX).unwrap();
+        let mut envelope: serde_json::Value =
+            serde_json::from_slice(&STANDARD.decode(envelope).unwrap()).unwrap();
+        envelope["kdf"]["log_n"] = 40.into();
+        let costly = format!("{BLOB_PREFIX}{}", STANDARD.encode(envelope.to_string()));
+        let err = open(&costly, Some("hunter2")).unwrap_err().to_string();
+        assert!(err.contains("scrypt parameters"), "{err}");
+
+        assert!(is_session_file("session_signer.json"));
+        assert!(!is_session_file("config_history.json"));
+        assert!(!is_session_file("../cartridge.json"));
+    }
+}
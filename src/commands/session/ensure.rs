@@ -0,0 +1,261 @@
+use super::authorize::{self, AuthorizeOptions, PolicyFile, PolicyStorage};
+use crate::{
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use account_sdk::storage::{filestorage::FileSystemBackend, StorageBackend, StorageValue};
+use serde::Serialize;
+use starknet::core::types::Felt;
+
+#[derive(Serialize)]
+pub struct EnsureOutput {
+    pub status: String,
+    pub address: String,
+    pub chain_id: String,
+    pub expires_at: u64,
+    pub expires_in_seconds: u64,
+}
+
+/// Keep the session that matches the requested policies and chain and lives at least `min_ttl`,
+/// or run the full `session auth` flow to replace it. Safe to re-run from bootstrap scripts.
+pub async fn execute(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    options: AuthorizeOptions,
+    min_ttl: &str,
+    account: Option<&str>,
+) -> Result<()> {
+    let AuthorizeOptions {
+        preset_names,
+        file,
+        template,
+        chain_id,
+        rpc_url,
+        ..
+    } = &options;
+    if preset_names.is_empty() && file.is_none() && template.is_none() {
+        return Err(CliError::InvalidInput(
+            "Session policies are required. Use --preset <name>, --file <path>, or --template <name> --contracts <addresses>".to_string(),
+        ));
+    }
+    let required_until = authorize::parse_expiration(min_ttl)?;
+
+    let requested_rpc_url = match (chain_id, rpc_url) {
+        (Some(chain_id), _) => crate::chains::rpc_url_for(config, chain_id)?,
+        (None, Some(url)) => url.clone(),
+        (None, None) => config.session.rpc_url.clone(),
+    };
+    let provider = starknet::providers::jsonrpc::JsonRpcClient::new(crate::api::rpc_transport(
+        url::Url::parse(&requested_rpc_url)
+            .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?,
+    ));
+    let chain_name = crate::chains::chain_name(&provider).await?;
+    let requested = authorize::resolve_policies(
+        config,
+        formatter,
+        preset_names,
+        file.as_deref(),
+        template.clone(),
+        &requested_rpc_url,
+    )
+    .await?;
+
+    match existing_session(
+        config,
+        formatter,
+        &requested,
+        &chain_name,
+        required_until,
+        account,
+    )
+    .await?
+    {
+        Ok(output) => {
+            formatter.success(&output);
+            Ok(())
+        }
+        Err(reason) => {
+            formatter.info(&format!("{reason}; authorizing a new session"));
+            authorize::execute(
+                config,
+                formatter,
+                AuthorizeOptions {
+                    overwrite: true,
+                    print_policies: false,
+                    ..options
+                },
+                account,
+            )
+            .await
+        }
+    }
+}
+
+/// The stored session if it can be kept, or why it can't.
+async fn existing_session(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    requested: &PolicyFile,
+    chain_name: &str,
+    required_until: u64,
+    account: Option<&str>,
+) -> Result<std::result::Result<EnsureOutput, String>> {
+    let storage_path = config.resolve_storage_path(account);
+    let backend = FileSystemBackend::new(storage_path.clone());
+
+    let Some(controller) = backend.controller().ok().flatten() else {
+        return Ok(Err("No session".to_string()));
+    };
+    let session_chain = starknet::core::utils::parse_cairo_short_string(&controller.chain_id)
+        .unwrap_or_else(|_| format!("0x{:x}", controller.chain_id));
+    if session_chain != chain_name {
+        return Ok(Err(format!(
+            "Session is for {session_chain}, not {chain_name}"
+        )));
+    }
+
+    let session_key = format!(
+        "@cartridge/session/0x{:x}/0x{:x}",
+        controller.address, controller.chain_id
+    );
+    let Some(metadata) = backend.session(&session_key).ok().flatten() else {
+        return Ok(Err("No session".to_string()));
+    };
+    let expires_at = metadata.session.inner.expires_at;
+    if metadata.session.is_expired() {
+        return Ok(Err("Session has expired".to_string()));
+    }
+    if expires_at < required_until {
+        return Ok(Err("Session expires sooner than --min-ttl".to_string()));
+    }
+
+    let stored: Option<PolicyStorage> = match backend.get("session_policies").ok().flatten() {
+        Some(StorageValue::String(json)) => serde_json::from_str(&json).ok(),
+        _ => None,
+    };
+    let Some(stored) = stored else {
+        return Ok(Err("Session policies are unknown".to_string()));
+    };
+    if let Some(missing) = uncovered(&stored, requested) {
+        return Ok(Err(format!("Session does not allow {missing}")));
+    }
+
+    match super::revocation::ensure_not_revoked(config, formatter, &storage_path, &session_key)
+        .await
+    {
+        Ok(()) => {}
+        Err(CliError::SessionRevoked) => return Ok(Err("Session was revoked".to_string())),
+        Err(e) => return Err(e),
+    }
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    Ok(Ok(EnsureOutput {
+        status: "active".to_string(),
+        address: format!("0x{:x}", controller.address),
+        chain_id: session_chain,
+        expires_at,
+        expires_in_seconds: expires_at.saturating_sub(now),
+    }))
+}
+
+/// The first requested policy the stored session lacks, if any. Addresses compare as felts and
+/// methods must agree on `authorized` and spending `amount`.
+fn uncovered(stored: &PolicyStorage, requested: &PolicyFile) -> Option<String> {
+    let felt = |address: &str| Felt::from_hex(address).ok();
+    for (address, contract) in &requested.contracts {
+        let granted = stored
+            .contracts
+            .iter()
+            .find(|(stored_address, _)| felt(stored_address) == felt(address))
+            .map(|(_, policy)| policy);
+        for method in &contract.methods {
+            let covered = granted.is_some_and(|granted| {
+                granted.methods.iter().any(|m| {
+                    m.entrypoint == method.entrypoint
+                        && m.authorized == method.authorized
+                        && m.amount == method.amount
+                })
+            });
+            if !covered {
+                return Some(format!("{} on {address}", method.entrypoint));
+            }
+        }
+    }
+    let stored_messages = stored.messages.as_deref().unwrap_or_default();
+    requested
+        .messages
+        .iter()
+        .flatten()
+        .find(|message| !stored_messages.contains(message))
+        .map(|message| {
+            format!(
+                "typed data '{}'",
+                message["primaryType"].as_str().unwrap_or("unknown")
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::session::authorize::{ContractPolicy, MethodPolicy};
+    use std::collections::HashMap;
+
+    fn contracts(address: &str, entrypoints: &[&str]) -> HashMap<String, ContractPolicy> {
+        let methods = entrypoints
+            .iter()
+            .map(|entrypoint| MethodPolicy {
+                name: entrypoint.to_string(),
+                entrypoint: entrypoint.to_string(),
+                description: None,
+                amount: None,
+                authorized: true,
+            })
+            .collect();
+        HashMap::from([(
+            address.to_string(),
+            ContractPolicy {
+                name: None,
+                methods,
+            },
+        )])
+    }
+
+    #[test]
+    fn detects_policies_missing_from_the_session() {
+        let stored = PolicyStorage {
+            contracts: contracts("0x0abc", &["move", "attack"]),
+            messages: None,
+        };
+        let subset = PolicyFile {
+            contracts: contracts("0xABC", &["move"]),
+            messages: None,
+        };
+        assert_eq!(uncovered(&stored, &subset), None);
+
+        let wider = PolicyFile {
+            contracts: contracts("0xabc", &["move", "flee"]),
+            messages: None,
+        };
+        assert_eq!(uncovered(&stored, &wider).as_deref(), Some("flee on 0xabc"));
+
+        let other_contract = PolicyFile {
+            contracts: contracts("0xdef", &["move"]),
+            messages: None,
+        };
+        assert!(uncovered(&stored, &other_contract).is_some());
+
+        let with_message = PolicyFile {
+            contracts: HashMap::new(),
+            messages: Some(vec![serde_json::json!({ "primaryType": "Move" })]),
+        };
+        assert_eq!(
+            uncovered(&stored, &with_message).as_deref(),
+            Some("typed data 'Move'")
+        );
+    }
+}
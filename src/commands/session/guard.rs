@@ -0,0 +1,318 @@
+use crate::{
+    commands::audit::{AuditEntry, AUDIT_FILE},
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use serde::Serialize;
+use starknet::core::types::Felt;
+use std::collections::{HashSet, VecDeque};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Window the per-hour limits are counted over, in seconds
+const WINDOW_SECS: i64 = 3600;
+
+/// Expectations an account's submissions are checked against. Unset limits are not checked.
+#[derive(Debug, Default, Clone)]
+pub struct GuardLimits {
+    pub max_transactions_per_hour: Option<usize>,
+    pub max_transfers_per_hour: Option<usize>,
+    /// Alert on calls to a contract the account never called before the guard started
+    pub new_contracts: bool,
+}
+
+/// One deviation, printed as an NDJSON line with `--json`
+#[derive(Debug, Serialize)]
+pub struct Alert {
+    pub event: &'static str,
+    /// 'transaction_rate', 'transfer_rate' or 'new_contract'
+    pub rule: &'static str,
+    pub message: String,
+    pub timestamp: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract_address: Option<String>,
+}
+
+/// Rolling state of the account's submissions: contracts it has called and, for the last hour,
+/// when it submitted and how many transfers each submission made.
+struct Guard {
+    limits: GuardLimits,
+    transfer_selector: Felt,
+    known_contracts: HashSet<Felt>,
+    window: VecDeque<(i64, usize)>,
+}
+
+impl Guard {
+    fn new(limits: GuardLimits) -> Self {
+        Self {
+            limits,
+            transfer_selector: starknet::core::utils::get_selector_from_name("transfer")
+                .unwrap_or_default(),
+            known_contracts: HashSet::new(),
+            window: VecDeque::new(),
+        }
+    }
+
+    /// Record a submission, returning the expectations it breaks. Only the 'submitted' line of a
+    /// transaction counts; its later 'confirmed' or 'reverted' line is the same transaction.
+    fn observe(&mut self, entry: &AuditEntry) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        if entry.result != "submitted" {
+            return alerts;
+        }
+        let time = chrono::DateTime::parse_from_rfc3339(&entry.timestamp)
+            .map(|dt| dt.timestamp())
+            .unwrap_or_else(|_| chrono::Utc::now().timestamp());
+        let alert = |rule, message: String, contract_address: Option<String>| Alert {
+            event: "alert",
+            rule,
+            message,
+            timestamp: entry.timestamp.clone(),
+            transaction_hash: entry.transaction_hash.clone(),
+            contract_address,
+        };
+
+        let mut transfers = 0;
+        for call in &entry.calls {
+            let Ok(contract) = Felt::from_hex(&call.contract_address) else {
+                continue;
+            };
+            if self.is_transfer(&call.entrypoint) {
+                transfers += 1;
+            }
+            if self.known_contracts.insert(contract) && self.limits.new_contracts {
+                alerts.push(alert(
+                    "new_contract",
+                    format!("Call to contract 0x{contract:x}, never called before"),
+                    Some(format!("0x{contract:x}")),
+                ));
+            }
+        }
+
+        self.window.push_back((time, transfers));
+        while self
+            .window
+            .front()
+            .is_some_and(|(at, _)| *at <= time - WINDOW_SECS)
+        {
+            self.window.pop_front();
+        }
+        let transactions = self.window.len();
+        if let Some(max) = self.limits.max_transactions_per_hour {
+            if transactions > max {
+                alerts.push(alert(
+                    "transaction_rate",
+                    format!(
+                        "{transactions} transactions in the last hour (expected at most {max})"
+                    ),
+                    None,
+                ));
+            }
+        }
+        let transfers: usize = self.window.iter().map(|(_, transfers)| transfers).sum();
+        if let Some(max) = self.limits.max_transfers_per_hour {
+            if transfers > max {
+                alerts.push(alert(
+                    "transfer_rate",
+                    format!("{transfers} transfers in the last hour (expected at most {max})"),
+                    None,
+                ));
+            }
+        }
+        alerts
+    }
+
+    /// `transfer` by name, or by selector as the audit log records calls built in code
+    fn is_transfer(&self, entrypoint: &str) -> bool {
+        match entrypoint.strip_prefix("selector:") {
+            Some(selector) => Felt::from_hex(selector).ok() == Some(self.transfer_selector),
+            None => entrypoint == "transfer",
+        }
+    }
+}
+
+/// Watch the audit log for the account's submissions and alert when they deviate from `limits`.
+/// Submissions logged before the guard started only seed the known contracts and the hourly
+/// counts. With `block`, the first alert also turns on read-only mode in the config file so
+/// further transactions are refused until it is turned off. Runs until Ctrl-C.
+pub async fn execute(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    limits: GuardLimits,
+    block: bool,
+    interval: u64,
+    account: Option<&str>,
+) -> Result<()> {
+    if limits.max_transactions_per_hour.is_none()
+        && limits.max_transfers_per_hour.is_none()
+        && !limits.new_contracts
+    {
+        return Err(CliError::InvalidInput(
+            "Nothing to guard: pass --max-tx-per-hour, --max-transfers-per-hour or --new-contracts"
+                .to_string(),
+        ));
+    }
+    let path = config.resolve_storage_path(None).join(AUDIT_FILE);
+    let ours = |entry: &AuditEntry| entry.account.as_deref() == account;
+
+    let mut guard = Guard::new(limits);
+    let (history, mut offset) = read_from(&path, 0)?;
+    for entry in history.iter().filter(|entry| ours(entry)) {
+        guard.observe(entry);
+    }
+    let mut blocked = false;
+
+    formatter.info(&format!(
+        "Guarding {} (Ctrl-C to stop)",
+        account.unwrap_or("the default account")
+    ));
+    let mut ticker = tokio::time::interval(std::time::Duration::from_secs(interval.max(1)));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+        let entries = match read_from(&path, offset) {
+            Ok((entries, next)) => {
+                offset = next;
+                entries
+            }
+            Err(e) => {
+                formatter.warning(&e.to_string());
+                continue;
+            }
+        };
+        for entry in entries.iter().filter(|entry| ours(entry)) {
+            for alert in guard.observe(entry) {
+                if config.cli.json_output {
+                    if let Ok(line) = serde_json::to_string(&alert) {
+                        println!("{line}");
+                    }
+                } else {
+                    formatter.warning(&alert.message);
+                }
+                if block && !blocked {
+                    crate::commands::config_cmd::execute_set(
+                        formatter,
+                        "read-only".to_string(),
+                        "true".to_string(),
+                    )
+                    .await?;
+                    formatter.warning(
+                        "Read-only mode turned on: transactions are refused until \
+                         'controller config set read-only false'",
+                    );
+                    blocked = true;
+                }
+            }
+        }
+    }
+}
+
+/// Audit entries appended after byte `offset`, and the offset to continue from. A trailing line
+/// still being written is left for the next read.
+fn read_from(path: &Path, offset: u64) -> Result<(Vec<AuditEntry>, u64)> {
+    let file_error = |e: std::io::Error| CliError::FileError {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    };
+    let mut file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((Vec::new(), 0)),
+        Err(e) => return Err(file_error(e)),
+    };
+    // The log is only ever appended to; a shorter file was replaced, so start over
+    let offset = if file.metadata().map_err(file_error)?.len() < offset {
+        0
+    } else {
+        offset
+    };
+    file.seek(SeekFrom::Start(offset)).map_err(file_error)?;
+    let mut content = String::new();
+    file.read_to_string(&mut content).map_err(file_error)?;
+
+    let complete = content.rfind('\n').map_or(0, |end| end + 1);
+    let entries = content[..complete]
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    Ok((entries, offset + complete as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::execute::CallSpec;
+
+    fn submitted(minute: u32, contract: &str, entrypoint: &str) -> AuditEntry {
+        let mut entry = AuditEntry::new(
+            "execute",
+            None,
+            Felt::from(0xabcu64),
+            "SN_SEPOLIA",
+            vec![CallSpec {
+                contract_address: contract.to_string(),
+                entrypoint: entrypoint.to_string(),
+                calldata: Vec::new(),
+            }],
+            Vec::new(),
+            true,
+        );
+        entry.timestamp = format!("2025-01-01T00:{minute:02}:00Z");
+        entry.result = "submitted".to_string();
+        entry
+    }
+
+    #[test]
+    fn alerts_on_rates_and_new_contracts() {
+        let mut guard = Guard::new(GuardLimits {
+            max_transactions_per_hour: Some(3),
+            max_transfers_per_hour: Some(1),
+            new_contracts: true,
+        });
+        let transfer = format!(
+            "selector:0x{:x}",
+            starknet::core::utils::get_selector_from_name("transfer").unwrap()
+        );
+
+        let rules = |alerts: Vec<Alert>| alerts.iter().map(|a| a.rule).collect::<Vec<_>>();
+        assert_eq!(
+            rules(guard.observe(&submitted(0, "0x1", "move"))),
+            ["new_contract"]
+        );
+        assert!(guard.observe(&submitted(1, "0x01", "transfer")).is_empty());
+        assert_eq!(
+            rules(guard.observe(&submitted(2, "0x1", &transfer))),
+            ["transfer_rate"]
+        );
+        assert_eq!(
+            rules(guard.observe(&submitted(3, "0x2", "move"))),
+            ["new_contract", "transaction_rate", "transfer_rate"]
+        );
+
+        let mut confirmed = submitted(4, "0x3", "move");
+        confirmed.result = "confirmed".to_string();
+        assert!(guard.observe(&confirmed).is_empty());
+    }
+
+    #[test]
+    fn reads_only_complete_lines() {
+        let dir = std::env::temp_dir().join(format!("controller-guard-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(AUDIT_FILE);
+        let line = serde_json::to_string(&submitted(0, "0x1", "move")).unwrap();
+        std::fs::write(&path, format!("{line}\n{{\"partial")).unwrap();
+
+        let (entries, offset) = read_from(&path, 0).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(offset, line.len() as u64 + 1);
+        let (entries, _) = read_from(&path, offset).unwrap();
+        assert!(entries.is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -9,9 +9,10 @@ use serde::{Deserialize, Serialize};
 #[derive(Serialize)]
 pub struct ListOutput {
     pub total_count: u64,
-    pub page: u32,
-    pub total_pages: u32,
     pub sessions: Vec<SessionEntry>,
+    /// Cursor of the last session in this page; pass to `--after` to fetch the next page
+    pub end_cursor: Option<String>,
+    pub has_next_page: bool,
 }
 
 #[derive(Serialize)]
@@ -28,11 +29,9 @@ pub async fn execute(
     formatter: &dyn OutputFormatter,
     chain_id: Option<String>,
     limit: u32,
-    page: u32,
+    after: Option<String>,
     account: Option<&str>,
 ) -> Result<()> {
-    let page = page.max(1);
-
     let storage_path = config.resolve_storage_path(account);
     let backend = FileSystemBackend::new(storage_path);
     let controller = backend
@@ -56,20 +55,15 @@ pub async fn execute(
             _ => None,
         });
 
-    // Walk through pages to reach the requested one
-    let mut result =
-        query_sessions(&config.session.api_url, &address, &chain_id, limit, None).await?;
-
-    for _ in 1..page {
-        match result.page_info.end_cursor {
-            Some(ref c) => {
-                result =
-                    query_sessions(&config.session.api_url, &address, &chain_id, limit, Some(c))
-                        .await?;
-            }
-            None => break,
-        }
-    }
+    // Pages are reached through the API's cursor, so each one fetches only its own sessions
+    let result = query_sessions(
+        &config.session.api_url,
+        &address,
+        &chain_id,
+        limit,
+        after.as_deref(),
+    )
+    .await?;
     let sessions: Vec<SessionEntry> = result
         .edges
         .iter()
@@ -90,14 +84,13 @@ pub async fn execute(
         })
         .collect();
 
-    let total_pages = (result.total_count as u32).div_ceil(limit);
-    let has_next = page < total_pages;
+    let has_next = result.page_info.has_next_page;
 
     let output = ListOutput {
         total_count: result.total_count,
-        page,
-        total_pages,
         sessions,
+        end_cursor: result.page_info.end_cursor.clone(),
+        has_next_page: has_next,
     };
 
     if config.cli.json_output {
@@ -130,11 +123,8 @@ pub async fn execute(
             println!();
         }
 
-        if total_pages > 1 {
-            formatter.info(&format!("Page {page}/{total_pages}"));
-        }
-        if has_next {
-            formatter.info(&format!("Use --page {} to see more.", page + 1));
+        if let (true, Some(cursor)) = (has_next, &output.end_cursor) {
+            formatter.info(&format!("Use --after {cursor} to see more."));
         }
     }
 
@@ -180,6 +170,8 @@ struct SessionsConnection {
 struct PageInfo {
     #[serde(rename = "endCursor")]
     end_cursor: Option<String>,
+    #[serde(rename = "hasNextPage", default)]
+    has_next_page: bool,
 }
 
 #[derive(Deserialize)]
@@ -204,10 +196,7 @@ async fn query_sessions(
     first: u32,
     after: Option<&str>,
 ) -> Result<SessionsConnection> {
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .map_err(|e| CliError::ApiError(format!("Failed to build HTTP client: {e}")))?;
+    let client = crate::api::http_client(std::time::Duration::from_secs(10))?;
 
     let query = r#"
         query ListSessions($address: String!, $chainID: String!, $first: Int!, $after: Cursor) {
@@ -224,6 +213,7 @@ async fn query_sessions(
                 totalCount
                 pageInfo {
                     endCursor
+                    hasNextPage
                 }
                 edges {
                     node {
@@ -278,12 +268,11 @@ async fn query_sessions(
         },
     };
 
-    let response = client
-        .post(api_url)
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| CliError::ApiError(format!("Failed to query sessions: {e}")))?;
+    let response = crate::api::send(
+        client.post(api_url).json(&request),
+        "Failed to query sessions",
+    )
+    .await?;
 
     if !response.status().is_success() {
         return Err(CliError::ApiError(format!(
@@ -299,10 +288,7 @@ async fn query_sessions(
 
     if let Some(errors) = graphql_response.errors {
         let messages: Vec<String> = errors.iter().map(|e| e.message.clone()).collect();
-        return Err(CliError::ApiError(format!(
-            "GraphQL errors: {}",
-            messages.join(", ")
-        )));
+        return Err(crate::api::graphql_error(&messages));
     }
 
     graphql_response
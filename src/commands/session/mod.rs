@@ -1,3 +1,10 @@
 pub mod authorize;
+pub mod bundle;
+pub mod ensure;
+pub mod guard;
 pub mod list;
+pub mod revocation;
 pub mod revoke;
+pub mod risk;
+pub mod templates;
+pub mod wait_expiry;
@@ -0,0 +1,65 @@
+use crate::{
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use account_sdk::storage::{filestorage::FileSystemBackend, StorageBackend, StorageValue};
+use std::path::Path;
+
+/// Local entries that make up the active session
+const SESSION_ENTRIES: &[&str] = &[
+    "session_signer",
+    "session_key_guid",
+    "session_policies",
+    "session_rpc_url",
+];
+
+/// With `check-revocation` enabled, ask the API whether the stored session was revoked (e.g.
+/// from the web app). A revoked session is removed locally and reported as `SessionRevoked`
+/// instead of letting transactions fail on-chain. If the API can't be reached, this warns and
+/// lets the command proceed.
+pub async fn ensure_not_revoked(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    storage_path: &Path,
+    session_key: &str,
+) -> Result<()> {
+    if !config.session.check_revocation {
+        return Ok(());
+    }
+
+    let mut backend = FileSystemBackend::new(storage_path.to_path_buf());
+    let Some(guid) = backend
+        .get("session_key_guid")
+        .ok()
+        .flatten()
+        .and_then(|v| match v {
+            StorageValue::String(guid) => Some(guid),
+            _ => None,
+        })
+    else {
+        return Ok(());
+    };
+
+    match crate::api::query_session_revoked(&config.session.api_url, &guid).await {
+        Ok(Some(true)) => {
+            clear_local(&mut backend, session_key);
+            Err(CliError::SessionRevoked)
+        }
+        Ok(_) => Ok(()),
+        Err(e) => {
+            formatter.warning(&format!(
+                "Could not check whether the session was revoked: {e}"
+            ));
+            Ok(())
+        }
+    }
+}
+
+/// Remove the active session from local storage, keeping the controller metadata.
+pub fn clear_local(backend: &mut FileSystemBackend, session_key: &str) {
+    // Some entries may not exist for older sessions
+    for key in std::iter::once(session_key).chain(SESSION_ENTRIES.iter().copied()) {
+        let _ = backend.remove(key);
+    }
+}
@@ -1,10 +1,192 @@
-use crate::{config::Config, error::Result, output::OutputFormatter};
+use crate::{
+    commands::audit::AuditEntry,
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use account_sdk::{
+    controller::Controller,
+    hash::MessageHashRev1,
+    signers::{Owner, Signer},
+    storage::{filestorage::FileSystemBackend, StorageBackend, StorageValue},
+};
+use serde::Serialize;
+use starknet::{
+    core::{
+        types::{Call, Felt},
+        utils::get_selector_from_name,
+    },
+    providers::Provider,
+};
 
+/// Seconds to wait for the revocation to be confirmed before clearing the session locally
+const CONFIRMATION_TIMEOUT_SECS: u64 = 300;
+
+#[derive(Serialize)]
+pub struct RevokeOutput {
+    pub session_hash: String,
+    pub transaction_hash: String,
+    /// Whether the revoked session was the active one, and was removed from local storage
+    pub cleared_local: bool,
+    pub message: String,
+}
+
+/// Revoke a session on-chain: the one with `session_hash`, or the active session when none is
+/// given. The controller's `revoke_session` takes the session's hash (not its signer GUID) and
+/// only accepts calls from the account itself, so it is sent as a paymaster outside execution
+/// rather than a session-validated `execute`. Revoking the active session also removes it
+/// locally once confirmed.
 pub async fn execute(
-    _config: &Config,
+    config: &Config,
     formatter: &dyn OutputFormatter,
-    _account: Option<&str>,
+    session_hash: Option<String>,
+    account: Option<&str>,
 ) -> Result<()> {
-    formatter.info("Not yet implemented");
+    let storage_path = config.resolve_storage_path(account);
+    let mut backend = FileSystemBackend::new(storage_path.clone());
+    let controller_metadata = backend
+        .controller()
+        .ok()
+        .flatten()
+        .ok_or(CliError::NoSession)?;
+    let session_key = format!(
+        "@cartridge/session/0x{:x}/0x{:x}",
+        controller_metadata.address, controller_metadata.chain_id
+    );
+    let session_metadata = backend
+        .session(&session_key)
+        .map_err(|e| CliError::Storage(e.to_string()))?
+        .ok_or(CliError::NoSession)?;
+    let credentials = session_metadata
+        .credentials
+        .ok_or_else(|| CliError::InvalidSessionData("No credentials found".to_string()))?;
+
+    let active_hash = session_metadata
+        .session
+        .inner
+        .get_message_hash_rev_1(controller_metadata.chain_id, controller_metadata.address);
+    let session_hash = match session_hash {
+        Some(hash) => parse_felt("session hash", &hash)?,
+        None => active_hash,
+    };
+
+    let rpc_url = match backend.get("session_rpc_url").ok().flatten() {
+        Some(StorageValue::String(url)) => url,
+        _ => config.session.rpc_url.clone(),
+    };
+    let rpc_parsed = url::Url::parse(&rpc_url)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
+    let signing_key = starknet::signers::SigningKey::from_secret_scalar(credentials.private_key);
+    let controller = Controller::new(
+        controller_metadata.username.clone(),
+        controller_metadata.class_hash,
+        rpc_parsed,
+        Owner::Signer(Signer::Starknet(signing_key)),
+        controller_metadata.address,
+        Some(FileSystemBackend::new(storage_path)),
+    )
+    .await
+    .map_err(|e| CliError::Storage(format!("Failed to create controller: {e}")))?;
+    let chain_name = crate::chains::chain_name(&controller.provider).await?;
+
+    let calls = vec![Call {
+        to: controller_metadata.address,
+        selector: get_selector_from_name("revoke_session")
+            .map_err(|e| CliError::Other(e.into()))?,
+        calldata: vec![session_hash],
+    }];
+    let mut audit = AuditEntry::new(
+        "session revoke",
+        account,
+        controller_metadata.address,
+        &chain_name,
+        crate::commands::audit::call_specs(&calls),
+        Vec::new(),
+        true,
+    );
+
+    formatter.info(&format!(
+        "Revoking session 0x{session_hash:x} on {chain_name}..."
+    ));
+    let submitted = controller
+        .execute_from_outside_v3(calls, None)
+        .await
+        .map_err(|e| CliError::TransactionFailed(format!("Paymaster execution failed: {e}")));
+    audit.submitted(
+        config,
+        formatter,
+        submitted.as_ref().map(|result| result.transaction_hash),
+    );
+    let result = submitted?;
+    let transaction_hash = format!("0x{:x}", result.transaction_hash);
+
+    formatter.info("Waiting for transaction confirmation...");
+    let start = std::time::Instant::now();
+    loop {
+        if start.elapsed() > std::time::Duration::from_secs(CONFIRMATION_TIMEOUT_SECS) {
+            return Err(CliError::TransactionFailed(format!(
+                "Revocation {transaction_hash} not confirmed after {CONFIRMATION_TIMEOUT_SECS} seconds"
+            )));
+        }
+        match controller
+            .provider
+            .get_transaction_receipt(result.transaction_hash)
+            .await
+        {
+            Ok(receipt) => {
+                audit.confirmed(config, formatter, &receipt.receipt);
+                if let starknet::core::types::ExecutionResult::Reverted { reason } =
+                    receipt.receipt.execution_result()
+                {
+                    return Err(CliError::TransactionFailed(format!(
+                        "Revocation {transaction_hash} reverted: {reason}"
+                    )));
+                }
+                break;
+            }
+            Err(_) => tokio::time::sleep(tokio::time::Duration::from_secs(2)).await,
+        }
+    }
+
+    let cleared_local = session_hash == active_hash;
+    if cleared_local {
+        super::revocation::clear_local(&mut backend, &session_key);
+    }
+
+    formatter.success(&RevokeOutput {
+        session_hash: format!("0x{session_hash:x}"),
+        transaction_hash,
+        cleared_local,
+        message: if cleared_local {
+            "Active session revoked and removed locally. Run 'controller session auth' for a new one"
+                .to_string()
+        } else {
+            "Session revoked".to_string()
+        },
+    });
     Ok(())
 }
+
+fn parse_felt(what: &str, value: &str) -> Result<Felt> {
+    let parsed = if value.starts_with("0x") {
+        Felt::from_hex(value)
+    } else {
+        Felt::from_dec_str(value)
+    };
+    parsed.map_err(|e| CliError::InvalidInput(format!("Invalid {what} '{value}': {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_session_hashes() {
+        assert_eq!(
+            parse_felt("session hash", "0x2a").unwrap(),
+            Felt::from(42u8)
+        );
+        assert_eq!(parse_felt("session hash", "42").unwrap(), Felt::from(42u8));
+        assert!(parse_felt("session hash", "nope").is_err());
+    }
+}
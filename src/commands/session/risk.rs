@@ -0,0 +1,78 @@
+use serde::Serialize;
+use starknet::core::types::Felt;
+use starknet::core::utils::get_selector_from_name;
+
+/// What granting an entrypoint lets a session do, from the built-in selector database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Risk {
+    /// Moves fungible tokens out of the account
+    ValueTransfer,
+    /// Lets another address spend the account's tokens or NFTs
+    Approval,
+    /// Moves NFTs out of the account
+    NftTransfer,
+    /// Not in the database: usually a game action, check the contract
+    Unknown,
+}
+
+impl Risk {
+    /// Grants highlighted before authorization
+    pub fn is_high(self) -> bool {
+        self != Risk::Unknown
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Risk::ValueTransfer => "value transfer",
+            Risk::Approval => "approval",
+            Risk::NftTransfer => "NFT transfer",
+            Risk::Unknown => "unknown",
+        }
+    }
+}
+
+/// Well-known token entrypoints (snake_case and camelCase variants of the ERC-20/721/1155
+/// interfaces). `transfer_from` is shared by ERC-20 and ERC-721 and is treated as a value
+/// transfer; the `safe_` variants only exist on NFT contracts.
+const KNOWN_ENTRYPOINTS: &[(&str, Risk)] = &[
+    ("transfer", Risk::ValueTransfer),
+    ("transfer_from", Risk::ValueTransfer),
+    ("transferFrom", Risk::ValueTransfer),
+    ("approve", Risk::Approval),
+    ("increase_allowance", Risk::Approval),
+    ("increaseAllowance", Risk::Approval),
+    ("set_approval_for_all", Risk::Approval),
+    ("setApprovalForAll", Risk::Approval),
+    ("safe_transfer_from", Risk::NftTransfer),
+    ("safeTransferFrom", Risk::NftTransfer),
+    ("safe_batch_transfer_from", Risk::NftTransfer),
+    ("safeBatchTransferFrom", Risk::NftTransfer),
+];
+
+/// Classify an entrypoint by its selector.
+pub fn classify(selector: Felt) -> Risk {
+    KNOWN_ENTRYPOINTS
+        .iter()
+        .find(|(name, _)| get_selector_from_name(name).is_ok_and(|known| known == selector))
+        .map(|(_, risk)| *risk)
+        .unwrap_or(Risk::Unknown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_known_selectors() {
+        let risk = |name: &str| classify(get_selector_from_name(name).unwrap());
+        assert_eq!(risk("transfer"), Risk::ValueTransfer);
+        assert_eq!(risk("transferFrom"), Risk::ValueTransfer);
+        assert_eq!(risk("approve"), Risk::Approval);
+        assert_eq!(risk("set_approval_for_all"), Risk::Approval);
+        assert_eq!(risk("safe_transfer_from"), Risk::NftTransfer);
+        assert_eq!(risk("spawn"), Risk::Unknown);
+        assert!(!Risk::Unknown.is_high());
+        assert!(Risk::Approval.is_high());
+    }
+}
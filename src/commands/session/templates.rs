@@ -0,0 +1,111 @@
+use super::authorize::{ContractPolicy, MethodPolicy, PolicyFile};
+use crate::error::{CliError, Result};
+use starknet::core::types::Felt;
+
+/// A built-in, parameterized policy set expanded locally for `session auth --template`.
+pub struct PolicyTemplate {
+    pub name: &'static str,
+    /// Name given to every contract the template is applied to
+    pub contract_name: &'static str,
+    /// (method name, entrypoint) pairs authorized on each contract
+    pub methods: &'static [(&'static str, &'static str)],
+}
+
+pub const TEMPLATES: &[PolicyTemplate] = &[
+    PolicyTemplate {
+        name: "erc20-transfers",
+        contract_name: "ERC20 token",
+        methods: &[("Transfer", "transfer"), ("Approve", "approve")],
+    },
+    PolicyTemplate {
+        name: "nft-trading",
+        contract_name: "NFT collection",
+        methods: &[
+            ("Approve", "approve"),
+            ("Set approval for all", "set_approval_for_all"),
+            ("Transfer from", "transfer_from"),
+            ("Safe transfer from", "safe_transfer_from"),
+        ],
+    },
+    // The `actions` system generated by `sozo init`
+    PolicyTemplate {
+        name: "dojo-world-default",
+        contract_name: "Dojo actions",
+        methods: &[("Spawn", "spawn"), ("Move", "move")],
+    },
+];
+
+/// Expand a template over the given contract addresses into a policy file.
+pub fn expand(name: &str, contracts: &[String]) -> Result<PolicyFile> {
+    let template = TEMPLATES.iter().find(|t| t.name == name).ok_or_else(|| {
+        let names: Vec<&str> = TEMPLATES.iter().map(|t| t.name).collect();
+        CliError::InvalidInput(format!(
+            "Unknown policy template '{name}'. Available templates: {}",
+            names.join(", ")
+        ))
+    })?;
+
+    if contracts.is_empty() {
+        return Err(CliError::InvalidInput(format!(
+            "Template '{name}' needs at least one contract. Pass --contracts 0x...,0x..."
+        )));
+    }
+
+    let contracts = contracts
+        .iter()
+        .map(|address| {
+            let felt = Felt::from_hex(address.trim()).map_err(|e| {
+                CliError::InvalidInput(format!("Invalid contract address '{address}': {e}"))
+            })?;
+            let methods = template
+                .methods
+                .iter()
+                .map(|(method_name, entrypoint)| MethodPolicy {
+                    name: method_name.to_string(),
+                    entrypoint: entrypoint.to_string(),
+                    description: None,
+                    amount: None,
+                    authorized: true,
+                })
+                .collect();
+            Ok((
+                format!("0x{felt:x}"),
+                ContractPolicy {
+                    name: Some(template.contract_name.to_string()),
+                    methods,
+                },
+            ))
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(PolicyFile {
+        contracts,
+        messages: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_template_per_contract() {
+        let policy = expand(
+            "erc20-transfers",
+            &["0x04718f".to_string(), "0x49d36".to_string()],
+        )
+        .unwrap();
+        assert_eq!(policy.contracts.len(), 2);
+        let strk = &policy.contracts["0x4718f"];
+        let entrypoints: Vec<&str> = strk.methods.iter().map(|m| m.entrypoint.as_str()).collect();
+        assert_eq!(entrypoints, vec!["transfer", "approve"]);
+    }
+
+    #[test]
+    fn rejects_unknown_template_and_bad_contracts() {
+        let err = expand("erc721", &["0x1".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("nft-trading"));
+        assert!(expand("nft-trading", &[]).is_err());
+        assert!(expand("nft-trading", &["not-hex".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use account_sdk::storage::{filestorage::FileSystemBackend, StorageBackend};
+use serde::Serialize;
+use std::time::Duration;
+
+/// Exit code when the session is within the requested window of expiry (still usable)
+pub const EXIT_EXPIRING: i32 = 3;
+/// Exit code when the session has already expired
+pub const EXIT_EXPIRED: i32 = 4;
+
+/// Upper bound on a single sleep, so a session re-authorized while waiting is picked up
+const MAX_SLEEP: Duration = Duration::from_secs(60);
+
+#[derive(Serialize)]
+pub struct WaitExpiryOutput {
+    pub state: String,
+    pub expires_at: u64,
+    pub expires_in_seconds: i64,
+    pub exit_code: i32,
+}
+
+#[derive(Debug, PartialEq)]
+enum Check {
+    Expired,
+    Expiring,
+    /// Seconds until the session enters the window
+    Wait(u64),
+}
+
+fn check(expires_at: u64, now: u64, within: u64) -> Check {
+    if now >= expires_at {
+        Check::Expired
+    } else if expires_at - now <= within {
+        Check::Expiring
+    } else {
+        Check::Wait(expires_at - now - within)
+    }
+}
+
+/// Block until the session is within `within` seconds of expiry, then report it and return the
+/// exit code to use: [`EXIT_EXPIRING`], [`EXIT_EXPIRED`], or 0 if `timeout` elapsed first.
+pub async fn execute(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    within: u64,
+    timeout: Option<u64>,
+    account: Option<&str>,
+) -> Result<i32> {
+    let storage_path = config.resolve_storage_path(account);
+    let start = std::time::Instant::now();
+    let mut announced = false;
+
+    loop {
+        // Re-read every iteration: the session may be replaced while we wait
+        let expires_at = session_expires_at(&FileSystemBackend::new(storage_path.clone()))?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let (state, exit_code) = match check(expires_at, now, within) {
+            Check::Expired => ("expired", EXIT_EXPIRED),
+            Check::Expiring => ("expiring", EXIT_EXPIRING),
+            Check::Wait(secs) => {
+                let remaining =
+                    timeout.map(|t| Duration::from_secs(t).saturating_sub(start.elapsed()));
+                if remaining.is_some_and(|r| r.is_zero()) {
+                    ("valid", 0)
+                } else {
+                    if !announced {
+                        formatter.info(&format!(
+                            "Session enters the {within}s expiry window in {secs}s, waiting..."
+                        ));
+                        announced = true;
+                    }
+                    let sleep = Duration::from_secs(secs.max(1))
+                        .min(MAX_SLEEP)
+                        .min(remaining.unwrap_or(MAX_SLEEP));
+                    tokio::time::sleep(sleep).await;
+                    continue;
+                }
+            }
+        };
+
+        formatter.success(&WaitExpiryOutput {
+            state: state.to_string(),
+            expires_at,
+            expires_in_seconds: expires_at as i64 - now as i64,
+            exit_code,
+        });
+        return Ok(exit_code);
+    }
+}
+
+fn session_expires_at(backend: &FileSystemBackend) -> Result<u64> {
+    let controller = backend
+        .controller()
+        .ok()
+        .flatten()
+        .ok_or(CliError::NoSession)?;
+    let session_key = format!(
+        "@cartridge/session/0x{:x}/0x{:x}",
+        controller.address, controller.chain_id
+    );
+    let metadata = backend
+        .session(&session_key)
+        .map_err(|e| CliError::Storage(e.to_string()))?
+        .ok_or(CliError::NoSession)?;
+    Ok(metadata.session.inner.expires_at)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_expiry_window() {
+        assert_eq!(check(1_000, 1_000, 60), Check::Expired);
+        assert_eq!(check(1_000, 2_000, 60), Check::Expired);
+        assert_eq!(check(1_000, 940, 60), Check::Expiring);
+        assert_eq!(check(1_000, 900, 60), Check::Wait(40));
+        assert_eq!(check(1_000, 999, 0), Check::Wait(1));
+    }
+}
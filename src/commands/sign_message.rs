@@ -0,0 +1,329 @@
+use crate::{
+    commands::session::authorize::PolicyStorage,
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use account_sdk::{
+    account::MessageSignerAccount,
+    controller::Controller,
+    signers::{Owner, Signer},
+    storage::{filestorage::FileSystemBackend, StorageBackend, StorageValue},
+};
+use serde::Serialize;
+use serde_json::Value;
+use starknet::core::types::{BlockId, BlockTag, Felt, FunctionCall, TypedData};
+use starknet::core::utils::get_selector_from_name;
+use starknet::providers::{JsonRpcClient, Provider, ProviderError};
+
+/// `is_valid_signature` result of a valid signature: the short string 'VALID'
+const VALID: Felt = Felt::from_hex_unchecked("0x56414c4944");
+
+#[derive(Serialize)]
+pub struct SignOutput {
+    pub address: String,
+    pub primary_type: String,
+    pub message_hash: String,
+    pub signature: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct VerifyOutput {
+    pub address: String,
+    pub message_hash: String,
+    pub valid: bool,
+}
+
+/// Sign SNIP-12 typed data with the session key. The message must match one of the typed data
+/// policies the session was authorized with.
+pub async fn sign(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    file: &str,
+    account: Option<&str>,
+) -> Result<()> {
+    let value = read_typed_data(file)?;
+
+    let storage_path = config.resolve_storage_path(account);
+    let backend = FileSystemBackend::new(storage_path.clone());
+    let controller_metadata = backend
+        .controller()
+        .map_err(|e| CliError::Storage(e.to_string()))?
+        .ok_or_else(|| {
+            CliError::InvalidSessionData(
+                "No controller metadata found. Run 'controller session auth' to create a session."
+                    .to_string(),
+            )
+        })?;
+    let session_key = format!(
+        "@cartridge/session/0x{:x}/0x{:x}",
+        controller_metadata.address, controller_metadata.chain_id
+    );
+    let session_metadata = backend
+        .session(&session_key)
+        .map_err(|e| CliError::Storage(e.to_string()))?
+        .ok_or(CliError::NoSession)?;
+
+    if session_metadata.session.is_expired() {
+        let expires_at =
+            chrono::DateTime::from_timestamp(session_metadata.session.inner.expires_at as i64, 0)
+                .unwrap_or_else(chrono::Utc::now);
+        return Err(CliError::SessionExpired(
+            expires_at.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+        ));
+    }
+
+    crate::commands::session::revocation::ensure_not_revoked(
+        config,
+        formatter,
+        &storage_path,
+        &session_key,
+    )
+    .await?;
+
+    let messages = stored_string(&backend, "session_policies")
+        .and_then(|json| serde_json::from_str::<PolicyStorage>(&json).ok())
+        .and_then(|policies| policies.messages)
+        .unwrap_or_default();
+    let primary_type = value["primaryType"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+    if !messages.iter().any(|policy| matches_policy(policy, &value)) {
+        return Err(CliError::PolicyViolation {
+            message: format!(
+                "Typed data '{primary_type}' is not covered by the session's message policies"
+            ),
+            details: format!(
+                "The session authorizes {} message policy(ies). Add this message's domain, \
+                 primaryType and types to the 'messages' of a policy file and re-authorize.",
+                messages.len()
+            ),
+        });
+    }
+
+    let message_hash = message_hash(&value, controller_metadata.address)?;
+
+    let credentials = session_metadata
+        .credentials
+        .ok_or_else(|| CliError::InvalidSessionData("No credentials found".to_string()))?;
+    let signing_key = starknet::signers::SigningKey::from_secret_scalar(credentials.private_key);
+    let owner = Owner::Signer(Signer::Starknet(signing_key));
+    let rpc_url = stored_string(&backend, "session_rpc_url")
+        .unwrap_or_else(|| config.session.rpc_url.clone());
+    let address = controller_metadata.address;
+
+    let controller = Controller::new(
+        controller_metadata.username.clone(),
+        controller_metadata.class_hash,
+        url::Url::parse(&rpc_url)
+            .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?,
+        owner,
+        address,
+        Some(backend),
+    )
+    .await
+    .map_err(|e| CliError::Storage(format!("Failed to create controller: {e}")))?;
+
+    formatter.info(&format!("Signing '{primary_type}' typed data..."));
+    let typed_data = serde_json::from_value(value)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid typed data: {e}")))?;
+    let signature = controller
+        .sign_message(typed_data)
+        .await
+        .map_err(|e| CliError::Other(anyhow::anyhow!("Failed to sign typed data: {e}")))?;
+
+    formatter.success(&SignOutput {
+        address: format!("0x{address:x}"),
+        primary_type,
+        message_hash: format!("0x{message_hash:x}"),
+        signature: signature.iter().map(|felt| format!("0x{felt:x}")).collect(),
+    });
+    Ok(())
+}
+
+/// Check a SNIP-12 signature against the account's `is_valid_signature`, defaulting to the
+/// session's controller address.
+#[allow(clippy::too_many_arguments)]
+pub async fn verify(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    file: &str,
+    signature: &str,
+    address: Option<String>,
+    chain_id: Option<String>,
+    rpc_url: Option<String>,
+    account: Option<&str>,
+) -> Result<()> {
+    let value = read_typed_data(file)?;
+    let signature = parse_signature(signature)?;
+
+    let address = match address {
+        Some(address) => Felt::from_hex(&address)
+            .map_err(|e| CliError::InvalidInput(format!("Invalid address '{address}': {e}")))?,
+        None => {
+            FileSystemBackend::new(config.resolve_storage_path(account))
+                .controller()
+                .map_err(|e| CliError::Storage(e.to_string()))?
+                .ok_or_else(|| {
+                    CliError::InvalidInput(
+                        "No session to take the signer from. Pass --address".to_string(),
+                    )
+                })?
+                .address
+        }
+    };
+    let message_hash = message_hash(&value, address)?;
+
+    let rpc_url = crate::chains::resolve_rpc_url(config, formatter, chain_id, rpc_url, account)?;
+    let provider = JsonRpcClient::new(crate::api::rpc_transport(
+        url::Url::parse(&rpc_url)
+            .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?,
+    ));
+
+    let mut calldata = vec![message_hash, Felt::from(signature.len())];
+    calldata.extend(signature);
+    let call = FunctionCall {
+        contract_address: address,
+        entry_point_selector: get_selector_from_name("is_valid_signature")
+            .map_err(|e| CliError::Other(e.into()))?,
+        calldata,
+    };
+    let valid = match provider.call(call, BlockId::Tag(BlockTag::Latest)).await {
+        Ok(result) => result
+            .first()
+            .is_some_and(|felt| *felt == VALID || *felt == Felt::ONE),
+        // Accounts may panic on a bad signature rather than return zero
+        Err(ProviderError::StarknetError(starknet::core::types::StarknetError::ContractError(
+            _,
+        ))) => false,
+        Err(e) => return Err(CliError::Network(e.to_string())),
+    };
+
+    formatter.success(&VerifyOutput {
+        address: format!("0x{address:x}"),
+        message_hash: format!("0x{message_hash:x}"),
+        valid,
+    });
+    Ok(())
+}
+
+fn read_typed_data(file: &str) -> Result<Value> {
+    let content = std::fs::read_to_string(file).map_err(|e| CliError::FileError {
+        path: file.to_string(),
+        message: e.to_string(),
+    })?;
+    serde_json::from_str(&content)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid typed data JSON in {file}: {e}")))
+}
+
+fn stored_string(backend: &FileSystemBackend, key: &str) -> Option<String> {
+    match backend.get(key).ok().flatten() {
+        Some(StorageValue::String(value)) => Some(value),
+        _ => None,
+    }
+}
+
+fn message_hash(value: &Value, address: Felt) -> Result<Felt> {
+    let typed_data: TypedData = serde_json::from_value(value.clone())
+        .map_err(|e| CliError::InvalidInput(format!("Invalid typed data: {e}")))?;
+    typed_data
+        .message_hash(address)
+        .map_err(|e| CliError::InvalidInput(format!("Failed to hash typed data: {e}")))
+}
+
+/// Signatures as printed by `sign-message`: a JSON array or comma-separated felts
+fn parse_signature(signature: &str) -> Result<Vec<Felt>> {
+    let felts: Vec<String> = match serde_json::from_str::<Vec<String>>(signature) {
+        Ok(felts) => felts,
+        Err(_) => signature
+            .split(',')
+            .map(|felt| felt.trim().to_string())
+            .filter(|felt| !felt.is_empty())
+            .collect(),
+    };
+    if felts.is_empty() {
+        return Err(CliError::InvalidInput("Signature is empty".to_string()));
+    }
+    felts
+        .iter()
+        .map(|felt| {
+            Felt::from_hex(felt)
+                .or_else(|_| Felt::from_dec_str(felt))
+                .map_err(|_| CliError::InvalidInput(format!("Invalid signature felt '{felt}'")))
+        })
+        .collect()
+}
+
+/// Whether typed data falls under a message policy: the same primary type, the policy's domain
+/// fields and the policy's type definitions. Domain values compare as strings, so `"1"` and `1`
+/// are the same revision.
+fn matches_policy(policy: &Value, typed_data: &Value) -> bool {
+    if policy["primaryType"] != typed_data["primaryType"] {
+        return false;
+    }
+    let as_string = |value: &Value| match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    let domain_matches = policy["domain"].as_object().is_none_or(|domain| {
+        domain
+            .iter()
+            .all(|(field, expected)| as_string(expected) == as_string(&typed_data["domain"][field]))
+    });
+    let types_match = policy["types"].as_object().is_none_or(|types| {
+        types
+            .iter()
+            .all(|(name, members)| typed_data["types"][name] == *members)
+    });
+    domain_matches && types_match
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn matches_typed_data_against_message_policies() {
+        let types = json!({
+            "StarknetDomain": [
+                {"name": "name", "type": "shortstring"},
+                {"name": "version", "type": "shortstring"},
+                {"name": "chainId", "type": "shortstring"},
+                {"name": "revision", "type": "shortstring"}
+            ],
+            "Move": [{"name": "direction", "type": "felt"}]
+        });
+        let policy = json!({
+            "primaryType": "Move",
+            "domain": {"name": "Game", "version": "1", "chainId": "SN_MAIN", "revision": "1"},
+            "types": types
+        });
+        let message = json!({
+            "types": types,
+            "primaryType": "Move",
+            "domain": {"name": "Game", "version": "1", "chainId": "SN_MAIN", "revision": 1},
+            "message": {"direction": "0x1"}
+        });
+        assert!(matches_policy(&policy, &message));
+
+        let mut other_chain = message.clone();
+        other_chain["domain"]["chainId"] = json!("SN_SEPOLIA");
+        assert!(!matches_policy(&policy, &other_chain));
+
+        let mut other_type = message.clone();
+        other_type["primaryType"] = json!("Attack");
+        assert!(!matches_policy(&policy, &other_type));
+
+        let mut changed_fields = message.clone();
+        changed_fields["types"]["Move"] = json!([{"name": "target", "type": "felt"}]);
+        assert!(!matches_policy(&policy, &changed_fields));
+
+        assert_eq!(
+            parse_signature("[\"0x1\",\"0x2\"]").unwrap(),
+            parse_signature("0x1, 2").unwrap()
+        );
+        assert!(parse_signature("").is_err());
+    }
+}
@@ -0,0 +1,474 @@
+use crate::commands::execute::with_headroom;
+use crate::error::{CliError, Result};
+use account_sdk::controller::Controller;
+use serde::Serialize;
+use starknet::accounts::{Account, AccountError};
+use starknet::core::types::{
+    BlockId, BlockTag, BroadcastedTransaction, Call, ExecuteInvocation, Felt, FunctionInvocation,
+    MaybePreConfirmedBlockWithTxHashes, SimulatedTransaction, SimulationFlag,
+    SimulationFlagForEstimateFee, StarknetError, StateDiff, TransactionTrace,
+};
+use starknet::providers::{Provider, ProviderError};
+
+/// What `execute --simulate` reports instead of submitting
+#[derive(Serialize)]
+pub struct SimulationOutput {
+    pub simulated: bool,
+    pub chain_id: String,
+    pub account: String,
+    /// 'succeeded' or 'reverted'
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    /// Absent when the transaction failed before a fee could be estimated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee: Option<SimulatedFee>,
+    /// The calls the account made, with their nested calls
+    pub trace: Vec<TracedCall>,
+    /// Gas of each call of the multicall, in call order, to see which one dominates the cost
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub gas_by_call: Vec<CallGas>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_diff: Option<StateDiffSummary>,
+    /// Block the simulation ran against, with `--at-block` or `--override-timestamp`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block: Option<PinnedBlock>,
+}
+
+/// A confirmed block a simulation is pinned to. Rerunning with `--at-block <block_number>`
+/// reproduces the result.
+#[derive(Debug, Clone, Serialize)]
+pub struct PinnedBlock {
+    pub block_number: u64,
+    pub block_hash: String,
+    pub timestamp: u64,
+}
+
+#[derive(Serialize)]
+pub struct SimulatedFee {
+    pub overall_fee: String,
+    pub l1_gas: u64,
+    pub l1_data_gas: u64,
+    pub l2_gas: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TracedCall {
+    pub contract_address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entrypoint: Option<String>,
+    pub selector: String,
+    pub result: Vec<String>,
+    pub events: usize,
+    /// Gas of the call including its nested calls
+    pub l1_gas: u64,
+    pub l2_gas: u64,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub reverted: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<TracedCall>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct CallGas {
+    /// Position of the call in the multicall
+    pub index: usize,
+    pub contract_address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entrypoint: Option<String>,
+    pub selector: String,
+    pub l1_gas: u64,
+    pub l2_gas: u64,
+    /// Share of the L2 gas spent by all calls, in percent. The rest of the transaction's L2 gas
+    /// goes to validation and the account's own execution.
+    pub l2_gas_percent: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StateDiffSummary {
+    pub storage_writes: Vec<StorageWrites>,
+    pub nonces_updated: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub deployed_contracts: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub replaced_classes: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StorageWrites {
+    pub contract_address: String,
+    pub keys: usize,
+}
+
+/// Run the calls through `starknet_simulateTransactions` from the controller, signed with the
+/// session as a real submission would be, without broadcasting. Fees are not charged, so an
+/// unfunded account can still be simulated. Against the pre-confirmed block unless `block` pins
+/// it to a confirmed one.
+pub async fn simulate(
+    controller: &Controller,
+    address: Felt,
+    calls: Vec<Call>,
+    chain_id: String,
+    block: Option<PinnedBlock>,
+) -> Result<SimulationOutput> {
+    let mut output = SimulationOutput {
+        simulated: true,
+        chain_id,
+        account: format!("0x{address:x}"),
+        status: "succeeded".to_string(),
+        revert_reason: None,
+        fee: None,
+        trace: Vec::new(),
+        gas_by_call: Vec::new(),
+        state_diff: None,
+        block: block.clone(),
+    };
+
+    let simulation = match &block {
+        Some(block) => {
+            simulate_at(
+                controller,
+                address,
+                calls,
+                BlockId::Number(block.block_number),
+            )
+            .await
+        }
+        None => controller
+            .execute_v3(calls)
+            .simulate(false, true)
+            .await
+            .map_err(|e| match e {
+                AccountError::Provider(e) => AccountError::Provider(e),
+                e => AccountError::Signing(e.to_string()),
+            }),
+    };
+    let simulation = match simulation {
+        Ok(simulation) => simulation,
+        // Calls that fail during estimation never produce a trace
+        Err(AccountError::Provider(ProviderError::StarknetError(
+            error @ StarknetError::TransactionExecutionError(_),
+        ))) => {
+            output.status = "reverted".to_string();
+            output.revert_reason = Some(error.to_string());
+            return Ok(output);
+        }
+        Err(e) => return Err(CliError::Network(format!("Simulation failed: {e}"))),
+    };
+
+    summarize(&mut output, simulation);
+    Ok(output)
+}
+
+/// `ExecutionV3::simulate` always runs against the account's block, so a pinned simulation takes
+/// the nonce and fee estimate at `block_id` and submits the signed request itself.
+async fn simulate_at(
+    controller: &Controller,
+    address: Felt,
+    calls: Vec<Call>,
+    block_id: BlockId,
+) -> std::result::Result<SimulatedTransaction, AccountError<String>> {
+    let provider = &controller.provider;
+    let nonce = provider
+        .get_nonce(block_id, address)
+        .await
+        .map_err(AccountError::Provider)?;
+
+    // Estimated like starknet-rs does: a signed query-only request with zero resource bounds
+    let query = controller
+        .execute_v3(calls.clone())
+        .nonce(nonce)
+        .l1_gas(0)
+        .l1_gas_price(0)
+        .l2_gas(0)
+        .l2_gas_price(0)
+        .l1_data_gas(0)
+        .l1_data_gas_price(0)
+        .tip(0)
+        .prepared()
+        .map_err(|e| AccountError::Signing(e.to_string()))?
+        .get_invoke_request(true, false)
+        .await
+        .map_err(|e| AccountError::Signing(e.to_string()))?;
+    let no_flags: [SimulationFlagForEstimateFee; 0] = [];
+    let fee = provider
+        .estimate_fee_single(BroadcastedTransaction::Invoke(query), no_flags, block_id)
+        .await
+        .map_err(AccountError::Provider)?;
+
+    let request = controller
+        .execute_v3(calls)
+        .nonce(nonce)
+        .l1_gas(with_headroom(fee.l1_gas_consumed))
+        .l1_gas_price(with_headroom(fee.l1_gas_price))
+        .l2_gas(with_headroom(fee.l2_gas_consumed))
+        .l2_gas_price(with_headroom(fee.l2_gas_price))
+        .l1_data_gas(with_headroom(fee.l1_data_gas_consumed))
+        .l1_data_gas_price(with_headroom(fee.l1_data_gas_price))
+        .tip(0)
+        .prepared()
+        .map_err(|e| AccountError::Signing(e.to_string()))?
+        .get_invoke_request(false, false)
+        .await
+        .map_err(|e| AccountError::Signing(e.to_string()))?;
+    provider
+        .simulate_transaction(
+            block_id,
+            BroadcastedTransaction::Invoke(request),
+            [SimulationFlag::SkipFeeCharge],
+        )
+        .await
+        .map_err(AccountError::Provider)
+}
+
+/// The block to pin a simulation to: `--at-block` (number, hash or 'latest', resolved to a number
+/// so the output can be replayed), or the last block at or before `--override-timestamp`. Starknet
+/// RPC can't fake a block timestamp, so the timestamp selects the block that was current then.
+pub async fn pin_block<P: Provider + Sync>(
+    provider: &P,
+    at_block: Option<String>,
+    timestamp: Option<u64>,
+) -> Result<Option<PinnedBlock>> {
+    if let Some(at_block) = at_block {
+        let block_id = super::call::parse_block_id(Some(at_block))?;
+        return Ok(Some(block_at(provider, block_id).await?));
+    }
+    let Some(timestamp) = timestamp else {
+        return Ok(None);
+    };
+
+    let latest = block_at(provider, BlockId::Tag(BlockTag::Latest)).await?;
+    if latest.timestamp <= timestamp {
+        return Ok(Some(latest));
+    }
+    let mut found = block_at(provider, BlockId::Number(0)).await?;
+    if found.timestamp > timestamp {
+        return Err(CliError::InvalidInput(format!(
+            "Timestamp {timestamp} is before the chain's first block ({})",
+            found.timestamp
+        )));
+    }
+    // Block timestamps never decrease: `found` is at or before the timestamp, `after` past it
+    let mut after = latest.block_number;
+    while found.block_number + 1 < after {
+        let middle = found.block_number + (after - found.block_number) / 2;
+        let block = block_at(provider, BlockId::Number(middle)).await?;
+        if block.timestamp <= timestamp {
+            found = block;
+        } else {
+            after = middle;
+        }
+    }
+    Ok(Some(found))
+}
+
+async fn block_at<P: Provider + Sync>(provider: &P, block_id: BlockId) -> Result<PinnedBlock> {
+    match provider.get_block_with_tx_hashes(block_id).await {
+        Ok(MaybePreConfirmedBlockWithTxHashes::Block(block)) => Ok(PinnedBlock {
+            block_number: block.block_number,
+            block_hash: format!("0x{:x}", block.block_hash),
+            timestamp: block.timestamp,
+        }),
+        Ok(MaybePreConfirmedBlockWithTxHashes::PreConfirmedBlock(_)) => Err(
+            CliError::InvalidInput("Simulations can only be pinned to a confirmed block".into()),
+        ),
+        Err(e) => Err(CliError::Network(format!("Failed to fetch block: {e}"))),
+    }
+}
+
+/// `--override-timestamp` as unix seconds or RFC 3339.
+pub fn parse_timestamp(value: &str) -> Result<u64> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Ok(seconds);
+    }
+    chrono::DateTime::parse_from_rfc3339(value)
+        .ok()
+        .and_then(|dt| u64::try_from(dt.timestamp()).ok())
+        .ok_or_else(|| {
+            CliError::InvalidInput(format!(
+                "Invalid --override-timestamp '{value}': use unix seconds or RFC 3339 like \
+                 2025-01-01T00:00:00Z"
+            ))
+        })
+}
+
+fn summarize(output: &mut SimulationOutput, simulation: SimulatedTransaction) {
+    let fee = simulation.fee_estimation;
+    output.fee = Some(SimulatedFee {
+        overall_fee: fee.overall_fee.to_string(),
+        l1_gas: fee.l1_gas_consumed,
+        l1_data_gas: fee.l1_data_gas_consumed,
+        l2_gas: fee.l2_gas_consumed,
+    });
+
+    if let TransactionTrace::Invoke(trace) = simulation.transaction_trace {
+        match trace.execute_invocation {
+            // The top-level invocation is the account's __execute__; its calls are ours
+            ExecuteInvocation::Success(invocation) => {
+                let selectors = super::selectors::SelectorDb::load();
+                output.trace = invocation
+                    .calls
+                    .iter()
+                    .map(|call| traced(call, &selectors))
+                    .collect();
+                output.gas_by_call = gas_by_call(&output.trace);
+            }
+            ExecuteInvocation::Reverted(reverted) => {
+                output.status = "reverted".to_string();
+                output.revert_reason = Some(reverted.revert_reason);
+            }
+        }
+        output.state_diff = trace.state_diff.as_ref().map(state_diff_summary);
+    }
+}
+
+fn traced(invocation: &FunctionInvocation, selectors: &super::selectors::SelectorDb) -> TracedCall {
+    TracedCall {
+        contract_address: format!("0x{:x}", invocation.contract_address),
+        entrypoint: selectors
+            .name(invocation.entry_point_selector)
+            .map(str::to_string),
+        selector: format!("{:#x}", invocation.entry_point_selector),
+        result: invocation
+            .result
+            .iter()
+            .map(|felt| format!("{felt:#x}"))
+            .collect(),
+        events: invocation.events.len(),
+        l1_gas: invocation.execution_resources.l1_gas,
+        l2_gas: invocation.execution_resources.l2_gas,
+        reverted: invocation.is_reverted,
+        calls: invocation
+            .calls
+            .iter()
+            .map(|call| traced(call, selectors))
+            .collect(),
+    }
+}
+
+/// Attribute gas to the calls of the multicall. The trace reports each call's resources for its
+/// whole call tree, so the top-level calls split the execution without double counting.
+fn gas_by_call(trace: &[TracedCall]) -> Vec<CallGas> {
+    let total: u64 = trace.iter().map(|call| call.l2_gas).sum();
+    trace
+        .iter()
+        .enumerate()
+        .map(|(index, call)| CallGas {
+            index,
+            contract_address: call.contract_address.clone(),
+            entrypoint: call.entrypoint.clone(),
+            selector: call.selector.clone(),
+            l1_gas: call.l1_gas,
+            l2_gas: call.l2_gas,
+            l2_gas_percent: if total == 0 {
+                0.0
+            } else {
+                (call.l2_gas as f64 * 1000.0 / total as f64).round() / 10.0
+            },
+        })
+        .collect()
+}
+
+fn state_diff_summary(diff: &StateDiff) -> StateDiffSummary {
+    StateDiffSummary {
+        storage_writes: diff
+            .storage_diffs
+            .iter()
+            .map(|item| StorageWrites {
+                contract_address: format!("0x{:x}", item.address),
+                keys: item.storage_entries.len(),
+            })
+            .collect(),
+        nonces_updated: diff.nonces.len(),
+        deployed_contracts: diff
+            .deployed_contracts
+            .iter()
+            .map(|item| format!("0x{:x}", item.address))
+            .collect(),
+        replaced_classes: diff
+            .replaced_classes
+            .iter()
+            .map(|item| format!("0x{:x}", item.contract_address))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starknet::core::types::{ContractStorageDiffItem, NonceUpdate, StorageEntry};
+
+    #[test]
+    fn parses_override_timestamps() {
+        assert_eq!(parse_timestamp("1735689600").unwrap(), 1_735_689_600);
+        assert_eq!(
+            parse_timestamp("2025-01-01T00:00:00Z").unwrap(),
+            1_735_689_600
+        );
+        assert!(parse_timestamp("yesterday").is_err());
+    }
+
+    #[test]
+    fn attributes_gas_to_top_level_calls() {
+        let call = |address: &str, l2_gas: u64, calls: Vec<TracedCall>| TracedCall {
+            contract_address: address.to_string(),
+            entrypoint: None,
+            selector: "0x1".to_string(),
+            result: vec![],
+            events: 0,
+            l1_gas: 0,
+            l2_gas,
+            reverted: false,
+            calls,
+        };
+        let trace = vec![
+            call("0xa", 300, vec![call("0xc", 200, vec![])]),
+            call("0xb", 900, vec![]),
+        ];
+        let gas = gas_by_call(&trace);
+        assert_eq!(gas.len(), 2);
+        assert_eq!((gas[0].index, gas[0].l2_gas), (0, 300));
+        assert_eq!(gas[0].l2_gas_percent, 25.0);
+        assert_eq!(gas[1].l2_gas_percent, 75.0);
+        assert_eq!(
+            gas_by_call(&[call("0xa", 0, vec![])])[0].l2_gas_percent,
+            0.0
+        );
+    }
+
+    #[test]
+    fn summarizes_state_diffs() {
+        let entry = |key: u64| StorageEntry {
+            key: Felt::from(key),
+            value: Felt::ONE,
+        };
+        let diff = StateDiff {
+            storage_diffs: vec![
+                ContractStorageDiffItem {
+                    address: Felt::from(0xabcu64),
+                    storage_entries: vec![entry(1), entry(2)],
+                },
+                ContractStorageDiffItem {
+                    address: Felt::from(0xdefu64),
+                    storage_entries: vec![entry(3)],
+                },
+            ],
+            deprecated_declared_classes: vec![],
+            declared_classes: vec![],
+            deployed_contracts: vec![],
+            replaced_classes: vec![],
+            nonces: vec![NonceUpdate {
+                contract_address: Felt::from(0x123u64),
+                nonce: Felt::from(7u64),
+            }],
+        };
+
+        let summary = state_diff_summary(&diff);
+        assert_eq!(summary.storage_writes.len(), 2);
+        assert_eq!(summary.storage_writes[0].contract_address, "0xabc");
+        assert_eq!(summary.storage_writes[0].keys, 2);
+        assert_eq!(summary.nonces_updated, 1);
+        assert!(summary.deployed_contracts.is_empty());
+    }
+}
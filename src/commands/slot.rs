@@ -0,0 +1,131 @@
+use crate::{
+    config::{ChainProfile, Config},
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use serde::Serialize;
+use starknet::providers::JsonRpcClient;
+use std::time::Duration;
+
+#[derive(Serialize)]
+pub struct SlotLinkOutput {
+    pub project: String,
+    /// Chain profile name, usable as `--chain-id`
+    pub chain_id: String,
+    pub rpc_url: String,
+    pub torii_url: Option<String>,
+    pub message: String,
+}
+
+/// Discover a Slot project's Katana RPC and Torii URL and save them as a chain profile, so
+/// `--chain-id <name>` works with `session auth`, `execute` and `call`. The deployments are
+/// found by probing their URLs: Katana must answer `starknet_chainId`, and Torii is saved only
+/// when it responds.
+pub async fn link(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    project: String,
+    name: Option<String>,
+) -> Result<()> {
+    validate_project(&project)?;
+    let (rpc_url, torii_url) = deployment_urls(config.environment().rpc_base, &project);
+
+    let url = url::Url::parse(&rpc_url)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
+    let provider = JsonRpcClient::new(crate::api::rpc_transport(url));
+    let detected = crate::chains::chain_name(&provider).await.map_err(|e| {
+        CliError::NotFoundError(format!(
+            "Katana for Slot project '{project}' is not reachable at {rpc_url}: {e}"
+        ))
+    })?;
+    let has_torii = probe_torii(&torii_url).await;
+    let chain_id = name.unwrap_or(detected);
+    if crate::chains::get(&chain_id).is_some() {
+        return Err(CliError::InvalidInput(format!(
+            "Katana reports the built-in chain ID {chain_id}. Pass --name to pick a profile name"
+        )));
+    }
+
+    let mut saved = Config::load().unwrap_or_default();
+    if let Some(existing) = saved.chains.get(&chain_id) {
+        if existing.slot_project.as_deref() != Some(project.as_str()) {
+            formatter.warning(&format!(
+                "Replacing the existing chain profile {chain_id} ({})",
+                existing.rpc_url
+            ));
+        }
+    }
+    let torii_url = has_torii.then_some(torii_url);
+    saved.chains.insert(
+        chain_id.clone(),
+        ChainProfile {
+            rpc_url: rpc_url.clone(),
+            torii_url: torii_url.clone(),
+            slot_project: Some(project.clone()),
+        },
+    );
+    saved
+        .save()
+        .map_err(|e| CliError::Config(format!("Failed to save config: {e}")))?;
+
+    formatter.success(&SlotLinkOutput {
+        message: format!(
+            "Linked Slot project '{project}'. Use --chain-id {chain_id} with session auth, execute and call"
+        ),
+        project,
+        chain_id,
+        rpc_url,
+        torii_url,
+    });
+    Ok(())
+}
+
+/// Slot project names are lowercase letters, digits and dashes
+fn validate_project(project: &str) -> Result<()> {
+    let valid = !project.is_empty()
+        && project
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(CliError::InvalidInput(format!(
+            "Invalid Slot project '{project}': use lowercase letters, digits and dashes"
+        )))
+    }
+}
+
+/// Katana and Torii of a Slot project are served under the environment's RPC base
+fn deployment_urls(rpc_base: &str, project: &str) -> (String, String) {
+    (
+        format!("{rpc_base}/x/{project}/katana"),
+        format!("{rpc_base}/x/{project}/torii"),
+    )
+}
+
+async fn probe_torii(torii_url: &str) -> bool {
+    let Ok(client) = crate::api::http_client(Duration::from_secs(10)) else {
+        return false;
+    };
+    crate::api::send(client.get(torii_url), "Failed to reach Torii")
+        .await
+        .is_ok_and(|response| response.status().is_success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_deployment_urls() {
+        assert!(validate_project("my-game-2").is_ok());
+        assert!(validate_project("My Game").is_err());
+        assert_eq!(
+            deployment_urls("https://api.cartridge.gg", "my-game"),
+            (
+                "https://api.cartridge.gg/x/my-game/katana".to_string(),
+                "https://api.cartridge.gg/x/my-game/torii".to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,249 @@
+use crate::{
+    commands::call::{decode_value, execute_single_call, parse_block_id, parse_calls_file},
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use serde::{Deserialize, Serialize};
+use starknet::providers::JsonRpcClient;
+use std::collections::BTreeMap;
+
+/// Results of a set of view calls at one point in time, as written by `snapshot take`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub taken_at: String,
+    pub rpc_url: String,
+    pub block_id: String,
+    pub entries: Vec<SnapshotEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    /// `contract.entrypoint(calldata)`, used to pair entries across snapshots
+    pub key: String,
+    pub contract: String,
+    pub entrypoint: String,
+    pub calldata: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Vec<String>>,
+    /// Decoded felt/u256 value, when the result has that shape
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct TakeOutput {
+    pub path: String,
+    pub entries: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SnapshotDiff {
+    pub changed: Vec<Change>,
+    /// Calls only present in the second snapshot
+    pub added: Vec<String>,
+    /// Calls only present in the first snapshot
+    pub removed: Vec<String>,
+    pub unchanged: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Change {
+    pub key: String,
+    pub before: Option<Vec<String>>,
+    pub after: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before_value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after_value: Option<String>,
+    /// `after - before` when both decoded values are integers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delta: Option<String>,
+    /// Result indexes that differ, for multi-felt results
+    pub changed_indexes: Vec<usize>,
+}
+
+/// Run every call in `calls_file` and write the results to `out`.
+#[allow(clippy::too_many_arguments)]
+pub async fn take(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    calls_file: String,
+    out: String,
+    chain_id: Option<String>,
+    rpc_url: Option<String>,
+    block_id: Option<String>,
+    account: Option<&str>,
+) -> Result<()> {
+    let rpc_url = crate::chains::resolve_rpc_url(config, formatter, chain_id, rpc_url, account)?;
+    let url = url::Url::parse(&rpc_url)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
+    let provider = JsonRpcClient::new(crate::api::rpc_transport(url));
+    let block = parse_block_id(block_id.clone())?;
+    let calls = parse_calls_file(&calls_file)?;
+
+    let mut entries = Vec::new();
+    for call in calls {
+        let key = entry_key(&call.contract_address, &call.entrypoint, &call.calldata);
+        let (result, error) = match execute_single_call(&provider, &call, block).await {
+            Ok(result) => (Some(result), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        entries.push(SnapshotEntry {
+            key,
+            value: result.as_deref().and_then(decode_value),
+            contract: call.contract_address,
+            entrypoint: call.entrypoint,
+            calldata: call.calldata,
+            result,
+            error,
+        });
+    }
+
+    let failed = entries.iter().filter(|e| e.error.is_some()).count();
+    let snapshot = Snapshot {
+        taken_at: chrono::Utc::now().to_rfc3339(),
+        rpc_url,
+        block_id: block_id.unwrap_or_else(|| "latest".to_string()),
+        entries,
+    };
+    let json = serde_json::to_string_pretty(&snapshot)
+        .map_err(|e| CliError::InvalidInput(format!("Failed to serialize snapshot: {e}")))?;
+    std::fs::write(&out, format!("{json}\n")).map_err(|e| CliError::FileError {
+        path: out.clone(),
+        message: e.to_string(),
+    })?;
+
+    if failed > 0 {
+        formatter.warning(&format!(
+            "{failed} call(s) failed; their errors are recorded in the snapshot"
+        ));
+    }
+    formatter.success(&TakeOutput {
+        path: out,
+        entries: snapshot.entries.len(),
+        failed,
+    });
+    Ok(())
+}
+
+/// Compare two snapshots taken from the same calls file.
+pub async fn diff(formatter: &dyn OutputFormatter, before: String, after: String) -> Result<()> {
+    let before = load(&before)?;
+    let after = load(&after)?;
+    formatter.success(&compare(&before.entries, &after.entries));
+    Ok(())
+}
+
+fn load(path: &str) -> Result<Snapshot> {
+    let content = std::fs::read_to_string(path).map_err(|e| CliError::FileError {
+        path: path.to_string(),
+        message: e.to_string(),
+    })?;
+    serde_json::from_str(&content)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid snapshot '{path}': {e}")))
+}
+
+fn entry_key(contract: &str, entrypoint: &str, calldata: &[String]) -> String {
+    format!("{contract}.{entrypoint}({})", calldata.join(","))
+}
+
+fn compare(before: &[SnapshotEntry], after: &[SnapshotEntry]) -> SnapshotDiff {
+    let after_by_key: BTreeMap<&str, &SnapshotEntry> =
+        after.iter().map(|e| (e.key.as_str(), e)).collect();
+    let mut diff = SnapshotDiff::default();
+
+    for old in before {
+        let Some(new) = after_by_key.get(old.key.as_str()) else {
+            diff.removed.push(old.key.clone());
+            continue;
+        };
+        if old.result == new.result && old.error == new.error {
+            diff.unchanged += 1;
+            continue;
+        }
+        let empty = Vec::new();
+        let (old_felts, new_felts) = (
+            old.result.as_ref().unwrap_or(&empty),
+            new.result.as_ref().unwrap_or(&empty),
+        );
+        let changed_indexes = (0..old_felts.len().max(new_felts.len()))
+            .filter(|&i| old_felts.get(i) != new_felts.get(i))
+            .collect();
+        diff.changed.push(Change {
+            key: old.key.clone(),
+            before: old.result.clone(),
+            after: new.result.clone(),
+            delta: delta(old.value.as_deref(), new.value.as_deref()),
+            before_value: old.value.clone(),
+            after_value: new.value.clone(),
+            changed_indexes,
+        });
+    }
+
+    let before_keys: Vec<&str> = before.iter().map(|e| e.key.as_str()).collect();
+    diff.added = after
+        .iter()
+        .filter(|e| !before_keys.contains(&e.key.as_str()))
+        .map(|e| e.key.clone())
+        .collect();
+    diff
+}
+
+fn delta(before: Option<&str>, after: Option<&str>) -> Option<String> {
+    let before: i128 = before?.parse().ok()?;
+    let after: i128 = after?.parse().ok()?;
+    let delta = after.checked_sub(before)?;
+    Some(if delta > 0 {
+        format!("+{delta}")
+    } else {
+        delta.to_string()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(entrypoint: &str, result: &[&str]) -> SnapshotEntry {
+        let result: Vec<String> = result.iter().map(|r| r.to_string()).collect();
+        SnapshotEntry {
+            key: entry_key("0x1", entrypoint, &[]),
+            contract: "0x1".to_string(),
+            entrypoint: entrypoint.to_string(),
+            calldata: Vec::new(),
+            value: decode_value(&result),
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn diffs_decoded_results() {
+        let before = vec![
+            entry("health", &["0x64"]),
+            entry("gold", &["0xa", "0x0"]),
+            entry("position", &["0x1", "0x2", "0x3"]),
+            entry("level", &["0x1"]),
+        ];
+        let after = vec![
+            entry("health", &["0x5a"]),
+            entry("gold", &["0xa", "0x0"]),
+            entry("position", &["0x1", "0x4", "0x3"]),
+            entry("xp", &["0x0"]),
+        ];
+
+        let diff = compare(&before, &after);
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.removed, vec!["0x1.level()"]);
+        assert_eq!(diff.added, vec!["0x1.xp()"]);
+        assert_eq!(diff.changed.len(), 2);
+        assert_eq!(diff.changed[0].key, "0x1.health()");
+        assert_eq!(diff.changed[0].delta.as_deref(), Some("-10"));
+        assert_eq!(diff.changed[1].delta, None);
+        assert_eq!(diff.changed[1].changed_indexes, vec![1]);
+    }
+}
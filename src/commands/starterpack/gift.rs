@@ -0,0 +1,139 @@
+use crate::config::Config;
+use crate::error::{InputRequest, Result};
+use crate::output::{Capture, OutputFormatter};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct GiftOutput {
+    recipient: String,
+    recipient_address: String,
+    quantity: u32,
+    payment_token: String,
+    total_cost: String,
+    /// The `starterpack purchase --direct` result
+    #[serde(flatten)]
+    purchase: serde_json::Value,
+}
+
+/// Buy a starterpack for another player: quote it, resolve the recipient (a username or an
+/// address), confirm the cost, then purchase it directly to the recipient's controller.
+#[allow(clippy::too_many_arguments)]
+pub async fn execute(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    id: String,
+    recipient: String,
+    quantity: u32,
+    yes: bool,
+    chain_id: Option<String>,
+    rpc_url: Option<String>,
+    wait: bool,
+    timeout: u64,
+    no_paymaster: bool,
+    account: Option<&str>,
+) -> Result<()> {
+    let recipient_address =
+        crate::commands::transfer::resolve_recipient(config, &recipient).await?;
+
+    let mut json_config = config.clone();
+    json_config.cli.json_output = true;
+    let capture = Capture::new(formatter);
+    super::quote::execute(
+        &json_config,
+        &capture,
+        id.clone(),
+        quantity,
+        chain_id.clone(),
+        rpc_url.clone(),
+        account,
+    )
+    .await?;
+    let quote = capture.take().unwrap_or_default();
+
+    let recipient_address = format!("0x{recipient_address:x}");
+    formatter.info(&gift_summary(
+        &id,
+        quantity,
+        &recipient,
+        &recipient_address,
+        &quote,
+    ));
+    if !yes && !config.cli.json_output {
+        return Err(InputRequest::confirm("Purchase the gift?", "--yes"));
+    }
+
+    let capture = Capture::new(formatter);
+    super::purchase::execute(
+        config,
+        &capture,
+        id,
+        Some(recipient_address.clone()),
+        quantity,
+        false,
+        true,
+        chain_id,
+        rpc_url,
+        wait,
+        timeout,
+        no_paymaster,
+        account,
+    )
+    .await?;
+
+    if config.cli.json_output {
+        formatter.success(&GiftOutput {
+            recipient,
+            recipient_address,
+            quantity,
+            payment_token: quote["payment_token"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            total_cost: quote["total_cost"].as_str().unwrap_or_default().to_string(),
+            purchase: capture.take().unwrap_or_default(),
+        });
+    }
+    Ok(())
+}
+
+/// 'Gift 2 x starterpack #5 to alice (0x123) for 21.4 STRK'
+fn gift_summary(
+    id: &str,
+    quantity: u32,
+    recipient: &str,
+    recipient_address: &str,
+    quote: &serde_json::Value,
+) -> String {
+    let to = if recipient.starts_with("0x") || recipient.starts_with("0X") {
+        recipient_address.to_string()
+    } else {
+        format!("{recipient} ({recipient_address})")
+    };
+    let cost = match (
+        quote["total_cost"].as_str(),
+        quote["payment_token_symbol"].as_str(),
+    ) {
+        (Some(cost), Some(symbol)) => format!(" for {cost} {symbol}"),
+        (Some(cost), None) => format!(" for {cost}"),
+        _ => String::new(),
+    };
+    format!("Gift {quantity} x starterpack #{id} to {to}{cost}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_the_gift() {
+        let quote = serde_json::json!({"total_cost": "10.7", "payment_token_symbol": "STRK"});
+        assert_eq!(
+            gift_summary("5", 2, "alice", "0x123", &quote),
+            "Gift 2 x starterpack #5 to alice (0x123) for 10.7 STRK"
+        );
+        assert_eq!(
+            gift_summary("5", 1, "0x0123", "0x123", &serde_json::Value::Null),
+            "Gift 1 x starterpack #5 to 0x123"
+        );
+    }
+}
@@ -4,9 +4,9 @@ use crate::output::OutputFormatter;
 use cainome_cairo_serde::{ByteArray, CairoSerde};
 use serde::{Deserialize, Serialize};
 use starknet::core::types::{BlockId, BlockTag, FunctionCall};
-use starknet::providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider};
+use starknet::providers::{JsonRpcClient, Provider};
 
-use super::{parse_starterpack_id, resolve_rpc_url, STARTERPACK_CONTRACT};
+use super::parse_starterpack_id;
 
 #[derive(Serialize, Deserialize)]
 struct StarterpackMetadata {
@@ -30,12 +30,17 @@ pub async fn execute(
     id: String,
     chain_id: Option<String>,
     rpc_url: Option<String>,
+    account: Option<&str>,
 ) -> Result<()> {
-    let rpc_url = resolve_rpc_url(chain_id, rpc_url, config, formatter)?;
+    let rpc_url = crate::chains::resolve_rpc_url(config, formatter, chain_id, rpc_url, account)?;
 
     let url = url::Url::parse(&rpc_url)
         .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
-    let provider = JsonRpcClient::new(HttpTransport::new(url));
+    let provider = JsonRpcClient::new(crate::api::rpc_transport(url));
+
+    let chain_name = crate::chains::chain_name(&provider).await?;
+    let starterpack_contract =
+        crate::chains::resolve_contract(config, &chain_name, crate::chains::STARTERPACK)?;
 
     let id_felt = parse_starterpack_id(&id)?;
 
@@ -47,7 +52,7 @@ pub async fn execute(
     let result = provider
         .call(
             FunctionCall {
-                contract_address: STARTERPACK_CONTRACT,
+                contract_address: starterpack_contract,
                 entry_point_selector: selector,
                 calldata: vec![id_felt],
             },
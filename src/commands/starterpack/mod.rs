@@ -1,14 +1,12 @@
+pub mod gift;
 pub mod info;
 pub mod purchase;
 pub mod quote;
 
+use crate::api::RpcTransport;
 use cainome_cairo_serde::{ByteArray, CairoSerde};
 use starknet::core::types::{BlockId, BlockTag, Felt, FunctionCall};
-use starknet::providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider};
-
-/// Hardcoded starterpack contract address
-pub const STARTERPACK_CONTRACT: Felt =
-    Felt::from_hex_unchecked("0x3eb03b8f2be0ec2aafd186d72f6d8f3dd320dbc89f2b6802bca7465f6ccaa43");
+use starknet::providers::{JsonRpcClient, Provider};
 
 /// Token info queried on-chain from the ERC20 contract
 pub struct TokenInfo {
@@ -18,7 +16,7 @@ pub struct TokenInfo {
 
 /// Query ERC20 symbol and decimals from the token contract
 pub async fn query_token_info(
-    provider: &JsonRpcClient<HttpTransport>,
+    provider: &JsonRpcClient<RpcTransport>,
     token_address: Felt,
 ) -> crate::error::Result<TokenInfo> {
     let symbol = query_token_symbol(provider, token_address).await?;
@@ -27,7 +25,7 @@ pub async fn query_token_info(
 }
 
 async fn query_token_symbol(
-    provider: &JsonRpcClient<HttpTransport>,
+    provider: &JsonRpcClient<RpcTransport>,
     token_address: Felt,
 ) -> crate::error::Result<String> {
     let selector = starknet::core::utils::get_selector_from_name("symbol")
@@ -64,7 +62,7 @@ async fn query_token_symbol(
 }
 
 async fn query_token_decimals(
-    provider: &JsonRpcClient<HttpTransport>,
+    provider: &JsonRpcClient<RpcTransport>,
     token_address: Felt,
 ) -> crate::error::Result<u8> {
     let selector = starknet::core::utils::get_selector_from_name("decimals")
@@ -158,30 +156,3 @@ pub fn parse_starterpack_id(id: &str) -> crate::error::Result<Felt> {
         crate::error::CliError::InvalidInput(format!("Invalid starterpack ID '{id}': {e}"))
     })
 }
-
-/// Resolve RPC URL from chain_id, explicit rpc_url, or config
-pub fn resolve_rpc_url(
-    chain_id: Option<String>,
-    rpc_url: Option<String>,
-    config: &crate::config::Config,
-    formatter: &dyn crate::output::OutputFormatter,
-) -> crate::error::Result<String> {
-    if let Some(url) = rpc_url {
-        return Ok(url);
-    }
-
-    if let Some(chain) = chain_id {
-        match chain.as_str() {
-            "SN_MAIN" => Ok("https://api.cartridge.gg/x/starknet/mainnet".to_string()),
-            "SN_SEPOLIA" => Ok("https://api.cartridge.gg/x/starknet/sepolia".to_string()),
-            _ => Err(crate::error::CliError::InvalidInput(format!(
-                "Unsupported chain ID '{chain}'. Supported chains: SN_MAIN, SN_SEPOLIA"
-            ))),
-        }
-    } else if !config.session.rpc_url.is_empty() {
-        Ok(config.session.rpc_url.clone())
-    } else {
-        formatter.warning("No --chain-id or --rpc-url specified, using SN_SEPOLIA by default");
-        Ok("https://api.cartridge.gg/x/starknet/sepolia".to_string())
-    }
-}
@@ -1,3 +1,4 @@
+use crate::commands::audit::AuditEntry;
 use crate::commands::session::authorize::PolicyStorage;
 use crate::config::Config;
 use crate::error::{CliError, Result};
@@ -9,17 +10,21 @@ use account_sdk::{
 };
 use serde::Serialize;
 use starknet::core::types::{BlockId, BlockTag, Call, Felt, FunctionCall};
-use starknet::providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider};
+use starknet::providers::{JsonRpcClient, Provider};
 
 use super::{
     felt_to_u128, format_token_amount, parse_starterpack_id, query_token_info, StarterpackQuote,
-    STARTERPACK_CONTRACT,
 };
 
 #[derive(Serialize)]
 struct PurchaseOutput {
     transaction_hash: String,
     message: String,
+    chain_id: String,
+    explorer_url: Option<String>,
+    paymaster_used: bool,
+    /// RFC 3339 timestamp of when the transaction was submitted
+    submitted_at: String,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -56,7 +61,7 @@ pub async fn execute(
     }
 
     // Default to UI mode
-    execute_ui(config, formatter, &id, chain_id, rpc_url).await
+    execute_ui(config, formatter, &id, chain_id, rpc_url, account).await
 }
 
 /// Open the starterpack purchase UI in the browser
@@ -66,11 +71,16 @@ async fn execute_ui(
     id: &str,
     chain_id: Option<String>,
     rpc_url: Option<String>,
+    account: Option<&str>,
 ) -> Result<()> {
     // Determine chain_id string for URL
-    let chain_id_str = resolve_chain_id_string(chain_id, rpc_url, config, formatter).await?;
+    let chain_id_str =
+        resolve_chain_id_string(chain_id, rpc_url, config, formatter, account).await?;
 
-    let url = format!("https://x.cartridge.gg/starterpack/{id}/{chain_id_str}");
+    let url = format!(
+        "{}/starterpack/{id}/{chain_id_str}",
+        config.session.keychain_url
+    );
 
     formatter.info("Opening starterpack purchase page...");
 
@@ -108,11 +118,11 @@ async fn execute_direct(
     let quantity_felt = Felt::from(quantity);
 
     // Resolve --chain-id to RPC URL
-    let rpc_url = resolve_chain_id_to_rpc(chain_id, rpc_url)?;
+    let rpc_url = resolve_chain_id_to_rpc(config, chain_id, rpc_url)?;
 
     // Load controller metadata
     let storage_path = config.resolve_storage_path(account);
-    let backend = FileSystemBackend::new(storage_path);
+    let backend = FileSystemBackend::new(storage_path.clone());
 
     let controller_metadata = backend
         .controller()
@@ -177,18 +187,18 @@ async fn execute_direct(
 
     // Validate Cartridge RPC endpoint
     if let Some(ref url) = rpc_url {
-        if !url.starts_with("https://api.cartridge.gg") {
-            return Err(CliError::InvalidInput(
-                "Only Cartridge RPC endpoints are supported. Use: https://api.cartridge.gg/x/starknet/mainnet or https://api.cartridge.gg/x/starknet/sepolia".to_string()
-            ));
-        }
+        crate::chains::ensure_cartridge_rpc(config, url)?;
     }
 
     let rpc_parsed = url::Url::parse(&effective_rpc_url)
         .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
 
     // First, get the quote to know the payment token and amount
-    let provider = JsonRpcClient::new(HttpTransport::new(rpc_parsed.clone()));
+    let provider = JsonRpcClient::new(crate::api::rpc_transport(rpc_parsed.clone()));
+
+    let chain_name = crate::chains::chain_name(&provider).await?;
+    let starterpack_contract =
+        crate::chains::resolve_contract(config, &chain_name, crate::chains::STARTERPACK)?;
 
     formatter.info("Fetching quote...");
 
@@ -198,7 +208,7 @@ async fn execute_direct(
     let quote_result = provider
         .call(
             FunctionCall {
-                contract_address: STARTERPACK_CONTRACT,
+                contract_address: starterpack_contract,
                 entry_point_selector: quote_selector,
                 calldata: vec![id_felt, quantity_felt, Felt::ZERO],
             },
@@ -229,7 +239,7 @@ async fn execute_direct(
             _ => None,
         });
 
-    validate_purchase_policies(&stored_policies, quote.payment_token)?;
+    validate_purchase_policies(&stored_policies, quote.payment_token, starterpack_contract)?;
 
     // Build multicall: approve + issue
     let approve_selector = starknet::core::utils::get_selector_from_name("approve")
@@ -243,14 +253,14 @@ async fn execute_direct(
             to: quote.payment_token,
             selector: approve_selector,
             calldata: vec![
-                STARTERPACK_CONTRACT,
+                starterpack_contract,
                 quote.total_cost_low,
                 quote.total_cost_high,
             ],
         },
         // issue(recipient, starterpack_id, quantity, referrer=None, referrer_group=None)
         Call {
-            to: STARTERPACK_CONTRACT,
+            to: starterpack_contract,
             selector: issue_selector,
             calldata: vec![
                 recipient_felt,
@@ -274,50 +284,86 @@ async fn execute_direct(
     .await
     .map_err(|e| CliError::Storage(format!("Failed to create controller: {e}")))?;
 
-    let chain_name = match controller.provider.chain_id().await {
-        Ok(felt) => starknet::core::utils::parse_cairo_short_string(&felt)
-            .unwrap_or_else(|_| format!("0x{felt:x}")),
-        Err(_) => starknet::core::utils::parse_cairo_short_string(&controller_metadata.chain_id)
-            .unwrap_or_else(|_| format!("0x{:x}", controller_metadata.chain_id)),
-    };
-    let is_mainnet = chain_name == "SN_MAIN";
+    let specs = crate::commands::audit::call_specs(&calls);
+    let policies_matched =
+        crate::commands::execute::matched_policies(&specs, stored_policies.as_ref());
+    let mut audit = AuditEntry::new(
+        "starterpack purchase",
+        account,
+        controller_metadata.address,
+        &chain_name,
+        specs,
+        policies_matched,
+        !no_paymaster,
+    );
 
     // Execute
-    let result = if no_paymaster {
-        formatter.info(&format!(
-            "Purchasing starterpack #{id} on {chain_name} without paymaster..."
-        ));
-        let estimate = controller
-            .estimate_invoke_fee(calls.clone())
-            .await
-            .map_err(|e| CliError::TransactionFailed(format!("Fee estimation failed: {e}")))?;
-        controller
-            .execute(calls, Some(estimate), None)
-            .await
-            .map_err(|e| CliError::TransactionFailed(format!("Transaction failed: {e}")))?
-    } else {
-        formatter.info(&format!("Purchasing starterpack #{id} on {chain_name}..."));
-        controller
-            .execute_from_outside_v3(calls, None)
-            .await
-            .map_err(|e| {
-                CliError::TransactionFailed(format!(
-                    "Paymaster execution failed: {e}\nUse --no-paymaster to force self-pay"
-                ))
-            })?
-    };
+    let submitted: Result<_> = async {
+        Ok(if no_paymaster {
+            formatter.info(&format!(
+                "Purchasing starterpack #{id} on {chain_name} without paymaster..."
+            ));
+            let estimate = controller
+                .estimate_invoke_fee(calls.clone())
+                .await
+                .map_err(|e| CliError::TransactionFailed(format!("Fee estimation failed: {e}")))?;
+            crate::commands::balance::ensure_fee_balance(
+                config,
+                formatter,
+                &controller.provider,
+                controller_metadata.address,
+                Felt::from(estimate.overall_fee),
+            )
+            .await?;
+            controller
+                .execute(calls, Some(estimate), None)
+                .await
+                .map_err(|e| CliError::TransactionFailed(format!("Transaction failed: {e}")))?
+        } else {
+            formatter.info(&format!("Purchasing starterpack #{id} on {chain_name}..."));
+            controller
+                .execute_from_outside_v3(calls, None)
+                .await
+                .map_err(|e| {
+                    CliError::TransactionFailed(format!(
+                        "Paymaster execution failed: {e}\nUse --no-paymaster to force self-pay"
+                    ))
+                })?
+        })
+    }
+    .await;
+    audit.submitted(
+        config,
+        formatter,
+        submitted.as_ref().map(|result| result.transaction_hash),
+    );
+    let result = submitted?;
+
+    // Purchases always spend the payment token
+    crate::commands::balance::invalidate_cache_after_tx(
+        config,
+        &storage_path,
+        controller_metadata.address,
+        &[],
+        true,
+    );
 
     let transaction_hash = format!("0x{:x}", result.transaction_hash);
-    let voyager_subdomain = if is_mainnet { "" } else { "sepolia." };
+    let explorer_url = crate::chains::explorer_tx_url(&chain_name, &transaction_hash);
 
     if config.cli.json_output {
         formatter.success(&PurchaseOutput {
             transaction_hash: transaction_hash.clone(),
             message: "Starterpack purchased successfully".to_string(),
+            chain_id: chain_name,
+            explorer_url,
+            paymaster_used: !no_paymaster,
+            submitted_at: chrono::Utc::now().to_rfc3339(),
         });
     } else {
         formatter.info(&format!(
-            "Transaction: https://{voyager_subdomain}voyager.online/tx/{transaction_hash}"
+            "Transaction: {}",
+            explorer_url.as_deref().unwrap_or(&transaction_hash)
         ));
     }
 
@@ -340,8 +386,9 @@ async fn execute_direct(
                 .get_transaction_receipt(result.transaction_hash)
                 .await
             {
-                Ok(_) => {
+                Ok(receipt) => {
                     formatter.info("Transaction confirmed!");
+                    audit.confirmed(config, formatter, &receipt.receipt);
                     break;
                 }
                 Err(_) => {
@@ -356,14 +403,18 @@ async fn execute_direct(
 
 /// Validate that the session policies include `approve` on the payment token
 /// and `issue` on the starterpack contract. Returns an error if any are missing.
-fn validate_purchase_policies(policies: &Option<PolicyStorage>, payment_token: Felt) -> Result<()> {
+fn validate_purchase_policies(
+    policies: &Option<PolicyStorage>,
+    payment_token: Felt,
+    starterpack_contract: Felt,
+) -> Result<()> {
     let mut missing = Vec::new();
 
     match policies {
         None => {
             missing.push(format!("approve on payment token (0x{payment_token:x})"));
             missing.push(format!(
-                "issue on starterpack contract (0x{STARTERPACK_CONTRACT:x})"
+                "issue on starterpack contract (0x{starterpack_contract:x})"
             ));
         }
         Some(policies) => {
@@ -376,12 +427,12 @@ fn validate_purchase_policies(policies: &Option<PolicyStorage>, payment_token: F
             }
 
             let has_issue = policies.contracts.iter().any(|(addr, policy)| {
-                Felt::from_hex(addr).ok() == Some(STARTERPACK_CONTRACT)
+                Felt::from_hex(addr).ok() == Some(starterpack_contract)
                     && policy.methods.iter().any(|m| m.entrypoint == "issue")
             });
             if !has_issue {
                 missing.push(format!(
-                    "issue on starterpack contract (0x{STARTERPACK_CONTRACT:x})"
+                    "issue on starterpack contract (0x{starterpack_contract:x})"
                 ));
             }
         }
@@ -404,28 +455,18 @@ async fn resolve_chain_id_string(
     rpc_url: Option<String>,
     config: &Config,
     formatter: &dyn OutputFormatter,
+    account: Option<&str>,
 ) -> Result<String> {
-    if let Some(chain) = chain_id {
-        return match chain.as_str() {
-            "SN_MAIN" | "SN_SEPOLIA" => Ok(chain),
-            _ => Err(CliError::InvalidInput(format!(
-                "Unsupported chain ID '{chain}'. Supported chains: SN_MAIN, SN_SEPOLIA"
-            ))),
-        };
-    }
-
-    let rpc_url = if let Some(url) = rpc_url {
-        url
-    } else if !config.session.rpc_url.is_empty() {
-        config.session.rpc_url.clone()
-    } else {
-        formatter.warning("No --chain-id or --rpc-url specified, using SN_SEPOLIA by default");
-        return Ok("SN_SEPOLIA".to_string());
+    // A custom chain is named in the config; the UI needs the ID its Katana reports
+    let rpc_url = match chain_id {
+        Some(chain) if crate::chains::get(&chain).is_some() => return Ok(chain),
+        Some(chain) => crate::chains::rpc_url_for(config, &chain)?,
+        None => crate::chains::resolve_rpc_url(config, formatter, None, rpc_url, account)?,
     };
 
     let url = url::Url::parse(&rpc_url)
         .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
-    let provider = JsonRpcClient::new(HttpTransport::new(url));
+    let provider = JsonRpcClient::new(crate::api::rpc_transport(url));
 
     let chain_felt = provider
         .chain_id()
@@ -438,21 +479,50 @@ async fn resolve_chain_id_string(
 
 /// Resolve --chain-id to an RPC URL, or pass through --rpc-url as-is
 fn resolve_chain_id_to_rpc(
+    config: &Config,
     chain_id: Option<String>,
     rpc_url: Option<String>,
 ) -> Result<Option<String>> {
     match chain_id {
-        Some(chain) => match chain.as_str() {
-            "SN_MAIN" => Ok(Some(
-                "https://api.cartridge.gg/x/starknet/mainnet".to_string(),
-            )),
-            "SN_SEPOLIA" => Ok(Some(
-                "https://api.cartridge.gg/x/starknet/sepolia".to_string(),
-            )),
-            _ => Err(CliError::InvalidInput(format!(
-                "Unsupported chain ID '{chain}'. Supported chains: SN_MAIN, SN_SEPOLIA"
-            ))),
-        },
+        Some(chain) => crate::chains::rpc_url_for(config, &chain).map(Some),
         None => Ok(rpc_url),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::NullFormatter;
+
+    #[tokio::test]
+    async fn resolves_builtin_and_configured_chains() {
+        let mut config = Config::default();
+        let chain = resolve_chain_id_string(
+            Some("SN_MAIN".to_string()),
+            None,
+            &config,
+            &NullFormatter,
+            None,
+        )
+        .await;
+        assert_eq!(chain.unwrap(), "SN_MAIN");
+
+        config.chains.insert(
+            "my-slot-instance".to_string(),
+            crate::config::ChainProfile {
+                rpc_url: "https://slot.example/katana".to_string(),
+                ..Default::default()
+            },
+        );
+        let err = resolve_chain_id_string(
+            Some("SN_GOERLI".to_string()),
+            None,
+            &config,
+            &NullFormatter,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("my-slot-instance"));
+    }
+}
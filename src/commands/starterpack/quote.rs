@@ -3,11 +3,10 @@ use crate::error::{CliError, Result};
 use crate::output::OutputFormatter;
 use serde::Serialize;
 use starknet::core::types::{BlockId, BlockTag, Felt, FunctionCall};
-use starknet::providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider};
+use starknet::providers::{JsonRpcClient, Provider};
 
 use super::{
-    felt_to_u128, format_token_amount, parse_starterpack_id, query_token_info, resolve_rpc_url,
-    StarterpackQuote, STARTERPACK_CONTRACT,
+    felt_to_u128, format_token_amount, parse_starterpack_id, query_token_info, StarterpackQuote,
 };
 
 #[derive(Serialize)]
@@ -15,6 +14,7 @@ struct QuoteOutput {
     starterpack_id: String,
     chain_id: String,
     payment_token: String,
+    payment_token_symbol: String,
     base_price: String,
     referral_fee: String,
     protocol_fee: String,
@@ -28,12 +28,13 @@ pub async fn execute(
     quantity: u32,
     chain_id: Option<String>,
     rpc_url: Option<String>,
+    account: Option<&str>,
 ) -> Result<()> {
-    let rpc_url = resolve_rpc_url(chain_id, rpc_url, config, formatter)?;
+    let rpc_url = crate::chains::resolve_rpc_url(config, formatter, chain_id, rpc_url, account)?;
 
     let url = url::Url::parse(&rpc_url)
         .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
-    let provider = JsonRpcClient::new(HttpTransport::new(url));
+    let provider = JsonRpcClient::new(crate::api::rpc_transport(url));
 
     let id_felt = parse_starterpack_id(&id)?;
     let quantity_felt = Felt::from(quantity);
@@ -41,21 +42,16 @@ pub async fn execute(
     let selector = starknet::core::utils::get_selector_from_name("quote")
         .map_err(|e| CliError::InvalidInput(format!("Invalid entrypoint: {e}")))?;
 
-    let chain_name = provider
-        .chain_id()
-        .await
-        .map_err(|e| CliError::Network(format!("Failed to get chain ID: {e}")))
-        .and_then(|felt| {
-            starknet::core::utils::parse_cairo_short_string(&felt)
-                .map_err(|e| CliError::InvalidInput(format!("Failed to parse chain ID: {e}")))
-        })?;
+    let chain_name = crate::chains::chain_name(&provider).await?;
+    let starterpack_contract =
+        crate::chains::resolve_contract(config, &chain_name, crate::chains::STARTERPACK)?;
 
     formatter.info("Fetching quote...");
 
     let result = provider
         .call(
             FunctionCall {
-                contract_address: STARTERPACK_CONTRACT,
+                contract_address: starterpack_contract,
                 entry_point_selector: selector,
                 calldata: vec![id_felt, quantity_felt, Felt::ZERO],
             },
@@ -83,6 +79,7 @@ pub async fn execute(
             starterpack_id: id,
             chain_id: chain_name,
             payment_token: format!("0x{:x}", quote.payment_token),
+            payment_token_symbol: token_info.symbol,
             base_price,
             referral_fee,
             protocol_fee,
@@ -1,4 +1,8 @@
 use crate::{
+    commands::{
+        approve_for::{self, ScheduledRevokeInfo},
+        schedule::{self, ScheduledExecution},
+    },
     config::Config,
     error::{CliError, Result},
     output::OutputFormatter,
@@ -12,6 +16,12 @@ use serde::{Deserialize, Serialize};
 #[derive(Serialize)]
 pub struct StatusOutput {
     pub session: Option<SessionInfo>,
+    /// Allowances from `approve-for` waiting to be revoked
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub scheduled_revokes: Vec<ScheduledRevokeInfo>,
+    /// Executions from `execute --at/--in` waiting for their time
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub scheduled_executions: Vec<ScheduledExecution>,
 }
 
 #[derive(Serialize)]
@@ -65,6 +75,14 @@ pub async fn execute(
 
         match backend.session(&session_key) {
             Ok(Some(metadata)) => {
+                crate::commands::session::revocation::ensure_not_revoked(
+                    config,
+                    formatter,
+                    &storage_path,
+                    &session_key,
+                )
+                .await?;
+
                 let now = std::time::SystemTime::now()
                     .duration_since(std::time::UNIX_EPOCH)
                     .unwrap()
@@ -158,12 +176,45 @@ pub async fn execute(
         None
     };
 
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let scheduled_revokes: Vec<ScheduledRevokeInfo> = approve_for::load_schedule(&storage_path)
+        .iter()
+        .map(|revoke| revoke.info(now))
+        .collect();
+
+    let scheduled_executions: Vec<ScheduledExecution> = schedule::load_schedule(&storage_path)
+        .into_iter()
+        .filter(|execution| execution.status == "pending")
+        .collect();
+
     let output = StatusOutput {
         session: session_info,
+        scheduled_revokes,
+        scheduled_executions,
     };
 
     formatter.success(&output);
 
+    let due = output.scheduled_revokes.iter().filter(|r| r.is_due).count();
+    if due > 0 {
+        formatter.warning(&format!(
+            "{due} scheduled revoke(s) are due. Run 'controller revoke-due' to submit them."
+        ));
+    }
+    let due = output
+        .scheduled_executions
+        .iter()
+        .filter(|execution| execution.is_due(now))
+        .count();
+    if due > 0 {
+        formatter.warning(&format!(
+            "{due} scheduled execution(s) are due. Run 'controller execute-due' to submit them."
+        ));
+    }
+
     if output.session.is_none() {
         formatter.info("No session found. Run 'controller session auth' to get started.");
     }
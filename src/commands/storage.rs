@@ -0,0 +1,376 @@
+use crate::{
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize)]
+pub struct HardenOutput {
+    pub path: String,
+    pub updated: Vec<String>,
+    pub message: String,
+}
+
+/// A file or directory under the storage path whose mode grants group/other access.
+#[derive(Debug)]
+pub struct LoosePermission {
+    pub path: PathBuf,
+    pub mode: u32,
+    pub is_dir: bool,
+}
+
+/// Warn when session storage is readable by other users, optionally fixing it in place.
+///
+/// Called before any command that loads the session signer. Errors while inspecting
+/// the directory are ignored: this is a best-effort guard, not a gate.
+pub fn check_permissions(storage_path: &Path, fix: bool, formatter: &dyn OutputFormatter) {
+    let loose = match find_loose_permissions(storage_path) {
+        Ok(loose) => loose,
+        Err(_) => return,
+    };
+    if loose.is_empty() {
+        return;
+    }
+
+    if fix {
+        match harden_paths(&loose) {
+            Ok(()) => formatter.info(&format!(
+                "Fixed permissions on {} path(s) under {}",
+                loose.len(),
+                storage_path.display()
+            )),
+            Err(e) => formatter.warning(&format!("Failed to fix storage permissions: {e}")),
+        }
+        return;
+    }
+
+    let listed = loose
+        .iter()
+        .take(3)
+        .map(|p| format!("{} ({:o})", p.path.display(), p.mode))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let more = if loose.len() > 3 {
+        format!(" and {} more", loose.len() - 3)
+    } else {
+        String::new()
+    };
+    formatter.warning(&format!(
+        "Session storage is readable by other users: {listed}{more}. \
+         Run 'controller storage harden' or pass --fix-permissions to restrict access."
+    ));
+}
+
+/// Set 0700 on directories and 0600 on files under the storage path.
+pub async fn harden(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    account: Option<&str>,
+) -> Result<()> {
+    let storage_path = config.resolve_storage_path(account);
+    if !storage_path.exists() {
+        return Err(CliError::NotFoundError(format!(
+            "Storage path does not exist: {}",
+            storage_path.display()
+        )));
+    }
+
+    let loose = find_loose_permissions(&storage_path).map_err(|e| CliError::FileError {
+        path: storage_path.display().to_string(),
+        message: e.to_string(),
+    })?;
+    harden_paths(&loose).map_err(|e| CliError::FileError {
+        path: storage_path.display().to_string(),
+        message: e.to_string(),
+    })?;
+
+    let output = HardenOutput {
+        path: storage_path.display().to_string(),
+        updated: loose.iter().map(|p| p.path.display().to_string()).collect(),
+        message: if loose.is_empty() {
+            "Storage permissions already restricted.".to_string()
+        } else {
+            format!("Restricted permissions on {} path(s).", loose.len())
+        },
+    };
+    formatter.success(&output);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+pub fn find_loose_permissions(root: &Path) -> std::io::Result<Vec<LoosePermission>> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut loose = Vec::new();
+    if !root.exists() {
+        return Ok(loose);
+    }
+
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(path) = stack.pop() {
+        let metadata = std::fs::symlink_metadata(&path)?;
+        if metadata.file_type().is_symlink() {
+            continue;
+        }
+        let mode = metadata.permissions().mode() & 0o777;
+        if mode & 0o077 != 0 {
+            loose.push(LoosePermission {
+                path: path.clone(),
+                mode,
+                is_dir: metadata.is_dir(),
+            });
+        }
+        if metadata.is_dir() {
+            for entry in std::fs::read_dir(&path)? {
+                stack.push(entry?.path());
+            }
+        }
+    }
+
+    Ok(loose)
+}
+
+#[cfg(not(unix))]
+pub fn find_loose_permissions(_root: &Path) -> std::io::Result<Vec<LoosePermission>> {
+    Ok(Vec::new())
+}
+
+#[cfg(unix)]
+pub fn harden_paths(paths: &[LoosePermission]) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    for entry in paths {
+        let mode = if entry.is_dir { 0o700 } else { 0o600 };
+        std::fs::set_permissions(&entry.path, std::fs::Permissions::from_mode(mode))?;
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn harden_paths(_paths: &[LoosePermission]) -> std::io::Result<()> {
+    Ok(())
+}
+
+/// Replace `path` with `contents` so readers see either the old file or the new one, never a
+/// truncated mix: the data is written and synced to a temp file beside it, then renamed over.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let parent = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    std::fs::create_dir_all(parent)?;
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| std::io::Error::other("path has no file name"))?;
+    // Unique per write, so concurrent writers in one process (MCP tools, batch tasks) never
+    // truncate each other's temp file
+    static WRITES: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+    let tmp = parent.join(format!(
+        ".{}.tmp-{}-{}",
+        file_name.to_string_lossy(),
+        std::process::id(),
+        WRITES.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+    ));
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let written = options.open(&tmp).and_then(|mut file| {
+        file.write_all(contents.as_ref())?;
+        file.sync_all()
+    });
+    if let Err(e) = written.and_then(|()| std::fs::rename(&tmp, path)) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e);
+    }
+
+    // Persist the rename itself; not supported on every platform, so best effort
+    #[cfg(unix)]
+    if let Ok(dir) = std::fs::File::open(parent) {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+/// Store `value` under `key` in the same `<key>.json` file `FileSystemBackend::set` uses, but
+/// through [`write_atomic`] so a crash mid-write can't leave a truncated signer or policy file.
+pub fn write_value(
+    storage_path: &Path,
+    key: &str,
+    value: &account_sdk::storage::StorageValue,
+) -> Result<()> {
+    let path = storage_path.join(format!("{key}.json"));
+    let json = serde_json::to_string(value)
+        .map_err(|e| CliError::Storage(format!("Failed to serialize {key}: {e}")))?;
+    write_atomic(&path, json).map_err(|e| CliError::FileError {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    })
+}
+
+/// A storage file that failed to parse and was moved aside.
+#[derive(Debug)]
+pub struct Quarantined {
+    pub path: PathBuf,
+    pub moved_to: PathBuf,
+}
+
+/// Move unparseable JSON files in the storage directory aside as `<name>.corrupt-<timestamp>`,
+/// so a file truncated by a crash reads as missing instead of breaking every command.
+///
+/// Only caches the CLI writes itself through [`write_atomic`] are considered: session files are
+/// written in place by account_sdk, so a concurrent `execute` may be caught mid-write and a
+/// valid session would be thrown away. The `--once` submission record is not a cache either;
+/// moving it aside would silently re-allow duplicates, so `replay::check` fails on it instead.
+pub fn quarantine_corrupt(storage_path: &Path) -> std::io::Result<Vec<Quarantined>> {
+    let mut quarantined = Vec::new();
+    if !storage_path.is_dir() {
+        return Ok(quarantined);
+    }
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%S");
+    for entry in std::fs::read_dir(storage_path)? {
+        let path = entry?.path();
+        if !path.is_file() || !is_rebuildable(&path) {
+            continue;
+        }
+        let parses = std::fs::read(&path)
+            .is_ok_and(|data| serde_json::from_slice::<serde_json::Value>(&data).is_ok());
+        if parses {
+            continue;
+        }
+        let mut moved_to = path.clone().into_os_string();
+        moved_to.push(format!(".corrupt-{stamp}"));
+        let moved_to = PathBuf::from(moved_to);
+        std::fs::rename(&path, &moved_to)?;
+        quarantined.push(Quarantined { path, moved_to });
+    }
+    Ok(quarantined)
+}
+
+/// JSON caches the CLI writes atomically and rebuilds on its own when they go missing
+fn is_rebuildable(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy())
+        .unwrap_or_default();
+    name.starts_with("balance_cache_")
+        || name == "lookup_cache.json"
+        || name == crate::presets::CACHE_FILE
+        || name == super::submit_queue::NONCE_FILE
+}
+
+/// Quarantine corrupt cache files before credentials are read.
+///
+/// Best effort like [`check_permissions`]: the command still runs and rebuilds the cache.
+pub fn check_integrity(storage_path: &Path, formatter: &dyn OutputFormatter) {
+    let Ok(quarantined) = quarantine_corrupt(storage_path) else {
+        return;
+    };
+    for file in &quarantined {
+        formatter.warning(&format!(
+            "{} was corrupt (likely an interrupted write) and was moved to {}",
+            file.path.display(),
+            file.moved_to.display()
+        ));
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn mode_of(path: &Path) -> u32 {
+        std::fs::metadata(path).unwrap().permissions().mode() & 0o777
+    }
+
+    #[test]
+    fn detects_and_hardens_world_readable_storage() {
+        let root = std::env::temp_dir().join(format!(
+            "controller-cli-storage-test-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        let signer = root.join("session.json");
+        std::fs::write(&signer, "{}").unwrap();
+        std::fs::set_permissions(&root, std::fs::Permissions::from_mode(0o755)).unwrap();
+        std::fs::set_permissions(&signer, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        let loose = find_loose_permissions(&root).unwrap();
+        assert_eq!(loose.len(), 2);
+
+        harden_paths(&loose).unwrap();
+        assert_eq!(mode_of(&root), 0o700);
+        assert_eq!(mode_of(&signer), 0o600);
+        assert!(find_loose_permissions(&root).unwrap().is_empty());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn missing_storage_has_no_findings() {
+        let root = std::env::temp_dir().join("controller-cli-storage-test-missing");
+        let _ = std::fs::remove_dir_all(&root);
+        assert!(find_loose_permissions(&root).unwrap().is_empty());
+    }
+
+    #[test]
+    fn writes_atomically_and_quarantines_corrupt_files() {
+        let root = std::env::temp_dir().join(format!(
+            "controller-cli-storage-test-atomic-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+        let policies = root.join("session_policies.json");
+        write_atomic(&policies, "{}").unwrap();
+        write_atomic(&policies, r#"{"contracts":{}}"#).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&policies).unwrap(),
+            r#"{"contracts":{}}"#
+        );
+        assert_eq!(mode_of(&policies), 0o600);
+        assert_eq!(std::fs::read_dir(&root).unwrap().count(), 1);
+
+        let writers: Vec<_> = (0..8)
+            .map(|i| {
+                let policies = policies.clone();
+                std::thread::spawn(move || write_atomic(&policies, format!("{{\"n\":{i}}}")))
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap().unwrap();
+        }
+        assert!(serde_json::from_str::<serde_json::Value>(
+            &std::fs::read_to_string(&policies).unwrap()
+        )
+        .is_ok());
+        assert_eq!(std::fs::read_dir(&root).unwrap().count(), 1);
+
+        // A session file may be mid-write by another process, so it is never moved
+        let signer = root.join("session_signer.json");
+        std::fs::write(&signer, r#"{"private_k"#).unwrap();
+        let cache = root.join("balance_cache_0x1.json");
+        std::fs::write(&cache, r#"{"bal"#).unwrap();
+        let replay = root.join(super::super::replay::REPLAY_FILE);
+        std::fs::write(&replay, r#"[{"fing"#).unwrap();
+        let quarantined = quarantine_corrupt(&root).unwrap();
+        assert_eq!(quarantined.len(), 1);
+        assert_eq!(quarantined[0].path, cache);
+        assert!(!cache.exists() && quarantined[0].moved_to.exists());
+        assert!(signer.exists() && !is_rebuildable(&signer));
+        assert!(replay.exists() && !is_rebuildable(&replay));
+        assert!(policies.exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
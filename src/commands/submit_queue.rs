@@ -0,0 +1,196 @@
+use crate::{
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use serde::{Deserialize, Serialize};
+use starknet::core::types::{BlockId, BlockTag, Felt};
+use starknet::providers::Provider;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+pub const LOCK_FILE: &str = "submit.lock";
+pub const NONCE_FILE: &str = "last_nonce.json";
+
+/// A lock older than this is assumed to belong to a crashed invocation
+const STALE_LOCK: Duration = Duration::from_secs(120);
+/// How long to wait for another invocation to finish submitting
+const LOCK_TIMEOUT: Duration = Duration::from_secs(60);
+/// How long to wait for the previous self-paid transaction's nonce to be consumed
+const NONCE_TIMEOUT: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Exclusive per-account submission slot, released on drop.
+///
+/// Concurrent `execute` invocations sharing a session serialize on this lock so self-paid
+/// transactions don't race on the account nonce.
+pub struct SubmissionLock {
+    path: PathBuf,
+}
+
+impl SubmissionLock {
+    pub async fn acquire(storage_path: &Path, formatter: &dyn OutputFormatter) -> Result<Self> {
+        let path = storage_path.join(LOCK_FILE);
+        let start = std::time::Instant::now();
+        let mut announced = false;
+
+        loop {
+            match std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(mut file) => {
+                    use std::io::Write;
+                    let _ = write!(file, "{}", std::process::id());
+                    return Ok(Self { path });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    if is_stale(&path) {
+                        let _ = std::fs::remove_file(&path);
+                        continue;
+                    }
+                    if start.elapsed() > LOCK_TIMEOUT {
+                        return Err(CliError::TimeoutError(format!(
+                            "Another execute for this account is still submitting after {}s. \
+                             Remove {} if no other invocation is running, or pass --no-queue",
+                            LOCK_TIMEOUT.as_secs(),
+                            path.display()
+                        )));
+                    }
+                    if !announced {
+                        formatter.info("Waiting for another submission from this account...");
+                        announced = true;
+                    }
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+                Err(e) => {
+                    return Err(CliError::Storage(format!(
+                        "Failed to create submission lock {}: {e}",
+                        path.display()
+                    )))
+                }
+            }
+        }
+    }
+}
+
+impl Drop for SubmissionLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Whether the lock at `path` was left behind by a crashed invocation
+pub fn is_stale(path: &Path) -> bool {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age > STALE_LOCK)
+}
+
+#[derive(Serialize, Deserialize)]
+struct LastNonce {
+    nonce: String,
+}
+
+fn last_submitted_nonce(storage_path: &Path) -> Option<Felt> {
+    let content = std::fs::read_to_string(storage_path.join(NONCE_FILE)).ok()?;
+    let last: LastNonce = serde_json::from_str(&content).ok()?;
+    Felt::from_hex(&last.nonce).ok()
+}
+
+/// Wait (briefly) until the nonce used by the previous self-paid submission has been consumed
+/// on-chain, then return the nonce the next transaction will use.
+pub async fn wait_for_nonce<P: Provider + Sync>(
+    provider: &P,
+    storage_path: &Path,
+    account_address: Felt,
+    formatter: &dyn OutputFormatter,
+) -> Result<Felt> {
+    let previous = last_submitted_nonce(storage_path);
+    let start = std::time::Instant::now();
+    let mut announced = false;
+
+    loop {
+        let current = provider
+            .get_nonce(BlockId::Tag(BlockTag::Latest), account_address)
+            .await
+            .map_err(|e| CliError::Network(format!("Failed to get account nonce: {e}")))?;
+
+        match previous {
+            Some(previous) if current <= previous => {
+                if start.elapsed() > NONCE_TIMEOUT {
+                    formatter.warning(&format!(
+                        "Previous transaction (nonce 0x{previous:x}) not yet included after {}s; submitting anyway",
+                        NONCE_TIMEOUT.as_secs()
+                    ));
+                    return Ok(current);
+                }
+                if !announced {
+                    formatter
+                        .info("Waiting for the previous transaction's nonce to be consumed...");
+                    announced = true;
+                }
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+            _ => return Ok(current),
+        }
+    }
+}
+
+/// Remember the nonce of a submitted self-paid transaction for the next invocation.
+pub fn record_nonce(storage_path: &Path, nonce: Felt) {
+    let last = LastNonce {
+        nonce: format!("0x{nonce:x}"),
+    };
+    if let Ok(json) = serde_json::to_string(&last) {
+        let _ = super::storage::write_atomic(&storage_path.join(NONCE_FILE), json);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::NullFormatter;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "controller-queue-test-{name}-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn lock_is_exclusive_and_released_on_drop() {
+        let dir = temp_dir("lock");
+        let lock = SubmissionLock::acquire(&dir, &NullFormatter).await.unwrap();
+        assert!(dir.join(LOCK_FILE).exists());
+
+        // A second acquire can't complete while the first is held
+        let second = tokio::time::timeout(
+            Duration::from_millis(200),
+            SubmissionLock::acquire(&dir, &NullFormatter),
+        )
+        .await;
+        assert!(second.is_err());
+
+        drop(lock);
+        assert!(!dir.join(LOCK_FILE).exists());
+        assert!(SubmissionLock::acquire(&dir, &NullFormatter).await.is_ok());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn records_last_nonce() {
+        let dir = temp_dir("nonce");
+        assert!(last_submitted_nonce(&dir).is_none());
+        record_nonce(&dir, Felt::from(7u64));
+        assert_eq!(last_submitted_nonce(&dir), Some(Felt::from(7u64)));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
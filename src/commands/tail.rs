@@ -0,0 +1,242 @@
+use crate::{
+    api::RpcTransport,
+    commands::{
+        decode,
+        encode::FETCH_ABI,
+        events::{event_output, EventOutput},
+        selectors::SelectorDb,
+    },
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use serde_json::Value;
+use starknet::core::types::{BlockId, EventFilter, Felt};
+use starknet::providers::{JsonRpcClient, Provider};
+
+/// How often `--follow` checks for new blocks
+const POLL_SECS: u64 = 3;
+
+/// Print a contract's events from the last `blocks` blocks as a feed, then with `follow` keep
+/// printing events from new blocks until Ctrl-C. Events are decoded with `abi`, or the
+/// contract's on-chain ABI when none is given; with `--json` each event is one NDJSON line.
+#[allow(clippy::too_many_arguments)]
+pub async fn execute(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    contract: String,
+    abi: Option<String>,
+    blocks: u64,
+    follow: bool,
+    chain_id: Option<String>,
+    rpc_url: Option<String>,
+    account: Option<&str>,
+) -> Result<()> {
+    let address = Felt::from_hex(&contract)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid contract address: {e}")))?;
+
+    let abi = match abi.as_deref() {
+        Some(FETCH_ABI) | None => {
+            match crate::commands::encode::fetch_abi(
+                config,
+                formatter,
+                &contract,
+                chain_id.clone(),
+                rpc_url.clone(),
+                account,
+            )
+            .await
+            {
+                Ok(abi) => Some(abi),
+                Err(e) if abi.is_none() => {
+                    formatter.warning(&format!(
+                        "Could not fetch the ABI ({e}); showing raw events named from the selector database"
+                    ));
+                    None
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Some(path) => Some(decode::load_abi(path)?),
+    };
+
+    let rpc_url = crate::chains::resolve_rpc_url(config, formatter, chain_id, rpc_url, account)?;
+    let url = url::Url::parse(&rpc_url)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
+    let provider = JsonRpcClient::new(crate::api::rpc_transport(url));
+    let selectors = SelectorDb::load();
+    let feed = Feed {
+        provider: &provider,
+        address,
+        abi: abi.as_deref(),
+        selectors: &selectors,
+        json: config.cli.json_output,
+    };
+
+    let head = feed.head().await?;
+    let printed = feed
+        .print_range((head + 1).saturating_sub(blocks), head)
+        .await?;
+    if !follow {
+        if printed == 0 && !config.cli.json_output {
+            formatter.info(&format!(
+                "No events from {contract} in the last {blocks} block(s)"
+            ));
+        }
+        return Ok(());
+    }
+
+    formatter.info(&format!(
+        "Following {contract} from block {head} (Ctrl-C to stop)"
+    ));
+    let mut next = head + 1;
+    let mut ticker = tokio::time::interval(std::time::Duration::from_secs(POLL_SECS));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+        let head = match feed.head().await {
+            Ok(head) => head,
+            Err(e) => {
+                formatter.warning(&e.to_string());
+                continue;
+            }
+        };
+        if head < next {
+            continue;
+        }
+        match feed.print_range(next, head).await {
+            Ok(_) => next = head + 1,
+            // Retried from the same block on the next tick
+            Err(e) => formatter.warning(&e.to_string()),
+        }
+    }
+}
+
+struct Feed<'a> {
+    provider: &'a JsonRpcClient<RpcTransport>,
+    address: Felt,
+    abi: Option<&'a [Value]>,
+    selectors: &'a SelectorDb,
+    json: bool,
+}
+
+impl Feed<'_> {
+    async fn head(&self) -> Result<u64> {
+        self.provider
+            .block_number()
+            .await
+            .map_err(|e| CliError::Network(format!("Failed to get the latest block: {e}")))
+    }
+
+    /// Print the contract's events in blocks `from..=to`, returning how many there were
+    async fn print_range(&self, from: u64, to: u64) -> Result<usize> {
+        if from > to {
+            return Ok(0);
+        }
+        let filter = EventFilter {
+            from_block: Some(BlockId::Number(from)),
+            to_block: Some(BlockId::Number(to)),
+            address: Some(self.address),
+            keys: None,
+        };
+        let mut printed = 0;
+        let mut token = None;
+        loop {
+            let page = self
+                .provider
+                .get_events(filter.clone(), token, 100)
+                .await
+                .map_err(|e| CliError::Network(format!("Failed to get events: {e}")))?;
+            for event in page.events {
+                let (event, _) = event_output(event, self.abi, self.selectors);
+                if self.json {
+                    if let Ok(line) = serde_json::to_string(&event) {
+                        println!("{line}");
+                    }
+                } else {
+                    println!("{}", feed_line(&event));
+                }
+                printed += 1;
+            }
+            token = page.continuation_token;
+            if token.is_none() {
+                return Ok(printed);
+            }
+        }
+    }
+}
+
+/// One event as a feed line: block, name and arguments, then the transaction
+/// (e.g. `#812345  Moved  direction=Left player=0x12  tx 0x7a3f…91c2`).
+fn feed_line(event: &EventOutput) -> String {
+    let block = event
+        .block_number
+        .map(|n| format!("#{n}"))
+        .unwrap_or_else(|| "pending".to_string());
+    let name = event
+        .name
+        .clone()
+        .or_else(|| event.keys.first().cloned())
+        .unwrap_or_else(|| "?".to_string());
+    let args = match &event.decoded {
+        Some(Value::Object(members)) => members
+            .iter()
+            .map(|(member, value)| format!("{member}={}", compact(value)))
+            .collect::<Vec<_>>()
+            .join(" "),
+        Some(other) => compact(other),
+        None => {
+            let keys = event.keys.get(1..).unwrap_or_default();
+            format!("keys=[{}] data=[{}]", keys.join(","), event.data.join(","))
+        }
+    };
+    let hash = &event.transaction_hash;
+    let tx = if hash.len() > 12 {
+        format!("{}…{}", &hash[..6], &hash[hash.len() - 4..])
+    } else {
+        hash.clone()
+    };
+    format!("{block}  {name}  {args}  tx {tx}")
+}
+
+/// Strings without quotes, anything else as compact JSON
+fn compact(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn formats_feed_lines() {
+        let mut event = EventOutput {
+            block_number: Some(812345),
+            transaction_hash: "0x7a3f00000000000000000000000091c2".to_string(),
+            from_address: "0x123".to_string(),
+            name: Some("Moved".to_string()),
+            keys: vec!["0x1".to_string(), "0x12".to_string()],
+            data: vec!["0x0".to_string()],
+            decoded: Some(json!({"player": "0x12", "direction": "Left", "pos": [1, 2]})),
+        };
+        assert_eq!(
+            feed_line(&event),
+            "#812345  Moved  direction=Left player=0x12 pos=[1,2]  tx 0x7a3f…91c2"
+        );
+
+        event.name = None;
+        event.decoded = None;
+        event.block_number = None;
+        assert_eq!(
+            feed_line(&event),
+            "pending  0x1  keys=[0x12] data=[0x0]  tx 0x7a3f…91c2"
+        );
+    }
+}
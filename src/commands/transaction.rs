@@ -1,11 +1,14 @@
+use super::selectors::SelectorDb;
+use crate::api::RpcTransport;
 use crate::config::Config;
 use crate::error::{CliError, Result};
 use crate::output::OutputFormatter;
 use serde::Serialize;
 use starknet::core::types::Felt;
-use starknet::providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider};
+use starknet::providers::{JsonRpcClient, Provider};
 
 /// Get transaction status and details
+#[allow(clippy::too_many_arguments)]
 pub async fn execute(
     config: &Config,
     formatter: &dyn OutputFormatter,
@@ -14,14 +17,15 @@ pub async fn execute(
     rpc_url: Option<String>,
     wait: bool,
     timeout: u64,
+    account: Option<&str>,
 ) -> Result<()> {
     // Determine RPC URL
-    let rpc_url = resolve_rpc_url(chain_id, rpc_url, config, formatter)?;
+    let rpc_url = crate::chains::resolve_rpc_url(config, formatter, chain_id, rpc_url, account)?;
 
     // Build the provider
     let url = url::Url::parse(&rpc_url)
         .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
-    let provider = JsonRpcClient::new(HttpTransport::new(url));
+    let provider = JsonRpcClient::new(crate::api::rpc_transport(url));
 
     // Validate transaction hash
     let tx_hash = Felt::from_hex(&hash)
@@ -70,7 +74,7 @@ pub async fn execute(
 }
 
 async fn get_transaction(
-    provider: &JsonRpcClient<HttpTransport>,
+    provider: &JsonRpcClient<RpcTransport>,
     tx_hash: Felt,
 ) -> Result<Option<TransactionOutput>> {
     // Get transaction by hash
@@ -89,6 +93,7 @@ async fn get_transaction(
                             .iter()
                             .map(|f| format!("0x{f:x}"))
                             .collect(),
+                        calls: decode_calls(&invoke_v3.calldata, &SelectorDb::load()),
                         version: "0x3".to_string(),
                         signature: invoke_v3
                             .signature
@@ -153,6 +158,7 @@ async fn get_transaction(
                         r#type: "INVOKE".to_string(),
                         sender_address: None,
                         calldata: vec![],
+                        calls: Vec::new(),
                         version: "0x1".to_string(),
                         signature: vec![],
                         nonce: "0x0".to_string(),
@@ -169,6 +175,7 @@ async fn get_transaction(
                     r#type: "DECLARE".to_string(),
                     sender_address: None,
                     calldata: vec![],
+                    calls: Vec::new(),
                     version: "0x3".to_string(),
                     signature: vec![],
                     nonce: "0x0".to_string(),
@@ -184,6 +191,7 @@ async fn get_transaction(
                     r#type: "DEPLOY_ACCOUNT".to_string(),
                     sender_address: None,
                     calldata: vec![],
+                    calls: Vec::new(),
                     version: "0x3".to_string(),
                     signature: vec![],
                     nonce: "0x0".to_string(),
@@ -199,6 +207,7 @@ async fn get_transaction(
                     r#type: "L1_HANDLER".to_string(),
                     sender_address: None,
                     calldata: vec![],
+                    calls: Vec::new(),
                     version: "0x0".to_string(),
                     signature: vec![],
                     nonce: "0x0".to_string(),
@@ -214,6 +223,7 @@ async fn get_transaction(
                     r#type: "DEPLOY".to_string(),
                     sender_address: None,
                     calldata: vec![],
+                    calls: Vec::new(),
                     version: "0x0".to_string(),
                     signature: vec![],
                     nonce: "0x0".to_string(),
@@ -245,6 +255,9 @@ struct TransactionOutput {
     #[serde(rename = "sender_address")]
     sender_address: Option<String>,
     calldata: Vec<String>,
+    /// Account calls decoded from an invoke's `__execute__` calldata
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    calls: Vec<CallOutput>,
     version: String,
     signature: Vec<String>,
     nonce: String,
@@ -261,6 +274,48 @@ struct TransactionOutput {
     fee_data_availability_mode: String,
 }
 
+#[derive(Debug, PartialEq, Serialize)]
+struct CallOutput {
+    to: String,
+    selector: String,
+    /// Entrypoint name from the selector database, when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entrypoint: Option<String>,
+    calldata: Vec<String>,
+}
+
+/// Split account `__execute__` calldata (`[n, (to, selector, len, data...)...]`) into calls.
+/// Anything that doesn't fit the layout yields no calls rather than a guess.
+fn decode_calls(calldata: &[Felt], selectors: &SelectorDb) -> Vec<CallOutput> {
+    let Some((count, mut rest)) = calldata.split_first() else {
+        return Vec::new();
+    };
+    let Ok(count) = u64::try_from(*count) else {
+        return Vec::new();
+    };
+    let mut calls = Vec::new();
+    for _ in 0..count {
+        let [to, selector, len, tail @ ..] = rest else {
+            return Vec::new();
+        };
+        let Some(len) = usize::try_from(*len).ok().filter(|len| *len <= tail.len()) else {
+            return Vec::new();
+        };
+        calls.push(CallOutput {
+            to: format!("0x{to:x}"),
+            selector: format!("0x{selector:x}"),
+            entrypoint: selectors.name(*selector).map(str::to_string),
+            calldata: tail[..len].iter().map(|f| format!("0x{f:x}")).collect(),
+        });
+        rest = &tail[len..];
+    }
+    if rest.is_empty() {
+        calls
+    } else {
+        Vec::new()
+    }
+}
+
 #[derive(Debug, Serialize)]
 struct ResourceBounds {
     #[serde(rename = "l1_gas")]
@@ -279,33 +334,32 @@ struct GasBounds {
     max_price_per_unit: String,
 }
 
-/// Resolve RPC URL from chain_id, explicit rpc_url, or config
-fn resolve_rpc_url(
-    chain_id: Option<String>,
-    rpc_url: Option<String>,
-    config: &Config,
-    formatter: &dyn OutputFormatter,
-) -> Result<String> {
-    // If explicit RPC URL provided, use it
-    if let Some(url) = rpc_url {
-        return Ok(url);
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starknet::core::utils::get_selector_from_name;
 
-    // If chain_id provided, map to known RPC URL
-    if let Some(chain) = chain_id {
-        match chain.as_str() {
-            "SN_MAIN" => Ok("https://api.cartridge.gg/x/starknet/mainnet".to_string()),
-            "SN_SEPOLIA" => Ok("https://api.cartridge.gg/x/starknet/sepolia".to_string()),
-            _ => Err(CliError::InvalidInput(format!(
-                "Unsupported chain ID '{chain}'. Supported chains: SN_MAIN, SN_SEPOLIA"
-            ))),
-        }
-    } else if !config.session.rpc_url.is_empty() {
-        // Fall back to config default
-        Ok(config.session.rpc_url.clone())
-    } else {
-        // No chain_id, no rpc_url, no config default - use Sepolia with warning
-        formatter.warning("No --chain-id or --rpc-url specified, using SN_SEPOLIA by default");
-        Ok("https://api.cartridge.gg/x/starknet/sepolia".to_string())
+    #[test]
+    fn decodes_execute_calldata() {
+        let transfer = get_selector_from_name("transfer").unwrap();
+        let calldata = [
+            Felt::TWO,
+            Felt::from(0xaa_u64),
+            transfer,
+            Felt::TWO,
+            Felt::from(0xbb_u64),
+            Felt::from(100_u64),
+            Felt::from(0xcc_u64),
+            Felt::from(0x123_u64),
+            Felt::ZERO,
+        ];
+        let calls = decode_calls(&calldata, &SelectorDb::load());
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].entrypoint.as_deref(), Some("transfer"));
+        assert_eq!(calls[0].calldata, vec!["0xbb", "0x64"]);
+        assert!(calls[1].calldata.is_empty());
+
+        // Truncated calldata is not guessed at
+        assert!(decode_calls(&calldata[..5], &SelectorDb::load()).is_empty());
     }
 }
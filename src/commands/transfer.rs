@@ -0,0 +1,170 @@
+use crate::{
+    chains,
+    commands::{
+        balance,
+        lookup::{self, CacheMode},
+    },
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use starknet::core::types::{BlockId, BlockTag, Felt, FunctionCall};
+use starknet::providers::{JsonRpcClient, Provider};
+
+/// Send `amount` (in whole tokens) of `token` to `recipient`. The token is a symbol from the
+/// token lists or config, or a contract address; the recipient an address or controller username.
+/// The transfer goes through `execute`, so session policies, approval thresholds and `--explain`
+/// apply as usual.
+#[allow(clippy::too_many_arguments)]
+pub async fn execute(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    token: String,
+    recipient: String,
+    amount: String,
+    wait: bool,
+    timeout: u64,
+    chain_id: Option<String>,
+    rpc_url: Option<String>,
+    no_paymaster: bool,
+    account: Option<&str>,
+) -> Result<()> {
+    let (token_address, listed_decimals) = resolve_token_arg(config, &token)?;
+    let recipient_address = resolve_recipient(config, &recipient).await?;
+
+    let rpc_url = chains::resolve_rpc_url(config, formatter, chain_id, rpc_url, account)?;
+    let url = url::Url::parse(&rpc_url)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
+    let provider = JsonRpcClient::new(crate::api::rpc_transport(url));
+    let decimals = match onchain_decimals(&provider, token_address).await {
+        Some(decimals) => decimals,
+        None => {
+            let decimals = listed_decimals.unwrap_or(18);
+            formatter.warning(&format!(
+                "Could not read decimals from 0x{token_address:x}; assuming {decimals}"
+            ));
+            decimals
+        }
+    };
+
+    let raw_amount = balance::parse_token_amount(&amount, decimals)
+        .filter(|raw| *raw > 0)
+        .ok_or_else(|| {
+            CliError::InvalidInput(format!(
+                "Invalid amount '{amount}': expected a positive token amount with at most {decimals} decimals, like '100' or '0.5'"
+            ))
+        })?;
+
+    formatter.info(&format!(
+        "Transferring {amount} {} to {recipient}{}...",
+        if token.starts_with("0x") {
+            "tokens"
+        } else {
+            &token
+        },
+        if recipient.starts_with("0x") {
+            String::new()
+        } else {
+            format!(" (0x{recipient_address:x})")
+        }
+    ));
+
+    crate::commands::execute::execute(
+        config,
+        formatter,
+        Some(format!("0x{token_address:x}")),
+        Some("transfer".to_string()),
+        Some(format!("0x{recipient_address:x},u256:{raw_amount}")),
+        None,
+        crate::commands::execute::ExecuteOptions {
+            wait,
+            timeout,
+            rpc_url: Some(rpc_url),
+            no_paymaster,
+            ..Default::default()
+        },
+        account,
+    )
+    .await
+}
+
+/// A token argument as its address and listed decimals: a symbol is resolved from config and the
+/// token lists, an address is taken as-is (its decimals are then only known on-chain).
+fn resolve_token_arg(config: &Config, token: &str) -> Result<(Felt, Option<u8>)> {
+    if token.starts_with("0x") || token.starts_with("0X") {
+        let address = Felt::from_hex(token)
+            .map_err(|e| CliError::InvalidInput(format!("Invalid token address: {e}")))?;
+        return Ok((address, None));
+    }
+    balance::resolve_token(config, token)
+        .map(|(address, decimals)| (address, Some(decimals)))
+        .ok_or_else(|| {
+            CliError::InvalidInput(format!(
+                "Unknown token '{token}'. Pass its address, or add it with 'controller config set token.{token} <address>'"
+            ))
+        })
+}
+
+/// A recipient address, or the controller address of a username
+pub async fn resolve_recipient(config: &Config, recipient: &str) -> Result<Felt> {
+    if recipient.starts_with("0x") || recipient.starts_with("0X") {
+        return Felt::from_hex(recipient)
+            .map_err(|e| CliError::InvalidInput(format!("Invalid recipient address: {e}")));
+    }
+    let resolved = lookup::resolve(config, &[recipient.to_string()], &[], CacheMode::Default)
+        .await?
+        .into_iter()
+        .find(|entry| entry.username.eq_ignore_ascii_case(recipient))
+        .ok_or_else(|| {
+            CliError::InvalidInput(format!(
+                "No controller account found for username '{recipient}'"
+            ))
+        })?;
+    Felt::from_hex(&resolved.address).map_err(|e| {
+        CliError::InvalidInput(format!(
+            "Lookup returned an invalid address for '{recipient}': {e}"
+        ))
+    })
+}
+
+async fn onchain_decimals<P: Provider + Sync>(provider: &P, token: Felt) -> Option<u8> {
+    let selector = starknet::core::utils::get_selector_from_name("decimals").ok()?;
+    let result = provider
+        .call(
+            FunctionCall {
+                contract_address: token,
+                entry_point_selector: selector,
+                calldata: vec![],
+            },
+            BlockId::Tag(BlockTag::Latest),
+        )
+        .await
+        .ok()?;
+    result.first().and_then(|d| u8::try_from(*d).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_token_arguments() {
+        let mut config = Config::default();
+        let (_, decimals) = resolve_token_arg(&config, "usdc").unwrap();
+        assert_eq!(decimals, Some(6));
+
+        let (address, decimals) = resolve_token_arg(&config, "0x0123").unwrap();
+        assert_eq!(address, Felt::from(0x123u64));
+        assert_eq!(decimals, None);
+
+        assert!(resolve_token_arg(&config, "GOLD").is_err());
+        config
+            .tokens
+            .insert("GOLD".to_string(), "0x456".to_string());
+        assert_eq!(
+            resolve_token_arg(&config, "gold").unwrap(),
+            (Felt::from(0x456u64), Some(18))
+        );
+        assert!(resolve_token_arg(&config, "0xzz").is_err());
+    }
+}
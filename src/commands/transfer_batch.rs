@@ -0,0 +1,360 @@
+use crate::{
+    commands::{
+        balance, batch,
+        execute::{self, CallSpec, ExecuteOptions, Execution},
+        lookup::{self, CacheMode},
+    },
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use serde::Serialize;
+use starknet::core::types::Felt;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Calldata budget per transaction. The network limit is 4000 felts; the rest is left for the
+/// session signature and paymaster wrapping.
+const MAX_CALLDATA_FELTS: usize = 3_000;
+/// Felts one `transfer(recipient, u256)` adds to `__execute__` calldata: address, selector,
+/// calldata length, recipient, amount low and high
+const TRANSFER_CALL_FELTS: usize = 6;
+
+#[derive(Serialize)]
+pub struct TransferBatchOutput {
+    /// Job the chunks are audited under, for `controller batch status`
+    pub batch_id: String,
+    pub token: String,
+    pub recipients: usize,
+    pub sent: usize,
+    pub transaction_hashes: Vec<String>,
+    pub results_file: String,
+}
+
+/// The per-chunk `execute` plans, printed under `--explain`
+#[derive(Serialize)]
+pub struct TransferBatchPlan {
+    pub token: String,
+    pub recipients: usize,
+    pub chunks: Vec<execute::ExecutePlan>,
+    pub results_file: String,
+}
+
+/// One `recipient,amount` row of the input CSV
+#[derive(Debug, Clone, PartialEq)]
+struct Row {
+    line: usize,
+    recipient: String,
+    amount: String,
+}
+
+/// Per-row outcome written to the results CSV
+struct Outcome {
+    address: String,
+    status: &'static str,
+    transaction_hash: String,
+    error: String,
+}
+
+/// Transfer `token` to every `(recipient, amount)` row of a CSV, chunked into multicalls and
+/// submitted one after another. Stops at the first failed chunk; the results CSV records which
+/// rows were sent, failed or never attempted.
+#[allow(clippy::too_many_arguments)]
+pub async fn execute(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    file: String,
+    token: String,
+    chunk_size: Option<usize>,
+    results: Option<String>,
+    chain_id: Option<String>,
+    rpc_url: Option<String>,
+    account: Option<&str>,
+) -> Result<()> {
+    let content = std::fs::read_to_string(&file).map_err(|e| CliError::FileError {
+        path: file.clone(),
+        message: e.to_string(),
+    })?;
+    let rows = parse_rows(&content)?;
+    if rows.is_empty() {
+        return Err(CliError::InvalidInput(format!(
+            "No recipients found in {file}"
+        )));
+    }
+
+    let (token_address, decimals) = balance::resolve_token(config, &token).ok_or_else(|| {
+        CliError::InvalidInput(format!(
+            "Unknown token '{token}'. Add it with 'controller config set token.{token} <address>'"
+        ))
+    })?;
+
+    // Validate every row and resolve usernames up front so nothing is sent for a bad file
+    let mut amounts = Vec::with_capacity(rows.len());
+    for row in &rows {
+        let amount = balance::parse_token_amount(&row.amount, decimals).ok_or_else(|| {
+            CliError::InvalidInput(format!(
+                "Line {}: invalid amount '{}': expected a token amount like '100' or '0.5'",
+                row.line, row.amount
+            ))
+        })?;
+        amounts.push(amount);
+    }
+    let addresses = resolve_recipients(config, &rows).await?;
+
+    let per_chunk = chunk_size
+        .unwrap_or(usize::MAX)
+        .clamp(1, MAX_CALLDATA_FELTS / TRANSFER_CALL_FELTS);
+    let chunks = rows.len().div_ceil(per_chunk);
+    let results_path = results
+        .map(PathBuf::from)
+        .unwrap_or_else(|| results_path(&file));
+
+    let batch_id = batch::new_job_id();
+    if !config.cli.explain {
+        formatter.info(&format!("Batch job {batch_id}"));
+    }
+    let mut config = config.clone();
+    config.cli.batch_id = Some(batch_id.clone());
+
+    let mut outcomes: Vec<Option<Outcome>> = (0..rows.len()).map(|_| None).collect();
+    let mut transaction_hashes = Vec::new();
+    let mut plans = Vec::new();
+
+    for (index, start) in (0..rows.len()).step_by(per_chunk).enumerate() {
+        let end = (start + per_chunk).min(rows.len());
+        formatter.info(&format!(
+            "Chunk {}/{chunks}: sending {} transfer(s) (rows {}-{})...",
+            index + 1,
+            end - start,
+            start + 1,
+            end
+        ));
+
+        let calls: Vec<CallSpec> = (start..end)
+            .map(|i| CallSpec {
+                contract_address: format!("0x{token_address:x}"),
+                entrypoint: "transfer".to_string(),
+                calldata: vec![addresses[i].clone(), format!("u256:{}", amounts[i])],
+            })
+            .collect();
+        let execution = execute::execute_calls(
+            &config,
+            formatter,
+            calls,
+            ExecuteOptions {
+                wait: true,
+                chain_id: chain_id.clone(),
+                rpc_url: rpc_url.clone(),
+                ..Default::default()
+            },
+            account,
+        )
+        .await;
+        if config.cli.explain {
+            if let Execution::Planned(plan) = execution? {
+                plans.push(plan);
+            }
+            continue;
+        }
+        let result = execution
+            .and_then(|execution| execution.into_submitted("Chunk"))
+            .map(|output| output.transaction_hash);
+
+        let (status, transaction_hash, error) = match &result {
+            Ok(hash) => ("sent", hash.clone(), String::new()),
+            Err(e) => ("failed", String::new(), e.to_string()),
+        };
+        for (outcome, address) in outcomes[start..end].iter_mut().zip(&addresses[start..end]) {
+            *outcome = Some(Outcome {
+                address: address.clone(),
+                status,
+                transaction_hash: transaction_hash.clone(),
+                error: error.clone(),
+            });
+        }
+        // Rewritten after every chunk so an interrupted run still leaves a record
+        write_results(&results_path, &rows, &outcomes)?;
+
+        match result {
+            Ok(hash) => {
+                formatter.info(&format!("Chunk {}/{chunks} confirmed: {hash}", index + 1));
+                transaction_hashes.push(hash);
+            }
+            Err(e) => {
+                formatter.warning(&format!(
+                    "Stopped at chunk {}/{chunks}. Rows not sent are marked in {}; \
+                     'controller batch status {batch_id}' lists the chunks sent",
+                    index + 1,
+                    results_path.display()
+                ));
+                return Err(e);
+            }
+        }
+    }
+
+    if config.cli.explain {
+        formatter.success(&TransferBatchPlan {
+            token: token.to_uppercase(),
+            recipients: rows.len(),
+            chunks: plans,
+            results_file: results_path.display().to_string(),
+        });
+        return Ok(());
+    }
+
+    formatter.success(&TransferBatchOutput {
+        batch_id,
+        token: token.to_uppercase(),
+        recipients: rows.len(),
+        sent: rows.len(),
+        transaction_hashes,
+        results_file: results_path.display().to_string(),
+    });
+    Ok(())
+}
+
+/// Parse `recipient,amount` rows, skipping blank lines, `#` comments and a header row.
+fn parse_rows(content: &str) -> Result<Vec<Row>> {
+    let mut rows = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line
+            .split(',')
+            .map(|field| field.trim().trim_matches('"').trim())
+            .collect();
+        let [recipient, amount] = fields.as_slice() else {
+            return Err(CliError::InvalidInput(format!(
+                "Line {}: expected 'recipient,amount', got '{line}'",
+                index + 1
+            )));
+        };
+        if rows.is_empty() && recipient.eq_ignore_ascii_case("recipient") {
+            continue;
+        }
+        rows.push(Row {
+            line: index + 1,
+            recipient: recipient.to_string(),
+            amount: amount.to_string(),
+        });
+    }
+    Ok(rows)
+}
+
+/// Map each row's recipient to an address: hex addresses as-is, anything else looked up as a
+/// controller username.
+async fn resolve_recipients(config: &Config, rows: &[Row]) -> Result<Vec<String>> {
+    let mut usernames: Vec<String> = rows
+        .iter()
+        .filter(|row| !row.recipient.starts_with("0x"))
+        .map(|row| row.recipient.to_lowercase())
+        .collect();
+    usernames.sort();
+    usernames.dedup();
+
+    let known: HashMap<String, String> = if usernames.is_empty() {
+        HashMap::new()
+    } else {
+        lookup::resolve(config, &usernames, &[], CacheMode::Default)
+            .await?
+            .into_iter()
+            .map(|entry| (entry.username.to_lowercase(), entry.address))
+            .collect()
+    };
+
+    rows.iter()
+        .map(|row| {
+            if row.recipient.starts_with("0x") {
+                let address = Felt::from_hex(&row.recipient).map_err(|e| {
+                    CliError::InvalidInput(format!(
+                        "Line {}: invalid address '{}': {e}",
+                        row.line, row.recipient
+                    ))
+                })?;
+                return Ok(format!("0x{address:x}"));
+            }
+            known
+                .get(&row.recipient.to_lowercase())
+                .cloned()
+                .ok_or_else(|| {
+                    CliError::InvalidInput(format!(
+                        "Line {}: unknown username '{}'",
+                        row.line, row.recipient
+                    ))
+                })
+        })
+        .collect()
+}
+
+/// `recipients.csv` -> `recipients.results.csv`, next to the input
+fn results_path(file: &str) -> PathBuf {
+    let path = Path::new(file);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "transfers".to_string());
+    path.with_file_name(format!("{stem}.results.csv"))
+}
+
+fn write_results(path: &Path, rows: &[Row], outcomes: &[Option<Outcome>]) -> Result<()> {
+    let mut csv = String::from("recipient,address,amount,status,transaction_hash,error\n");
+    for (row, outcome) in rows.iter().zip(outcomes) {
+        let (address, status, hash, error) = match outcome {
+            Some(o) => (
+                o.address.as_str(),
+                o.status,
+                o.transaction_hash.as_str(),
+                o.error.as_str(),
+            ),
+            None => ("", "pending", "", ""),
+        };
+        csv.push_str(&format!(
+            "{},{address},{},{status},{hash},\"{}\"\n",
+            row.recipient,
+            row.amount,
+            error.replace('"', "'")
+        ));
+    }
+    std::fs::write(path, csv).map_err(|e| CliError::FileError {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rows_with_header_and_comments() {
+        let rows = parse_rows("recipient,amount\n# team\n0x123, 10\n\n\"shinobi\",0.5\n").unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                Row {
+                    line: 3,
+                    recipient: "0x123".to_string(),
+                    amount: "10".to_string()
+                },
+                Row {
+                    line: 5,
+                    recipient: "shinobi".to_string(),
+                    amount: "0.5".to_string()
+                },
+            ]
+        );
+
+        let err = parse_rows("0x1,10\n0x2\n").unwrap_err();
+        assert!(err.to_string().contains("Line 2"));
+    }
+
+    #[test]
+    fn names_results_next_to_input() {
+        assert_eq!(
+            results_path("/tmp/drops/recipients.csv"),
+            PathBuf::from("/tmp/drops/recipients.results.csv")
+        );
+    }
+}
@@ -0,0 +1,425 @@
+use crate::{
+    api::RpcTransport,
+    chains,
+    commands::{
+        balance,
+        execute::{CallSpec, Execution},
+    },
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+};
+use account_sdk::storage::{filestorage::FileSystemBackend, StorageBackend};
+use serde::Serialize;
+use starknet::core::types::{BlockId, BlockTag, Felt, FunctionCall};
+use starknet::core::utils::get_selector_from_name;
+use starknet::providers::{JsonRpcClient, Provider};
+
+#[derive(Serialize)]
+pub struct VaultBalanceOutput {
+    pub vault: String,
+    pub asset: String,
+    pub shares: String,
+    pub shares_raw: String,
+    /// What the shares are worth now, per the vault's `convert_to_assets`
+    pub assets: String,
+    pub assets_raw: String,
+}
+
+#[derive(Serialize)]
+pub struct VaultOutput {
+    /// 'deposit', 'withdraw' or 'redeem'
+    pub action: String,
+    pub vault: String,
+    pub asset: String,
+    /// Assets moved, as previewed before submitting
+    pub assets: String,
+    /// Shares minted or burned, as previewed before submitting
+    pub shares: String,
+    pub transaction_hash: String,
+}
+
+/// An ERC-4626 vault and the token it holds, read from the chain.
+struct Vault {
+    provider: JsonRpcClient<RpcTransport>,
+    address: Felt,
+    asset: Felt,
+    asset_decimals: u8,
+    share_decimals: u8,
+}
+
+impl Vault {
+    async fn open(vault: &str, rpc_url: &str) -> Result<Self> {
+        let address = Felt::from_hex(vault)
+            .map_err(|e| CliError::InvalidInput(format!("Invalid vault address: {e}")))?;
+        let url = url::Url::parse(rpc_url)
+            .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
+        let provider = JsonRpcClient::new(crate::api::rpc_transport(url));
+
+        let asset = view(&provider, address, "asset", vec![])
+            .await?
+            .first()
+            .copied()
+            .ok_or_else(|| {
+                CliError::InvalidInput(format!(
+                    "{vault} returned no asset; is it an ERC-4626 vault?"
+                ))
+            })?;
+        let asset_decimals = decimals(&provider, asset).await;
+        let share_decimals = decimals(&provider, address).await;
+        Ok(Self {
+            provider,
+            address,
+            asset,
+            asset_decimals,
+            share_decimals,
+        })
+    }
+
+    /// Call a view that takes and returns a single u256, e.g. `preview_deposit(assets)`
+    async fn convert(&self, entrypoint: &str, amount: u128) -> Result<u128> {
+        let result = view(
+            &self.provider,
+            self.address,
+            entrypoint,
+            vec![Felt::from(amount), Felt::ZERO],
+        )
+        .await?;
+        parse_u256(entrypoint, &result)
+    }
+
+    async fn shares_of(&self, owner: Felt) -> Result<u128> {
+        let result = view(&self.provider, self.address, "balance_of", vec![owner]).await?;
+        parse_u256("balance_of", &result)
+    }
+}
+
+async fn view(
+    provider: &JsonRpcClient<RpcTransport>,
+    contract_address: Felt,
+    entrypoint: &str,
+    calldata: Vec<Felt>,
+) -> Result<Vec<Felt>> {
+    let entry_point_selector = get_selector_from_name(entrypoint)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid entrypoint: {e}")))?;
+    provider
+        .call(
+            FunctionCall {
+                contract_address,
+                entry_point_selector,
+                calldata,
+            },
+            BlockId::Tag(BlockTag::Latest),
+        )
+        .await
+        .map_err(|e| {
+            CliError::Network(format!(
+                "{entrypoint} on 0x{contract_address:x} failed: {e}"
+            ))
+        })
+}
+
+/// A token's decimals, or 18 when it doesn't say
+async fn decimals(provider: &JsonRpcClient<RpcTransport>, token: Felt) -> u8 {
+    match view(provider, token, "decimals", vec![]).await {
+        Ok(result) => result
+            .first()
+            .and_then(|d| u8::try_from(*d).ok())
+            .unwrap_or(18),
+        Err(_) => 18,
+    }
+}
+
+/// A u256 return value (low, high) as u128; amounts beyond that are refused rather than truncated.
+fn parse_u256(entrypoint: &str, result: &[Felt]) -> Result<u128> {
+    let (low, high) = match result {
+        [low] => (*low, Felt::ZERO),
+        [low, high, ..] => (*low, *high),
+        [] => {
+            return Err(CliError::InvalidInput(format!(
+                "{entrypoint} returned nothing"
+            )))
+        }
+    };
+    match (u128::try_from(low), high == Felt::ZERO) {
+        (Ok(value), true) => Ok(value),
+        _ => Err(CliError::InvalidInput(format!(
+            "{entrypoint} returned an amount too large to handle"
+        ))),
+    }
+}
+
+/// The controller address of the session, which holds the shares
+fn owner(config: &Config, account: Option<&str>) -> Result<Felt> {
+    FileSystemBackend::new(config.resolve_storage_path(account))
+        .controller()
+        .ok()
+        .flatten()
+        .map(|controller| controller.address)
+        .ok_or(CliError::NoSession)
+}
+
+/// Approve the vault for `assets`, then deposit them with the shares minted to `owner`.
+fn deposit_calls(vault: Felt, asset: Felt, owner: Felt, assets: u128) -> Vec<CallSpec> {
+    vec![
+        CallSpec {
+            contract_address: format!("0x{asset:x}"),
+            entrypoint: "approve".to_string(),
+            calldata: vec![format!("0x{vault:x}"), format!("u256:{assets}")],
+        },
+        CallSpec {
+            contract_address: format!("0x{vault:x}"),
+            entrypoint: "deposit".to_string(),
+            calldata: vec![format!("u256:{assets}"), format!("0x{owner:x}")],
+        },
+    ]
+}
+
+/// `withdraw(assets, receiver, owner)` or `redeem(shares, receiver, owner)`, paid out to `owner`.
+fn withdraw_call(vault: Felt, owner: Felt, entrypoint: &str, amount: u128) -> CallSpec {
+    CallSpec {
+        contract_address: format!("0x{vault:x}"),
+        entrypoint: entrypoint.to_string(),
+        calldata: vec![
+            format!("u256:{amount}"),
+            format!("0x{owner:x}"),
+            format!("0x{owner:x}"),
+        ],
+    }
+}
+
+/// Show the account's shares in a vault and what they are worth in the underlying asset.
+pub async fn balance(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    vault: String,
+    chain_id: Option<String>,
+    rpc_url: Option<String>,
+    account: Option<&str>,
+) -> Result<()> {
+    let owner = owner(config, account)?;
+    let rpc_url = chains::resolve_rpc_url(config, formatter, chain_id, rpc_url, account)?;
+    let vault = Vault::open(&vault, &rpc_url).await?;
+
+    let shares = vault.shares_of(owner).await?;
+    let assets = if shares == 0 {
+        0
+    } else {
+        vault.convert("convert_to_assets", shares).await?
+    };
+    formatter.success(&VaultBalanceOutput {
+        vault: format!("0x{:x}", vault.address),
+        asset: format!("0x{:x}", vault.asset),
+        shares: balance::format_u128_balance(shares, vault.share_decimals),
+        shares_raw: shares.to_string(),
+        assets: balance::format_u128_balance(assets, vault.asset_decimals),
+        assets_raw: assets.to_string(),
+    });
+    Ok(())
+}
+
+/// Deposit `amount` of the vault's asset (in token units, e.g. '100' or '0.5'), approving the
+/// vault for exactly that amount in the same multicall.
+#[allow(clippy::too_many_arguments)]
+pub async fn deposit(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    vault: String,
+    amount: String,
+    chain_id: Option<String>,
+    rpc_url: Option<String>,
+    no_paymaster: bool,
+    account: Option<&str>,
+) -> Result<()> {
+    let owner = owner(config, account)?;
+    let rpc_url = chains::resolve_rpc_url(config, formatter, chain_id, rpc_url, account)?;
+    let vault = Vault::open(&vault, &rpc_url).await?;
+
+    let assets = parse_amount(&amount, vault.asset_decimals)?;
+    let shares = vault.convert("preview_deposit", assets).await?;
+    if shares == 0 {
+        return Err(CliError::InvalidInput(format!(
+            "Depositing {amount} would mint no shares"
+        )));
+    }
+    formatter.info(&format!(
+        "Depositing {} for ~{} shares...",
+        balance::format_u128_balance(assets, vault.asset_decimals),
+        balance::format_u128_balance(shares, vault.share_decimals)
+    ));
+
+    let calls = deposit_calls(vault.address, vault.asset, owner, assets);
+    submit(
+        config,
+        formatter,
+        &vault,
+        "deposit",
+        calls,
+        (assets, shares),
+        rpc_url,
+        no_paymaster,
+        account,
+    )
+    .await
+}
+
+/// Withdraw `amount` of the vault's asset, or with `in_shares` redeem `amount` shares. 'max'
+/// redeems every share the account holds.
+#[allow(clippy::too_many_arguments)]
+pub async fn withdraw(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    vault: String,
+    amount: String,
+    in_shares: bool,
+    chain_id: Option<String>,
+    rpc_url: Option<String>,
+    no_paymaster: bool,
+    account: Option<&str>,
+) -> Result<()> {
+    let owner = owner(config, account)?;
+    let rpc_url = chains::resolve_rpc_url(config, formatter, chain_id, rpc_url, account)?;
+    let vault = Vault::open(&vault, &rpc_url).await?;
+    let held = vault.shares_of(owner).await?;
+    if held == 0 {
+        return Err(CliError::InvalidInput(format!(
+            "The account holds no shares of 0x{:x}",
+            vault.address
+        )));
+    }
+
+    let (entrypoint, assets, shares) = if amount.eq_ignore_ascii_case("max") {
+        let assets = vault.convert("preview_redeem", held).await?;
+        ("redeem", assets, held)
+    } else if in_shares {
+        let shares = parse_amount(&amount, vault.share_decimals)?;
+        let assets = vault.convert("preview_redeem", shares).await?;
+        ("redeem", assets, shares)
+    } else {
+        let assets = parse_amount(&amount, vault.asset_decimals)?;
+        let shares = vault.convert("preview_withdraw", assets).await?;
+        ("withdraw", assets, shares)
+    };
+    if shares > held {
+        return Err(CliError::InvalidInput(format!(
+            "Withdrawing {} needs {} shares but the account holds {}",
+            balance::format_u128_balance(assets, vault.asset_decimals),
+            balance::format_u128_balance(shares, vault.share_decimals),
+            balance::format_u128_balance(held, vault.share_decimals)
+        )));
+    }
+    formatter.info(&format!(
+        "Withdrawing ~{} for {} shares...",
+        balance::format_u128_balance(assets, vault.asset_decimals),
+        balance::format_u128_balance(shares, vault.share_decimals)
+    ));
+
+    let amount = if entrypoint == "redeem" {
+        shares
+    } else {
+        assets
+    };
+    let calls = vec![withdraw_call(vault.address, owner, entrypoint, amount)];
+    submit(
+        config,
+        formatter,
+        &vault,
+        entrypoint,
+        calls,
+        (assets, shares),
+        rpc_url,
+        no_paymaster,
+        account,
+    )
+    .await
+}
+
+fn parse_amount(amount: &str, decimals: u8) -> Result<u128> {
+    balance::parse_token_amount(amount, decimals)
+        .filter(|amount| *amount > 0)
+        .ok_or_else(|| {
+            CliError::InvalidInput(format!(
+                "Invalid amount '{amount}': expected a positive token amount like '100' or '0.5'"
+            ))
+        })
+}
+
+/// Submit the calls like `execute --wait`, which checks them against the session policies, then
+/// report the previewed `(assets, shares)` with the transaction.
+#[allow(clippy::too_many_arguments)]
+async fn submit(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    vault: &Vault,
+    action: &str,
+    calls: Vec<CallSpec>,
+    (assets, shares): (u128, u128),
+    rpc_url: String,
+    no_paymaster: bool,
+    account: Option<&str>,
+) -> Result<()> {
+    let execution = crate::commands::execute::execute_calls(
+        config,
+        formatter,
+        calls,
+        crate::commands::execute::ExecuteOptions {
+            wait: true,
+            rpc_url: Some(rpc_url),
+            no_paymaster,
+            ..Default::default()
+        },
+        account,
+    )
+    .await?;
+    if let Execution::Planned(plan) = &execution {
+        formatter.success(plan);
+        return Ok(());
+    }
+    let transaction_hash = execution
+        .into_submitted(&format!("The {action}"))?
+        .transaction_hash;
+
+    if config.cli.json_output {
+        formatter.success(&VaultOutput {
+            action: action.to_string(),
+            vault: format!("0x{:x}", vault.address),
+            asset: format!("0x{:x}", vault.asset),
+            assets: balance::format_u128_balance(assets, vault.asset_decimals),
+            shares: balance::format_u128_balance(shares, vault.share_decimals),
+            transaction_hash,
+        });
+    } else {
+        formatter.info(&format!("Confirmed: {transaction_hash}"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_vault_calls() {
+        let (vault, asset, owner) = (
+            Felt::from(0xa1u64),
+            Felt::from(0xb2u64),
+            Felt::from(0xc3u64),
+        );
+
+        let calls = deposit_calls(vault, asset, owner, 500);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].contract_address, "0xb2");
+        assert_eq!(calls[0].entrypoint, "approve");
+        assert_eq!(calls[0].calldata, vec!["0xa1", "u256:500"]);
+        assert_eq!(calls[1].entrypoint, "deposit");
+        assert_eq!(calls[1].calldata, vec!["u256:500", "0xc3"]);
+
+        let call = withdraw_call(vault, owner, "redeem", 7);
+        assert_eq!(call.contract_address, "0xa1");
+        assert_eq!(call.calldata, vec!["u256:7", "0xc3", "0xc3"]);
+
+        assert_eq!(parse_u256("x", &[Felt::from(9u64), Felt::ZERO]).unwrap(), 9);
+        assert!(parse_u256("x", &[Felt::ONE, Felt::ONE]).is_err());
+        assert!(parse_amount("0", 18).is_err());
+    }
+}
@@ -0,0 +1,222 @@
+use crate::{
+    config::Config,
+    error::{CliError, Result},
+    output::OutputFormatter,
+    version,
+};
+use serde::Serialize;
+use starknet::providers::{JsonRpcClient, Provider};
+use std::time::Duration;
+
+#[derive(Serialize)]
+pub struct VersionOutput {
+    pub version: String,
+    pub commit: String,
+    pub build_date: String,
+    pub account_sdk: String,
+    pub rpc_spec_versions: Vec<String>,
+    pub requires: Requirements,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub check: Option<CompatibilityCheck>,
+}
+
+/// What this build needs from Cartridge services
+#[derive(Serialize)]
+pub struct Requirements {
+    pub api_fields: Vec<String>,
+    pub keychain_routes: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct CompatibilityCheck {
+    pub compatible: bool,
+    pub rpc: Probe,
+    pub api: Probe,
+    pub keychain: Probe,
+}
+
+#[derive(Serialize)]
+pub struct Probe {
+    pub url: String,
+    /// `None` when compatibility could not be determined (e.g. the service was unreachable)
+    pub compatible: Option<bool>,
+    pub detail: String,
+}
+
+/// Print build metadata and, with `check`, probe the configured RPC, API and keychain.
+pub async fn execute(config: &Config, formatter: &dyn OutputFormatter, check: bool) -> Result<()> {
+    let check = if check {
+        formatter.info("Checking compatibility with the configured Cartridge services...");
+        let rpc = probe_rpc(&config.session.rpc_url).await;
+        let api = probe_api(&config.session.api_url).await;
+        let keychain = probe_keychain(&config.session.keychain_url).await;
+        let compatible = [&rpc, &api, &keychain]
+            .iter()
+            .all(|probe| probe.compatible == Some(true));
+        Some(CompatibilityCheck {
+            compatible,
+            rpc,
+            api,
+            keychain,
+        })
+    } else {
+        None
+    };
+
+    let incompatible = check.as_ref().is_some_and(|c| !c.compatible);
+    formatter.success(&VersionOutput {
+        version: version::CURRENT_VERSION.to_string(),
+        commit: version::GIT_COMMIT.to_string(),
+        build_date: build_date(version::BUILD_TIMESTAMP),
+        account_sdk: version::ACCOUNT_SDK_VERSION.to_string(),
+        rpc_spec_versions: to_strings(version::SUPPORTED_RPC_SPECS),
+        requires: Requirements {
+            api_fields: to_strings(version::REQUIRED_API_FIELDS),
+            keychain_routes: to_strings(version::REQUIRED_KEYCHAIN_ROUTES),
+        },
+        check,
+    });
+    if incompatible {
+        formatter.warning(
+            "Some Cartridge services did not pass the compatibility check; see 'check' above",
+        );
+    }
+    Ok(())
+}
+
+fn to_strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|v| v.to_string()).collect()
+}
+
+fn build_date(timestamp: &str) -> String {
+    timestamp
+        .parse::<i64>()
+        .ok()
+        .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+        .map(|date| date.to_rfc3339())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+async fn probe_rpc(rpc_url: &str) -> Probe {
+    let result = async {
+        let url = url::Url::parse(rpc_url)
+            .map_err(|e| CliError::InvalidInput(format!("Invalid RPC URL: {e}")))?;
+        JsonRpcClient::new(crate::api::rpc_transport(url))
+            .spec_version()
+            .await
+            .map_err(|e| CliError::Network(format!("starknet_specVersion failed: {e}")))
+    }
+    .await;
+
+    match result {
+        Ok(spec) => {
+            let compatible = version::rpc_spec_supported(&spec);
+            Probe {
+                url: rpc_url.to_string(),
+                compatible: Some(compatible),
+                detail: if compatible {
+                    format!("RPC spec {spec}")
+                } else {
+                    format!(
+                        "RPC spec {spec} is not supported (expected {})",
+                        version::SUPPORTED_RPC_SPECS.join(" or ")
+                    )
+                },
+            }
+        }
+        Err(e) => unreachable_probe(rpc_url, e),
+    }
+}
+
+/// Introspect the API's query fields and look for the ones the CLI uses.
+async fn probe_api(api_url: &str) -> Probe {
+    let query = "query { __schema { queryType { fields { name } } } }";
+    let result = crate::api::graphql_query(
+        api_url,
+        query,
+        serde_json::Value::Object(Default::default()),
+    )
+    .await;
+
+    match result {
+        Ok((data, _)) => {
+            let Some(fields) = data["__schema"]["queryType"]["fields"].as_array() else {
+                return Probe {
+                    url: api_url.to_string(),
+                    compatible: None,
+                    detail: "Schema introspection is not available".to_string(),
+                };
+            };
+            let names: Vec<&str> = fields.iter().filter_map(|f| f["name"].as_str()).collect();
+            let missing = missing_fields(&names);
+            Probe {
+                url: api_url.to_string(),
+                compatible: Some(missing.is_empty()),
+                detail: if missing.is_empty() {
+                    "All required query fields are present".to_string()
+                } else {
+                    format!("Missing query fields: {}", missing.join(", "))
+                },
+            }
+        }
+        Err(e) => unreachable_probe(api_url, e),
+    }
+}
+
+async fn probe_keychain(keychain_url: &str) -> Probe {
+    let url = format!(
+        "{}{}",
+        keychain_url.trim_end_matches('/'),
+        version::REQUIRED_KEYCHAIN_ROUTES[0]
+    );
+    let result = async {
+        let client = crate::api::http_client(Duration::from_secs(10))?;
+        crate::api::send(client.get(&url), "Failed to reach keychain").await
+    }
+    .await;
+
+    match result {
+        Ok(response) => {
+            let status = response.status();
+            let compatible = !status.is_client_error() && !status.is_server_error();
+            Probe {
+                url,
+                compatible: Some(compatible),
+                detail: format!("GET returned {status}"),
+            }
+        }
+        Err(e) => unreachable_probe(&url, e),
+    }
+}
+
+fn unreachable_probe(url: &str, error: CliError) -> Probe {
+    Probe {
+        url: url.to_string(),
+        compatible: None,
+        detail: error.to_string(),
+    }
+}
+
+fn missing_fields(present: &[&str]) -> Vec<String> {
+    version::REQUIRED_API_FIELDS
+        .iter()
+        .filter(|field| !present.contains(field))
+        .map(|field| field.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_missing_api_fields_and_build_date() {
+        assert!(missing_fields(&["sessions", "subscribeCreateSession", "account"]).is_empty());
+        assert_eq!(
+            missing_fields(&["sessions"]),
+            vec!["subscribeCreateSession"]
+        );
+        assert_eq!(build_date("0"), "1970-01-01T00:00:00+00:00");
+        assert_eq!(build_date("unknown"), "unknown");
+    }
+}
@@ -1,6 +1,27 @@
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// `--config-dir`, set once at startup before the config is loaded
+static CONFIG_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Point config, default storage and caches at `dir` instead of the platform config directory.
+pub fn set_config_dir(dir: &str) {
+    let _ = CONFIG_DIR.set(PathBuf::from(shellexpand::tilde(dir).to_string()));
+}
+
+/// Directory holding `config.toml`, the default session storage and the CLI's caches:
+/// `--config-dir`, then `CARTRIDGE_CONFIG_DIR`, then `<platform config dir>/controller-cli`.
+pub fn config_dir() -> Option<PathBuf> {
+    if let Some(dir) = CONFIG_DIR.get() {
+        return Some(dir.clone());
+    }
+    match std::env::var("CARTRIDGE_CONFIG_DIR") {
+        Ok(dir) if !dir.is_empty() => Some(PathBuf::from(shellexpand::tilde(&dir).to_string())),
+        _ => dirs::config_dir().map(|p| p.join("controller-cli")),
+    }
+}
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Config {
@@ -10,6 +31,35 @@ pub struct Config {
     pub cli: CliConfig,
     #[serde(default)]
     pub tokens: BTreeMap<String, String>,
+    /// Per-chain contract address overrides, keyed by chain ID then contract name
+    #[serde(default)]
+    pub contracts: BTreeMap<String, BTreeMap<String, String>>,
+    /// Per-token value thresholds (in whole token units) above which `execute` requires a second
+    /// operator to approve a pending intent, keyed by token symbol
+    #[serde(default)]
+    pub approval_thresholds: BTreeMap<String, String>,
+    /// Faucet endpoints for test chains, keyed by chain ID (used by `controller faucet`)
+    #[serde(default)]
+    pub faucets: BTreeMap<String, String>,
+    /// Calldata values `execute` prepends to every call to a contract (e.g. a world address),
+    /// keyed by contract address
+    #[serde(default)]
+    pub calldata_prefixes: BTreeMap<String, String>,
+    /// Chains outside the built-in registry (e.g. Slot deployments), keyed by chain ID, so
+    /// `--chain-id` resolves to their RPC
+    #[serde(default)]
+    pub chains: BTreeMap<String, ChainProfile>,
+}
+
+/// Endpoints of a custom chain, written by `controller slot link` or `config set chain.<CHAIN>.*`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChainProfile {
+    pub rpc_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub torii_url: Option<String>,
+    /// Slot project the endpoints were discovered from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slot_project: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +72,15 @@ pub struct SessionConfig {
     pub keychain_url: String,
     #[serde(default = "default_api_url")]
     pub api_url: String,
+    /// Behaviour when no chain is given: 'sepolia', 'strict' or 'auto'
+    #[serde(default = "default_chain")]
+    pub default_chain: String,
+    /// Cartridge environment ('production', 'staging' or 'dev')
+    #[serde(default = "default_env")]
+    pub env: String,
+    /// Ask the API whether the session was revoked before `status` and `execute`
+    #[serde(default)]
+    pub check_revocation: bool,
     /// Whether rpc_url was explicitly set (via config file or env var)
     #[serde(skip)]
     pub rpc_url_explicitly_set: bool,
@@ -31,15 +90,55 @@ pub struct SessionConfig {
 pub struct CliConfig {
     #[serde(default)]
     pub json_output: bool,
+    /// Simulate `execute` instead of submitting (`execute --simulate`, never saved)
+    #[serde(skip)]
+    pub simulate: bool,
+    /// Block to pin `execute --simulate` to (`--at-block`, never saved)
+    #[serde(skip)]
+    pub simulate_at_block: Option<String>,
+    /// Pin `execute --simulate` to the block current at this time (`--override-timestamp`, never
+    /// saved)
+    #[serde(skip)]
+    pub simulate_timestamp: Option<String>,
     #[serde(default = "default_true")]
     pub use_colors: bool,
     #[serde(default = "default_callback_timeout")]
     pub callback_timeout_seconds: u64,
+    /// Batch job this run's submissions are audited under (`execute --batch-id`, never saved)
+    #[serde(skip)]
+    pub batch_id: Option<String>,
+    /// Refuse commands that sign or submit transactions
+    #[serde(default)]
+    pub read_only: bool,
+    /// Extra margin (percent of the estimated fee) required in the fee token before self-paid
+    /// transactions are submitted
+    #[serde(default = "default_fee_buffer")]
+    pub fee_buffer_percent: u64,
+    /// How long `execute --once` refuses to re-submit an identical call file, in seconds
+    #[serde(default = "default_replay_window")]
+    pub replay_window_seconds: u64,
+    /// Application identifier sent as `x-client-app` on Cartridge API and RPC requests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_app: Option<String>,
+    /// Print the plan of transacting commands instead of submitting (`--explain`, never saved)
+    #[serde(skip)]
+    pub explain: bool,
+    /// Use cached presets instead of fetching them from GitHub (`--offline`, never saved)
+    #[serde(skip)]
+    pub offline: bool,
+    /// Tip for self-paid transactions, in FRI per unit of L2 gas (`execute --tip` overrides it)
+    #[serde(default)]
+    pub tip: u64,
+    /// The caller answers `CliError::InputRequired` by asking the user and running the command
+    /// again (the `controller` front-end on a terminal, never saved). Without it, choices that
+    /// have a non-interactive fallback take it instead of asking.
+    #[serde(skip)]
+    pub interactive: bool,
 }
 
 fn default_storage_path() -> String {
-    dirs::config_dir()
-        .map(|p| p.join("controller-cli").to_string_lossy().to_string())
+    config_dir()
+        .map(|p| p.to_string_lossy().to_string())
         .unwrap_or_else(|| "~/.config/controller-cli".to_string())
 }
 
@@ -55,6 +154,14 @@ fn default_api_url() -> String {
     "https://api.cartridge.gg/query".to_string()
 }
 
+fn default_chain() -> String {
+    "sepolia".to_string()
+}
+
+fn default_env() -> String {
+    crate::chains::PRODUCTION.to_string()
+}
+
 fn default_true() -> bool {
     true
 }
@@ -63,6 +170,14 @@ fn default_callback_timeout() -> u64 {
     300
 }
 
+fn default_fee_buffer() -> u64 {
+    20
+}
+
+fn default_replay_window() -> u64 {
+    3600
+}
+
 impl Default for SessionConfig {
     fn default() -> Self {
         Self {
@@ -70,6 +185,9 @@ impl Default for SessionConfig {
             rpc_url: default_rpc_url(),
             keychain_url: default_keychain_url(),
             api_url: default_api_url(),
+            default_chain: default_chain(),
+            env: default_env(),
+            check_revocation: false,
             rpc_url_explicitly_set: false,
         }
     }
@@ -79,8 +197,20 @@ impl Default for CliConfig {
     fn default() -> Self {
         Self {
             json_output: false,
+            simulate: false,
+            simulate_at_block: None,
+            simulate_timestamp: None,
             use_colors: default_true(),
             callback_timeout_seconds: default_callback_timeout(),
+            batch_id: None,
+            read_only: false,
+            fee_buffer_percent: default_fee_buffer(),
+            replay_window_seconds: default_replay_window(),
+            client_app: None,
+            explain: false,
+            offline: false,
+            tip: 0,
+            interactive: false,
         }
     }
 }
@@ -106,9 +236,9 @@ impl Config {
     }
 
     pub fn config_path() -> anyhow::Result<PathBuf> {
-        let config_dir = dirs::config_dir()
-            .ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))?;
-        Ok(config_dir.join("controller-cli").join("config.toml"))
+        let config_dir =
+            config_dir().ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))?;
+        Ok(config_dir.join("config.toml"))
     }
 
     pub const VALID_KEYS: &'static [&'static str] = &[
@@ -119,15 +249,20 @@ impl Config {
         "json-output",
         "colors",
         "callback-timeout",
+        "default-chain",
+        "env",
+        "read-only",
+        "check-revocation",
+        "client-app",
+        "replay-window",
+        "fee-buffer",
+        "tip",
     ];
 
     pub fn save(&self) -> anyhow::Result<()> {
         let config_path = Self::config_path()?;
-        if let Some(parent) = config_path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
         let contents = toml::to_string_pretty(self)?;
-        std::fs::write(&config_path, contents)?;
+        crate::commands::storage::write_atomic(&config_path, contents)?;
         Ok(())
     }
 
@@ -140,6 +275,55 @@ impl Config {
                 .ok_or_else(|| anyhow::anyhow!("No custom token configured for '{symbol}'"));
         }
 
+        if let Some(rest) = alias.strip_prefix("contracts.") {
+            let (chain, name) = Self::parse_contract_key(rest)?;
+            return self
+                .contracts
+                .get(chain)
+                .and_then(|c| c.get(name))
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("No {name} contract override for chain {chain}"));
+        }
+
+        if let Some(symbol) = alias.strip_prefix("approval-threshold.") {
+            return self
+                .approval_thresholds
+                .get(symbol)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("No approval threshold configured for '{symbol}'"));
+        }
+
+        if let Some(chain) = alias.strip_prefix("faucet.") {
+            return self
+                .faucets
+                .get(chain)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("No faucet configured for chain {chain}"));
+        }
+
+        if let Some(rest) = alias.strip_prefix("chain.") {
+            let (chain, field) = Self::parse_chain_key(rest)?;
+            let profile = self
+                .chains
+                .get(chain)
+                .ok_or_else(|| anyhow::anyhow!("No chain profile for {chain}"))?;
+            return match field {
+                "rpc-url" => Ok(profile.rpc_url.clone()),
+                _ => profile
+                    .torii_url
+                    .clone()
+                    .ok_or_else(|| anyhow::anyhow!("No Torii URL configured for chain {chain}")),
+            };
+        }
+
+        if let Some(contract) = alias.strip_prefix("calldata-prefix.") {
+            let contract = Self::parse_prefix_contract(contract)?;
+            return self
+                .calldata_prefix(contract)
+                .map(str::to_string)
+                .ok_or_else(|| anyhow::anyhow!("No calldata prefix configured for {contract:#x}"));
+        }
+
         match alias {
             "rpc-url" => Ok(self.session.rpc_url.clone()),
             "keychain-url" => Ok(self.session.keychain_url.clone()),
@@ -148,8 +332,16 @@ impl Config {
             "json-output" => Ok(self.cli.json_output.to_string()),
             "colors" => Ok(self.cli.use_colors.to_string()),
             "callback-timeout" => Ok(self.cli.callback_timeout_seconds.to_string()),
+            "default-chain" => Ok(self.session.default_chain.clone()),
+            "env" => Ok(self.session.env.clone()),
+            "read-only" => Ok(self.cli.read_only.to_string()),
+            "check-revocation" => Ok(self.session.check_revocation.to_string()),
+            "client-app" => Ok(self.cli.client_app.clone().unwrap_or_default()),
+            "replay-window" => Ok(self.cli.replay_window_seconds.to_string()),
+            "fee-buffer" => Ok(self.cli.fee_buffer_percent.to_string()),
+            "tip" => Ok(self.cli.tip.to_string()),
             _ => anyhow::bail!(
-                "Unknown config key '{}'. Valid keys: {}, token.<symbol>",
+                "Unknown config key '{}'. Valid keys: {}, token.<symbol>, contracts.<CHAIN>.<name>, approval-threshold.<symbol>, faucet.<CHAIN>, calldata-prefix.<address>, chain.<CHAIN>.rpc-url, chain.<CHAIN>.torii-url",
                 alias,
                 Self::VALID_KEYS.join(", ")
             ),
@@ -162,6 +354,76 @@ impl Config {
             return Ok(());
         }
 
+        if let Some(rest) = alias.strip_prefix("contracts.") {
+            let (chain, name) = Self::parse_contract_key(rest)?;
+            starknet::core::types::Felt::from_hex(value)
+                .map_err(|_| anyhow::anyhow!("Invalid contract address '{value}'"))?;
+            self.contracts
+                .entry(chain.to_string())
+                .or_default()
+                .insert(name.to_string(), value.to_string());
+            return Ok(());
+        }
+
+        if let Some(symbol) = alias.strip_prefix("approval-threshold.") {
+            if crate::commands::balance::parse_token_amount(value, 18).is_none() {
+                anyhow::bail!(
+                    "Invalid approval threshold '{value}': expected a token amount like '100' or '0.5'"
+                );
+            }
+            self.approval_thresholds
+                .insert(symbol.to_string(), value.to_string());
+            return Ok(());
+        }
+
+        if let Some(chain) = alias.strip_prefix("faucet.") {
+            if chain.is_empty() {
+                anyhow::bail!("Invalid faucet key 'faucet.'. Expected faucet.<CHAIN>");
+            }
+            url::Url::parse(value).map_err(|_| anyhow::anyhow!("Invalid faucet URL '{value}'"))?;
+            self.faucets.insert(chain.to_string(), value.to_string());
+            return Ok(());
+        }
+
+        if let Some(rest) = alias.strip_prefix("chain.") {
+            let (chain, field) = Self::parse_chain_key(rest)?;
+            if crate::chains::get(chain).is_some() {
+                anyhow::bail!("{chain} is a built-in chain and can't be overridden");
+            }
+            url::Url::parse(value).map_err(|_| anyhow::anyhow!("Invalid URL '{value}'"))?;
+            match field {
+                "rpc-url" => {
+                    self.chains.entry(chain.to_string()).or_default().rpc_url = value.to_string()
+                }
+                _ => {
+                    self.chains
+                        .get_mut(chain)
+                        .ok_or_else(|| {
+                            anyhow::anyhow!(
+                                "No chain profile for {chain}. Set chain.{chain}.rpc-url first"
+                            )
+                        })?
+                        .torii_url = Some(value.to_string())
+                }
+            }
+            return Ok(());
+        }
+
+        if let Some(contract) = alias.strip_prefix("calldata-prefix.") {
+            let key = format!("{:#x}", Self::parse_prefix_contract(contract)?);
+            // An empty value removes the prefix
+            if value.trim().is_empty() {
+                self.calldata_prefixes.remove(&key);
+                return Ok(());
+            }
+            for item in value.split(',') {
+                crate::commands::calldata::parse_calldata_value(item.trim())
+                    .map_err(|e| anyhow::anyhow!("Invalid calldata prefix '{value}': {e}"))?;
+            }
+            self.calldata_prefixes.insert(key, value.to_string());
+            return Ok(());
+        }
+
         match alias {
             "rpc-url" => self.session.rpc_url = value.to_string(),
             "keychain-url" => self.session.keychain_url = value.to_string(),
@@ -184,8 +446,54 @@ impl Config {
                     )
                 })?;
             }
+            "default-chain" => {
+                if crate::chains::DefaultChain::parse(value).is_none() {
+                    anyhow::bail!(
+                        "Invalid value for default-chain: expected one of {}",
+                        crate::chains::DefaultChain::VALUES.join(", ")
+                    );
+                }
+                self.session.default_chain = value.to_string();
+            }
+            "read-only" => {
+                self.cli.read_only = value.parse::<bool>().map_err(|_| {
+                    anyhow::anyhow!("Invalid value for read-only: expected 'true' or 'false'")
+                })?;
+            }
+            "check-revocation" => {
+                self.session.check_revocation = value.parse::<bool>().map_err(|_| {
+                    anyhow::anyhow!(
+                        "Invalid value for check-revocation: expected 'true' or 'false'"
+                    )
+                })?;
+            }
+            "client-app" => {
+                Self::validate_client_app(value).map_err(|e| anyhow::anyhow!(e))?;
+                self.cli.client_app = Some(value.to_string());
+            }
+            "fee-buffer" => {
+                self.cli.fee_buffer_percent = value.parse::<u64>().map_err(|_| {
+                    anyhow::anyhow!("Invalid value for fee-buffer: expected a percentage like '20'")
+                })?;
+            }
+            "replay-window" => {
+                self.cli.replay_window_seconds = value.parse::<u64>().map_err(|_| {
+                    anyhow::anyhow!("Invalid value for replay-window: expected a number of seconds")
+                })?;
+            }
+            "env" => {
+                Self::check_environment(value)?;
+                self.session.env = value.to_string();
+            }
+            "tip" => {
+                self.cli.tip = value.parse::<u64>().map_err(|_| {
+                    anyhow::anyhow!(
+                        "Invalid value for tip: expected FRI per unit of L2 gas like '1000000'"
+                    )
+                })?;
+            }
             _ => anyhow::bail!(
-                "Unknown config key '{}'. Valid keys: {}, token.<symbol>",
+                "Unknown config key '{}'. Valid keys: {}, token.<symbol>, contracts.<CHAIN>.<name>, approval-threshold.<symbol>, faucet.<CHAIN>, calldata-prefix.<address>, chain.<CHAIN>.rpc-url, chain.<CHAIN>.torii-url",
                 alias,
                 Self::VALID_KEYS.join(", ")
             ),
@@ -193,6 +501,174 @@ impl Config {
         Ok(())
     }
 
+    fn parse_prefix_contract(contract: &str) -> anyhow::Result<starknet::core::types::Felt> {
+        starknet::core::types::Felt::from_hex(contract).map_err(|_| {
+            anyhow::anyhow!(
+                "Invalid calldata prefix key 'calldata-prefix.{contract}'. Expected calldata-prefix.<address>"
+            )
+        })
+    }
+
+    /// Keys as listed by `config list`: the fixed keys, then whichever open-ended entries
+    /// (contract overrides, thresholds, faucets, prefixes, chains) are set.
+    pub fn entries(&self) -> Vec<(String, String)> {
+        let mut entries: Vec<(String, String)> = Self::VALID_KEYS
+            .iter()
+            .map(|&key| {
+                let value = self
+                    .get_by_alias(key)
+                    .unwrap_or_else(|_| "<error>".to_string());
+                (key.to_string(), value)
+            })
+            .collect();
+        for (chain, contracts) in &self.contracts {
+            for (name, address) in contracts {
+                entries.push((format!("contracts.{chain}.{name}"), address.clone()));
+            }
+        }
+        for (symbol, threshold) in &self.approval_thresholds {
+            entries.push((format!("approval-threshold.{symbol}"), threshold.clone()));
+        }
+        for (chain, url) in &self.faucets {
+            entries.push((format!("faucet.{chain}"), url.clone()));
+        }
+        for (contract, prefix) in &self.calldata_prefixes {
+            entries.push((format!("calldata-prefix.{contract}"), prefix.clone()));
+        }
+        for (chain, profile) in &self.chains {
+            entries.push((format!("chain.{chain}.rpc-url"), profile.rpc_url.clone()));
+            if let Some(torii_url) = &profile.torii_url {
+                entries.push((format!("chain.{chain}.torii-url"), torii_url.clone()));
+            }
+        }
+        entries
+    }
+
+    /// Merge a JSON object into the config (RFC 7386 merge patch: objects merge recursively,
+    /// `null` removes a field, anything else replaces it). Field names follow the config file
+    /// (e.g. `{"chains":{"WP_MY_SLOT":{"rpc_url":"..."}}}`). Unknown fields and invalid values
+    /// are rejected and leave the config untouched.
+    pub fn merge_json(&mut self, patch: &serde_json::Value) -> anyhow::Result<()> {
+        if !patch.is_object() {
+            anyhow::bail!("Config patch must be a JSON object");
+        }
+        let mut merged = serde_json::to_value(&*self)?;
+        merge_patch(&mut merged, patch);
+
+        let mut config: Config = serde_json::from_value(merged)
+            .map_err(|e| anyhow::anyhow!("Invalid config patch: {e}"))?;
+        // Serde ignores fields it doesn't know, so look for patched fields that didn't survive
+        let roundtrip = serde_json::to_value(&config)?;
+        if let Some(path) = unknown_field(&roundtrip, patch, "") {
+            anyhow::bail!("Unknown config field '{path}'");
+        }
+
+        // Replay every value through `set_by_alias` so the patch gets the same checks as
+        // `config set <key> <value>`
+        let mut scratch = Config::default();
+        for (key, value) in config.entries() {
+            if key == "client-app" && value.is_empty() {
+                continue;
+            }
+            scratch
+                .set_by_alias(&key, &value)
+                .map_err(|e| anyhow::anyhow!("Invalid config patch: {e}"))?;
+        }
+
+        config.session.rpc_url_explicitly_set = self.session.rpc_url_explicitly_set
+            || patch
+                .pointer("/session/rpc_url")
+                .is_some_and(|v| !v.is_null());
+        *self = config;
+        Ok(())
+    }
+
+    /// Read a field by its dotted path in the config file (e.g. `session.rpc_url` or
+    /// `chains.WP_MY_SLOT`). Tables come back as JSON objects.
+    pub fn get_by_path(&self, path: &str) -> anyhow::Result<serde_json::Value> {
+        let mut value = serde_json::to_value(self)?;
+        let mut walked = String::new();
+        for segment in path.split('.') {
+            let serde_json::Value::Object(mut fields) = value else {
+                anyhow::bail!("Config field '{walked}' has no field '{segment}'");
+            };
+            value = fields.remove(segment).ok_or_else(|| {
+                let known: Vec<&str> = fields.keys().map(String::as_str).collect();
+                anyhow::anyhow!(
+                    "Unknown config field '{path}'. Fields under '{}': {}",
+                    if walked.is_empty() { "config" } else { &walked },
+                    known.join(", ")
+                )
+            })?;
+            if !walked.is_empty() {
+                walked.push('.');
+            }
+            walked.push_str(segment);
+        }
+        Ok(value)
+    }
+
+    /// Calldata prepended to calls to `contract`, matching addresses regardless of leading zeros.
+    pub fn calldata_prefix(&self, contract: starknet::core::types::Felt) -> Option<&str> {
+        self.calldata_prefixes
+            .iter()
+            .find(|(address, _)| {
+                starknet::core::types::Felt::from_hex(address).ok() == Some(contract)
+            })
+            .map(|(_, prefix)| prefix.as_str())
+    }
+
+    /// Split a `<CHAIN>.<name>` contract key.
+    fn parse_contract_key(key: &str) -> anyhow::Result<(&str, &str)> {
+        match key.split_once('.') {
+            Some((chain, name)) if !chain.is_empty() && !name.is_empty() => Ok((chain, name)),
+            _ => anyhow::bail!(
+                "Invalid contract key 'contracts.{key}'. Expected contracts.<CHAIN>.<name>"
+            ),
+        }
+    }
+
+    fn parse_chain_key(key: &str) -> anyhow::Result<(&str, &str)> {
+        match key.split_once('.') {
+            Some((chain, field)) if !chain.is_empty() && matches!(field, "rpc-url" | "torii-url") => {
+                Ok((chain, field))
+            }
+            _ => anyhow::bail!(
+                "Invalid chain key 'chain.{key}'. Expected chain.<CHAIN>.rpc-url or chain.<CHAIN>.torii-url"
+            ),
+        }
+    }
+
+    fn check_environment(name: &str) -> anyhow::Result<&'static crate::chains::Environment> {
+        crate::chains::environment(name).ok_or_else(|| {
+            let names: Vec<&str> = crate::chains::ENVIRONMENTS.iter().map(|e| e.name).collect();
+            anyhow::anyhow!(
+                "Invalid environment '{name}': expected one of {}",
+                names.join(", ")
+            )
+        })
+    }
+
+    /// The active Cartridge environment (production unless switched).
+    pub fn environment(&self) -> &'static crate::chains::Environment {
+        crate::chains::environment(&self.session.env).unwrap_or(&crate::chains::ENVIRONMENTS[0])
+    }
+
+    /// Switch keychain, API and default RPC to an environment as one set. An explicitly
+    /// configured RPC URL is kept.
+    pub fn apply_environment(&mut self, name: &str) -> anyhow::Result<()> {
+        let env = Self::check_environment(name)?;
+        self.session.env = env.name.to_string();
+        self.session.keychain_url = env.keychain_url.to_string();
+        self.session.api_url = env.api_url.to_string();
+        if !self.session.rpc_url_explicitly_set {
+            if let Some(sepolia) = crate::chains::get("SN_SEPOLIA") {
+                self.session.rpc_url = sepolia.rpc_url(env);
+            }
+        }
+        Ok(())
+    }
+
     pub fn merge_from_env(&mut self) {
         if let Ok(path) = std::env::var("CARTRIDGE_STORAGE_PATH") {
             self.session.storage_path = path;
@@ -204,6 +680,28 @@ impl Config {
         if let Ok(json_output) = std::env::var("CARTRIDGE_JSON_OUTPUT") {
             self.cli.json_output = json_output.eq_ignore_ascii_case("true") || json_output == "1";
         }
+        if let Ok(read_only) = std::env::var("CARTRIDGE_READ_ONLY") {
+            self.cli.read_only = read_only.eq_ignore_ascii_case("true") || read_only == "1";
+        }
+        if let Ok(app) = std::env::var("CARTRIDGE_CLIENT_APP") {
+            if Self::validate_client_app(&app).is_ok() {
+                self.cli.client_app = Some(app);
+            }
+        }
+    }
+
+    /// Validate a client app identifier: it is sent as an HTTP header, so it must be non-empty
+    /// printable ASCII (e.g. 'my-agent/1.2').
+    pub fn validate_client_app(app: &str) -> std::result::Result<(), String> {
+        if app.trim().is_empty() || app.len() > 128 {
+            return Err("Client app must be 1-128 characters".to_string());
+        }
+        if !app.chars().all(|c| c.is_ascii_graphic() || c == ' ') {
+            return Err(format!(
+                "Invalid client app '{app}': only printable ASCII is allowed (e.g. 'my-agent/1.2')"
+            ));
+        }
+        Ok(())
     }
 
     /// Validate an account label: must be non-empty, alphanumeric with hyphens/underscores,
@@ -248,6 +746,51 @@ impl Config {
     }
 }
 
+fn merge_patch(target: &mut serde_json::Value, patch: &serde_json::Value) {
+    let serde_json::Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = serde_json::Value::Object(Default::default());
+    }
+    if let serde_json::Value::Object(fields) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                fields.remove(key);
+            } else {
+                merge_patch(
+                    fields.entry(key.clone()).or_insert(serde_json::Value::Null),
+                    value,
+                );
+            }
+        }
+    }
+}
+
+/// Dotted path of the first non-null field in `patch` that is missing from `config`.
+fn unknown_field(
+    config: &serde_json::Value,
+    patch: &serde_json::Value,
+    prefix: &str,
+) -> Option<String> {
+    let serde_json::Value::Object(patch) = patch else {
+        return None;
+    };
+    patch.iter().find_map(|(key, value)| {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match config.get(key) {
+            _ if value.is_null() => None,
+            None => Some(path),
+            Some(field) => unknown_field(field, value, &path),
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -343,4 +886,184 @@ mod tests {
         };
         let _ = config.resolve_storage_path(Some("../etc"));
     }
+
+    #[test]
+    fn contract_override_roundtrip() {
+        let mut config = Config::default();
+        config
+            .set_by_alias("contracts.SN_SEPOLIA.marketplace", "0x123")
+            .unwrap();
+        assert_eq!(
+            config
+                .get_by_alias("contracts.SN_SEPOLIA.marketplace")
+                .unwrap(),
+            "0x123"
+        );
+        assert!(config
+            .get_by_alias("contracts.SN_MAIN.marketplace")
+            .is_err());
+    }
+
+    #[test]
+    fn contract_override_rejects_bad_input() {
+        let mut config = Config::default();
+        assert!(config
+            .set_by_alias("contracts.SN_SEPOLIA.marketplace", "not-an-address")
+            .is_err());
+        assert!(config.set_by_alias("contracts.SN_SEPOLIA", "0x1").is_err());
+    }
+
+    #[test]
+    fn approval_threshold_roundtrip() {
+        let mut config = Config::default();
+        config
+            .set_by_alias("approval-threshold.STRK", "100")
+            .unwrap();
+        assert_eq!(
+            config.get_by_alias("approval-threshold.STRK").unwrap(),
+            "100"
+        );
+        assert!(config
+            .set_by_alias("approval-threshold.ETH", "lots")
+            .is_err());
+    }
+
+    #[test]
+    fn faucet_roundtrip() {
+        let mut config = Config::default();
+        config
+            .set_by_alias("faucet.WP_MY_SLOT", "https://faucet.example/fund")
+            .unwrap();
+        assert_eq!(
+            config.get_by_alias("faucet.WP_MY_SLOT").unwrap(),
+            "https://faucet.example/fund"
+        );
+        assert!(config
+            .set_by_alias("faucet.SN_SEPOLIA", "not a url")
+            .is_err());
+        assert!(config.get_by_alias("faucet.SN_SEPOLIA").is_err());
+    }
+
+    #[test]
+    fn chain_profile_roundtrip() {
+        let mut config = Config::default();
+        assert!(config
+            .set_by_alias("chain.WP_MY_SLOT.torii-url", "https://example.com/torii")
+            .is_err());
+        config
+            .set_by_alias("chain.WP_MY_SLOT.rpc-url", "https://example.com/katana")
+            .unwrap();
+        config
+            .set_by_alias("chain.WP_MY_SLOT.torii-url", "https://example.com/torii")
+            .unwrap();
+        assert_eq!(
+            config.get_by_alias("chain.WP_MY_SLOT.rpc-url").unwrap(),
+            "https://example.com/katana"
+        );
+        assert_eq!(
+            config.get_by_alias("chain.WP_MY_SLOT.torii-url").unwrap(),
+            "https://example.com/torii"
+        );
+        assert!(config
+            .set_by_alias("chain.SN_MAIN.rpc-url", "https://example.com")
+            .is_err());
+        assert!(config
+            .set_by_alias("chain.WP_MY_SLOT.explorer", "https://example.com")
+            .is_err());
+    }
+
+    #[test]
+    fn calldata_prefix_roundtrip() {
+        let mut config = Config::default();
+        config
+            .set_by_alias("calldata-prefix.0x0abc", "0x123,str:ns")
+            .unwrap();
+        assert_eq!(
+            config.get_by_alias("calldata-prefix.0xabc").unwrap(),
+            "0x123,str:ns"
+        );
+        assert_eq!(
+            config.calldata_prefix(starknet::core::types::Felt::from_hex("0xabc").unwrap()),
+            Some("0x123,str:ns")
+        );
+        assert!(config
+            .set_by_alias("calldata-prefix.0xabc", "u256:oops")
+            .is_err());
+        assert!(config.set_by_alias("calldata-prefix.game", "0x1").is_err());
+
+        config.set_by_alias("calldata-prefix.0xabc", "").unwrap();
+        assert!(config.get_by_alias("calldata-prefix.0xabc").is_err());
+    }
+
+    #[test]
+    fn read_only_roundtrip() {
+        let mut config = Config::default();
+        assert_eq!(config.get_by_alias("read-only").unwrap(), "false");
+        config.set_by_alias("read-only", "true").unwrap();
+        assert!(config.cli.read_only);
+        assert!(config.set_by_alias("read-only", "yes").is_err());
+    }
+
+    #[test]
+    fn client_app_roundtrip() {
+        let mut config = Config::default();
+        assert_eq!(config.get_by_alias("client-app").unwrap(), "");
+        config.set_by_alias("client-app", "my-agent/1.2").unwrap();
+        assert_eq!(config.get_by_alias("client-app").unwrap(), "my-agent/1.2");
+        assert!(config.set_by_alias("client-app", "").is_err());
+        assert!(config.set_by_alias("client-app", "agent\n").is_err());
+    }
+
+    #[test]
+    fn check_revocation_roundtrip() {
+        let mut config = Config::default();
+        assert_eq!(config.get_by_alias("check-revocation").unwrap(), "false");
+        config.set_by_alias("check-revocation", "true").unwrap();
+        assert!(config.session.check_revocation);
+        assert!(config.set_by_alias("check-revocation", "1").is_err());
+    }
+
+    #[test]
+    fn merges_json_patches() {
+        let mut config = Config::default();
+        config
+            .merge_json(&serde_json::json!({
+                "chains": {"WP_MY_SLOT": {"rpc_url": "https://example.com/katana"}},
+                "cli": {"read_only": true},
+                "tokens": {"MYTOKEN": "0x123"}
+            }))
+            .unwrap();
+        assert!(config.cli.read_only);
+        assert_eq!(
+            config.get_by_alias("chain.WP_MY_SLOT.rpc-url").unwrap(),
+            "https://example.com/katana"
+        );
+        assert_eq!(
+            config.get_by_path("chains.WP_MY_SLOT.rpc_url").unwrap(),
+            "https://example.com/katana"
+        );
+        assert_eq!(
+            config.get_by_path("cli.read_only").unwrap(),
+            serde_json::json!(true)
+        );
+        assert!(config.get_by_path("cli.nope").is_err());
+
+        config
+            .merge_json(&serde_json::json!({"tokens": {"MYTOKEN": null}}))
+            .unwrap();
+        assert!(config.tokens.is_empty());
+
+        // Unknown fields, wrong types and values set_by_alias rejects leave the config alone
+        assert!(config
+            .merge_json(&serde_json::json!({"profiles": {"dev": {}}}))
+            .is_err());
+        assert!(config
+            .merge_json(&serde_json::json!({"cli": {"read_only": "yes"}}))
+            .is_err());
+        assert!(config
+            .merge_json(&serde_json::json!({"session": {"env": "moon"}}))
+            .is_err());
+        assert!(config.cli.read_only);
+        assert_eq!(config.session.env, crate::chains::PRODUCTION);
+    }
 }
@@ -8,8 +8,10 @@ pub enum CliError {
     #[error("Session expired at {0}. Run 'controller session auth' to create a new session")]
     SessionExpired(String),
 
+    #[error("Session was revoked (e.g. from the Cartridge web app) and removed locally")]
+    SessionRevoked,
+
     #[error("Policy violation: {message}")]
-    #[allow(dead_code)] // Reserved for future policy validation
     PolicyViolation { message: String, details: String },
 
     #[error("Invalid session data: {0}")]
@@ -28,6 +30,14 @@ pub enum CliError {
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
+    #[error(
+        "Chain ID mismatch: RPC endpoint is on {rpc_chain} but session is for {session_chain}"
+    )]
+    ChainMismatch {
+        session_chain: String,
+        rpc_chain: String,
+    },
+
     #[error("Callback timeout: No authorization received within {0} seconds")]
     CallbackTimeout(u64),
 
@@ -38,6 +48,12 @@ pub enum CliError {
     #[error("API error: {0}")]
     ApiError(String),
 
+    #[error(
+        "Rate limited by the Cartridge API{}",
+        .retry_after.map(|s| format!(", retry after {s}s")).unwrap_or_default()
+    )]
+    RateLimited { retry_after: Option<u64> },
+
     #[error("Timeout: {0}")]
     TimeoutError(String),
 
@@ -50,6 +66,43 @@ pub enum CliError {
     #[error("Config error: {0}")]
     Config(String),
 
+    #[error("Read-only mode: {0}")]
+    ReadOnly(String),
+
+    #[error(
+        "Insufficient {token} for fees: {required} required (estimate plus buffer), {available} available"
+    )]
+    InsufficientFunds {
+        token: String,
+        required: String,
+        available: String,
+        shortfall: String,
+    },
+
+    #[error("Call file already submitted at {submitted_at} (transaction {transaction_hash})")]
+    DuplicateSubmission {
+        transaction_hash: String,
+        submitted_at: String,
+    },
+
+    #[error(
+        "Transaction {transaction_hash} succeeded but emitted no event matching: {}",
+        .missing.join(", ")
+    )]
+    MissingEvents {
+        transaction_hash: String,
+        missing: Vec<String>,
+    },
+
+    #[error("Condition not met, nothing submitted: {condition} (call returned {})", .actual.join(", "))]
+    ConditionNotMet {
+        condition: String,
+        actual: Vec<String>,
+    },
+
+    #[error("Input required: {}", .0.question)]
+    InputRequired(InputRequest),
+
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -59,39 +112,324 @@ impl CliError {
         match self {
             CliError::NoSession => "NoSession",
             CliError::SessionExpired(_) => "SessionExpired",
+            CliError::SessionRevoked => "SessionRevoked",
             CliError::PolicyViolation { .. } => "PolicyViolation",
             CliError::InvalidSessionData(_) => "InvalidSessionData",
             CliError::Storage(_) => "StorageError",
             CliError::Network(_) => "NetworkError",
             CliError::TransactionFailed(_) => "TransactionFailed",
             CliError::InvalidInput(_) => "InvalidInput",
+            CliError::ChainMismatch { .. } => "ChainMismatch",
             CliError::CallbackTimeout(_) => "CallbackTimeout",
             CliError::ServerError(_) => "ServerError",
             CliError::ApiError(_) => "ApiError",
+            CliError::RateLimited { .. } => "RateLimited",
             CliError::TimeoutError(_) => "TimeoutError",
             CliError::NotFoundError(_) => "NotFoundError",
             CliError::FileError { .. } => "FileError",
             CliError::Config(_) => "ConfigError",
+            CliError::ReadOnly(_) => "ReadOnlyMode",
+            CliError::InsufficientFunds { .. } => "InsufficientFunds",
+            CliError::DuplicateSubmission { .. } => "DuplicateSubmission",
+            CliError::MissingEvents { .. } => "MissingEvents",
+            CliError::ConditionNotMet { .. } => "ConditionNotMet",
+            CliError::InputRequired(_) => "InputRequired",
             CliError::Other(_) => "UnknownError",
         }
     }
 
-    pub fn recovery_hint(&self) -> Option<&'static str> {
+    /// Process exit code. Failed post-conditions get their own code so scripts can tell a
+    /// transaction that did nothing apart from one that was never submitted.
+    pub fn exit_code(&self) -> i32 {
         match self {
-            CliError::NoSession => Some("Run 'controller session auth' to create a session. Use --account <name> for multi-account setups"),
-            CliError::SessionExpired(_) => {
-                Some("Run 'controller session auth' to create a new session. Use --account <name> to target a specific account")
-            }
-            CliError::InvalidSessionData(_) => {
-                Some("Run 'controller session auth' to create a new session. Use --account <name> to target a specific account")
-            }
-            CliError::PolicyViolation { .. } => {
-                Some("Run 'controller session auth' with updated policies")
-            }
-            CliError::CallbackTimeout(_) => Some("Run 'controller session auth' to try again"),
+            CliError::MissingEvents { .. } => 5,
+            CliError::ConditionNotMet { .. } => 6,
+            _ => 1,
+        }
+    }
+
+    pub fn recovery_hint(&self, ctx: &HintContext) -> Option<String> {
+        match self {
+            CliError::NoSession => Some(match &ctx.account {
+                Some(_) => format!(
+                    "Run '{}' to create a session for this account",
+                    ctx.command("session auth --preset <name>")
+                ),
+                None => format!(
+                    "Run '{}' to create a session. Use --account <name> for multi-account setups",
+                    ctx.command("session auth --preset <name>")
+                ),
+            }),
+            CliError::SessionExpired(_)
+            | CliError::SessionRevoked
+            | CliError::InvalidSessionData(_) => Some(match &ctx.account {
+                Some(_) => format!(
+                    "Run '{}' to create a new session",
+                    ctx.command("session auth --overwrite")
+                ),
+                None => format!(
+                    "Run '{}' to create a new session. Use --account <name> to target a specific account",
+                    ctx.command("session auth --overwrite")
+                ),
+            }),
+            CliError::PolicyViolation { .. } => Some(format!(
+                "Run '{}' with updated policies",
+                ctx.command("session auth --overwrite")
+            )),
+            CliError::ChainMismatch { session_chain, .. } => Some(format!(
+                "Pass --chain-id {session_chain}, or --switch-chain to use the session's chain automatically"
+            )),
+            CliError::RateLimited { retry_after } => Some(match retry_after {
+                Some(secs) => format!("Wait {secs}s before retrying"),
+                None => "Back off for a minute before retrying".to_string(),
+            }),
+            CliError::ReadOnly(_) => Some(
+                "Read-only mode is enabled by --read-only, CARTRIDGE_READ_ONLY or the read-only \
+                 config key. Run 'controller config set read-only false' on a host allowed to transact"
+                    .to_string(),
+            ),
+            CliError::InsufficientFunds { token, shortfall, .. } => Some(format!(
+                "Send at least {shortfall} {token} to the account, run '{}' on a test chain, \
+                 or drop --no-paymaster to let the paymaster cover fees",
+                ctx.command(&format!("faucet --token {token}"))
+            )),
+            CliError::DuplicateSubmission { .. } => Some(
+                "Check the previous transaction before retrying, or pass --force to submit the \
+                 same file again"
+                    .to_string(),
+            ),
+            CliError::MissingEvents { .. } => Some(format!(
+                "The transaction was included but did not have the expected effect. Inspect its \
+                 events with '{}' before retrying",
+                ctx.command("receipt <transaction_hash>")
+            )),
+            CliError::ConditionNotMet { .. } => Some(format!(
+                "The on-chain state changed or isn't ready yet. Re-read it with '{}' and decide \
+                 whether to act before retrying",
+                ctx.command("call <contract> <entrypoint>")
+            )),
+            CliError::CallbackTimeout(_) => Some(format!(
+                "Run '{}' to try again",
+                ctx.command("session auth")
+            )),
+            CliError::InputRequired(request) => Some(match &request.answer {
+                Answer::Confirm => format!("Pass {} to go ahead without asking", request.flag),
+                Answer::Choice { options, .. } if options.len() <= MAX_LISTED_OPTIONS => {
+                    format!("Pass {} <value>, one of: {}", request.flag, options.join(", "))
+                }
+                Answer::Choice { .. } | Answer::Text { .. } => {
+                    format!("Pass {} <value>", request.flag)
+                }
+            }),
             _ => None,
         }
     }
 }
 
+/// Choices listed in the `InputRequired` hint; longer lists (e.g. presets) are left out
+const MAX_LISTED_OPTIONS: usize = 10;
+
+/// A question a command can't answer itself. The library never reads stdin: it returns this as
+/// `CliError::InputRequired`, and the `controller` front-end asks the user and runs the command
+/// again with `flag` set to the answer. Other callers pass the flag up front instead.
+#[derive(Debug, Clone)]
+pub struct InputRequest {
+    pub question: String,
+    /// Flag carrying the answer, e.g. '--yes' or '--chain'
+    pub flag: String,
+    pub answer: Answer,
+}
+
+#[derive(Debug, Clone)]
+pub enum Answer {
+    /// Yes or no; yes sets `flag`, no cancels the command
+    Confirm,
+    /// One of `options`, or `default` when left blank
+    Choice {
+        options: Vec<String>,
+        default: Option<String>,
+    },
+    /// Free text, or `default` when left blank
+    Text { default: Option<String> },
+}
+
+impl InputRequest {
+    pub fn confirm(question: impl Into<String>, flag: &str) -> CliError {
+        CliError::InputRequired(Self {
+            question: question.into(),
+            flag: flag.to_string(),
+            answer: Answer::Confirm,
+        })
+    }
+
+    pub fn choice(
+        question: impl Into<String>,
+        flag: &str,
+        options: Vec<String>,
+        default: Option<String>,
+    ) -> CliError {
+        CliError::InputRequired(Self {
+            question: question.into(),
+            flag: flag.to_string(),
+            answer: Answer::Choice { options, default },
+        })
+    }
+
+    pub fn text(question: impl Into<String>, flag: &str, default: Option<String>) -> CliError {
+        CliError::InputRequired(Self {
+            question: question.into(),
+            flag: flag.to_string(),
+            answer: Answer::Text { default },
+        })
+    }
+}
+
+/// Invocation details used to tailor recovery hints to how the CLI was run.
+#[derive(Debug, Clone, Default)]
+pub struct HintContext {
+    pub account: Option<String>,
+}
+
+impl HintContext {
+    pub fn new(account: Option<&str>) -> Self {
+        Self {
+            account: account.map(str::to_string),
+        }
+    }
+
+    /// Render a full `controller ...` invocation, carrying over `--account` when set.
+    fn command(&self, args: &str) -> String {
+        match &self.account {
+            Some(account) => format!("controller {args} --account {account}"),
+            None => format!("controller {args}"),
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, CliError>;
+
+/// One of each error, so hint text can be checked against the CLI's commands. Each sample
+/// names the next one in an exhaustive match, so a new variant doesn't compile until it is
+/// chained in.
+#[cfg(test)]
+pub(crate) fn all_errors() -> Vec<CliError> {
+    let mut errors = Vec::new();
+    let mut next = Some(CliError::NoSession);
+    while let Some(error) = next {
+        next = match &error {
+            CliError::NoSession => Some(CliError::SessionExpired("2024-01-01".to_string())),
+            CliError::SessionExpired(_) => Some(CliError::SessionRevoked),
+            CliError::SessionRevoked => Some(CliError::PolicyViolation {
+                message: "m".to_string(),
+                details: "d".to_string(),
+            }),
+            CliError::PolicyViolation { .. } => {
+                Some(CliError::InvalidSessionData("bad".to_string()))
+            }
+            CliError::InvalidSessionData(_) => Some(CliError::Storage("disk full".to_string())),
+            CliError::Storage(_) => Some(CliError::Network("unreachable".to_string())),
+            CliError::Network(_) => Some(CliError::TransactionFailed("reverted".to_string())),
+            CliError::TransactionFailed(_) => Some(CliError::InvalidInput("x".to_string())),
+            CliError::InvalidInput(_) => Some(CliError::ChainMismatch {
+                session_chain: "SN_MAIN".to_string(),
+                rpc_chain: "SN_SEPOLIA".to_string(),
+            }),
+            CliError::ChainMismatch { .. } => Some(CliError::CallbackTimeout(360)),
+            CliError::CallbackTimeout(_) => Some(CliError::ServerError("502".to_string())),
+            CliError::ServerError(_) => Some(CliError::ApiError("bad query".to_string())),
+            CliError::ApiError(_) => Some(CliError::RateLimited {
+                retry_after: Some(30),
+            }),
+            CliError::RateLimited { .. } => Some(CliError::TimeoutError("rpc".to_string())),
+            CliError::TimeoutError(_) => Some(CliError::NotFoundError("preset".to_string())),
+            CliError::NotFoundError(_) => Some(CliError::FileError {
+                path: "calls.json".to_string(),
+                message: "missing".to_string(),
+            }),
+            CliError::FileError { .. } => Some(CliError::Config("bad key".to_string())),
+            CliError::Config(_) => Some(CliError::ReadOnly("submits".to_string())),
+            CliError::ReadOnly(_) => Some(CliError::InsufficientFunds {
+                token: "STRK".to_string(),
+                required: "1.2".to_string(),
+                available: "0.5".to_string(),
+                shortfall: "0.7".to_string(),
+            }),
+            CliError::InsufficientFunds { .. } => Some(CliError::DuplicateSubmission {
+                transaction_hash: "0x123".to_string(),
+                submitted_at: "2024-01-01T00:00:00Z".to_string(),
+            }),
+            CliError::DuplicateSubmission { .. } => Some(CliError::MissingEvents {
+                transaction_hash: "0x123".to_string(),
+                missing: vec!["MoveExecuted".to_string()],
+            }),
+            CliError::MissingEvents { .. } => Some(CliError::ConditionNotMet {
+                condition: "0x123:get_state:()==0x2".to_string(),
+                actual: vec!["0x1".to_string()],
+            }),
+            CliError::ConditionNotMet { .. } => Some(InputRequest::confirm("Continue?", "--yes")),
+            CliError::InputRequired(_) => Some(CliError::Other(anyhow::anyhow!("boom"))),
+            CliError::Other(_) => None,
+        };
+        errors.push(error);
+    }
+    errors
+}
+
+/// Every quoted `controller ...` invocation in a message
+#[cfg(test)]
+pub(crate) fn quoted_commands(text: &str) -> Vec<String> {
+    text.split('\'')
+        .skip(1)
+        .step_by(2)
+        .filter(|s| s.starts_with("controller "))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hints_carry_account() {
+        let ctx = HintContext::new(Some("player1"));
+        for error in all_errors() {
+            let Some(hint) = error.recovery_hint(&ctx) else {
+                continue;
+            };
+            // Config is shared by every account
+            for invocation in quoted_commands(&hint)
+                .into_iter()
+                .filter(|invocation| !invocation.starts_with("controller config "))
+            {
+                assert!(
+                    invocation.ends_with("--account player1"),
+                    "hint for {} should target the account: {hint}",
+                    error.error_code()
+                );
+            }
+        }
+
+        let hint = CliError::NoSession
+            .recovery_hint(&HintContext::default())
+            .unwrap();
+        assert!(!hint.contains("player1"));
+        assert!(hint.contains("--account <name>"));
+    }
+
+    #[test]
+    fn test_all_errors_has_every_variant() {
+        let codes: std::collections::HashSet<_> =
+            all_errors().iter().map(CliError::error_code).collect();
+        assert_eq!(codes.len(), all_errors().len());
+        assert!(codes.contains("ReadOnlyMode"));
+    }
+
+    #[test]
+    fn test_no_hint_for_generic_errors() {
+        let ctx = HintContext::default();
+        assert!(CliError::InvalidInput("x".to_string())
+            .recovery_hint(&ctx)
+            .is_none());
+    }
+}
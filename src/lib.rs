@@ -0,0 +1,28 @@
+//! Session management and command logic behind the `controller` CLI.
+//!
+//! The binary only parses arguments for `cli::run` and asks the questions it returns; the crate
+//! itself never reads answers from stdin. Other Rust tools (game servers, bots) can depend on it
+//! to authorize sessions, execute calls and read config without shelling out:
+//!
+//! ```no_run
+//! use controller_core::{commands, config::Config, error::HintContext, output};
+//!
+//! # async fn run() -> controller_core::error::Result<()> {
+//! let mut config = Config::load()?;
+//! config.merge_from_env();
+//! let formatter =
+//!     output::create_formatter(true, false, HintContext::default(), output::OutputSink::Stdout);
+//! commands::status::execute(&config, &*formatter, None).await
+//! # }
+//! ```
+
+pub mod api;
+pub mod chains;
+pub mod cli;
+pub mod commands;
+pub mod config;
+pub mod error;
+pub mod meta;
+pub mod output;
+pub mod presets;
+pub mod version;
@@ -0,0 +1,129 @@
+//! Counters behind the optional `meta` block of JSON outputs (`--meta`): wall-clock duration,
+//! JSON-RPC calls, Cartridge API requests, retries, cache hits and fees paid by the account.
+//! Counting is always on and costs an atomic add; the block is only emitted once enabled.
+
+use serde::Serialize;
+use starknet::core::types::Felt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+static STARTED: OnceLock<Instant> = OnceLock::new();
+static RPC_CALLS: AtomicU64 = AtomicU64::new(0);
+static API_REQUESTS: AtomicU64 = AtomicU64::new(0);
+static RETRIES: AtomicU64 = AtomicU64::new(0);
+static CACHE_HITS: AtomicU64 = AtomicU64::new(0);
+static FEE_PAID: Mutex<Option<FeePaid>> = Mutex::new(None);
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Meta {
+    pub duration_ms: u64,
+    /// JSON-RPC requests sent through the CLI's providers (a batch counts each request)
+    pub rpc_calls: u64,
+    /// Requests to the Cartridge API (GraphQL, lookup, token lists, ...)
+    pub api_requests: u64,
+    /// Requests retried after a rate limit or a transient failure
+    pub retries: u64,
+    /// Results served from a local cache (balances, token lists, lookups)
+    pub cache_hits: u64,
+    /// Fees the account paid for transactions confirmed during this run; none when sponsored
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_paid: Option<FeePaid>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeePaid {
+    /// Raw amount, in the unit's smallest denomination
+    pub amount: String,
+    /// 'FRI' (STRK) or 'WEI' (ETH)
+    pub unit: String,
+}
+
+/// Start the clock and emit the `meta` block from now on.
+pub fn enable() {
+    let _ = STARTED.set(Instant::now());
+}
+
+pub fn rpc_calls(count: u64) {
+    RPC_CALLS.fetch_add(count, Ordering::Relaxed);
+}
+
+pub fn api_request() {
+    API_REQUESTS.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn retries(count: u64) {
+    RETRIES.fetch_add(count, Ordering::Relaxed);
+}
+
+pub fn cache_hit() {
+    CACHE_HITS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Record the actual fee of a confirmed transaction the account paid for.
+pub fn fee_paid(amount: Felt, unit: &str) {
+    if let Ok(mut fee) = FEE_PAID.lock() {
+        *fee = add_fee(fee.take(), amount, unit);
+    }
+}
+
+/// Fees of several transactions are summed while they share a unit; mixed units keep the first
+fn add_fee(current: Option<FeePaid>, amount: Felt, unit: &str) -> Option<FeePaid> {
+    let Ok(amount) = u128::try_from(amount) else {
+        return current;
+    };
+    match current {
+        None => Some(FeePaid {
+            amount: amount.to_string(),
+            unit: unit.to_string(),
+        }),
+        Some(fee) if fee.unit == unit => {
+            let total = fee.amount.parse::<u128>().ok()?.saturating_add(amount);
+            Some(FeePaid {
+                amount: total.to_string(),
+                unit: fee.unit,
+            })
+        }
+        Some(fee) => Some(fee),
+    }
+}
+
+/// The counters so far, or `None` unless `--meta` was given
+pub fn snapshot() -> Option<Meta> {
+    let started = STARTED.get()?;
+    Some(Meta {
+        duration_ms: started.elapsed().as_millis() as u64,
+        rpc_calls: RPC_CALLS.load(Ordering::Relaxed),
+        api_requests: API_REQUESTS.load(Ordering::Relaxed),
+        retries: RETRIES.load(Ordering::Relaxed),
+        cache_hits: CACHE_HITS.load(Ordering::Relaxed),
+        fee_paid: FEE_PAID.lock().ok().and_then(|fee| fee.clone()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums_fees_of_the_same_unit() {
+        let fee = add_fee(None, Felt::from(100u64), "FRI");
+        let fee = add_fee(fee, Felt::from(50u64), "FRI");
+        assert_eq!(
+            fee,
+            Some(FeePaid {
+                amount: "150".to_string(),
+                unit: "FRI".to_string()
+            })
+        );
+        let fee = add_fee(fee, Felt::from(7u64), "WEI");
+        assert_eq!(fee.as_ref().map(|f| f.amount.as_str()), Some("150"));
+
+        // The block stays off until enabled; counters run regardless
+        cache_hit();
+        assert!(snapshot().is_none());
+        enable();
+        let meta = snapshot().unwrap();
+        assert!(meta.cache_hits >= 1);
+    }
+}
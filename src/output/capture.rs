@@ -0,0 +1,45 @@
+use crate::error::CliError;
+use crate::output::OutputFormatter;
+use std::sync::Mutex;
+
+/// Keeps the result of a nested command instead of printing it, forwarding progress, warnings
+/// and errors to the wrapped formatter. Lets composite commands reuse `execute` and report one
+/// combined result.
+pub struct Capture<'a> {
+    inner: &'a dyn OutputFormatter,
+    result: Mutex<Option<serde_json::Value>>,
+}
+
+impl<'a> Capture<'a> {
+    pub fn new(inner: &'a dyn OutputFormatter) -> Self {
+        Self {
+            inner,
+            result: Mutex::new(None),
+        }
+    }
+
+    /// The last captured result, if the nested command produced one
+    pub fn take(&self) -> Option<serde_json::Value> {
+        self.result.lock().ok().and_then(|mut result| result.take())
+    }
+}
+
+impl OutputFormatter for Capture<'_> {
+    fn success(&self, data: &dyn erased_serde::Serialize) {
+        if let Ok(mut result) = self.result.lock() {
+            *result = serde_json::to_value(data).ok();
+        }
+    }
+
+    fn error(&self, error: &CliError) {
+        self.inner.error(error);
+    }
+
+    fn info(&self, message: &str) {
+        self.inner.info(message);
+    }
+
+    fn warning(&self, message: &str) {
+        self.inner.warning(message);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::error::{CliError, HintContext};
+use crate::output::json::{collect, collected, error_document, success_document};
+use crate::output::OutputFormatter;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Writes the structured result (success or error document) to a file, forwarding everything
+/// else to the terminal formatter. With `tee`, results are also shown on the terminal.
+pub struct FileSink {
+    inner: Box<dyn OutputFormatter>,
+    path: PathBuf,
+    tee: bool,
+    hints: HintContext,
+    warnings: Mutex<Vec<String>>,
+}
+
+impl FileSink {
+    pub fn new(
+        inner: Box<dyn OutputFormatter>,
+        path: PathBuf,
+        tee: bool,
+        hints: HintContext,
+    ) -> Self {
+        Self {
+            inner,
+            path,
+            tee,
+            hints,
+            warnings: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn write(&self, document: &serde_json::Value) -> bool {
+        let contents = serde_json::to_string_pretty(document).unwrap();
+        match std::fs::write(&self.path, format!("{contents}\n")) {
+            Ok(()) => true,
+            Err(e) => {
+                self.inner.warning(&format!(
+                    "Failed to write output file {}: {e}",
+                    self.path.display()
+                ));
+                false
+            }
+        }
+    }
+}
+
+impl OutputFormatter for FileSink {
+    fn success(&self, data: &dyn erased_serde::Serialize) {
+        let written = self.write(&success_document(data, &collected(&self.warnings)));
+        if self.tee || !written {
+            self.inner.success(data);
+        } else {
+            self.inner
+                .info(&format!("Result written to {}", self.path.display()));
+        }
+    }
+
+    fn error(&self, error: &CliError) {
+        // Errors always reach the terminal so failures are never silent
+        self.write(&error_document(
+            error,
+            &self.hints,
+            &collected(&self.warnings),
+        ));
+        self.inner.error(error);
+    }
+
+    fn info(&self, message: &str) {
+        self.inner.info(message);
+    }
+
+    fn warning(&self, message: &str) {
+        collect(&self.warnings, message);
+        self.inner.warning(message);
+    }
+}
@@ -1,15 +1,17 @@
-use crate::error::CliError;
+use crate::error::{CliError, HintContext};
 use crate::output::OutputFormatter;
 use colored::*;
 
-pub struct HumanFormatter;
+pub struct HumanFormatter {
+    hints: HintContext,
+}
 
 impl HumanFormatter {
-    pub fn new(use_colors: bool) -> Self {
+    pub fn new(use_colors: bool, hints: HintContext) -> Self {
         if !use_colors {
             colored::control::set_override(false);
         }
-        Self
+        Self { hints }
     }
 
     fn format_success_symbol(&self) -> String {
@@ -49,7 +51,7 @@ impl OutputFormatter for HumanFormatter {
         eprintln!("{} {}", self.format_error_symbol(), "Error".red().bold());
         eprintln!("{}", error.to_string().red());
 
-        if let Some(hint) = error.recovery_hint() {
+        if let Some(hint) = error.recovery_hint(&self.hints) {
             eprintln!("\n{} {}", self.format_info_symbol(), hint.cyan());
         }
     }
@@ -1,37 +1,156 @@
-use crate::error::CliError;
+use crate::error::{Answer, CliError, HintContext};
 use crate::output::OutputFormatter;
 use serde_json::json;
+use std::sync::Mutex;
 
-pub struct JsonFormatter;
+pub struct JsonFormatter {
+    hints: HintContext,
+    warnings: Mutex<Vec<String>>,
+}
 
-impl OutputFormatter for JsonFormatter {
-    fn success(&self, data: &dyn erased_serde::Serialize) {
-        let output = json!({
-            "status": "success",
-            "data": data
+impl JsonFormatter {
+    pub fn new(hints: HintContext) -> Self {
+        Self {
+            hints,
+            warnings: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+/// Snapshot of the warnings collected so far, for inclusion in the final document
+pub fn collected(warnings: &Mutex<Vec<String>>) -> Vec<String> {
+    warnings.lock().map(|w| w.clone()).unwrap_or_default()
+}
+
+/// Record a warning for the final document
+pub fn collect(warnings: &Mutex<Vec<String>>, message: &str) {
+    if let Ok(mut w) = warnings.lock() {
+        w.push(message.to_string());
+    }
+}
+
+/// The `{"status": "success", "data": ..., "warnings": [...]}` envelope shared by all
+/// structured outputs
+pub fn success_document(
+    data: &dyn erased_serde::Serialize,
+    warnings: &[String],
+) -> serde_json::Value {
+    let mut output = json!({
+        "status": "success",
+        "data": data,
+        "warnings": warnings
+    });
+    if let CliError::InputRequired(request) = error {
+        let (options, default) = match &request.answer {
+            Answer::Confirm => (None, None),
+            Answer::Choice { options, default } => (Some(options), default.as_ref()),
+            Answer::Text { default } => (None, default.as_ref()),
+        };
+        output["details"] = json!({
+            "question": request.question,
+            "flag": request.flag,
+            "options": options,
+            "default": default
         });
-        println!("{}", serde_json::to_string_pretty(&output).unwrap());
     }
+    if let Some(meta) = crate::meta::snapshot() {
+        output["meta"] = json!(meta);
+    }
+    output
+}
 
-    fn error(&self, error: &CliError) {
-        let mut output = json!({
-            "status": "error",
-            "error_code": error.error_code(),
-            "message": error.to_string(),
+/// The `{"status": "error", ...}` envelope shared by all structured outputs
+pub fn error_document(
+    error: &CliError,
+    hints: &HintContext,
+    warnings: &[String],
+) -> serde_json::Value {
+    let mut output = json!({
+        "status": "error",
+        "error_code": error.error_code(),
+        "message": error.to_string(),
+        "warnings": warnings,
+    });
+
+    if let Some(hint) = error.recovery_hint(hints) {
+        output["recovery_hint"] = json!(hint);
+    }
+
+    // Add detailed error info for specific error types
+    if let CliError::PolicyViolation { message, details } = error {
+        output["details"] = json!({
+            "message": message,
+            "details": details
+        });
+    }
+    if let CliError::ChainMismatch {
+        session_chain,
+        rpc_chain,
+    } = error
+    {
+        output["details"] = json!({
+            "session_chain": session_chain,
+            "rpc_chain": rpc_chain
+        });
+    }
+    if let CliError::RateLimited { retry_after } = error {
+        output["details"] = json!({ "retry_after": retry_after });
+    }
+    if let CliError::InsufficientFunds {
+        token,
+        required,
+        available,
+        shortfall,
+    } = error
+    {
+        output["details"] = json!({
+            "token": token,
+            "required": required,
+            "available": available,
+            "shortfall": shortfall
+        });
+    }
+    if let CliError::DuplicateSubmission {
+        transaction_hash,
+        submitted_at,
+    } = error
+    {
+        output["details"] = json!({
+            "transaction_hash": transaction_hash,
+            "submitted_at": submitted_at
         });
+    }
+    if let CliError::MissingEvents {
+        transaction_hash,
+        missing,
+    } = error
+    {
+        output["details"] = json!({
+            "transaction_hash": transaction_hash,
+            "missing": missing
+        });
+    }
+    if let CliError::ConditionNotMet { condition, actual } = error {
+        output["details"] = json!({
+            "condition": condition,
+            "actual": actual
+        });
+    }
+    if let Some(meta) = crate::meta::snapshot() {
+        output["meta"] = json!(meta);
+    }
 
-        if let Some(hint) = error.recovery_hint() {
-            output["recovery_hint"] = json!(hint);
-        }
+    output
+}
 
-        // Add detailed error info for specific error types
-        if let CliError::PolicyViolation { message, details } = error {
-            output["details"] = json!({
-                "message": message,
-                "details": details
-            });
-        }
+impl OutputFormatter for JsonFormatter {
+    fn success(&self, data: &dyn erased_serde::Serialize) {
+        let output = success_document(data, &collected(&self.warnings));
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    }
 
+    fn error(&self, error: &CliError) {
+        let output = error_document(error, &self.hints, &collected(&self.warnings));
         eprintln!("{}", serde_json::to_string_pretty(&output).unwrap());
     }
 
@@ -44,10 +163,60 @@ impl OutputFormatter for JsonFormatter {
     }
 
     fn warning(&self, message: &str) {
+        // Stream to stderr so stdout stays a single result document, and keep a copy for it
+        collect(&self.warnings, message);
         let output = json!({
             "status": "warning",
             "message": message
         });
-        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+        eprintln!("{}", serde_json::to_string_pretty(&output).unwrap());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn documents_carry_collected_warnings() {
+        let formatter = JsonFormatter::new(HintContext::default());
+        formatter.warning("Skipping FOO: balance_of failed");
+
+        let warnings = collected(&formatter.warnings);
+        let success = success_document(&"ok", &warnings);
+        assert_eq!(success["warnings"][0], "Skipping FOO: balance_of failed");
+
+        let error = error_document(&CliError::NoSession, &formatter.hints, &[]);
+        assert_eq!(error["warnings"], json!([]));
+    }
+
+    #[test]
+    fn chain_mismatch_has_structured_details() {
+        let error = CliError::ChainMismatch {
+            session_chain: "SN_SEPOLIA".to_string(),
+            rpc_chain: "SN_MAIN".to_string(),
+        };
+        let document = error_document(&error, &HintContext::default(), &[]);
+        assert_eq!(document["error_code"], "ChainMismatch");
+        assert_eq!(document["details"]["session_chain"], "SN_SEPOLIA");
+        assert_eq!(document["details"]["rpc_chain"], "SN_MAIN");
+        assert!(document["recovery_hint"]
+            .as_str()
+            .unwrap()
+            .contains("--switch-chain"));
+    }
+
+    #[test]
+    fn rate_limited_carries_retry_after() {
+        let error = CliError::RateLimited {
+            retry_after: Some(12),
+        };
+        assert_eq!(
+            error.to_string(),
+            "Rate limited by the Cartridge API, retry after 12s"
+        );
+        let document = error_document(&error, &HintContext::default(), &[]);
+        assert_eq!(document["error_code"], "RateLimited");
+        assert_eq!(document["details"]["retry_after"], 12);
     }
 }
@@ -1,22 +1,63 @@
+mod capture;
+mod file;
 mod human;
 mod json;
 
+pub use capture::Capture;
+pub use file::FileSink;
 pub use human::HumanFormatter;
-pub use json::JsonFormatter;
+pub use json::{error_document, success_document, JsonFormatter};
 
-use crate::error::CliError;
+use crate::error::{CliError, HintContext};
+use std::path::PathBuf;
 
 pub trait OutputFormatter {
     fn success(&self, data: &dyn erased_serde::Serialize);
     fn error(&self, error: &CliError);
     fn info(&self, message: &str);
     fn warning(&self, message: &str);
+    /// Structured progress ahead of the result, such as a transaction that was sent and is now
+    /// being waited on. Only streaming output prints it.
+    fn progress(&self, _data: &dyn erased_serde::Serialize) {}
 }
 
-pub fn create_formatter(use_json: bool, use_colors: bool) -> Box<dyn OutputFormatter> {
-    if use_json {
-        Box::new(JsonFormatter)
+/// Drops everything, for tests of code that reports progress
+#[cfg(test)]
+pub(crate) struct NullFormatter;
+
+#[cfg(test)]
+impl OutputFormatter for NullFormatter {
+    fn success(&self, _data: &dyn erased_serde::Serialize) {}
+    fn error(&self, _error: &CliError) {}
+    fn info(&self, _message: &str) {}
+    fn warning(&self, _message: &str) {}
+}
+
+/// Where structured results go in addition to (or instead of) the terminal
+pub enum OutputSink {
+    /// Results are printed to the terminal only
+    Stdout,
+    /// Results are written to a file; the terminal only gets progress and a pointer to it
+    File(PathBuf),
+    /// Results are written to a file and printed to the terminal
+    Tee(PathBuf),
+}
+
+pub fn create_formatter(
+    use_json: bool,
+    use_colors: bool,
+    hints: HintContext,
+    sink: OutputSink,
+) -> Box<dyn OutputFormatter> {
+    let terminal: Box<dyn OutputFormatter> = if use_json {
+        Box::new(JsonFormatter::new(hints.clone()))
     } else {
-        Box::new(HumanFormatter::new(use_colors))
+        Box::new(HumanFormatter::new(use_colors, hints.clone()))
+    };
+
+    match sink {
+        OutputSink::Stdout => terminal,
+        OutputSink::File(path) => Box::new(FileSink::new(terminal, path, false, hints)),
+        OutputSink::Tee(path) => Box::new(FileSink::new(terminal, path, true, hints)),
     }
 }
@@ -1,9 +1,13 @@
+use crate::config::Config;
 use crate::error::{CliError, Result};
+use crate::output::OutputFormatter;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 
-const PRESETS_BASE_URL: &str =
-    "https://raw.githubusercontent.com/cartridge-gg/presets/refs/heads/main/configs";
+const PRESETS_BASE_URL: &str = "https://raw.githubusercontent.com/cartridge-gg/presets/refs/heads";
+const PRESETS_INDEX_URL: &str =
+    "https://api.github.com/repos/cartridge-gg/presets/contents/configs";
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct PresetConfig {
@@ -41,27 +45,283 @@ pub struct MethodConfig {
     pub description: Option<String>,
 }
 
-/// Fetch preset configuration from GitHub
-pub async fn fetch_preset(preset_name: &str) -> Result<PresetConfig> {
-    let url = format!("{PRESETS_BASE_URL}/{preset_name}/config.json");
+/// Fetched presets and preset indexes keyed by URL, relative to the base storage path
+pub const CACHE_FILE: &str = "preset_cache.json";
+/// How long a cached preset is used before GitHub is asked whether it changed
+const CACHE_TTL_SECS: u64 = 6 * 60 * 60;
 
-    let response = reqwest::get(&url).await.map_err(|e| {
-        CliError::InvalidInput(format!("Failed to fetch preset '{preset_name}': {e}"))
-    })?;
+#[derive(Default, Serialize, Deserialize)]
+struct PresetCache {
+    #[serde(default)]
+    entries: BTreeMap<String, CachedEntry>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct CachedEntry {
+    fetched_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    etag: Option<String>,
+    body: serde_json::Value,
+}
+
+impl PresetCache {
+    fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, path: &Path) {
+        if let Ok(json) = serde_json::to_string(self) {
+            let _ = crate::commands::storage::write_atomic(path, json);
+        }
+    }
+}
+
+/// Presets are shared across accounts, so they live in the base storage path
+fn cache_path(config: &Config) -> PathBuf {
+    config.resolve_storage_path(None).join(CACHE_FILE)
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+enum Fetched {
+    Body(serde_json::Value, Option<String>),
+    NotModified,
+    NotFound,
+}
 
-    if !response.status().is_success() {
+/// GET a JSON document, conditional on `etag`. Errors are unreachable hosts and statuses other
+/// than 404, including GitHub's 403/429 rate limits.
+async fn fetch(url: &str, etag: Option<&str>) -> std::result::Result<Fetched, String> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut request = client.get(url).header("User-Agent", "controller-cli");
+    if let Some(etag) = etag {
+        request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+    }
+    let response = request.send().await.map_err(|e| e.to_string())?;
+
+    match response.status() {
+        reqwest::StatusCode::NOT_MODIFIED => Ok(Fetched::NotModified),
+        reqwest::StatusCode::NOT_FOUND => Ok(Fetched::NotFound),
+        status if status.is_success() => {
+            let etag = response
+                .headers()
+                .get(reqwest::header::ETAG)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string);
+            let body = response
+                .json()
+                .await
+                .map_err(|e| format!("invalid response: {e}"))?;
+            Ok(Fetched::Body(body, etag))
+        }
+        status => Err(format!("GitHub returned {status}")),
+    }
+}
+
+/// A document from the cache or GitHub. A cached copy is used as is while younger than the TTL
+/// or with `--offline`; an older one is revalidated with its ETag, and used with a warning when
+/// GitHub is unreachable or rate-limited. `refresh` downloads it again regardless.
+/// `None` when GitHub has no such document.
+async fn cached(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    url: &str,
+    what: &str,
+    refresh: bool,
+) -> Result<Option<serde_json::Value>> {
+    let path = cache_path(config);
+    let mut cache = PresetCache::load(&path);
+    let entry = cache.entries.get(url).cloned();
+    let now = now_secs();
+
+    if config.cli.offline {
+        let Some(entry) = entry else {
+            return Err(CliError::InvalidInput(format!(
+                "{what} is not cached. Run once without --offline, or 'controller presets refresh'"
+            )));
+        };
+        crate::meta::cache_hit();
+        return Ok(Some(entry.body));
+    }
+    if let Some(entry) = entry.as_ref().filter(|e| !refresh && is_fresh(e, now)) {
+        crate::meta::cache_hit();
+        return Ok(Some(entry.body.clone()));
+    }
+
+    let etag = entry
+        .as_ref()
+        .filter(|_| !refresh)
+        .and_then(|e| e.etag.clone());
+    match (fetch(url, etag.as_deref()).await, entry) {
+        (Ok(Fetched::Body(body, etag)), _) => {
+            cache.entries.insert(
+                url.to_string(),
+                CachedEntry {
+                    fetched_at: now,
+                    etag,
+                    body: body.clone(),
+                },
+            );
+            cache.save(&path);
+            Ok(Some(body))
+        }
+        (Ok(Fetched::NotModified), Some(mut entry)) => {
+            entry.fetched_at = now;
+            let body = entry.body.clone();
+            cache.entries.insert(url.to_string(), entry);
+            cache.save(&path);
+            Ok(Some(body))
+        }
+        (Ok(Fetched::NotModified), None) => Err(CliError::ApiError(format!(
+            "Unexpected 'not modified' response for {what}"
+        ))),
+        (Ok(Fetched::NotFound), _) => Ok(None),
+        (Err(e), Some(entry)) if !refresh => {
+            formatter.warning(&format!(
+                "Could not refresh {what} ({e}); using the copy cached {} ago",
+                format_age(now.saturating_sub(entry.fetched_at))
+            ));
+            Ok(Some(entry.body))
+        }
+        (Err(e), _) => Err(CliError::Network(format!("Failed to fetch {what}: {e}"))),
+    }
+}
+
+fn is_fresh(entry: &CachedEntry, now: u64) -> bool {
+    now.saturating_sub(entry.fetched_at) <= CACHE_TTL_SECS
+}
+
+fn format_age(secs: u64) -> String {
+    match secs {
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86400 => format!("{}h", s / 3600),
+        s => format!("{}d", s / 86400),
+    }
+}
+
+fn preset_url(presets_ref: &str, preset_name: &str) -> String {
+    format!("{PRESETS_BASE_URL}/{presets_ref}/configs/{preset_name}/config.json")
+}
+
+/// Fetch a preset's configuration from the configured branch of the presets repository, through
+/// the local cache
+pub async fn fetch_preset(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    preset_name: &str,
+) -> Result<PresetConfig> {
+    load_preset(config, formatter, preset_name, false).await
+}
+
+async fn load_preset(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    preset_name: &str,
+    refresh: bool,
+) -> Result<PresetConfig> {
+    let url = preset_url(config.environment().presets_ref, preset_name);
+    let Some(body) = cached(
+        config,
+        formatter,
+        &url,
+        &format!("preset '{preset_name}'"),
+        refresh,
+    )
+    .await?
+    else {
         return Err(CliError::InvalidInput(format!(
-            "Preset '{preset_name}' not found. Check available presets at: https://github.com/cartridge-gg/presets/tree/main/configs"
+            "Preset '{preset_name}' not found. Check available presets with 'controller presets list'"
         )));
-    }
+    };
 
-    let preset: PresetConfig = response.json().await.map_err(|e| {
+    serde_json::from_value(body).map_err(|e| {
         CliError::InvalidInput(format!(
             "Failed to parse preset '{preset_name}' configuration: {e}"
         ))
-    })?;
+    })
+}
 
-    Ok(preset)
+#[derive(Deserialize)]
+struct PresetIndexEntry {
+    name: String,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+/// The names of all available presets, from the GitHub directory index through the local cache
+pub async fn list_presets(config: &Config, formatter: &dyn OutputFormatter) -> Result<Vec<String>> {
+    load_index(config, formatter, false).await
+}
+
+async fn load_index(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    refresh: bool,
+) -> Result<Vec<String>> {
+    let url = format!(
+        "{PRESETS_INDEX_URL}?ref={}",
+        config.environment().presets_ref
+    );
+    let body = cached(config, formatter, &url, "the preset index", refresh)
+        .await?
+        .ok_or_else(|| CliError::ApiError("Preset index not found".to_string()))?;
+    let entries: Vec<PresetIndexEntry> = serde_json::from_value(body)
+        .map_err(|e| CliError::ApiError(format!("Failed to parse preset index: {e}")))?;
+
+    let mut names: Vec<String> = entries
+        .into_iter()
+        .filter(|e| e.kind == "dir")
+        .map(|e| e.name)
+        .collect();
+    names.sort();
+
+    Ok(names)
+}
+
+/// Download the preset index and the given presets again, ignoring the cache. Without names,
+/// every preset already cached for the configured branch is refreshed. Returns the presets
+/// refreshed.
+pub async fn refresh(
+    config: &Config,
+    formatter: &dyn OutputFormatter,
+    names: Vec<String>,
+) -> Result<Vec<String>> {
+    load_index(config, formatter, true).await?;
+    let names = if names.is_empty() {
+        cached_presets(config)
+    } else {
+        names
+    };
+    for name in &names {
+        load_preset(config, formatter, name, true).await?;
+    }
+    Ok(names)
+}
+
+/// Names of the presets cached for the configured branch
+fn cached_presets(config: &Config) -> Vec<String> {
+    let prefix = preset_url(config.environment().presets_ref, "");
+    let prefix = prefix.trim_end_matches("/config.json");
+    PresetCache::load(&cache_path(config))
+        .entries
+        .into_keys()
+        .filter_map(|url| {
+            url.strip_prefix(prefix)?
+                .strip_suffix("/config.json")
+                .map(str::to_string)
+        })
+        .collect()
 }
 
 /// Extract chain-specific policies from preset
@@ -82,3 +342,49 @@ pub fn extract_chain_policies(
 
     Ok(chain_config.policies.clone())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::NullFormatter;
+
+    #[tokio::test]
+    async fn offline_uses_only_the_cache() {
+        let dir =
+            std::env::temp_dir().join(format!("controller-presets-test-{}", std::process::id()));
+        let mut config = Config::default();
+        config.session.storage_path = dir.to_string_lossy().to_string();
+        config.cli.offline = true;
+
+        let presets_ref = config.environment().presets_ref;
+        let mut cache = PresetCache::default();
+        cache.entries.insert(
+            preset_url(presets_ref, "my-game"),
+            CachedEntry {
+                fetched_at: 0,
+                etag: Some("\"abc\"".to_string()),
+                body: serde_json::json!({
+                    "origin": ["my-game.xyz"],
+                    "chains": { "SN_MAIN": { "policies": { "contracts": {} } } }
+                }),
+            },
+        );
+        cache.save(&cache_path(&config));
+
+        // Stale, but --offline never revalidates
+        assert!(!is_fresh(
+            &cache.entries[&preset_url(presets_ref, "my-game")],
+            CACHE_TTL_SECS + 1
+        ));
+        let preset = fetch_preset(&config, &NullFormatter, "my-game")
+            .await
+            .unwrap();
+        assert_eq!(preset.origin, vec!["my-game.xyz"]);
+        assert!(fetch_preset(&config, &NullFormatter, "other")
+            .await
+            .is_err());
+        assert_eq!(cached_presets(&config), vec!["my-game"]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,161 @@
+use controller_core::error::{Answer, InputRequest};
+use std::ffi::OsString;
+use std::io::Write;
+
+/// Choices listed at once; longer lists (e.g. presets) are searched first
+const MAX_LISTED: usize = 20;
+
+/// Ask the question a command returned as `InputRequired` and turn the answer into the argument
+/// to run it again with. `None` when the user declines or stdin closes.
+pub fn ask(request: &InputRequest) -> Option<OsString> {
+    let value = match &request.answer {
+        Answer::Confirm => {
+            let input = read(&format!("{} [y/N] ", request.question))?;
+            return matches!(input.to_lowercase().as_str(), "y" | "yes")
+                .then(|| request.flag.clone().into());
+        }
+        Answer::Choice { options, default } => {
+            choose(&request.question, options, default.as_deref())?
+        }
+        Answer::Text { default } => loop {
+            let suffix = match default.as_deref() {
+                Some(default) if !default.is_empty() => format!(" [{default}]"),
+                _ => String::new(),
+            };
+            let input = read(&format!("{}{suffix}: ", request.question))?;
+            match (input.is_empty(), default) {
+                (false, _) => break input,
+                (true, Some(default)) => break default.clone(),
+                (true, None) => continue,
+            }
+        },
+    };
+    Some(format!("{}={value}", request.flag).into())
+}
+
+/// Add an answer right after the subcommand, before any trailing values it takes.
+pub fn add_answer(args: &mut Vec<OsString>, command_path: &str, answer: OsString) {
+    let mut position = 1;
+    for name in command_path.split_whitespace() {
+        if let Some(offset) = args[position..].iter().position(|arg| arg == name) {
+            position += offset + 1;
+        }
+    }
+    args.insert(position.min(args.len()), answer);
+}
+
+/// Pick by number or name from the listed options; anything else searches them.
+fn choose(question: &str, options: &[String], default: Option<&str>) -> Option<String> {
+    let all: Vec<&str> = options.iter().map(String::as_str).collect();
+    let mut shown = all.clone();
+    loop {
+        eprintln!();
+        if shown.len() <= MAX_LISTED {
+            for (i, option) in shown.iter().enumerate() {
+                eprintln!("  [{}] {option}", i + 1);
+            }
+        } else {
+            eprintln!("  {} options, type part of a name to search", shown.len());
+        }
+        let suffix = default
+            .map(|default| format!(" [{default}]"))
+            .unwrap_or_default();
+        let input = read(&format!("{question}{suffix}: "))?;
+
+        if input.is_empty() {
+            if let Some(default) = default {
+                return Some(default.to_string());
+            }
+            shown = all.clone();
+            continue;
+        }
+        if let Some(choice) = pick(&input, &shown) {
+            return Some(choice.to_string());
+        }
+        let found = search(&all, &input);
+        match found.len() {
+            0 => eprintln!("Nothing matches '{input}'."),
+            1 => {
+                let confirm = read(&format!("Use '{}'? [Y/n] ", found[0]))?;
+                if matches!(confirm.to_lowercase().as_str(), "" | "y" | "yes") {
+                    return Some(found[0].to_string());
+                }
+            }
+            _ => shown = found,
+        }
+    }
+}
+
+/// Case-insensitive substring search. An empty query matches everything.
+fn search<'a>(options: &[&'a str], query: &str) -> Vec<&'a str> {
+    let query = query.to_lowercase();
+    options
+        .iter()
+        .filter(|option| option.to_lowercase().contains(&query))
+        .copied()
+        .collect()
+}
+
+/// Resolve a choice given either as a 1-based index or as an exact (case-insensitive) name.
+fn pick<'a>(input: &str, options: &[&'a str]) -> Option<&'a str> {
+    if let Ok(index) = input.parse::<usize>() {
+        return index.checked_sub(1).and_then(|i| options.get(i)).copied();
+    }
+    options
+        .iter()
+        .find(|o| o.eq_ignore_ascii_case(input))
+        .copied()
+}
+
+/// One trimmed line from stdin, or `None` once it is closed
+fn read(question: &str) -> Option<String> {
+    eprint!("{question}");
+    std::io::stderr().flush().ok();
+    let mut input = String::new();
+    match std::io::stdin().read_line(&mut input) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(input.trim().to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_matches_substring_case_insensitive() {
+        let options = ["loot-survivor", "pistols", "Dope-Wars"];
+        assert_eq!(search(&options, "LOOT"), vec!["loot-survivor"]);
+        assert_eq!(search(&options, "dope"), vec!["Dope-Wars"]);
+        assert_eq!(search(&options, "").len(), 3);
+        assert!(search(&options, "realms").is_empty());
+    }
+
+    #[test]
+    fn pick_accepts_index_or_name() {
+        let options = ["SN_MAIN", "SN_SEPOLIA"];
+        assert_eq!(pick("1", &options), Some("SN_MAIN"));
+        assert_eq!(pick("2", &options), Some("SN_SEPOLIA"));
+        assert_eq!(pick("sn_main", &options), Some("SN_MAIN"));
+        assert_eq!(pick("0", &options), None);
+        assert_eq!(pick("3", &options), None);
+        assert_eq!(pick("SN_GOERLI", &options), None);
+    }
+
+    #[test]
+    fn answers_go_after_the_subcommand() {
+        let mut args: Vec<OsString> = ["controller", "--json", "session", "auth", "--preset", "x"]
+            .into_iter()
+            .map(OsString::from)
+            .collect();
+        add_answer(&mut args, "session auth", "--overwrite".into());
+        assert_eq!(args[4], "--overwrite");
+
+        let mut args: Vec<OsString> = ["controller", "execute", "0x1", "transfer", "0x2"]
+            .into_iter()
+            .map(OsString::from)
+            .collect();
+        add_answer(&mut args, "execute", "--auto-fix-policies".into());
+        assert_eq!(args[2], "--auto-fix-policies");
+    }
+}
@@ -2,7 +2,19 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Commit the binary was built from (set by build.rs)
+pub const GIT_COMMIT: &str = env!("CONTROLLER_CLI_GIT_COMMIT");
+/// Unix timestamp of the build (set by build.rs, honours SOURCE_DATE_EPOCH)
+pub const BUILD_TIMESTAMP: &str = env!("CONTROLLER_CLI_BUILD_TIMESTAMP");
+/// controller-rs tag account_sdk is built from (set by build.rs)
+pub const ACCOUNT_SDK_VERSION: &str = env!("CONTROLLER_CLI_ACCOUNT_SDK");
+/// Starknet JSON-RPC spec versions (major.minor) spoken by the bundled starknet-rs
+pub const SUPPORTED_RPC_SPECS: &[&str] = &["0.9"];
+/// Cartridge API query fields the CLI depends on; an API missing any of them is incompatible
+pub const REQUIRED_API_FIELDS: &[&str] = &["subscribeCreateSession", "sessions"];
+/// Keychain routes opened by `session auth`
+pub const REQUIRED_KEYCHAIN_ROUTES: &[&str] = &["/session"];
 const GITHUB_RELEASES_URL: &str =
     "https://api.github.com/repos/cartridge-gg/controller-cli/releases/latest";
 const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60); // 24 hours
@@ -14,7 +26,7 @@ struct VersionCache {
 }
 
 fn cache_path() -> Option<PathBuf> {
-    dirs::config_dir().map(|p| p.join("controller-cli").join(".version-cache"))
+    crate::config::config_dir().map(|p| p.join(".version-cache"))
 }
 
 fn read_cache() -> Option<VersionCache> {
@@ -25,9 +37,6 @@ fn read_cache() -> Option<VersionCache> {
 
 fn write_cache(version: &str) {
     if let Some(path) = cache_path() {
-        if let Some(parent) = path.parent() {
-            let _ = std::fs::create_dir_all(parent);
-        }
         let cache = VersionCache {
             latest_version: version.to_string(),
             checked_at: SystemTime::now()
@@ -36,7 +45,7 @@ fn write_cache(version: &str) {
                 .as_secs(),
         };
         if let Ok(json) = serde_json::to_string(&cache) {
-            let _ = std::fs::write(path, json);
+            let _ = crate::commands::storage::write_atomic(&path, json);
         }
     }
 }
@@ -132,10 +141,28 @@ pub async fn check_for_update() -> Option<String> {
     }
 }
 
+/// Whether an RPC's `starknet_specVersion` (e.g. "0.9.0") is one this build speaks.
+pub fn rpc_spec_supported(spec_version: &str) -> bool {
+    let mut parts = spec_version.trim().trim_start_matches('v').split('.');
+    let major_minor = match (parts.next(), parts.next()) {
+        (Some(major), Some(minor)) => format!("{major}.{minor}"),
+        _ => return false,
+    };
+    SUPPORTED_RPC_SPECS.contains(&major_minor.as_str())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_rpc_spec_supported() {
+        assert!(rpc_spec_supported("0.9.0"));
+        assert!(rpc_spec_supported("v0.9.0-rc.1"));
+        assert!(!rpc_spec_supported("0.7.1"));
+        assert!(!rpc_spec_supported("garbage"));
+    }
+
     #[test]
     fn test_parse_version_plain() {
         assert_eq!(parse_version("0.1.11"), Some((0, 1, 11)));